@@ -0,0 +1,23 @@
+//! Writing announcements of state changes for screen readers.
+
+use std::io::Write;
+use std::path::Path;
+
+use crate::error::Result;
+
+/// Append announcements to `path`, one per line, creating the file if needed.
+/// A screen reader can follow it with e.g. `tail -f`.
+pub fn append(path: &Path, lines: &[String]) -> Result<()> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    let mut text = String::new();
+    for line in lines {
+        // Multi-line messages are spoken as one announcement
+        text.push_str(&line.replace('\n', " "));
+        text.push('\n');
+    }
+    file.write_all(text.as_bytes())?;
+    Ok(())
+}
@@ -0,0 +1,68 @@
+//! Copying text to the system clipboard through the terminal (OSC 52),
+//! with the local clipboard as a fallback, and pasting from the local
+//! clipboard.
+
+use std::io::Write;
+use std::sync::Mutex;
+
+use base64::Engine;
+
+use crate::error::{Error, Result};
+
+/// The local clipboard, kept open because on X11 and Wayland the copied
+/// text is served by the process that owns it.
+static LOCAL: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+
+/// Build the OSC 52 escape sequence that sets the clipboard to `text`.
+pub fn osc52_sequence(text: &str) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    format!("\x1b]52;c;{}\x07", encoded)
+}
+
+/// Put `text` on the system clipboard.
+///
+/// The host terminal is asked to set it with OSC 52, which also works over
+/// SSH; terminals that don't support it ignore the sequence. The local
+/// clipboard is set as well where there is one, for those terminals.
+pub fn copy(text: &str) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    stdout.write_all(osc52_sequence(text).as_bytes())?;
+    stdout.flush()?;
+    if let Err(e) = copy_local(text) {
+        log::debug!("Local clipboard unavailable: {}", e);
+    }
+    Ok(())
+}
+
+/// Read the text on the local clipboard.
+///
+/// Terminals can't be asked for their clipboard, so over SSH this fails;
+/// the terminal's own paste still works there.
+pub fn paste() -> Result<String> {
+    with_local(|clipboard| clipboard.get_text()).map_err(|e| Error::Clipboard(e.to_string()))
+}
+
+fn copy_local(text: &str) -> std::result::Result<(), arboard::Error> {
+    with_local(|clipboard| clipboard.set_text(text))
+}
+
+fn with_local<T>(
+    f: impl FnOnce(&mut arboard::Clipboard) -> std::result::Result<T, arboard::Error>,
+) -> std::result::Result<T, arboard::Error> {
+    let mut local = LOCAL.lock().unwrap_or_else(|e| e.into_inner());
+    let clipboard = match local.as_mut() {
+        Some(clipboard) => clipboard,
+        None => local.insert(arboard::Clipboard::new()?),
+    };
+    f(clipboard)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc52_sequence() {
+        assert_eq!(osc52_sequence("hi"), "\x1b]52;c;aGk=\x07");
+    }
+}
@@ -0,0 +1,87 @@
+//! User configuration files under `~/.config/testlist/`.
+
+use std::path::{Path, PathBuf};
+
+use crate::data::config::Config;
+use crate::data::theme::{Theme, ThemeFile};
+use crate::error::Result;
+
+/// The testlist config directory: `$XDG_CONFIG_HOME/testlist`, falling
+/// back to `~/.config/testlist`.
+fn config_dir() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("testlist"))
+}
+
+/// Location of the user theme file.
+pub fn theme_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("theme.ron"))
+}
+
+/// Location of the config file.
+pub fn config_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("config.ron"))
+}
+
+/// Load the config file, returning the defaults if it does not exist.
+pub fn load_config(path: &Path) -> Result<Config> {
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+    Config::parse(&std::fs::read_to_string(path)?)
+}
+
+/// Write the config file, creating its directory if needed.
+pub fn save_config(path: &Path, config: &Config) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, config.to_ron()?)?;
+    Ok(())
+}
+
+/// Load a theme file, returning `None` if it does not exist.
+pub fn load_theme(path: &Path) -> Result<Option<Theme>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(path)?;
+    ThemeFile::parse(&content)?.into_theme().map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    #[test]
+    fn test_save_and_load_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("testlist").join("config.ron");
+        assert_eq!(load_config(&path).unwrap(), Config::default());
+
+        let mut config = Config::default();
+        config.layout.notes = false;
+        save_config(&path, &config).unwrap();
+        assert_eq!(load_config(&path).unwrap(), config);
+    }
+
+    #[test]
+    fn test_load_missing_theme() {
+        let dir = tempfile::tempdir().unwrap();
+        let theme = load_theme(&dir.path().join("theme.ron")).unwrap();
+        assert!(theme.is_none());
+    }
+
+    #[test]
+    fn test_load_theme_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("theme.ron");
+        std::fs::write(&path, r##"Theme(bg: Some("#1e1e2e"))"##).unwrap();
+        let theme = load_theme(&path).unwrap().unwrap();
+        assert_eq!(theme.bg(), Color::Rgb(0x1e, 0x1e, 0x2e));
+    }
+}
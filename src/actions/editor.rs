@@ -0,0 +1,43 @@
+//! Editing text in the user's external editor.
+
+use std::io::Write;
+use std::process::Command;
+
+use crate::error::{Error, Result};
+
+/// The editor command: `$VISUAL`, then `$EDITOR`, then `vi`.
+pub fn editor_command() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|cmd| !cmd.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// Open `text` in the external editor via a temp file and return the saved content.
+///
+/// The editor command may include arguments (e.g. `code --wait`). The caller
+/// is responsible for releasing the terminal first. The temp file gets a
+/// random name and is created only if it doesn't exist yet, so nothing
+/// planted in the shared temp directory can take its place; it is removed
+/// when this returns, on success or error.
+pub fn edit_text(text: &str) -> Result<String> {
+    let mut file = tempfile::Builder::new()
+        .prefix("testlist-notes-")
+        .suffix(".md")
+        .tempfile()?;
+    file.write_all(text.as_bytes())?;
+    file.flush()?;
+    let path = file.path();
+
+    let command = editor_command();
+    let mut parts = command.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = Command::new(program).args(parts).arg(path).status();
+
+    match status {
+        Ok(status) if status.success() => Ok(std::fs::read_to_string(path)?),
+        Ok(status) => Err(Error::Editor(format!("{} exited with {}", command, status))),
+        Err(e) => Err(Error::Editor(format!("could not run {}: {}", command, e))),
+    }
+}
@@ -0,0 +1,56 @@
+//! Capture details of the machine a session runs on.
+
+use crate::data::results::Environment;
+
+/// Capture OS, architecture, and hostname, then apply `pairs` on top.
+pub fn capture_environment(pairs: &[(String, String)]) -> Environment {
+    let mut env = Environment {
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        hostname: hostname(),
+        ..Default::default()
+    };
+    for (key, value) in pairs {
+        env.set(key, value);
+    }
+    env
+}
+
+/// Best-effort hostname lookup without extra dependencies.
+fn hostname() -> Option<String> {
+    let from_env = std::env::var("HOSTNAME")
+        .or_else(|_| std::env::var("COMPUTERNAME"))
+        .ok();
+    let from_file = || {
+        std::fs::read_to_string("/etc/hostname")
+            .ok()
+            .map(|s| s.trim().to_string())
+    };
+    let from_command = || {
+        std::process::Command::new("hostname")
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+    };
+    from_env
+        .or_else(from_file)
+        .or_else(from_command)
+        .filter(|h| !h.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_environment_applies_pairs() {
+        let env = capture_environment(&[
+            ("product_version".to_string(), "2.0".to_string()),
+            ("db".to_string(), "sqlite".to_string()),
+        ]);
+        assert_eq!(env.os, std::env::consts::OS);
+        assert_eq!(env.product_version, Some("2.0".to_string()));
+        assert_eq!(env.custom.get("db"), Some(&"sqlite".to_string()));
+    }
+}
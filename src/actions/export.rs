@@ -0,0 +1,144 @@
+//! Exporting testlists and results as JSON, for tools without a RON parser.
+//!
+//! Every export is one object:
+//!
+//! - `export_version`: [`EXPORT_VERSION`]
+//! - `kind`: `"testlist"` or `"results"`
+//! - `testlist`: the definition with includes, sections and matrices resolved
+//! - `results`: the results file as loaded (migrated to the current schema),
+//!   only for `"results"`
+//!
+//! Object keys are sorted, so exporting the same file twice gives the same
+//! output.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::data::definition::{Format, Testlist};
+use crate::data::results::TestlistResults;
+use crate::error::Result;
+
+/// Version of the JSON export structure, raised on incompatible changes.
+pub const EXPORT_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct Export<'a> {
+    export_version: u32,
+    kind: &'static str,
+    testlist: &'a Testlist,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    results: Option<&'a TestlistResults>,
+}
+
+fn to_json(export: &Export) -> Result<String> {
+    // Going through `Value` sorts the keys of maps such as `checklist_results`.
+    let value = serde_json::to_value(export)?;
+    let mut json = serde_json::to_string_pretty(&value)?;
+    json.push('\n');
+    Ok(json)
+}
+
+/// Export a testlist definition as JSON.
+pub fn testlist_json(testlist: &Testlist) -> Result<String> {
+    to_json(&Export {
+        export_version: EXPORT_VERSION,
+        kind: "testlist",
+        testlist,
+        results: None,
+    })
+}
+
+/// Export a results file, together with its testlist, as JSON.
+pub fn results_json(testlist: &Testlist, results: &TestlistResults) -> Result<String> {
+    to_json(&Export {
+        export_version: EXPORT_VERSION,
+        kind: "results",
+        testlist,
+        results: Some(results),
+    })
+}
+
+/// Check whether a file holds results rather than a testlist definition.
+///
+/// Results files are always RON and have a top-level `results` field.
+pub fn is_results_file(path: &Path) -> Result<bool> {
+    if Format::from_path(path) != Format::Ron {
+        return Ok(false);
+    }
+    let value: ron::Value = ron::from_str(&std::fs::read_to_string(path)?)?;
+    let ron::Value::Map(fields) = value else {
+        return Ok(false);
+    };
+    let key = ron::Value::String("results".to_string());
+    let found = fields.iter().any(|(k, _)| *k == key);
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::Test;
+    use crate::data::results::Status;
+    use crate::fixtures;
+
+    fn make_testlist() -> Testlist {
+        let mut testlist = fixtures::testlist(vec![Test {
+            id: "build".to_string(),
+            title: "Build".to_string(),
+            ..Default::default()
+        }]);
+        testlist.meta.title = "Release".to_string();
+        testlist.meta.created = "2025-01-01".to_string();
+        testlist
+    }
+
+    #[test]
+    fn test_testlist_json() {
+        let json = testlist_json(&make_testlist()).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["export_version"], EXPORT_VERSION);
+        assert_eq!(value["kind"], "testlist");
+        assert_eq!(value["testlist"]["tests"][0]["id"], "build");
+        assert!(value.get("results").is_none());
+    }
+
+    #[test]
+    fn test_results_json_is_stable() {
+        let testlist = make_testlist();
+        let mut results = TestlistResults::new_for_testlist(&testlist, "release.ron", "alice");
+        results.results[0].status = Status::Failed;
+        for n in 0..10 {
+            results
+                .checklist_results
+                .insert(format!("build:verify:{}", n), true);
+        }
+        let json = results_json(&testlist, &results).unwrap();
+        assert_eq!(json, results_json(&testlist, &results).unwrap());
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["kind"], "results");
+        assert_eq!(value["results"]["results"][0]["status"], "Failed");
+        assert_eq!(value["results"]["meta"]["tester"], "alice");
+    }
+
+    #[test]
+    fn test_is_results_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let testlist = make_testlist();
+        let results_path = dir.path().join("t.results.ron");
+        TestlistResults::new_for_testlist(&testlist, "t.ron", "alice")
+            .save(&results_path)
+            .unwrap();
+        assert!(is_results_file(&results_path).unwrap());
+
+        let definition = dir.path().join("t.testlist.ron");
+        crate::actions::files::create_template(
+            &definition,
+            Format::Ron,
+            &crate::data::template::TEMPLATES[0],
+        )
+        .unwrap();
+        assert!(!is_results_file(&definition).unwrap());
+        assert!(!is_results_file(&dir.path().join("t.testlist.yaml")).unwrap());
+    }
+}
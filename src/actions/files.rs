@@ -1,86 +1,440 @@
 //! File I/O operations for testlist and results.
 
-use crate::data::definition::Testlist;
-use crate::data::results::TestlistResults;
+use crate::data::definition::{Format, Testlist};
+use crate::data::results::{assets_dir, recorded_testlist, TestlistResults};
+use crate::data::strict;
+use crate::data::template::Template;
 use crate::error::Result;
-use std::path::Path;
+use crate::import::csv::ColumnMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
-/// Load a testlist definition from a RON file.
+/// Load a testlist definition, detecting RON, YAML, or JSON from the extension.
 pub fn load_testlist(path: &Path) -> Result<Testlist> {
     Testlist::load(path)
 }
 
+/// Check whether a testlist path is `-`, meaning standard input.
+pub fn is_stdin(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+/// Read a testlist definition from standard input, failing on unknown
+/// fields if `strict`.
+///
+/// `include` directives are ignored, as there is no file to resolve them
+/// against.
+pub fn read_testlist_stdin(format: Format, strict: bool) -> Result<Testlist> {
+    let mut content = String::new();
+    std::io::Read::read_to_string(&mut std::io::stdin(), &mut content)?;
+    if strict {
+        strict::reject_unknown(
+            Path::new("-"),
+            strict::unknown_testlist_fields(&content, format)?,
+        )?;
+    }
+    Testlist::parse(&content, format)
+}
+
+/// Load a testlist definition in an explicit format.
+pub fn load_testlist_as(path: &Path, format: Format) -> Result<Testlist> {
+    Testlist::load_as(path, format)
+}
+
+/// Load a testlist definition in an explicit format, failing on unknown fields.
+pub fn load_testlist_strict(path: &Path, format: Format) -> Result<Testlist> {
+    Testlist::load_strict(path, format)
+}
+
+/// Save a testlist definition in the given format.
+pub fn save_testlist(testlist: &Testlist, path: &Path, format: Format) -> Result<()> {
+    testlist.save(path, format)
+}
+
+/// Last modification time of a file, or `None` if it can't be read.
+pub fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
 /// Load results from a RON file, with backward compatibility migration.
 pub fn load_results(path: &Path, testlist: &Testlist) -> Result<TestlistResults> {
     TestlistResults::load(path, testlist)
 }
 
-/// Save results to a RON file.
-pub fn save_results(results: &TestlistResults, path: &Path) -> Result<()> {
+/// Load results like [`load_results`], failing on unknown fields.
+pub fn load_results_strict(path: &Path, testlist: &Testlist) -> Result<TestlistResults> {
+    TestlistResults::load_strict(path, testlist)
+}
+
+/// The testlist a results file was recorded against, as stored in its
+/// metadata: relative to the working directory if it exists there,
+/// otherwise relative to the results file. `None` if no path was recorded
+/// or the testlist was read from standard input.
+pub fn results_testlist(results_path: &Path) -> Result<Option<PathBuf>> {
+    let content = std::fs::read_to_string(results_path)?;
+    let recorded = recorded_testlist(&content)?.filter(|r| !is_stdin(Path::new(r)));
+    Ok(recorded.map(|recorded| {
+        let path = PathBuf::from(recorded);
+        match results_path.parent() {
+            Some(dir) if !path.exists() && path.is_relative() => dir.join(path),
+            _ => path,
+        }
+    }))
+}
+
+/// Save results to a RON file, first backing up the existing file and
+/// keeping the newest `backups` backups; `0` makes no backup.
+pub fn save_results(results: &TestlistResults, path: &Path, backups: usize) -> Result<()> {
+    rotate_backups(path, backups)?;
     results.save(path)
 }
 
-/// Create a new testlist template file.
-pub fn create_template(path: &Path) -> std::io::Result<()> {
-    let template = r##"Testlist(
-    meta: Meta(
-        title: "My Test Checklist",
-        description: "Description of what you're testing",
-        created: "2025-01-24T00:00:00Z",
-        version: "1",
-    ),
-    tests: [
-        Test(
-            id: "build",
-            title: "Build the project",
-            description: "Verify the project builds without errors.",
-            setup: [],
-            action: "Run the build command",
-            verify: [
-                "Build completes without errors",
-                "No warnings in output",
-            ],
-            suggested_command: Some("cargo build"),
-        ),
-        Test(
-            id: "tests",
-            title: "Run test suite",
-            description: "Verify all tests pass.",
-            setup: [
-                "Ensure build completed successfully",
-            ],
-            action: "Run the test suite",
-            verify: [
-                "All tests pass",
-                "No flaky tests",
-            ],
-            suggested_command: Some("cargo test"),
-        ),
-        Test(
-            id: "manual-check",
-            title: "Manual verification",
-            description: r#"
-Perform manual testing of the application.
-
-Pay attention to:
-- User interface responsiveness
-- Error handling
-- Edge cases
-            "#,
-            setup: [
-                "Start the application",
-                "Prepare test data",
-            ],
-            action: "Test the main features manually",
-            verify: [
-                "Features work as expected",
-                "No crashes or errors",
-                "Performance is acceptable",
-            ],
-            suggested_command: None,
-        ),
-    ],
-)
-"##;
-    std::fs::write(path, template)
+/// Copy a completed run into the archive directory, creating it if needed.
+///
+/// The copy is named after the results file and the completion time, e.g.
+/// `release.results.20250124T100000.ron`. Returns the copy's path.
+pub fn archive_results(
+    results: &TestlistResults,
+    results_path: &Path,
+    archive_dir: &Path,
+) -> Result<PathBuf> {
+    std::fs::create_dir_all(archive_dir)?;
+    let stem = results_path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy();
+    let completed = results
+        .meta
+        .completed
+        .as_deref()
+        .and_then(|at| chrono::DateTime::parse_from_rfc3339(at).ok())
+        .map(|at| at.with_timezone(&chrono::Local))
+        .unwrap_or_else(chrono::Local::now);
+    let stamp = completed.format("%Y%m%dT%H%M%S");
+    let mut path = archive_dir.join(format!("{}.{}.ron", stem, stamp));
+    let mut n = 1;
+    while path.exists() {
+        path = archive_dir.join(format!("{}.{}-{}.ron", stem, stamp, n));
+        n += 1;
+    }
+    results.save(&path)?;
+    Ok(path)
+}
+
+/// Load every results file (`*.ron`) in an archive directory.
+pub fn load_archive(archive_dir: &Path, testlist: &Testlist) -> Result<Vec<TestlistResults>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(archive_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|e| e == "ron") && path.is_file())
+        .collect();
+    paths.sort();
+    paths
+        .iter()
+        .map(|path| TestlistResults::load(path, testlist))
+        .collect()
+}
+
+/// Timestamp in a backup's name; a `-<n>` suffix tells apart backups made
+/// within the same second.
+const BACKUP_STAMP: &str = "%Y%m%dT%H%M%S";
+
+/// Backups of a file, `<path>.bak.<timestamp>`, newest first.
+pub fn backups(path: &Path) -> Vec<PathBuf> {
+    let Some(name) = path
+        .file_name()
+        .map(|n| format!("{}.bak.", n.to_string_lossy()))
+    else {
+        return Vec::new();
+    };
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut found: Vec<((String, usize), PathBuf)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let rest = file_name.strip_prefix(&name)?;
+            let (stamp, n) = match rest.split_once('-') {
+                Some((stamp, n)) => (stamp, n.parse().ok()?),
+                None => (rest, 0),
+            };
+            chrono::NaiveDateTime::parse_from_str(stamp, BACKUP_STAMP).ok()?;
+            Some(((stamp.to_string(), n), path.with_file_name(&file_name)))
+        })
+        .collect();
+    found.sort_by(|a, b| b.0.cmp(&a.0));
+    found.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Copy the current file to `<path>.bak.<timestamp>` and delete all but the
+/// newest `keep` backups. Does nothing if `path` does not exist yet.
+fn rotate_backups(path: &Path, keep: usize) -> Result<()> {
+    if keep == 0 || !path.exists() {
+        return Ok(());
+    }
+    let stamp = chrono::Local::now().format(BACKUP_STAMP).to_string();
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".bak.{}", stamp));
+    let mut backup = PathBuf::from(&name);
+    let mut n = 1;
+    while backup.exists() {
+        backup = PathBuf::from(format!("{}-{}", name.to_string_lossy(), n));
+        n += 1;
+    }
+    std::fs::copy(path, &backup)?;
+    for old in backups(path).into_iter().skip(keep) {
+        std::fs::remove_file(old)?;
+    }
+    Ok(())
+}
+
+/// Copy an attachment into the results' assets directory.
+///
+/// Returns the copy's path relative to the results file's directory, so the
+/// results file and its assets folder can be moved together. A file with the
+/// same name but different content is never overwritten; a numeric suffix is
+/// added instead.
+pub fn copy_attachment(source: &Path, results_path: &Path) -> Result<PathBuf> {
+    let dir = assets_dir(results_path);
+    std::fs::create_dir_all(&dir)?;
+
+    let stem = source.file_stem().unwrap_or_default().to_string_lossy();
+    let ext = source
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    let mut dest = dir.join(format!("{}{}", stem, ext));
+    let mut n = 1;
+    while dest.exists() && std::fs::read(&dest)? != std::fs::read(source)? {
+        dest = dir.join(format!("{}-{}{}", stem, n, ext));
+        n += 1;
+    }
+    if !dest.exists() {
+        std::fs::copy(source, &dest)?;
+    }
+    Ok(relative_to_results(&dest, results_path))
+}
+
+/// Read an image into a `data:` URI for embedding in an HTML report.
+pub fn image_data_uri(path: &Path) -> Result<String> {
+    use base64::Engine;
+    let mime = match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        _ => "image/png",
+    };
+    let data = std::fs::read(path)?;
+    Ok(format!(
+        "data:{};base64,{}",
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(data)
+    ))
+}
+
+/// Express a path inside the results directory relative to it.
+pub fn relative_to_results(path: &Path, results_path: &Path) -> PathBuf {
+    let base = results_path.parent().unwrap_or(Path::new(""));
+    path.strip_prefix(base).unwrap_or(path).to_path_buf()
+}
+
+/// Import a Markdown task list and write it as a testlist definition.
+///
+/// The output format is detected from the output path's extension.
+pub fn import_markdown(input: &Path, output: &Path) -> Result<Testlist> {
+    let markdown = std::fs::read_to_string(input)?;
+    let source_name = input
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let testlist = crate::import::markdown::from_markdown(&markdown, &source_name);
+    testlist.save(output, Format::from_path(output))?;
+    Ok(testlist)
+}
+
+/// Import CSV test cases and write them as a testlist definition.
+///
+/// Without a `map`, columns are found by their header names. The output
+/// format is detected from the output path's extension.
+pub fn import_csv(input: &Path, map: Option<&ColumnMap>, output: &Path) -> Result<Testlist> {
+    let data = std::fs::read_to_string(input)?;
+    let source_name = input
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let testlist = crate::import::csv::from_csv(&data, map, &source_name)?;
+    testlist.save(output, Format::from_path(output))?;
+    Ok(testlist)
+}
+
+/// Import a TestRail suite XML export and write it as a testlist definition.
+///
+/// The output format is detected from the output path's extension.
+pub fn import_testrail(input: &Path, output: &Path) -> Result<Testlist> {
+    let xml = std::fs::read_to_string(input)?;
+    let source_name = input
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let testlist = crate::import::testrail::from_xml(&xml, &source_name)?;
+    testlist.save(output, Format::from_path(output))?;
+    Ok(testlist)
+}
+
+/// Create a new testlist from a template in the given format.
+///
+/// RON templates are written verbatim to keep their comments-friendly
+/// layout; YAML and JSON are converted from them.
+pub fn create_template(path: &Path, format: Format, template: &Template) -> Result<()> {
+    match format {
+        Format::Ron => std::fs::write(path, template.source)?,
+        _ => Testlist::parse(template.source, Format::Ron)?.save(path, format)?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::template::TEMPLATES;
+    use crate::fixtures;
+
+    #[test]
+    fn test_archive_and_load_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let testlist = Testlist::parse(TEMPLATES[0].source, Format::Ron).unwrap();
+        let mut results = TestlistResults::new_for_testlist(&testlist, "t.ron", "alice");
+        results.meta.completed = Some("2025-01-24T10:00:00Z".to_string());
+        let archive = dir.path().join("archive");
+        let results_path = dir.path().join("t.results.ron");
+        let first = archive_results(&results, &results_path, &archive).unwrap();
+        let second = archive_results(&results, &results_path, &archive).unwrap();
+        assert_ne!(first, second);
+        assert!(first
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .starts_with("t.results.2025012"));
+        std::fs::write(archive.join("notes.txt"), "not a run").unwrap();
+        assert_eq!(load_archive(&archive, &testlist).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_results_testlist_from_stdin_is_not_recorded() {
+        let dir = tempfile::tempdir().unwrap();
+        let testlist = Testlist::parse(TEMPLATES[0].source, Format::Ron).unwrap();
+        let results_path = dir.path().join("t.results.ron");
+        TestlistResults::new_for_testlist(&testlist, "-", "alice")
+            .save(&results_path)
+            .unwrap();
+        assert_eq!(results_testlist(&results_path).unwrap(), None);
+
+        TestlistResults::new_for_testlist(&testlist, "t.ron", "alice")
+            .save(&results_path)
+            .unwrap();
+        assert_eq!(
+            results_testlist(&results_path).unwrap(),
+            Some(dir.path().join("t.ron"))
+        );
+    }
+
+    #[test]
+    fn test_image_data_uri() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shot.JPG");
+        std::fs::write(&path, b"abc").unwrap();
+        assert_eq!(
+            image_data_uri(&path).unwrap(),
+            "data:image/jpeg;base64,YWJj"
+        );
+        assert!(image_data_uri(&dir.path().join("missing.png")).is_err());
+    }
+
+    #[test]
+    fn test_create_template_all_formats() {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, format) in [
+            ("t.testlist.ron", Format::Ron),
+            ("t.testlist.yaml", Format::Yaml),
+            ("t.testlist.json", Format::Json),
+        ] {
+            let path = dir.path().join(name);
+            create_template(&path, format, &TEMPLATES[0]).unwrap();
+            let testlist = load_testlist(&path).unwrap();
+            assert_eq!(testlist.tests.len(), 3);
+            assert_eq!(testlist.tests[0].id, "build");
+        }
+
+        let path = dir.path().join("r.testlist.yaml");
+        let release = crate::data::template::find("release").unwrap();
+        create_template(&path, Format::Yaml, release).unwrap();
+        let testlist = load_testlist(&path).unwrap();
+        assert_eq!(testlist.tests[0].section.as_deref(), Some("Preparation"));
+    }
+
+    #[test]
+    fn test_save_rotates_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let testlist_path = dir.path().join("t.testlist.ron");
+        create_template(&testlist_path, Format::Ron, &TEMPLATES[0]).unwrap();
+        let testlist = load_testlist(&testlist_path).unwrap();
+        let path = dir.path().join("t.testlist.results.ron");
+        let mut results = TestlistResults::new_for_testlist(&testlist, "t.testlist.ron", "alice");
+
+        // Saves 1..=4 with two backups kept; the first save has nothing to back up
+        for n in 1..=4 {
+            results.meta.tester = format!("save-{}", n);
+            save_results(&results, &path, 2).unwrap();
+        }
+        let tester = |p: &Path| load_results(p, &testlist).unwrap().meta.tester;
+        assert_eq!(tester(&path), "save-4");
+        let backups = backups(&path);
+        assert_eq!(backups.len(), 2);
+        assert_eq!(tester(&backups[0]), "save-3");
+        assert_eq!(tester(&backups[1]), "save-2");
+    }
+
+    #[test]
+    fn test_save_without_backups() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("t.testlist.results.ron");
+        std::fs::write(&path, "old").unwrap();
+        let testlist = fixtures::testlist(vec![]);
+        let results = TestlistResults::new_for_testlist(&testlist, "t.testlist.ron", "alice");
+        save_results(&results, &path, 0).unwrap();
+        assert!(backups(&path).is_empty());
+    }
+
+    #[test]
+    fn test_copy_attachment_stores_relative_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let results_path = dir.path().join("qa.testlist.results.ron");
+        let source = dir.path().join("elsewhere.png");
+        std::fs::write(&source, "one").unwrap();
+
+        let stored = copy_attachment(&source, &results_path).unwrap();
+        assert_eq!(
+            stored,
+            PathBuf::from("qa.testlist.results.assets/elsewhere.png")
+        );
+        assert!(dir.path().join(&stored).is_file());
+
+        // Same content is reused, different content gets a new name
+        assert_eq!(copy_attachment(&source, &results_path).unwrap(), stored);
+        std::fs::write(&source, "two").unwrap();
+        assert_eq!(
+            copy_attachment(&source, &results_path).unwrap(),
+            PathBuf::from("qa.testlist.results.assets/elsewhere-1.png")
+        );
+    }
 }
@@ -0,0 +1,69 @@
+//! Detect the git checkout a session runs in.
+
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::data::results::GitInfo;
+
+/// Run git in `dir` and return its trimmed output, if it succeeded.
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Commit, branch, and dirty flag of the git checkout containing `dir`, or
+/// `None` if it isn't in one (or git isn't installed).
+pub fn capture_git(dir: &Path) -> Option<GitInfo> {
+    let commit = git(dir, &["rev-parse", "HEAD"]).filter(|c| !c.is_empty())?;
+    let branch = git(dir, &["symbolic-ref", "--short", "-q", "HEAD"]).filter(|b| !b.is_empty());
+    let dirty = git(dir, &["status", "--porcelain"]).is_some_and(|status| !status.is_empty());
+    Some(GitInfo {
+        commit,
+        branch,
+        dirty,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_capture_git() {
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(capture_git(dir.path()), None);
+
+        let run = |args: &[&str]| git(dir.path(), args);
+        if run(&["init", "-q", "-b", "main"]).is_none() {
+            return; // git not installed
+        }
+        std::fs::write(dir.path().join("a.txt"), "a").unwrap();
+        run(&["add", "a.txt"]).unwrap();
+        run(&[
+            "-c",
+            "user.name=t",
+            "-c",
+            "user.email=t@example.com",
+            "commit",
+            "-q",
+            "-m",
+            "a",
+        ])
+        .unwrap();
+        let info = capture_git(dir.path()).unwrap();
+        assert_eq!(info.commit.len(), 40);
+        assert_eq!(info.branch.as_deref(), Some("main"));
+        assert!(!info.dirty);
+
+        std::fs::write(dir.path().join("b.txt"), "b").unwrap();
+        assert!(capture_git(dir.path()).unwrap().dirty);
+    }
+}
@@ -0,0 +1,235 @@
+//! Read, file, and comment on GitHub issues through the REST API.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::error::{Error, Result};
+
+/// Repository (`owner/name`) of a GitHub remote URL such as
+/// `git@github.com:owner/name.git` or `https://github.com/owner/name`.
+pub fn parse_remote(url: &str) -> Option<String> {
+    let path = url
+        .trim()
+        .strip_prefix("git@github.com:")
+        .or_else(|| url.trim().split_once("github.com/").map(|(_, path)| path))?;
+    let repo = path.trim_end_matches('/').trim_end_matches(".git");
+    let (owner, name) = repo.split_once('/')?;
+    if owner.is_empty() || name.is_empty() || name.contains('/') {
+        return None;
+    }
+    Some(repo.to_string())
+}
+
+/// The GitHub repository of the `origin` remote of the git checkout in the
+/// current directory.
+pub fn detect_repo() -> Option<String> {
+    let output = Command::new("git")
+        .args(["remote", "get-url", "origin"])
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    parse_remote(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Quote a value for a curl config file.
+fn config_value(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Parse an issue or pull request reference: `123`, `#123`,
+/// `owner/name#123`, or its `https://github.com/owner/name/issues/123` (or
+/// `/pull/123`) URL. Returns the repository, if given, and the number.
+pub fn parse_issue_ref(text: &str) -> Option<(Option<String>, u64)> {
+    let text = text.trim();
+    if let Some((_, path)) = text.split_once("github.com/") {
+        let mut parts = path.trim_end_matches('/').split('/');
+        let (owner, name, kind, number) =
+            (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+        if owner.is_empty() || name.is_empty() || !matches!(kind, "issues" | "pull") {
+            return None;
+        }
+        let number = number.split(['#', '?']).next()?.parse().ok()?;
+        return Some((Some(format!("{}/{}", owner, name)), number));
+    }
+    let (repo, number) = match text.rsplit_once('#') {
+        Some((repo, number)) if !repo.is_empty() => {
+            parse_remote(&format!("github.com/{}", repo))?;
+            (Some(repo.to_string()), number)
+        }
+        Some((_, number)) => (None, number),
+        None => (None, text),
+    };
+    Some((repo, number.parse().ok()?))
+}
+
+/// Title, body, and web URL of an issue or pull request.
+#[derive(Debug, Clone)]
+pub struct Issue {
+    pub title: String,
+    pub body: String,
+    pub url: String,
+}
+
+/// Fetch issue or pull request `number` of `repo`.
+pub fn fetch_issue(repo: &str, number: u64) -> Result<Issue> {
+    let response = request("GET", &format!("repos/{}/issues/{}", repo, number), None)?;
+    let field = |name: &str| response[name].as_str().unwrap_or("").to_string();
+    Ok(Issue {
+        title: field("title"),
+        body: field("body"),
+        url: field("html_url"),
+    })
+}
+
+/// Create an issue in `repo` and return its URL.
+pub fn create_issue(repo: &str, title: &str, body: &str) -> Result<String> {
+    let payload = serde_json::json!({ "title": title, "body": body });
+    let response = request("POST", &format!("repos/{}/issues", repo), Some(&payload))?;
+    html_url(&response, "no issue URL in the response")
+}
+
+/// Comment on issue or pull request `number` of `repo` and return the
+/// comment's URL.
+pub fn comment_on_issue(repo: &str, number: u64, body: &str) -> Result<String> {
+    let payload = serde_json::json!({ "body": body });
+    let path = format!("repos/{}/issues/{}/comments", repo, number);
+    let response = request("POST", &path, Some(&payload))?;
+    html_url(&response, "no comment URL in the response")
+}
+
+fn html_url(response: &serde_json::Value, missing: &str) -> Result<String> {
+    match response["html_url"].as_str() {
+        Some(url) => Ok(url.to_string()),
+        None => Err(Error::GitHub(missing.to_string())),
+    }
+}
+
+/// Send an API request and return the JSON response.
+///
+/// Authenticates with `$GITHUB_TOKEN` or `$GH_TOKEN`, which only reads of
+/// public repositories may go without; `$GITHUB_API_URL` replaces
+/// `https://api.github.com` for GitHub Enterprise. The request is sent with
+/// `curl`, configured through stdin so the token doesn't appear in the
+/// process list. API errors are returned with GitHub's message.
+fn request(
+    method: &str,
+    path: &str,
+    payload: Option<&serde_json::Value>,
+) -> Result<serde_json::Value> {
+    let token = std::env::var("GITHUB_TOKEN").or_else(|_| std::env::var("GH_TOKEN"));
+    if token.is_err() && method != "GET" {
+        return Err(Error::GitHub(
+            "set GITHUB_TOKEN to write to GitHub".to_string(),
+        ));
+    }
+    let api = std::env::var("GITHUB_API_URL").unwrap_or("https://api.github.com".to_string());
+    let url = format!("{}/{}", api.trim_end_matches('/'), path);
+    let mut config = vec![
+        format!("url = {}", config_value(&url)),
+        format!("request = {}", config_value(method)),
+        "header = \"Accept: application/vnd.github+json\"".to_string(),
+        "write-out = \"\\n%{http_code}\"".to_string(),
+    ];
+    if let Ok(token) = token {
+        config.push(format!(
+            "header = {}",
+            config_value(&format!("Authorization: Bearer {}", token))
+        ));
+    }
+    if let Some(payload) = payload {
+        config.push(format!(
+            "data-binary = {}",
+            config_value(&payload.to_string())
+        ));
+    }
+
+    let mut child = Command::new("curl")
+        .args([
+            "--silent",
+            "--show-error",
+            "--max-time",
+            "30",
+            "--config",
+            "-",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| Error::GitHub(format!("could not run curl: {}", e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(config.join("\n").as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(Error::GitHub(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = stdout.rsplit_once('\n').unwrap_or(("", &stdout));
+    let response: serde_json::Value = serde_json::from_str(body)
+        .map_err(|_| Error::GitHub("unexpected response from the API".to_string()))?;
+    if !status.starts_with('2') {
+        return Err(Error::GitHub(format!(
+            "{} (HTTP {})",
+            response["message"].as_str().unwrap_or("request failed"),
+            status
+        )));
+    }
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_remote() {
+        for url in [
+            "git@github.com:VisVivaSpace/testlist.git",
+            "https://github.com/VisVivaSpace/testlist",
+            "https://github.com/VisVivaSpace/testlist.git\n",
+            "ssh://git@github.com/VisVivaSpace/testlist/",
+        ] {
+            assert_eq!(
+                parse_remote(url).as_deref(),
+                Some("VisVivaSpace/testlist"),
+                "{}",
+                url
+            );
+        }
+        assert_eq!(parse_remote("https://gitlab.com/a/b"), None);
+        assert_eq!(parse_remote("https://github.com/VisVivaSpace"), None);
+    }
+
+    #[test]
+    fn test_parse_issue_ref() {
+        let repo = Some("VisVivaSpace/testlist".to_string());
+        assert_eq!(parse_issue_ref("12"), Some((None, 12)));
+        assert_eq!(parse_issue_ref("#12"), Some((None, 12)));
+        assert_eq!(
+            parse_issue_ref("VisVivaSpace/testlist#12"),
+            Some((repo.clone(), 12))
+        );
+        assert_eq!(
+            parse_issue_ref("https://github.com/VisVivaSpace/testlist/issues/12"),
+            Some((repo.clone(), 12))
+        );
+        assert_eq!(
+            parse_issue_ref("https://github.com/VisVivaSpace/testlist/pull/34#issuecomment-1"),
+            Some((repo, 34))
+        );
+        assert_eq!(
+            parse_issue_ref("https://github.com/VisVivaSpace/testlist"),
+            None
+        );
+        assert_eq!(parse_issue_ref("docs/setup.md"), None);
+        assert_eq!(parse_issue_ref("a/b/c#1"), None);
+    }
+
+    #[test]
+    fn test_config_value_escapes_quotes() {
+        assert_eq!(config_value(r#"{"a":"b\n"}"#), r#""{\"a\":\"b\\n\"}""#);
+    }
+}
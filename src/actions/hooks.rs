@@ -0,0 +1,48 @@
+//! Run the hook commands configured in the config file.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use crate::queries::hooks::HookEvent;
+
+/// Start `command` through the shell with the event in `TESTLIST_*`
+/// environment variables and as JSON on stdin.
+///
+/// Doesn't wait for the hook; its output is discarded so it cannot draw over
+/// the TUI, and a failing exit status is only logged.
+pub fn run(command: &str, event: &HookEvent) -> std::io::Result<()> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    let mut child = shell
+        .arg(command)
+        .envs(event.env_vars())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let json = serde_json::to_string(event).unwrap_or_default();
+    let mut stdin = child.stdin.take();
+    let command = command.to_string();
+    std::thread::spawn(move || {
+        if let Some(stdin) = stdin.as_mut() {
+            // The hook may exit without reading its input
+            let _ = stdin.write_all(json.as_bytes());
+        }
+        drop(stdin);
+        match child.wait() {
+            Ok(status) if !status.success() => {
+                log::warn!("Hook `{}` exited with {}", command, status)
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Hook `{}` failed: {}", command, e),
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,185 @@
+//! Reading and writing the crash recovery journal next to a results file.
+//!
+//! The journal holds one RON-encoded entry per line and is removed whenever
+//! the results are saved. With append-only saving, saves are marked in the
+//! journal instead, and it is removed once the results are written on exit.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::data::journal::{split_saved, JournalEntry};
+use crate::data::results::write_atomic;
+use crate::error::Result;
+
+/// Path of the journal for a results file: `<results>.journal`.
+pub fn journal_path(results_path: &Path) -> PathBuf {
+    let mut name = results_path.as_os_str().to_os_string();
+    name.push(".journal");
+    PathBuf::from(name)
+}
+
+/// Append entries to the journal, creating it if needed.
+pub fn append(path: &Path, entries: &[JournalEntry]) -> Result<()> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    let mut lines = String::new();
+    for entry in entries {
+        lines.push_str(&ron::to_string(entry)?);
+        lines.push('\n');
+    }
+    file.write_all(lines.as_bytes())?;
+    file.sync_data()?;
+    Ok(())
+}
+
+/// Read a journal. A truncated last line, left by a crash mid-append, is skipped.
+pub fn load(path: &Path) -> Result<Vec<JournalEntry>> {
+    let content = std::fs::read_to_string(path)?;
+    let lines: Vec<&str> = content.lines().collect();
+    let mut entries = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        match ron::from_str(line) {
+            Ok(entry) => entries.push(entry),
+            Err(_) if i + 1 == lines.len() => break,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(entries)
+}
+
+/// Replace the journal with `entries`, e.g. to drop a truncated last line
+/// that later appends would otherwise leave in the middle of the file.
+pub fn rewrite(path: &Path, entries: &[JournalEntry]) -> Result<()> {
+    let mut lines = String::new();
+    for entry in entries {
+        lines.push_str(&ron::to_string(entry)?);
+        lines.push('\n');
+    }
+    write_atomic(path, lines.as_bytes())
+}
+
+/// Remove the journal after the results were saved.
+pub fn clear(path: &Path) -> Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Drop the unsaved changes at the end of a journal, keeping those saved
+/// with append-only saving. Removes the journal if none were saved.
+pub fn discard_unsaved(path: &Path) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+    let entries = load(path)?;
+    let (saved, _) = split_saved(&entries);
+    if saved.is_empty() {
+        return clear(path);
+    }
+    rewrite(path, saved)
+}
+
+/// Check whether a journal holds changes the results file lacks: it exists
+/// and was written after the results file (or there is no results file).
+pub fn has_unsaved_changes(journal: &Path, results_path: &Path) -> bool {
+    let modified = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+    match (modified(journal), modified(results_path)) {
+        (Some(journal), Some(results)) => journal >= results,
+        (Some(_), None) => true,
+        (None, _) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::results::Status;
+
+    fn entry(test_id: &str) -> JournalEntry {
+        JournalEntry::Status {
+            test_id: test_id.to_string(),
+            status: Status::Passed,
+            completed_at: None,
+            severity: None,
+            blocked_reason: None,
+            custom_status: None,
+        }
+    }
+
+    #[test]
+    fn test_append_load_and_clear() {
+        let dir = tempfile::tempdir().unwrap();
+        let results = dir.path().join("t.results.ron");
+        let journal = journal_path(&results);
+        assert!(!has_unsaved_changes(&journal, &results));
+
+        append(&journal, &[entry("t1")]).unwrap();
+        append(
+            &journal,
+            &[JournalEntry::Notes {
+                test_id: "t2".to_string(),
+                notes: Some("two\nlines".to_string()),
+            }],
+        )
+        .unwrap();
+        let entries = load(&journal).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0], entry("t1"));
+        assert!(has_unsaved_changes(&journal, &results));
+
+        clear(&journal).unwrap();
+        assert!(!journal.exists());
+        clear(&journal).unwrap();
+    }
+
+    #[test]
+    fn test_discard_unsaved_keeps_saved_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = dir.path().join("t.results.ron.journal");
+        append(&journal, &[entry("t1"), JournalEntry::Saved, entry("t2")]).unwrap();
+        discard_unsaved(&journal).unwrap();
+        assert_eq!(
+            load(&journal).unwrap(),
+            vec![entry("t1"), JournalEntry::Saved]
+        );
+
+        clear(&journal).unwrap();
+        append(&journal, &[entry("t1")]).unwrap();
+        discard_unsaved(&journal).unwrap();
+        assert!(!journal.exists());
+        discard_unsaved(&journal).unwrap();
+    }
+
+    #[test]
+    fn test_load_skips_truncated_last_line() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = dir.path().join("t.results.ron.journal");
+        append(&journal, &[entry("t1")]).unwrap();
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&journal)
+            .unwrap();
+        file.write_all(b"Status(test_id: \"t2\", sta").unwrap();
+        assert_eq!(load(&journal).unwrap(), vec![entry("t1")]);
+    }
+
+    #[test]
+    fn test_rewrite_drops_truncated_line_before_appending() {
+        let dir = tempfile::tempdir().unwrap();
+        let journal = dir.path().join("t.results.ron.journal");
+        append(&journal, &[entry("t1")]).unwrap();
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&journal)
+            .unwrap();
+        file.write_all(b"Status(test_id: \"t2\", sta").unwrap();
+
+        // As recovery does before the session appends again
+        rewrite(&journal, &load(&journal).unwrap()).unwrap();
+        append(&journal, &[entry("t3")]).unwrap();
+        assert_eq!(load(&journal).unwrap(), vec![entry("t1"), entry("t3")]);
+    }
+}
@@ -0,0 +1,81 @@
+//! Diagnostic log written to the file given with `--log-file`.
+//!
+//! The TUI owns the terminal, so loads, saves, status changes, and terminal
+//! errors are recorded here instead. Without `--log-file` no logger is
+//! installed and the `log` macros do nothing.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+use crate::error::Result;
+
+struct FileLogger {
+    file: Mutex<File>,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target().starts_with("testlist")
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        if let Ok(mut file) = self.file.lock() {
+            let _ = writeln!(file, "{}", format_record(record));
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.flush();
+        }
+    }
+}
+
+/// One log line: local timestamp, level, module, and message.
+fn format_record(record: &Record) -> String {
+    format!(
+        "{} {:<5} {}: {}",
+        chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%.3f%:z"),
+        record.level(),
+        record.target(),
+        record.args()
+    )
+}
+
+/// Append log records at debug level and above to `path`, creating it if
+/// needed.
+pub fn init(path: &Path) -> Result<()> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let logger = FileLogger {
+        file: Mutex::new(file),
+    };
+    // Only fails if a logger is already installed, which keeps that one
+    if log::set_boxed_logger(Box::new(logger)).is_ok() {
+        log::set_max_level(LevelFilter::Debug);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_record() {
+        let line = format_record(
+            &Record::builder()
+                .args(format_args!("Saved results to r.ron"))
+                .level(log::Level::Info)
+                .target("testlist::ui")
+                .build(),
+        );
+        assert!(line.ends_with(" INFO  testlist::ui: Saved results to r.ron"));
+    }
+}
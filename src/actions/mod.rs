@@ -1,4 +1,22 @@
-//! Actions layer: side-effect functions (file I/O, PTY).
+//! Actions layer: side-effect functions (file I/O, PTY, external programs).
 
+pub mod announce;
+pub mod clipboard;
+pub mod config;
+pub mod editor;
+pub mod environment;
+pub mod export;
 pub mod files;
+pub mod git;
+pub mod github;
+pub mod hooks;
+pub mod journal;
+pub mod logging;
+pub mod open;
+pub mod paths;
 pub mod pty;
+pub mod screenshot;
+pub mod script;
+pub mod serve;
+pub mod signing;
+pub mod webhook;
@@ -0,0 +1,25 @@
+//! Open files and URLs with the system's default application.
+
+use std::process::{Command, Stdio};
+
+/// Launch the platform's default handler for a file path or URL.
+///
+/// The handler's output is discarded so it cannot draw over the TUI.
+pub fn open_external(target: &str) -> std::io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(target)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
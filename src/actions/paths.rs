@@ -0,0 +1,132 @@
+//! Filesystem path helpers for path input fields.
+
+use std::path::PathBuf;
+
+/// Expand a leading `~` or `~/` to the home directory.
+pub fn expand_tilde(input: &str) -> PathBuf {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    match (input.strip_prefix('~'), home) {
+        (Some(""), Some(home)) => home,
+        (Some(rest), Some(home)) if rest.starts_with('/') => home.join(&rest[1..]),
+        _ => PathBuf::from(input),
+    }
+}
+
+/// Completions for a partially typed path, sorted, in the same form as typed.
+///
+/// Directories end with `/`. Hidden entries are only offered when the typed
+/// name starts with `.`.
+pub fn complete(input: &str) -> Vec<String> {
+    let (dir_part, prefix) = match input.rfind('/') {
+        Some(i) => input.split_at(i + 1),
+        None => ("", input),
+    };
+    let dir = if dir_part.is_empty() {
+        PathBuf::from(".")
+    } else {
+        expand_tilde(dir_part)
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    let mut candidates: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            let suffix = if entry.path().is_dir() { "/" } else { "" };
+            Some(format!("{}{}{}", dir_part, name, suffix))
+        })
+        .collect();
+    candidates.sort();
+    candidates
+}
+
+/// Clean up a path pasted from a file manager or shell: the first line,
+/// without surrounding quotes or a `file://` prefix, with `%20` escapes
+/// decoded.
+pub fn pasted_path(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default().trim();
+    let unquoted = ['\'', '"']
+        .iter()
+        .find_map(|&q| line.strip_prefix(q).and_then(|l| l.strip_suffix(q)))
+        .unwrap_or(line);
+    match unquoted.strip_prefix("file://") {
+        Some(path) => path.replace("%20", " "),
+        None => unquoted.to_string(),
+    }
+}
+
+/// Longest prefix shared by all candidates.
+pub fn common_prefix(candidates: &[String]) -> String {
+    let Some(first) = candidates.first() else {
+        return String::new();
+    };
+    let mut len = first.len();
+    for candidate in &candidates[1..] {
+        len = first
+            .char_indices()
+            .zip(candidate.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map_or(0, |((i, a), _)| i + a.len_utf8())
+            .min(len);
+    }
+    first[..len].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_tilde() {
+        let home = PathBuf::from(std::env::var("HOME").unwrap());
+        assert_eq!(expand_tilde("~"), home);
+        assert_eq!(expand_tilde("~/shots/a.png"), home.join("shots/a.png"));
+        assert_eq!(expand_tilde("shots/~a.png"), PathBuf::from("shots/~a.png"));
+    }
+
+    #[test]
+    fn test_complete_lists_matching_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("login.png"), "").unwrap();
+        std::fs::write(dir.path().join("logout.png"), "").unwrap();
+        std::fs::write(dir.path().join(".hidden.png"), "").unwrap();
+        std::fs::create_dir(dir.path().join("logs")).unwrap();
+        let base = format!("{}/", dir.path().display());
+
+        let candidates = complete(&format!("{}log", base));
+        assert_eq!(
+            candidates,
+            vec![
+                format!("{}login.png", base),
+                format!("{}logout.png", base),
+                format!("{}logs/", base),
+            ]
+        );
+        assert_eq!(complete(&format!("{}.h", base)).len(), 1);
+        assert!(complete(&format!("{}missing/x", base)).is_empty());
+    }
+
+    #[test]
+    fn test_pasted_path() {
+        assert_eq!(pasted_path("shots/a.png\n"), "shots/a.png");
+        assert_eq!(pasted_path("'/tmp/my shot.png'"), "/tmp/my shot.png");
+        assert_eq!(pasted_path("file:///tmp/my%20shot.png"), "/tmp/my shot.png");
+        assert_eq!(pasted_path(""), "");
+    }
+
+    #[test]
+    fn test_common_prefix() {
+        let candidates = vec![
+            "shots/login.png".to_string(),
+            "shots/logout.png".to_string(),
+        ];
+        assert_eq!(common_prefix(&candidates), "shots/log");
+        assert_eq!(common_prefix(&candidates[..1]), "shots/login.png");
+        assert_eq!(common_prefix(&[]), "");
+    }
+}
@@ -5,9 +5,368 @@
 //! lifecycle directly. This module provides helper functions for PTY operations
 //! that can be called from transforms or actions.
 
+use std::process::{Child, Command, ExitStatus, Stdio};
+
+use crate::error::{Error, Result};
+
+/// Marker printed after a run command, followed by its exit code.
+///
+/// The wrapped command line prints the marker and code as separate `printf`
+/// arguments, so the shell's echo of the line itself never matches.
+pub const EXIT_MARKER: &str = "__TESTLIST_EXIT_";
+
+/// Number of output lines kept for a command run.
+pub const OUTPUT_TAIL_LINES: usize = 20;
+
+/// Signals used to stop the terminal's foreground process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    Interrupt,
+    Kill,
+}
+
+impl Signal {
+    /// Name accepted by `kill -s`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Signal::Interrupt => "INT",
+            Signal::Kill => "KILL",
+        }
+    }
+
+    /// Exit code a shell reports for a process ended by this signal.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Signal::Interrupt => 130,
+            Signal::Kill => 137,
+        }
+    }
+}
+
+/// Send a signal to every process in a process group.
+pub fn signal_process_group(pgid: i32, signal: Signal) -> Result<()> {
+    let status = Command::new("kill")
+        .args(["-s", signal.name(), "--", &format!("-{}", pgid)])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::Signal(format!(
+            "kill -{} {} failed",
+            signal.name(),
+            pgid
+        )))
+    }
+}
+
+/// Spawn `command` in a shell with its output piped, for running commands
+/// where no PTY could be opened. Stdin is closed, as nothing can be typed
+/// into it. On Unix the command leads its own process group, so
+/// [`signal_process_group`] reaches everything it starts.
+pub fn spawn_piped(command: &str) -> std::io::Result<Child> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut shell, 0);
+    shell
+        .arg(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+}
+
+/// Exit code of a finished command, as a shell would report it: a command
+/// ended by a signal gets 128 plus the signal number.
+pub fn exit_code(status: ExitStatus) -> i32 {
+    #[cfg(unix)]
+    if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&status) {
+        return 128 + signal;
+    }
+    status.code().unwrap_or(-1)
+}
+
 /// Send a command string to the terminal (called from UI layer).
 /// This is a thin wrapper documenting the intent — actual sending
 /// happens through EmbeddedTerminal::send_str in the UI layer.
 pub fn prepare_command(suggested_command: Option<&str>) -> Option<String> {
     suggested_command.map(|s| s.to_string())
 }
+
+/// Family of the shell in the embedded terminal, which decides how a run
+/// command reports its exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellKind {
+    /// sh, bash, zsh, and other shells with `$?`.
+    Posix,
+    /// Windows PowerShell (`powershell.exe`) or PowerShell 7 (`pwsh`).
+    PowerShell,
+    /// The Windows command prompt, `cmd.exe`.
+    Cmd,
+}
+
+impl ShellKind {
+    /// Tell the family from the shell's program, e.g. `/bin/zsh` or
+    /// `C:\Windows\System32\cmd.exe`; unknown shells are taken to be POSIX-style.
+    pub fn detect(program: &str) -> ShellKind {
+        let name = program
+            .rsplit(['/', '\\'])
+            .next()
+            .unwrap_or(program)
+            .to_ascii_lowercase();
+        match name.strip_suffix(".exe").unwrap_or(&name) {
+            "pwsh" | "powershell" => ShellKind::PowerShell,
+            "cmd" => ShellKind::Cmd,
+            _ => ShellKind::Posix,
+        }
+    }
+}
+
+/// Shell the embedded terminal starts on Windows without a configured one:
+/// PowerShell 7 or Windows PowerShell if `on_path` finds them, otherwise
+/// `comspec` (the `COMSPEC` variable) or `cmd.exe`.
+pub fn default_windows_shell(on_path: impl Fn(&str) -> bool, comspec: Option<String>) -> String {
+    ["pwsh.exe", "powershell.exe"]
+        .into_iter()
+        .find(|program| on_path(program))
+        .map(str::to_string)
+        .or(comspec)
+        .unwrap_or_else(|| "cmd.exe".to_string())
+}
+
+/// Check whether `program` is a file in one of the `PATH` directories.
+pub fn find_on_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+}
+
+/// Build the line sent to the shell to run a command and report its exit code.
+///
+/// The marker is split from the code in a way the shell's echo of the line
+/// itself never matches: separate `printf` arguments for POSIX shells, a
+/// format string for PowerShell, and a delayed `%^ERRORLEVEL%` for cmd.
+pub fn wrap_command(command: &str, shell: ShellKind) -> String {
+    match shell {
+        ShellKind::Posix => {
+            format!("{}; printf '\\n%s%s\\n' {} \"$?\"\r", command, EXIT_MARKER)
+        }
+        // `$LASTEXITCODE` is only set by native programs, so a failed cmdlet counts as 1
+        ShellKind::PowerShell => format!(
+            "{}; $testlistExit = if ($?) {{ 0 }} elseif ($LASTEXITCODE) {{ $LASTEXITCODE }} else {{ 1 }}; \"`n{{0}}{{1}}\" -f '{}', $testlistExit\r",
+            command, EXIT_MARKER
+        ),
+        ShellKind::Cmd => format!(
+            "{} & echo. & call echo {}%^ERRORLEVEL%\r",
+            command, EXIT_MARKER
+        ),
+    }
+}
+
+/// Request for the cursor position (DSR 6), which ConPTY sends as it starts
+/// and waits on until it is answered.
+pub const CURSOR_POSITION_REQUEST: &[u8] = b"\x1b[6n";
+
+/// Number of cursor position requests in a chunk of PTY output.
+pub fn cursor_position_requests(output: &[u8]) -> usize {
+    output
+        .windows(CURSOR_POSITION_REQUEST.len())
+        .filter(|window| *window == CURSOR_POSITION_REQUEST)
+        .count()
+}
+
+/// Answer to a cursor position request, for a 0-based `row` and `col`.
+pub fn cursor_position_report(row: u16, col: u16) -> String {
+    format!("\x1b[{};{}R", row + 1, col + 1)
+}
+
+/// Bytes written to the PTY for pasted text.
+///
+/// Line endings become carriage returns, as typed Enter would send. When the
+/// program enabled bracketed paste, the text is wrapped in paste markers so
+/// multi-line input isn't executed line by line; an end marker inside the
+/// text is dropped so it can't terminate the paste early.
+pub fn paste_sequence(text: &str, bracketed: bool) -> String {
+    let text = text.replace("\r\n", "\r").replace('\n', "\r");
+    if bracketed {
+        format!("\x1b[200~{}\x1b[201~", text.replace("\x1b[201~", ""))
+    } else {
+        text
+    }
+}
+
+/// Find the exit marker in captured output.
+///
+/// Returns the byte offset where the marker starts and the exit code, once a
+/// complete marker line has arrived.
+pub fn find_exit_marker(output: &str) -> Option<(usize, i32)> {
+    let mut search_from = 0;
+    while let Some(found) = output[search_from..].find(EXIT_MARKER) {
+        let start = search_from + found;
+        let rest = &output[start + EXIT_MARKER.len()..];
+        // Windows exit codes such as crash statuses can be negative
+        let sign = usize::from(rest.starts_with('-'));
+        let end = sign
+            + rest[sign..]
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .count();
+        let terminated = rest[end..].starts_with(['\r', '\n']);
+        if end > sign && terminated {
+            return rest[..end].parse().ok().map(|code| (start, code));
+        }
+        search_from = start + EXIT_MARKER.len();
+    }
+    None
+}
+
+/// Remove terminal escape sequences and carriage returns from PTY output.
+pub fn strip_ansi(output: &str) -> String {
+    let mut clean = String::with_capacity(output.len());
+    let mut chars = output.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                // CSI: parameters end at a byte in @..~
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: ends at BEL or ESC \
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            },
+            '\r' => {}
+            c => clean.push(c),
+        }
+    }
+    clean
+}
+
+/// The last `lines` non-empty lines of command output, skipping the echoed command line.
+pub fn output_tail(output: &str, lines: usize) -> String {
+    let clean = strip_ansi(output);
+    let body: Vec<&str> = clean
+        .lines()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    body[body.len().saturating_sub(lines)..].join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paste_sequence() {
+        assert_eq!(paste_sequence("ls\r\npwd\n", false), "ls\rpwd\r");
+        assert_eq!(
+            paste_sequence("echo ü\nx\x1b[201~", true),
+            "\x1b[200~echo ü\rx\x1b[201~"
+        );
+    }
+
+    #[test]
+    fn test_cursor_position_requests() {
+        assert_eq!(cursor_position_requests(b"\x1b[?25l\x1b[6nPS> \x1b[6n"), 2);
+        assert_eq!(cursor_position_requests(b"\x1b[6"), 0);
+        assert_eq!(cursor_position_report(0, 4), "\x1b[1;5R");
+    }
+
+    #[test]
+    fn test_signal_exit_codes() {
+        assert_eq!(Signal::Interrupt.exit_code(), 128 + 2);
+        assert_eq!(Signal::Kill.exit_code(), 128 + 9);
+    }
+
+    #[test]
+    fn test_wrapped_command_echo_does_not_match_marker() {
+        let line = wrap_command("cargo test", ShellKind::Posix);
+        assert!(line.starts_with("cargo test; printf"));
+        for shell in [ShellKind::Posix, ShellKind::PowerShell, ShellKind::Cmd] {
+            let line = wrap_command("cargo test", shell);
+            assert!(line.ends_with('\r'));
+            assert_eq!(find_exit_marker(&line), None);
+            // As echoed back, with the shell's own line ending
+            assert_eq!(find_exit_marker(&format!("{}\n", line)), None);
+        }
+    }
+
+    #[test]
+    fn test_detect_shell_kind() {
+        assert_eq!(ShellKind::detect("/bin/zsh"), ShellKind::Posix);
+        assert_eq!(
+            ShellKind::detect("/usr/local/bin/pwsh"),
+            ShellKind::PowerShell
+        );
+        assert_eq!(
+            ShellKind::detect(r"C:\Windows\System32\WindowsPowerShell\v1.0\powershell.exe"),
+            ShellKind::PowerShell
+        );
+        assert_eq!(
+            ShellKind::detect(r"C:\Windows\system32\CMD.EXE"),
+            ShellKind::Cmd
+        );
+        assert_eq!(ShellKind::detect("bash.exe"), ShellKind::Posix);
+    }
+
+    #[test]
+    fn test_default_windows_shell() {
+        let comspec = Some(r"C:\Windows\system32\cmd.exe".to_string());
+        assert_eq!(default_windows_shell(|_| true, comspec.clone()), "pwsh.exe");
+        assert_eq!(
+            default_windows_shell(|p| p == "powershell.exe", comspec.clone()),
+            "powershell.exe"
+        );
+        assert_eq!(
+            default_windows_shell(|_| false, comspec),
+            r"C:\Windows\system32\cmd.exe"
+        );
+        assert_eq!(default_windows_shell(|_| false, None), "cmd.exe");
+    }
+
+    #[test]
+    fn test_find_exit_marker() {
+        let output = "cargo test\r\nok\r\n\r\n__TESTLIST_EXIT_101\r\n$ ";
+        let (start, code) = find_exit_marker(output).unwrap();
+        assert_eq!(code, 101);
+        assert_eq!(&output[start..start + 4], "__TE");
+        assert_eq!(find_exit_marker("__TESTLIST_EXIT_1"), None);
+        assert_eq!(
+            find_exit_marker("\r\n__TESTLIST_EXIT_-1073741819\r\n"),
+            Some((2, -1073741819))
+        );
+        assert_eq!(find_exit_marker("__TESTLIST_EXIT_-\r\n"), None);
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        let raw = "\x1b[1;32mok\x1b[0m\r\n\x1b]0;title\x07done";
+        assert_eq!(strip_ansi(raw), "ok\ndone");
+    }
+
+    #[test]
+    fn test_output_tail_skips_echo_and_keeps_last_lines() {
+        let raw = "make\r\nline 1\r\nline 2\r\n\r\nline 3\r\n";
+        assert_eq!(output_tail(raw, 2), "line 2\nline 3");
+    }
+}
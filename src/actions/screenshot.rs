@@ -0,0 +1,118 @@
+//! Capturing screenshots with a platform screenshot tool.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::error::{Error, Result};
+
+/// Pick a screenshot command for this platform.
+///
+/// macOS uses `screencapture`; elsewhere `grim` (Wayland) or `scrot` (X11)
+/// is used if found on `PATH`.
+pub fn detect_command() -> Option<String> {
+    if cfg!(target_os = "macos") {
+        return Some("screencapture -x {path}".to_string());
+    }
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let candidates: &[&str] = if wayland {
+        &["grim", "scrot"]
+    } else {
+        &["scrot", "grim"]
+    };
+    candidates
+        .iter()
+        .find(|program| find_in_path(program).is_some())
+        .map(|program| format!("{} {{path}}", program))
+}
+
+/// Build the argument list for a command template.
+///
+/// `{path}` is replaced by the output path; without a placeholder the path
+/// is appended as the last argument.
+pub fn command_args(template: &str, path: &Path) -> Vec<String> {
+    let path = path.to_string_lossy();
+    let mut args: Vec<String> = template
+        .split_whitespace()
+        .map(|arg| arg.replace("{path}", &path))
+        .collect();
+    if !template.contains("{path}") {
+        args.push(path.into_owned());
+    }
+    args
+}
+
+/// Capture a screenshot into `path`, creating its directory if needed.
+pub fn capture(template: Option<&str>, path: &Path) -> Result<()> {
+    let template = match template {
+        Some(t) => t.to_string(),
+        None => detect_command().ok_or_else(|| {
+            Error::Screenshot("no screenshot tool found (set --screenshot-command)".to_string())
+        })?,
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let args = command_args(&template, path);
+    let status = Command::new(&args[0])
+        .args(&args[1..])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| Error::Screenshot(format!("could not run {}: {}", args[0], e)))?;
+    if !status.success() {
+        return Err(Error::Screenshot(format!(
+            "{} exited with {}",
+            args[0], status
+        )));
+    }
+    if !path.exists() {
+        return Err(Error::Screenshot(format!(
+            "{} did not create {}",
+            args[0],
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+fn find_in_path(program: &str) -> Option<PathBuf> {
+    std::env::split_paths(&std::env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|candidate| candidate.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_args_replaces_placeholder() {
+        let args = command_args("grim -t png {path}", Path::new("out/shot.png"));
+        assert_eq!(args, vec!["grim", "-t", "png", "out/shot.png"]);
+    }
+
+    #[test]
+    fn test_command_args_appends_path() {
+        let args = command_args("scrot", Path::new("shot.png"));
+        assert_eq!(args, vec!["scrot", "shot.png"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_capture_with_custom_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("assets").join("shot.png");
+        capture(Some("touch {path}"), &path).unwrap();
+        assert!(path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_capture_reports_missing_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shot.png");
+        let err = capture(Some("true"), &path).unwrap_err();
+        assert!(matches!(err, Error::Screenshot(_)));
+    }
+}
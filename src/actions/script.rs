@@ -0,0 +1,273 @@
+//! Run Rhai scripts against a testlist and its results.
+//!
+//! Scripts read the run through `test_ids()`, `status(id)`, `notes(id)`,
+//! and `checked(id, section, item)`, test text with `matches(text, regex)`,
+//! and ask for changes with `set_status(id, status)`, `check(id, section,
+//! item)`, `uncheck(id, section, item)`, and `add_note(id, text)`. Changes
+//! are collected as [`ScriptAction`]s rather than applied while the script
+//! runs, so a failing script changes nothing.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Scope};
+
+use crate::data::definition::Testlist;
+use crate::data::results::{checklist_key, ChecklistSection, Status, TestResult, TestlistResults};
+use crate::data::script::ScriptAction;
+use crate::error::{Error, Result};
+
+/// Operations a script may run before it is stopped, so a runaway loop
+/// can't hang the TUI.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+type ScriptResult<T> = std::result::Result<T, Box<EvalAltResult>>;
+
+fn script_error<T>(e: impl std::fmt::Display) -> ScriptResult<T> {
+    Err(e.to_string().into())
+}
+
+/// Lowercase status name, as accepted by `set_status`; a custom status's
+/// name as declared.
+fn status_name(result: &TestResult) -> String {
+    match &result.custom_status {
+        Some(name) => name.clone(),
+        None => format!("{:?}", result.status).to_ascii_lowercase(),
+    }
+}
+
+/// Variables set for a test's script after its suggested command finished.
+pub struct CommandOutcome<'a> {
+    pub test_id: &'a str,
+    pub exit_code: i32,
+    pub output: &'a str,
+}
+
+/// Run `script` and return the changes it asked for.
+///
+/// With an `outcome`, the script also sees `test_id`, `exit_code`, and
+/// `output` (the command's captured output).
+pub fn run(
+    script: &str,
+    testlist: &Testlist,
+    results: &TestlistResults,
+    outcome: Option<&CommandOutcome>,
+) -> Result<Vec<ScriptAction>> {
+    let actions = Rc::new(RefCell::new(Vec::new()));
+    let engine = build_engine(testlist, results, &actions);
+    let mut scope = Scope::new();
+    if let Some(outcome) = outcome {
+        scope.push_constant("test_id", outcome.test_id.to_string());
+        scope.push_constant("exit_code", outcome.exit_code as rhai::INT);
+        scope.push_constant("output", outcome.output.to_string());
+    }
+    engine
+        .run_with_scope(&mut scope, script)
+        .map_err(|e| Error::Script(e.to_string()))?;
+    let actions = actions.borrow().clone();
+    Ok(actions)
+}
+
+fn build_engine(
+    testlist: &Testlist,
+    results: &TestlistResults,
+    actions: &Rc<RefCell<Vec<ScriptAction>>>,
+) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.on_print(|text| log::info!("script: {}", text));
+
+    let ids: Array = testlist
+        .tests
+        .iter()
+        .map(|t| Dynamic::from(t.id.clone()))
+        .collect();
+    engine.register_fn("test_ids", move || ids.clone());
+
+    let snapshot = Rc::new(results.clone());
+    let known: Rc<Vec<String>> = Rc::new(testlist.tests.iter().map(|t| t.id.clone()).collect());
+    let result = {
+        let snapshot = snapshot.clone();
+        move |id: &str| {
+            snapshot
+                .results
+                .iter()
+                .find(|r| r.test_id == id)
+                .cloned()
+                .map_or_else(|| script_error(format!("unknown test ID: {}", id)), Ok)
+        }
+    };
+    {
+        let result = result.clone();
+        engine.register_fn("status", move |id: &str| {
+            result(id).map(|r| status_name(&r))
+        });
+    }
+    engine.register_fn("notes", move |id: &str| {
+        result(id).map(|r| r.notes.unwrap_or_default())
+    });
+    engine.register_fn("checked", move |id: &str, section: &str, item: &str| {
+        let section: ChecklistSection = section.parse().or_else(script_error)?;
+        Ok::<_, Box<EvalAltResult>>(
+            snapshot
+                .checklist_results
+                .get(&checklist_key(id, section, item))
+                .copied()
+                .unwrap_or(false),
+        )
+    });
+    engine.register_fn("matches", |text: &str, pattern: &str| {
+        regex::Regex::new(pattern)
+            .map(|re| re.is_match(text))
+            .or_else(script_error)
+    });
+
+    let check_known = move |id: &str| {
+        if known.iter().any(|k| k == id) {
+            Ok(())
+        } else {
+            script_error(format!("unknown test ID: {}", id))
+        }
+    };
+    {
+        let (actions, check_known) = (actions.clone(), check_known.clone());
+        engine.register_fn("set_status", move |id: &str, status: &str| {
+            check_known(id)?;
+            let status: Status = status.parse().or_else(script_error)?;
+            actions.borrow_mut().push(ScriptAction::SetStatus {
+                test_id: id.to_string(),
+                status,
+            });
+            Ok::<_, Box<EvalAltResult>>(())
+        });
+    }
+    for (name, checked) in [("check", true), ("uncheck", false)] {
+        let (actions, check_known) = (actions.clone(), check_known.clone());
+        engine.register_fn(name, move |id: &str, section: &str, item: &str| {
+            check_known(id)?;
+            let section: ChecklistSection = section.parse().or_else(script_error)?;
+            actions.borrow_mut().push(ScriptAction::SetChecked {
+                test_id: id.to_string(),
+                section,
+                item_id: item.to_string(),
+                checked,
+            });
+            Ok::<_, Box<EvalAltResult>>(())
+        });
+    }
+    {
+        let actions = actions.clone();
+        engine.register_fn("add_note", move |id: &str, text: &str| {
+            check_known(id)?;
+            actions.borrow_mut().push(ScriptAction::AddNote {
+                test_id: id.to_string(),
+                text: text.to_string(),
+            });
+            Ok::<_, Box<EvalAltResult>>(())
+        });
+    }
+    engine
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::{ChecklistItem, Test};
+    use crate::fixtures;
+
+    fn make_testlist() -> Testlist {
+        fixtures::testlist(vec![Test {
+            id: "build".to_string(),
+            verify: vec![ChecklistItem {
+                id: "no-warnings".to_string(),
+                text: "No warnings".to_string(),
+            }],
+            ..Default::default()
+        }])
+    }
+
+    #[test]
+    fn test_script_checks_item_on_matching_output() {
+        let testlist = make_testlist();
+        let results = TestlistResults::new_for_testlist(&testlist, "t.ron", "alice");
+        let script = r#"
+            if exit_code == 0 && !matches(output, "warning:") {
+                check(test_id, "verify", "no-warnings");
+                set_status(test_id, "passed");
+            }
+        "#;
+        let outcome = CommandOutcome {
+            test_id: "build",
+            exit_code: 0,
+            output: "Finished dev profile",
+        };
+        let actions = run(script, &testlist, &results, Some(&outcome)).unwrap();
+        assert_eq!(
+            actions,
+            vec![
+                ScriptAction::SetChecked {
+                    test_id: "build".to_string(),
+                    section: ChecklistSection::Verify,
+                    item_id: "no-warnings".to_string(),
+                    checked: true,
+                },
+                ScriptAction::SetStatus {
+                    test_id: "build".to_string(),
+                    status: Status::Passed,
+                },
+            ]
+        );
+
+        let outcome = CommandOutcome {
+            output: "warning: unused variable",
+            ..outcome
+        };
+        assert!(run(script, &testlist, &results, Some(&outcome))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_script_reads_results() {
+        let testlist = make_testlist();
+        let mut results = TestlistResults::new_for_testlist(&testlist, "t.ron", "alice");
+        results.set_status("build", Status::Failed);
+        let script = r#"
+            for id in test_ids() {
+                if status(id) == "failed" && !checked(id, "verify", "no-warnings") {
+                    add_note(id, "still failing");
+                }
+            }
+        "#;
+        let actions = run(script, &testlist, &results, None).unwrap();
+        assert_eq!(
+            actions,
+            vec![ScriptAction::AddNote {
+                test_id: "build".to_string(),
+                text: "still failing".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_script_errors() {
+        let testlist = make_testlist();
+        let results = TestlistResults::new_for_testlist(&testlist, "t.ron", "alice");
+        for script in [
+            r#"set_status("missing", "passed")"#,
+            r#"set_status("build", "done")"#,
+            r#"check("build", "teardown", "x")"#,
+            r#"matches("x", "(")"#,
+            "loop {}",
+        ] {
+            assert!(
+                matches!(
+                    run(script, &testlist, &results, None),
+                    Err(Error::Script(_))
+                ),
+                "{}",
+                script
+            );
+        }
+    }
+}
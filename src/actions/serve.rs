@@ -0,0 +1,175 @@
+//! Minimal HTTP server publishing the progress of a session (`--serve`).
+//!
+//! `GET /` returns the HTML progress page and `GET /status.json` the same
+//! progress as JSON. Everything else is a 404; the server never changes
+//! the results. Each connection is handled on its own thread, so a client
+//! that is slow to send its request doesn't hold up the others.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::queries::progress::Progress;
+use crate::report::progress;
+
+/// How long a client may take to send its request.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Most bytes of a request read; the rest is ignored.
+const MAX_REQUEST: u64 = 8192;
+
+#[derive(Default)]
+struct Pages {
+    html: String,
+    json: String,
+}
+
+/// A running progress server; pages are replaced with [`ProgressServer::publish`].
+pub struct ProgressServer {
+    pages: Arc<Mutex<Pages>>,
+    addr: SocketAddr,
+}
+
+impl ProgressServer {
+    /// Listen on `addr` and start serving in the background.
+    pub fn start(addr: SocketAddr) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let addr = listener.local_addr()?;
+        let pages = Arc::new(Mutex::new(Pages::default()));
+        let served = pages.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let served = served.clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = respond(stream, &served) {
+                        log::debug!("Progress request failed: {}", e);
+                    }
+                });
+            }
+        });
+        log::info!("Serving progress on http://{}", addr);
+        Ok(Self { pages, addr })
+    }
+
+    /// Address the server listens on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Serve `progress` from now on.
+    pub fn publish(&self, progress: &Progress) {
+        let html = progress::render(progress);
+        let json = serde_json::to_string_pretty(progress).unwrap_or_default();
+        if let Ok(mut pages) = self.pages.lock() {
+            *pages = Pages { html, json };
+        }
+    }
+}
+
+/// Answer one request with the current pages.
+fn respond(stream: TcpStream, pages: &Mutex<Pages>) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut reader = BufReader::new((&stream).take(MAX_REQUEST));
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // Skip the headers; requests have no body we care about
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let path = target.split('?').next().unwrap_or("");
+    let (status, content_type, body) = {
+        let pages = pages.lock().map_err(|_| std::io::ErrorKind::Other)?;
+        match (method, path) {
+            ("GET" | "HEAD", "/") => ("200 OK", "text/html; charset=utf-8", pages.html.clone()),
+            ("GET" | "HEAD", "/status.json") => ("200 OK", "application/json", pages.json.clone()),
+            ("GET" | "HEAD", _) => ("404 Not Found", "text/plain", "Not found\n".to_string()),
+            _ => (
+                "405 Method Not Allowed",
+                "text/plain",
+                "Read-only\n".to_string(),
+            ),
+        }
+    };
+
+    let mut out = &stream;
+    write!(
+        out,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\
+         Cache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )?;
+    if method != "HEAD" {
+        out.write_all(body.as_bytes())?;
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::queries::stats::StatusCounts;
+    use std::io::Read;
+
+    fn get(addr: SocketAddr, request: &str) -> String {
+        let mut stream = TcpStream::connect(("127.0.0.1", addr.port())).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    fn start() -> ProgressServer {
+        ProgressServer::start(SocketAddr::from(([127, 0, 0, 1], 0))).unwrap()
+    }
+
+    #[test]
+    fn test_serves_published_progress() {
+        let server = start();
+        server.publish(&Progress {
+            testlist: "Release".to_string(),
+            tester: "alice".to_string(),
+            current_test: None,
+            counts: StatusCounts {
+                passed: 2,
+                ..Default::default()
+            },
+            tests: Vec::new(),
+        });
+
+        let json = get(
+            server.addr(),
+            "GET /status.json HTTP/1.1\r\nHost: x\r\n\r\n",
+        );
+        assert!(json.starts_with("HTTP/1.1 200 OK"));
+        assert!(json.contains("application/json"));
+        assert!(json.contains("\"passed\": 2"));
+
+        let html = get(server.addr(), "GET /?x=1 HTTP/1.1\r\n\r\n");
+        assert!(html.contains("<h1>Release</h1>"));
+
+        assert!(get(server.addr(), "GET /nope HTTP/1.1\r\n\r\n").contains("404"));
+        assert!(get(server.addr(), "POST / HTTP/1.1\r\n\r\n").contains("405"));
+    }
+
+    #[test]
+    fn test_slow_client_does_not_block_others() {
+        let server = start();
+        assert!(server.addr().ip().is_loopback());
+
+        // A client that never finishes its request, and one that floods it
+        let _idle = TcpStream::connect(server.addr()).unwrap();
+        let mut flood = TcpStream::connect(server.addr()).unwrap();
+        flood.write_all(&[b'a'; 20_000]).unwrap();
+
+        let started = std::time::Instant::now();
+        assert!(get(server.addr(), "GET / HTTP/1.1\r\n\r\n").starts_with("HTTP/1.1 200 OK"));
+        assert!(started.elapsed() < READ_TIMEOUT);
+    }
+}
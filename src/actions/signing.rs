@@ -0,0 +1,121 @@
+//! Detached signatures of saved results files, made and checked with
+//! minisign or GPG.
+//!
+//! The tools run without a terminal, so the key must be usable without
+//! typing a passphrase: a minisign key created with `-W`, or a GPG key whose
+//! passphrase the agent has cached.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::actions::paths::expand_tilde;
+use crate::data::config::{Signing, SigningTool};
+use crate::error::{Error, Result};
+
+/// Path of the signature of a results file: `<results>.minisig` for
+/// minisign, `<results>.asc` for GPG.
+pub fn signature_path(results_path: &Path, tool: SigningTool) -> PathBuf {
+    let mut name = results_path.as_os_str().to_os_string();
+    name.push(match tool {
+        SigningTool::Minisign => ".minisig",
+        SigningTool::Gpg => ".asc",
+    });
+    PathBuf::from(name)
+}
+
+/// Run a signing tool, returning the last line it printed to stderr if it fails.
+fn run(mut command: Command) -> std::result::Result<(), String> {
+    let program = command.get_program().to_string_lossy().into_owned();
+    let output = command
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("{}: {}", program, e))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let reason = stderr
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("exited with an error");
+    Err(reason.to_string())
+}
+
+/// Sign a saved results file, replacing its previous signature. Returns the
+/// signature's path.
+pub fn sign(results_path: &Path, signing: &Signing) -> Result<PathBuf> {
+    let signature = signature_path(results_path, signing.tool);
+    let command = match signing.tool {
+        SigningTool::Minisign => {
+            let mut command = Command::new("minisign");
+            command.arg("-S").arg("-m").arg(results_path);
+            command.arg("-x").arg(&signature);
+            if let Some(ref key) = signing.key {
+                command.arg("-s").arg(expand_tilde(key));
+            }
+            command
+        }
+        SigningTool::Gpg => {
+            // Fail instead of letting pinentry draw over the TUI
+            let mut command = Command::new("gpg");
+            command.args(["--batch", "--yes", "--pinentry-mode", "loopback"]);
+            if let Some(ref key) = signing.key {
+                command.arg("--local-user").arg(key);
+            }
+            command.args(["--armor", "--detach-sign", "--output"]);
+            command.arg(&signature).arg(results_path);
+            command
+        }
+    };
+    run(command).map_err(Error::Signing)?;
+    Ok(signature)
+}
+
+/// Check a results file against its signature.
+pub fn verify(results_path: &Path, signing: &Signing) -> Result<()> {
+    let signature = signature_path(results_path, signing.tool);
+    if !signature.exists() {
+        return Err(Error::BadSignature(format!(
+            "{} not found",
+            signature.display()
+        )));
+    }
+    let command = match signing.tool {
+        SigningTool::Minisign => {
+            let mut command = Command::new("minisign");
+            command.arg("-V").arg("-q").arg("-m").arg(results_path);
+            command.arg("-x").arg(&signature);
+            if let Some(ref key) = signing.public_key {
+                command.arg("-p").arg(expand_tilde(key));
+            }
+            command
+        }
+        SigningTool::Gpg => {
+            let mut command = Command::new("gpg");
+            command.args(["--batch", "--verify"]);
+            command.arg(&signature).arg(results_path);
+            command
+        }
+    };
+    run(command).map_err(Error::BadSignature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_path() {
+        let results = Path::new("runs/alice.results.ron");
+        assert_eq!(
+            signature_path(results, SigningTool::Minisign),
+            PathBuf::from("runs/alice.results.ron.minisig")
+        );
+        assert_eq!(
+            signature_path(results, SigningTool::Gpg),
+            PathBuf::from("runs/alice.results.ron.asc")
+        );
+    }
+}
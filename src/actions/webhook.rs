@@ -0,0 +1,37 @@
+//! POST notifications to the webhook configured in the config file.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Send `body` as JSON to `url` with `curl`.
+///
+/// Doesn't wait for the request; a failure is only logged.
+pub fn post(url: &str, body: &str) -> std::io::Result<()> {
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--max-time", "10"])
+        .args(["--header", "Content-Type: application/json"])
+        .args(["--data-binary", "@-", url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take();
+    let body = body.to_string();
+    let url = url.to_string();
+    std::thread::spawn(move || {
+        if let Some(stdin) = stdin.as_mut() {
+            let _ = stdin.write_all(body.as_bytes());
+        }
+        drop(stdin);
+        match child.wait_with_output() {
+            Ok(output) if !output.status.success() => log::warn!(
+                "Webhook {} failed: {}",
+                url,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+            Ok(_) => log::debug!("Notified webhook {}", url),
+            Err(e) => log::warn!("Webhook {} failed: {}", url, e),
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,300 @@
+//! User configuration stored in `~/.config/testlist/config.ron`.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Result;
+
+/// Which optional panes are shown next to the tests pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "Layout")]
+pub struct PaneLayout {
+    #[serde(default = "default_true")]
+    pub notes: bool,
+    #[serde(default = "default_true")]
+    pub terminal: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for PaneLayout {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+impl PaneLayout {
+    /// Tests, notes, and terminal.
+    pub const FULL: PaneLayout = PaneLayout {
+        notes: true,
+        terminal: true,
+    };
+    /// Tests and terminal.
+    pub const TESTS_TERMINAL: PaneLayout = PaneLayout {
+        notes: false,
+        terminal: true,
+    };
+    /// Tests only.
+    pub const TESTS_ONLY: PaneLayout = PaneLayout {
+        notes: false,
+        terminal: false,
+    };
+
+    /// The preset after this layout, in the order full, tests+terminal,
+    /// tests only. Custom layouts move on to the full preset.
+    pub fn next_preset(self) -> Self {
+        match self {
+            PaneLayout::FULL => PaneLayout::TESTS_TERMINAL,
+            PaneLayout::TESTS_TERMINAL => PaneLayout::TESTS_ONLY,
+            _ => PaneLayout::FULL,
+        }
+    }
+
+    /// Short description for status messages.
+    pub fn label(self) -> &'static str {
+        match (self.notes, self.terminal) {
+            (true, true) => "tests, notes, and terminal",
+            (true, false) => "tests and notes",
+            (false, true) => "tests and terminal",
+            (false, false) => "tests only",
+        }
+    }
+}
+
+/// Commands run when something happens in a session.
+///
+/// Each is run with `sh -c`; see `actions::hooks` for what it receives.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "Hooks", default)]
+pub struct Hooks {
+    /// Run when a test is marked Passed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_test_passed: Option<String>,
+    /// Run when a test is marked Failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_test_failed: Option<String>,
+    /// Run when the last open test gets a status.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_session_complete: Option<String>,
+}
+
+impl Hooks {
+    pub fn is_empty(&self) -> bool {
+        *self == Hooks::default()
+    }
+}
+
+/// HTTP endpoint notified when a test fails or a session completes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "Webhook")]
+pub struct Webhook {
+    pub url: String,
+    /// JSON body with `{placeholders}` filled in from the event; the event
+    /// itself plus a `text` summary is sent if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+/// Program that signs saved results files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SigningTool {
+    Minisign,
+    Gpg,
+}
+
+/// Detached signature written next to every saved results file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "Signing")]
+pub struct Signing {
+    pub tool: SigningTool,
+    /// minisign secret key file, or the GPG key to sign with; the tool's
+    /// default key if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// minisign public key file that `testlist verify` checks against; GPG
+    /// uses its keyring.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+}
+
+/// Number of results backups kept unless configured otherwise.
+pub const DEFAULT_BACKUPS: usize = 3;
+
+fn default_backups() -> usize {
+    DEFAULT_BACKUPS
+}
+
+/// Contents of the config file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "Config")]
+pub struct Config {
+    #[serde(default)]
+    pub layout: PaneLayout,
+    /// Backups kept of a results file before it is overwritten; 0 disables them.
+    #[serde(default = "default_backups")]
+    pub backups: usize,
+    /// Fail on unknown fields in testlists and results, like `--strict`.
+    #[serde(default)]
+    pub strict: bool,
+    /// Refuse to mark a test Passed until all of its verify items are
+    /// checked, for every testlist.
+    #[serde(default)]
+    pub require_checks: bool,
+    /// Refuse to mark a test Failed or Inconclusive without a note, for
+    /// every testlist.
+    #[serde(default)]
+    pub require_failure_notes: bool,
+    /// Refuse to mark a test Failed without a severity, for every testlist.
+    #[serde(default)]
+    pub require_severity: bool,
+    /// Note templates by name, for every testlist.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub note_templates: BTreeMap<String, String>,
+    /// Tester name used when `--tester` is not given, instead of `$USER`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tester: Option<String>,
+    /// Built-in theme to start with, `"dark"`, `"light"`, or
+    /// `"high-contrast"`; a user theme file takes precedence.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// Start in accessibility mode, like `--accessible`.
+    #[serde(default)]
+    pub accessible: bool,
+    /// File that state changes are announced to as plain text, like `--announce`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub announce: Option<PathBuf>,
+    /// Shell run in the embedded terminal instead of the default shell.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shell: Option<String>,
+    /// Save results every this many seconds while there are unsaved changes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub autosave_secs: Option<u64>,
+    /// Save by appending to the results journal instead of rewriting the
+    /// results file, which is only written in full on exit.
+    #[serde(default)]
+    pub append_only: bool,
+    /// Keys remapped in normal mode: `{'h': 'p'}` makes `h` act as `p`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub keymap: BTreeMap<char, char>,
+    /// Commands run on session events.
+    #[serde(default, skip_serializing_if = "Hooks::is_empty")]
+    pub hooks: Hooks,
+    /// Endpoint POSTed to on failures and session completion.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub webhook: Option<Webhook>,
+    /// Repository (`owner/name`) that `B` files issues in, instead of the
+    /// `origin` remote's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub github_repo: Option<String>,
+    /// Sign saved results with minisign or GPG.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing: Option<Signing>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            layout: PaneLayout::default(),
+            backups: DEFAULT_BACKUPS,
+            strict: false,
+            require_checks: false,
+            require_failure_notes: false,
+            require_severity: false,
+            note_templates: BTreeMap::new(),
+            tester: None,
+            theme: None,
+            accessible: false,
+            announce: None,
+            shell: None,
+            autosave_secs: None,
+            append_only: false,
+            keymap: BTreeMap::new(),
+            hooks: Hooks::default(),
+            webhook: None,
+            github_repo: None,
+            signing: None,
+        }
+    }
+}
+
+impl Config {
+    /// Parse a config file.
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(ron::from_str(content)?)
+    }
+
+    /// Serialize to RON.
+    pub fn to_ron(&self) -> Result<String> {
+        Ok(ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::default().struct_names(true),
+        )?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_cycle() {
+        assert_eq!(PaneLayout::FULL.next_preset(), PaneLayout::TESTS_TERMINAL);
+        assert_eq!(
+            PaneLayout::TESTS_TERMINAL.next_preset(),
+            PaneLayout::TESTS_ONLY
+        );
+        assert_eq!(PaneLayout::TESTS_ONLY.next_preset(), PaneLayout::FULL);
+        let notes_only = PaneLayout {
+            notes: true,
+            terminal: false,
+        };
+        assert_eq!(notes_only.next_preset(), PaneLayout::FULL);
+    }
+
+    #[test]
+    fn test_config_roundtrip_and_defaults() {
+        let config = Config {
+            layout: PaneLayout::TESTS_ONLY,
+            backups: 0,
+            strict: true,
+            require_checks: true,
+            require_failure_notes: true,
+            require_severity: false,
+            note_templates: BTreeMap::from([("Bug".to_string(), "Expected:\nActual:".to_string())]),
+            tester: Some("alice".to_string()),
+            theme: Some("light".to_string()),
+            accessible: true,
+            announce: Some(PathBuf::from("/tmp/testlist.announce")),
+            shell: Some("/bin/zsh".to_string()),
+            autosave_secs: Some(60),
+            append_only: true,
+            keymap: BTreeMap::from([('h', 'p')]),
+            hooks: Hooks {
+                on_test_failed: Some("notify-send failed".to_string()),
+                ..Default::default()
+            },
+            webhook: Some(Webhook {
+                url: "https://hooks.example.com/T1".to_string(),
+                template: None,
+            }),
+            github_repo: Some("acme/app".to_string()),
+            signing: Some(Signing {
+                tool: SigningTool::Minisign,
+                key: Some("~/.minisign/testlist.key".to_string()),
+                public_key: None,
+            }),
+        };
+        let parsed = Config::parse(&config.to_ron().unwrap()).unwrap();
+        assert_eq!(parsed, config);
+
+        let partial = Config::parse("Config(layout: (terminal: false))").unwrap();
+        assert!(partial.layout.notes);
+        assert!(!partial.layout.terminal);
+        assert_eq!(partial.backups, DEFAULT_BACKUPS);
+        assert_eq!(Config::parse("Config()").unwrap(), Config::default());
+    }
+}
@@ -0,0 +1,145 @@
+//! Cursor and selection for the terminal pane's copy mode.
+
+/// A cursor over the visible terminal rows, with an optional selection.
+///
+/// Positions are (row, column) in screen cells. A selection runs from
+/// `anchor` to the cursor, inclusive; linewise selections cover whole rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CopyMode {
+    pub cursor: (u16, u16),
+    pub anchor: Option<(u16, u16)>,
+    pub linewise: bool,
+    size: (u16, u16),
+}
+
+impl CopyMode {
+    /// Start copy mode at `cursor` on a screen of `size` (rows, columns).
+    pub fn new(cursor: (u16, u16), size: (u16, u16)) -> Self {
+        let mut mode = Self {
+            cursor: (0, 0),
+            anchor: None,
+            linewise: false,
+            size,
+        };
+        mode.move_to(cursor.0, cursor.1);
+        mode
+    }
+
+    /// Move the cursor, clamped to the screen.
+    pub fn move_to(&mut self, row: u16, col: u16) {
+        self.cursor = (
+            row.min(self.size.0.saturating_sub(1)),
+            col.min(self.size.1.saturating_sub(1)),
+        );
+    }
+
+    /// Move the cursor by a number of rows and columns.
+    pub fn move_by(&mut self, rows: i32, cols: i32) {
+        let row = (self.cursor.0 as i32 + rows).max(0) as u16;
+        let col = (self.cursor.1 as i32 + cols).max(0) as u16;
+        self.move_to(row, col);
+    }
+
+    /// Start a selection at the cursor, or end it if one of the same kind is active.
+    pub fn toggle_selection(&mut self, linewise: bool) {
+        if self.anchor.is_some() && self.linewise == linewise {
+            self.anchor = None;
+        } else {
+            self.anchor = Some(self.anchor.unwrap_or(self.cursor));
+            self.linewise = linewise;
+        }
+    }
+
+    /// Ordered start and end of the selection, or the cursor row when nothing is selected.
+    fn bounds(&self) -> ((u16, u16), (u16, u16)) {
+        match self.anchor {
+            Some(anchor) if !self.linewise => (anchor.min(self.cursor), anchor.max(self.cursor)),
+            Some(anchor) => (
+                (anchor.0.min(self.cursor.0), 0),
+                (anchor.0.max(self.cursor.0), u16::MAX),
+            ),
+            None => ((self.cursor.0, 0), (self.cursor.0, u16::MAX)),
+        }
+    }
+
+    /// Check whether a cell is inside the selection.
+    pub fn is_selected(&self, row: u16, col: u16) -> bool {
+        self.anchor.is_some() && {
+            let (start, end) = self.bounds();
+            (row, col) >= start && (row, col) <= end
+        }
+    }
+
+    /// The selected text of `rows`, given as one string per screen cell,
+    /// one line per row with trailing spaces trimmed. Without a selection,
+    /// the cursor row is taken.
+    pub fn selected_text(&self, rows: &[Vec<String>]) -> String {
+        let (start, end) = self.bounds();
+        let mut lines = Vec::new();
+        for row in start.0..=end.0 {
+            let Some(text) = rows.get(row as usize) else {
+                break;
+            };
+            let first = if row == start.0 { start.1 as usize } else { 0 };
+            let last = if row == end.0 {
+                end.1 as usize
+            } else {
+                usize::MAX
+            };
+            let line: String = text
+                .iter()
+                .skip(first)
+                .take(last.saturating_sub(first).saturating_add(1))
+                .map(String::as_str)
+                .collect();
+            lines.push(line.trim_end().to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows() -> Vec<Vec<String>> {
+        ["$ make", "error: boom   ", "$"]
+            .iter()
+            .map(|row| row.chars().map(String::from).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_movement_is_clamped() {
+        let mut mode = CopyMode::new((5, 90), (3, 80));
+        assert_eq!(mode.cursor, (2, 79));
+        mode.move_by(-4, 1);
+        assert_eq!(mode.cursor, (0, 79));
+        mode.move_by(1, -100);
+        assert_eq!(mode.cursor, (1, 0));
+    }
+
+    #[test]
+    fn test_charwise_selection() {
+        let mut mode = CopyMode::new((1, 7), (3, 80));
+        mode.toggle_selection(false);
+        mode.move_to(0, 2);
+        assert!(mode.is_selected(0, 5));
+        assert!(!mode.is_selected(0, 1));
+        assert!(!mode.is_selected(1, 8));
+        assert_eq!(mode.selected_text(&rows()), "make\nerror: b");
+    }
+
+    #[test]
+    fn test_linewise_selection_and_cursor_line() {
+        let mut mode = CopyMode::new((1, 3), (3, 80));
+        assert!(!mode.is_selected(1, 3));
+        assert_eq!(mode.selected_text(&rows()), "error: boom");
+        mode.toggle_selection(true);
+        mode.move_by(1, 0);
+        assert!(mode.is_selected(1, 0));
+        assert_eq!(mode.selected_text(&rows()), "error: boom\n$");
+        mode.toggle_selection(true);
+        assert_eq!(mode.anchor, None);
+    }
+}
@@ -1,14 +1,33 @@
 //! Types for testlist definition files (.testlist.ron).
 
 use serde::{Deserialize, Deserializer, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use super::results::Status;
+use super::strict;
+use crate::error::Error;
 
 /// Metadata for a testlist definition.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Meta {
     pub title: String,
     pub description: String,
     pub created: String,
     pub version: String,
+    /// Refuse to mark a test Passed until all of its verify items are checked.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub require_checks: bool,
+    /// Refuse to mark a test Failed or Inconclusive without a note.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub require_failure_notes: bool,
+    /// Refuse to mark a test Failed without a severity.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub require_severity: bool,
+    /// Note templates by name, insertable while writing notes.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub note_templates: BTreeMap<String, String>,
 }
 
 /// A checklist item with an ID and text.
@@ -68,8 +87,50 @@ where
     deserialize_checklist_items(deserializer, "verify")
 }
 
+/// How important a test is, used to order the tests pane.
+///
+/// Variants are declared highest first so the derived `Ord` sorts
+/// critical tests ahead of low-priority ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Priority {
+    Critical,
+    High,
+    Medium,
+    Low,
+}
+
+impl Priority {
+    /// Short lowercase label for display.
+    pub fn label(self) -> &'static str {
+        match self {
+            Priority::Critical => "critical",
+            Priority::High => "high",
+            Priority::Medium => "medium",
+            Priority::Low => "low",
+        }
+    }
+}
+
+/// A status declared by a testlist in addition to the built-in ones, such
+/// as "Passed with deviation". Results refer to it by `name`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusDef {
+    pub name: String,
+    /// A single character shown in the tests pane and reports in place of
+    /// a status icon.
+    pub icon: String,
+    /// Color of the icon, e.g. `"magenta"` or `"#ff8800"`; the theme's
+    /// accent color if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Whether a test with this status is done; otherwise it stays open
+    /// like a pending test.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub counts_as_complete: bool,
+}
+
 /// A single test item to verify.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Test {
     pub id: String,
     pub title: String,
@@ -80,27 +141,424 @@ pub struct Test {
     #[serde(default, deserialize_with = "deserialize_verify")]
     pub verify: Vec<ChecklistItem>,
     pub suggested_command: Option<String>,
+    /// Free-form labels (e.g. "smoke", "regression") used for filtering.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    /// IDs of tests that must pass before this one can be run.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+    /// Title of the section this test belongs to, if any.
+    ///
+    /// Filled in automatically for tests nested in a `Section`, but may also
+    /// be written directly on a flat test.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub section: Option<String>,
+    /// Reference material: file paths (relative to the testlist) or URLs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub references: Vec<String>,
+    /// Time budget for the test, e.g. `"90s"`, `"5m"`, or `"1h30m"`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_duration: Option<String>,
+    /// Variables whose values expand this test into one instance per combination.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub matrix: BTreeMap<String, Vec<String>>,
+    /// Variable values of an expanded matrix instance.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameters: BTreeMap<String, String>,
+    /// Rhai script run after the suggested command finishes, e.g. to check
+    /// verify items whose output it matches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+}
+
+impl Test {
+    /// Expected duration in seconds, if set and parseable.
+    pub fn expected_secs(&self) -> Option<u64> {
+        self.expected_duration.as_deref().and_then(parse_duration)
+    }
+
+    /// Expand a matrix test into concrete instances, one per combination of
+    /// variable values. Tests without a matrix are returned unchanged.
+    ///
+    /// Instance IDs append each value in variable-name order (`login-chrome-linux`)
+    /// and `{name}` placeholders are substituted in the title, description,
+    /// action, suggested command, and checklist text. Variables with no values
+    /// are ignored.
+    pub fn expand_matrix(mut self) -> Vec<Test> {
+        let matrix = std::mem::take(&mut self.matrix);
+        let mut combinations: Vec<BTreeMap<String, String>> = vec![BTreeMap::new()];
+        for (name, values) in matrix.iter().filter(|(_, v)| !v.is_empty()) {
+            combinations = combinations
+                .into_iter()
+                .flat_map(|combo| {
+                    values.iter().map(move |value| {
+                        let mut combo = combo.clone();
+                        combo.insert(name.clone(), value.clone());
+                        combo
+                    })
+                })
+                .collect();
+        }
+        if combinations.len() == 1 && combinations[0].is_empty() {
+            return vec![self];
+        }
+
+        combinations
+            .into_iter()
+            .map(|params| {
+                let subst = |text: &str| {
+                    params.iter().fold(text.to_string(), |acc, (name, value)| {
+                        acc.replace(&format!("{{{}}}", name), value)
+                    })
+                };
+                let subst_items = |items: &[ChecklistItem]| {
+                    items
+                        .iter()
+                        .map(|item| ChecklistItem {
+                            id: item.id.clone(),
+                            text: subst(&item.text),
+                        })
+                        .collect()
+                };
+                let suffix: Vec<&str> = params.values().map(|v| v.as_str()).collect();
+                Test {
+                    id: format!("{}-{}", self.id, suffix.join("-")),
+                    title: subst(&self.title),
+                    description: subst(&self.description),
+                    setup: subst_items(&self.setup),
+                    action: subst(&self.action),
+                    verify: subst_items(&self.verify),
+                    suggested_command: self.suggested_command.as_deref().map(subst),
+                    parameters: params.clone(),
+                    ..self.clone()
+                }
+            })
+            .collect()
+    }
+}
+
+/// Parse a duration like `"90"`, `"90s"`, `"5m"`, `"1h30m"` into seconds.
+pub fn parse_duration(text: &str) -> Option<u64> {
+    let text = text.trim();
+    if let Ok(secs) = text.parse::<u64>() {
+        return Some(secs);
+    }
+    let mut total = 0;
+    let mut number = String::new();
+    for c in text.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'h' | 'm' | 's' => {
+                let value: u64 = number.parse().ok()?;
+                number.clear();
+                total += match c {
+                    'h' => value * 3600,
+                    'm' => value * 60,
+                    _ => value,
+                };
+            }
+            ' ' => {}
+            _ => return None,
+        }
+    }
+    if number.is_empty() && !text.is_empty() {
+        Some(total)
+    } else {
+        None
+    }
+}
+
+/// Format seconds as `m:ss`, or `h:mm:ss` for an hour or more.
+pub fn format_duration(secs: u64) -> String {
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{}:{:02}", m, s)
+    }
+}
+
+/// A named group of tests.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Section {
+    pub title: String,
+    #[serde(default)]
+    pub tests: Vec<Test>,
 }
 
 /// Root type for testlist definition files.
+///
+/// Tests nested in `sections` are flattened into `tests` on load (after any
+/// top-level tests), each tagged with its section title, and matrix tests
+/// are expanded into their instances. Files listed in
+/// `include` are resolved by [`Testlist::load`] and their tests placed first.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "TestlistFile")]
 pub struct Testlist {
     pub meta: Meta,
     pub tests: Vec<Test>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub statuses: Vec<StatusDef>,
+    /// SHA-256 of the text the testlist was read from, in hex; `None` for
+    /// testlists built in memory. See [`Testlist::sha256`].
+    #[serde(skip)]
+    pub source_sha256: Option<String>,
+}
+
+/// On-disk shape of a testlist, before sections are flattened.
+#[derive(Deserialize)]
+#[serde(rename = "Testlist")]
+struct TestlistFile {
+    meta: Meta,
+    #[serde(default)]
+    include: Vec<PathBuf>,
+    #[serde(default)]
+    tests: Vec<Test>,
+    #[serde(default)]
+    sections: Vec<Section>,
+    #[serde(default)]
+    statuses: Vec<StatusDef>,
+}
+
+impl TestlistFile {
+    fn parse(content: &str, format: Format) -> crate::error::Result<Self> {
+        let file = match format {
+            Format::Ron => ron::from_str(content)?,
+            Format::Yaml => serde_yaml::from_str(content)?,
+            Format::Json => serde_json::from_str(content)?,
+        };
+        Ok(file)
+    }
+}
+
+impl From<TestlistFile> for Testlist {
+    fn from(file: TestlistFile) -> Self {
+        let mut tests = file.tests;
+        for section in file.sections {
+            tests.extend(section.tests.into_iter().map(|mut test| {
+                test.section = Some(section.title.clone());
+                test
+            }));
+        }
+        Testlist {
+            meta: file.meta,
+            tests: tests.into_iter().flat_map(Test::expand_matrix).collect(),
+            statuses: file.statuses,
+            source_sha256: None,
+        }
+    }
+}
+
+/// Append `new` tests to `tests`, rejecting IDs that are already present.
+fn append_unique(tests: &mut Vec<Test>, new: Vec<Test>, source: &Path) -> crate::error::Result<()> {
+    for test in new {
+        if tests.iter().any(|t| t.id == test.id) {
+            return Err(Error::DuplicateTestId(test.id, source.to_path_buf()));
+        }
+        tests.push(test);
+    }
+    Ok(())
+}
+
+/// Reject custom statuses without a name or a one-character icon, or whose name is taken by
+/// a built-in status or an earlier custom status.
+fn check_statuses(statuses: &[StatusDef]) -> crate::error::Result<()> {
+    for (i, def) in statuses.iter().enumerate() {
+        let problem = if def.name.trim().is_empty() {
+            "has no name"
+        } else if def.icon.chars().count() != 1 {
+            "needs an icon of one character"
+        } else if def.name.parse::<Status>().is_ok() {
+            "is a built-in status"
+        } else if statuses[..i].iter().any(|s| s.name == def.name) {
+            "is declared twice"
+        } else {
+            continue;
+        };
+        return Err(Error::InvalidStatus(format!("'{}' {}", def.name, problem)));
+    }
+    Ok(())
+}
+
+/// Serialization format of a testlist definition file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Ron,
+    Yaml,
+    Json,
+}
+
+impl Format {
+    /// Detect the format from a file extension, defaulting to RON.
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("yaml") | Some("yml") => Format::Yaml,
+            Some("json") => Format::Json,
+            _ => Format::Ron,
+        }
+    }
+}
+
+impl std::str::FromStr for Format {
+    type Err = Error;
+
+    fn from_str(s: &str) -> crate::error::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "ron" => Ok(Format::Ron),
+            "yaml" | "yml" => Ok(Format::Yaml),
+            "json" => Ok(Format::Json),
+            _ => Err(Error::UnknownFormat(s.to_string())),
+        }
+    }
 }
 
 impl Testlist {
-    /// Load a testlist from a RON file.
-    pub fn load(path: &std::path::Path) -> crate::error::Result<Self> {
+    /// Load a testlist, detecting the format from the file extension.
+    pub fn load(path: &Path) -> crate::error::Result<Self> {
+        Self::load_as(path, Format::from_path(path))
+    }
+
+    /// Load a testlist in an explicit format, resolving `include` directives.
+    ///
+    /// Included paths are relative to the including file and use the format
+    /// implied by their own extension. A file reached twice (e.g. a shared
+    /// setup file included from two places) contributes its tests once.
+    pub fn load_as(path: &Path, format: Format) -> crate::error::Result<Self> {
+        Self::load_hashed(path, format, false)
+    }
+
+    /// Load a testlist like [`Testlist::load_as`], failing on fields the
+    /// definition format doesn't have in it or any included file.
+    pub fn load_strict(path: &Path, format: Format) -> crate::error::Result<Self> {
+        Self::load_hashed(path, format, true)
+    }
+
+    fn load_hashed(path: &Path, format: Format, strict: bool) -> crate::error::Result<Self> {
+        let mut stack = Vec::new();
+        let mut loaded = HashSet::new();
+        let mut hasher = Sha256::new();
+        let mut testlist =
+            Self::load_with_includes(path, format, strict, &mut stack, &mut loaded, &mut hasher)?;
+        testlist.source_sha256 = Some(format!("{:x}", hasher.finalize()));
+        Ok(testlist)
+    }
+
+    fn load_with_includes(
+        path: &Path,
+        format: Format,
+        strict: bool,
+        stack: &mut Vec<PathBuf>,
+        loaded: &mut HashSet<PathBuf>,
+        hasher: &mut Sha256,
+    ) -> crate::error::Result<Self> {
+        let canonical = path
+            .canonicalize()
+            .map_err(|_| Error::TestlistNotFound(path.to_path_buf()))?;
+        if stack.contains(&canonical) {
+            return Err(Error::IncludeCycle(path.to_path_buf()));
+        }
+        loaded.insert(canonical.clone());
+        stack.push(canonical);
+
         let content = std::fs::read_to_string(path)?;
-        let testlist: Testlist = ron::from_str(&content)?;
+        hasher.update(content.as_bytes());
+        if strict {
+            strict::reject_unknown(path, strict::unknown_testlist_fields(&content, format)?)?;
+        }
+        let mut file = TestlistFile::parse(&content, format)?;
+        let base = path.parent().unwrap_or(Path::new(""));
+
+        let mut tests = Vec::new();
+        let mut statuses = Vec::new();
+        for include in std::mem::take(&mut file.include) {
+            let include_path = base.join(&include);
+            // Skip files already merged elsewhere; cycles are caught by the stack check
+            if let Ok(canonical) = include_path.canonicalize() {
+                if loaded.contains(&canonical) && !stack.contains(&canonical) {
+                    continue;
+                }
+            }
+            let included = Self::load_with_includes(
+                &include_path,
+                Format::from_path(&include_path),
+                strict,
+                stack,
+                loaded,
+                hasher,
+            )?;
+            append_unique(&mut tests, included.tests, &include_path)?;
+            statuses.extend(included.statuses);
+        }
+        stack.pop();
+
+        let own = Testlist::from(file);
+        append_unique(&mut tests, own.tests, path)?;
+        statuses.extend(own.statuses);
+        check_statuses(&statuses)?;
+        Ok(Testlist {
+            meta: own.meta,
+            tests,
+            statuses,
+            source_sha256: None,
+        })
+    }
+
+    /// Parse a testlist from a string.
+    ///
+    /// `include` directives are ignored since there is no file to resolve
+    /// them against; use [`Testlist::load`] for files that include others.
+    pub fn parse(content: &str, format: Format) -> crate::error::Result<Self> {
+        let mut testlist: Testlist = TestlistFile::parse(content, format)?.into();
+        check_statuses(&testlist.statuses)?;
+        testlist.source_sha256 = Some(format!("{:x}", Sha256::digest(content)));
         Ok(testlist)
     }
+
+    /// The custom status declared with `name`, if any.
+    pub fn custom_status(&self, name: &str) -> Option<&StatusDef> {
+        self.statuses.iter().find(|s| s.name == name)
+    }
+
+    /// Serialize the testlist to a string.
+    pub fn to_string_as(&self, format: Format) -> crate::error::Result<String> {
+        let content = match format {
+            Format::Ron => ron::ser::to_string_pretty(
+                self,
+                ron::ser::PrettyConfig::default().struct_names(true),
+            )?,
+            Format::Yaml => serde_yaml::to_string(self)?,
+            Format::Json => serde_json::to_string_pretty(self)?,
+        };
+        Ok(content)
+    }
+
+    /// Save the testlist in the given format.
+    pub fn save(&self, path: &Path, format: Format) -> crate::error::Result<()> {
+        std::fs::write(path, self.to_string_as(format)?)?;
+        Ok(())
+    }
+
+    /// SHA-256 of the testlist file as read, in hex, or `None` if the
+    /// testlist wasn't read from one. The bytes of each included file are
+    /// hashed after those of the file including it, so any edit to any of
+    /// them, comments and formatting included, changes the hash.
+    pub fn sha256(&self) -> Option<&str> {
+        self.source_sha256.as_deref()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures;
 
     #[test]
     fn test_parse_testlist_old_format() {
@@ -217,4 +675,408 @@ Testlist(
         assert_eq!(testlist.tests[0].verify[0].id, "verify-0");
         assert_eq!(testlist.tests[0].verify[2].id, "verify-2");
     }
+
+    #[test]
+    fn test_parse_priority_and_tags() {
+        let ron_str = r#"
+Testlist(
+    meta: Meta(title: "T", description: "", created: "", version: "1"),
+    tests: [
+        Test(
+            id: "t1",
+            title: "Test 1",
+            description: "",
+            action: "Do it",
+            suggested_command: None,
+            tags: ["smoke"],
+            priority: Some(Critical),
+        ),
+        Test(
+            id: "t2",
+            title: "Test 2",
+            description: "",
+            action: "Do it",
+            suggested_command: None,
+        ),
+    ],
+)
+"#;
+        let testlist: Testlist = ron::from_str(ron_str).unwrap();
+        assert_eq!(testlist.tests[0].tags, vec!["smoke"]);
+        assert_eq!(testlist.tests[0].priority, Some(Priority::Critical));
+        assert!(testlist.tests[1].tags.is_empty());
+        assert_eq!(testlist.tests[1].priority, None);
+    }
+
+    #[test]
+    fn test_parse_sections_flattened() {
+        let ron_str = r#"
+Testlist(
+    meta: Meta(title: "T", description: "", created: "", version: "1"),
+    tests: [
+        Test(id: "top", title: "Top", description: "", action: "Do it", suggested_command: None),
+    ],
+    sections: [
+        Section(
+            title: "Auth",
+            tests: [
+                Test(id: "login", title: "Login", description: "", action: "Log in", suggested_command: None),
+                Test(id: "logout", title: "Logout", description: "", action: "Log out", suggested_command: None),
+            ],
+        ),
+    ],
+)
+"#;
+        let testlist: Testlist = ron::from_str(ron_str).unwrap();
+        let ids: Vec<&str> = testlist.tests.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["top", "login", "logout"]);
+        assert_eq!(testlist.tests[0].section, None);
+        assert_eq!(testlist.tests[1].section, Some("Auth".to_string()));
+        assert_eq!(testlist.tests[2].section, Some("Auth".to_string()));
+    }
+
+    #[test]
+    fn test_flattened_testlist_roundtrips() {
+        let testlist = fixtures::testlist(vec![Test {
+            id: "login".to_string(),
+            section: Some("Auth".to_string()),
+            ..Default::default()
+        }]);
+        let ron_str = ron::to_string(&testlist).unwrap();
+        let parsed: Testlist = ron::from_str(&ron_str).unwrap();
+        assert_eq!(parsed.tests[0].section, Some("Auth".to_string()));
+    }
+
+    #[test]
+    fn test_parse_yaml() {
+        let yaml = r#"
+meta:
+  title: YAML
+  description: ""
+  created: ""
+  version: "1"
+tests:
+  - id: t1
+    title: Test 1
+    description: ""
+    action: Do it
+    verify:
+      - Check it
+      - id: v1
+        text: Check again
+    suggested_command: cargo test
+    priority: High
+sections:
+  - title: Auth
+    tests:
+      - id: login
+        title: Login
+        description: ""
+        action: Log in
+        suggested_command: null
+"#;
+        let testlist = Testlist::parse(yaml, Format::Yaml).unwrap();
+        assert_eq!(testlist.meta.title, "YAML");
+        assert_eq!(testlist.tests[0].verify[0].id, "verify-0");
+        assert_eq!(testlist.tests[0].verify[1].id, "v1");
+        assert_eq!(testlist.tests[0].priority, Some(Priority::High));
+        assert_eq!(testlist.tests[1].section, Some("Auth".to_string()));
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let json = r#"{
+  "meta": {"title": "JSON", "description": "", "created": "", "version": "1"},
+  "tests": [
+    {"id": "t1", "title": "Test 1", "description": "", "action": "Do it",
+     "setup": ["Step"], "suggested_command": null, "tags": ["smoke"]}
+  ]
+}"#;
+        let testlist = Testlist::parse(json, Format::Json).unwrap();
+        assert_eq!(testlist.meta.title, "JSON");
+        assert_eq!(testlist.tests[0].setup[0].id, "setup-0");
+        assert_eq!(testlist.tests[0].tags, vec!["smoke"]);
+    }
+
+    #[test]
+    fn test_format_from_path() {
+        use std::path::Path;
+        assert_eq!(
+            Format::from_path(Path::new("a.testlist.yaml")),
+            Format::Yaml
+        );
+        assert_eq!(Format::from_path(Path::new("a.YML")), Format::Yaml);
+        assert_eq!(Format::from_path(Path::new("a.json")), Format::Json);
+        assert_eq!(Format::from_path(Path::new("a.testlist.ron")), Format::Ron);
+        assert_eq!(Format::from_path(Path::new("noext")), Format::Ron);
+        assert_eq!("JSON".parse::<Format>().unwrap(), Format::Json);
+        assert!("toml".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn test_roundtrip_all_formats() {
+        let testlist = fixtures::testlist(vec![Test {
+            id: "t1".to_string(),
+            verify: vec![ChecklistItem {
+                id: "v0".to_string(),
+                text: "Check".to_string(),
+            }],
+            priority: Some(Priority::Low),
+            ..Default::default()
+        }]);
+        for format in [Format::Ron, Format::Yaml, Format::Json] {
+            let content = testlist.to_string_as(format).unwrap();
+            let parsed = Testlist::parse(&content, format).unwrap();
+            assert_eq!(parsed.tests[0].verify, testlist.tests[0].verify);
+            assert_eq!(parsed.tests[0].priority, Some(Priority::Low));
+        }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90"), Some(90));
+        assert_eq!(parse_duration("90s"), Some(90));
+        assert_eq!(parse_duration("5m"), Some(300));
+        assert_eq!(parse_duration("1h30m"), Some(5400));
+        assert_eq!(parse_duration("1h 5s"), Some(3605));
+        assert_eq!(parse_duration("5 minutes"), None);
+        assert_eq!(parse_duration("5x"), None);
+        assert_eq!(parse_duration("m"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0), "0:00");
+        assert_eq!(format_duration(75), "1:15");
+        assert_eq!(format_duration(3725), "1:02:05");
+    }
+
+    #[test]
+    fn test_matrix_expansion() {
+        let ron_str = r#"
+Testlist(
+    meta: Meta(title: "T", description: "", created: "", version: "1"),
+    tests: [
+        Test(
+            id: "login",
+            title: "Login on {browser}/{os}",
+            description: "",
+            setup: ["Open {browser}"],
+            action: "Log in",
+            suggested_command: Some("open -a {browser}"),
+            matrix: {
+                "browser": ["firefox", "chrome"],
+                "os": ["linux"],
+                "unused": [],
+            },
+        ),
+        Test(id: "plain", title: "Plain", description: "", action: "Do it", suggested_command: None),
+    ],
+)
+"#;
+        let testlist: Testlist = ron::from_str(ron_str).unwrap();
+        let ids: Vec<&str> = testlist.tests.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec!["login-firefox-linux", "login-chrome-linux", "plain"]
+        );
+        let chrome = &testlist.tests[1];
+        assert_eq!(chrome.title, "Login on chrome/linux");
+        assert_eq!(chrome.setup[0].text, "Open chrome");
+        assert_eq!(chrome.setup[0].id, "setup-0");
+        assert_eq!(chrome.suggested_command.as_deref(), Some("open -a chrome"));
+        assert_eq!(
+            chrome.parameters.get("browser").map(String::as_str),
+            Some("chrome")
+        );
+        assert!(chrome.matrix.is_empty());
+
+        // Expanded instances serialize without the matrix and reload unchanged
+        let reparsed =
+            Testlist::parse(&testlist.to_string_as(Format::Ron).unwrap(), Format::Ron).unwrap();
+        assert_eq!(reparsed.tests.len(), 3);
+    }
+
+    #[test]
+    fn test_matrix_instances_get_results() {
+        use crate::data::results::TestlistResults;
+
+        let test = Test {
+            id: "t".to_string(),
+            matrix: BTreeMap::from([("n".to_string(), vec!["1".to_string(), "2".to_string()])]),
+            ..Default::default()
+        };
+        let testlist = fixtures::testlist(test.expand_matrix());
+        let results = TestlistResults::new_for_testlist(&testlist, "t.ron", "me");
+        let ids: Vec<&str> = results.results.iter().map(|r| r.test_id.as_str()).collect();
+        assert_eq!(ids, vec!["t-1", "t-2"]);
+    }
+
+    fn write(dir: &Path, name: &str, content: &str) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn testlist_ron(include: &[&str], ids: &[&str]) -> String {
+        let include: Vec<String> = include.iter().map(|p| format!("{:?}", p)).collect();
+        let tests: Vec<String> = ids
+            .iter()
+            .map(|id| {
+                format!(
+                    r#"Test(id: "{}", title: "{}", description: "", action: "Do it", suggested_command: None)"#,
+                    id, id
+                )
+            })
+            .collect();
+        format!(
+            r#"Testlist(meta: Meta(title: "T", description: "", created: "", version: "1"), include: [{}], tests: [{}])"#,
+            include.join(", "),
+            tests.join(", ")
+        )
+    }
+
+    #[test]
+    fn test_load_resolves_includes_relative_to_parent() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("common")).unwrap();
+        write(
+            &dir.path().join("common"),
+            "setup.testlist.ron",
+            &testlist_ron(&["env.testlist.yaml"], &["setup"]),
+        );
+        write(
+            &dir.path().join("common"),
+            "env.testlist.yaml",
+            "meta: {title: E, description: '', created: '', version: '1'}\ntests:\n  - {id: env, title: Env, description: '', action: Check, suggested_command: null}\n",
+        );
+        let main = write(
+            dir.path(),
+            "main.testlist.ron",
+            &testlist_ron(&["common/setup.testlist.ron"], &["main"]),
+        );
+
+        let testlist = Testlist::load(&main).unwrap();
+        let ids: Vec<&str> = testlist.tests.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["env", "setup", "main"]);
+        assert_eq!(testlist.meta.title, "T");
+
+        // Editing an included file changes the hash of the testlist
+        write(
+            &dir.path().join("common"),
+            "env.testlist.yaml",
+            "meta: {title: E, description: '', created: '', version: '2'}\ntests:\n  - {id: env, title: Env, description: '', action: Check, suggested_command: null}\n",
+        );
+        assert_ne!(Testlist::load(&main).unwrap().sha256(), testlist.sha256());
+    }
+
+    #[test]
+    fn test_load_shared_include_counted_once() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "shared.ron", &testlist_ron(&[], &["shared"]));
+        write(dir.path(), "a.ron", &testlist_ron(&["shared.ron"], &["a"]));
+        write(dir.path(), "b.ron", &testlist_ron(&["shared.ron"], &["b"]));
+        let main = write(
+            dir.path(),
+            "main.ron",
+            &testlist_ron(&["a.ron", "b.ron"], &[]),
+        );
+
+        let testlist = Testlist::load(&main).unwrap();
+        let ids: Vec<&str> = testlist.tests.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["shared", "a", "b"]);
+    }
+
+    #[test]
+    fn test_load_detects_include_cycle() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "a.ron", &testlist_ron(&["b.ron"], &["a"]));
+        let b = write(dir.path(), "b.ron", &testlist_ron(&["a.ron"], &["b"]));
+        let err = Testlist::load(&b).unwrap_err();
+        assert!(matches!(err, Error::IncludeCycle(_)), "got {}", err);
+    }
+
+    #[test]
+    fn test_load_rejects_conflicting_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "common.ron", &testlist_ron(&[], &["build"]));
+        let main = write(
+            dir.path(),
+            "main.ron",
+            &testlist_ron(&["common.ron"], &["build"]),
+        );
+        let err = Testlist::load(&main).unwrap_err();
+        assert!(matches!(err, Error::DuplicateTestId(ref id, _) if id == "build"));
+    }
+
+    #[test]
+    fn test_load_missing_include() {
+        let dir = tempfile::tempdir().unwrap();
+        let main = write(dir.path(), "main.ron", &testlist_ron(&["nope.ron"], &["a"]));
+        let err = Testlist::load(&main).unwrap_err();
+        assert!(matches!(err, Error::TestlistNotFound(_)));
+    }
+
+    #[test]
+    fn test_parse_custom_statuses() {
+        let ron_str = r#"
+Testlist(
+    meta: Meta(title: "T", description: "", created: "", version: "1"),
+    tests: [],
+    statuses: [
+        StatusDef(name: "Passed with deviation", icon: "≈", color: Some("magenta"), counts_as_complete: true),
+        StatusDef(name: "Needs info", icon: "…"),
+    ],
+)
+"#;
+        let testlist = Testlist::parse(ron_str, Format::Ron).unwrap();
+        let def = testlist.custom_status("Passed with deviation").unwrap();
+        assert!(def.counts_as_complete);
+        assert!(
+            !testlist
+                .custom_status("Needs info")
+                .unwrap()
+                .counts_as_complete
+        );
+
+        let clash = ron_str.replace("Needs info", "skip");
+        let err = Testlist::parse(&clash, Format::Ron).unwrap_err();
+        assert!(matches!(err, Error::InvalidStatus(ref m) if m.contains("built-in")));
+    }
+
+    #[test]
+    fn test_sha256_hashes_the_file_as_written() {
+        let ron_str = r#"
+Testlist(
+    meta: Meta(title: "T", description: "", created: "", version: "1"),
+    tests: [
+        Test(id: "build", title: "Build", description: "", setup: [], action: "make",
+             verify: ["No warnings"], suggested_command: None),
+    ],
+)
+"#;
+        let testlist = Testlist::parse(ron_str, Format::Ron).unwrap();
+        assert_eq!(
+            testlist.sha256(),
+            Some(format!("{:x}", Sha256::digest(ron_str)).as_str())
+        );
+
+        let commented = Testlist::parse(&format!("// Build checks\n{}", ron_str), Format::Ron);
+        assert_ne!(commented.unwrap().sha256(), testlist.sha256());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("build.testlist.ron");
+        std::fs::write(&path, ron_str).unwrap();
+        assert_eq!(Testlist::load(&path).unwrap().sha256(), testlist.sha256());
+
+        let reparsed = Testlist::parse(&testlist.to_string_as(Format::Yaml).unwrap(), Format::Yaml);
+        assert_ne!(reparsed.unwrap().sha256(), testlist.sha256());
+    }
+
+    #[test]
+    fn test_priority_ordering() {
+        assert!(Priority::Critical < Priority::High);
+        assert!(Priority::Medium < Priority::Low);
+    }
 }
@@ -0,0 +1,342 @@
+//! Multi-line text buffer with a cursor, used by the notes editor.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Editable text as lines plus a cursor position.
+///
+/// `col` is a byte offset into the current line, always on a grapheme
+/// boundary, so the cursor steps over accented letters, emoji, and other
+/// multi-character clusters as a whole.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEditor {
+    lines: Vec<String>,
+    row: usize,
+    col: usize,
+}
+
+impl Default for TextEditor {
+    fn default() -> Self {
+        Self {
+            lines: vec![String::new()],
+            row: 0,
+            col: 0,
+        }
+    }
+}
+
+impl TextEditor {
+    /// Create an editor holding `text`, with the cursor at the end.
+    pub fn from_text(text: &str) -> Self {
+        let mut editor = Self {
+            lines: text.split('\n').map(str::to_string).collect(),
+            row: 0,
+            col: 0,
+        };
+        editor.move_to_end();
+        editor
+    }
+
+    /// The full text, lines joined with `\n`.
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// Check whether the buffer is empty.
+    pub fn is_empty(&self) -> bool {
+        self.lines.len() == 1 && self.lines[0].is_empty()
+    }
+
+    /// Cursor position as (row, display column); wide characters such as
+    /// CJK take two columns.
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.row, self.line()[..self.col].width())
+    }
+
+    /// Remove all text.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Insert a character at the cursor.
+    pub fn insert_char(&mut self, c: char) {
+        if c == '\n' {
+            self.newline();
+            return;
+        }
+        self.lines[self.row].insert(self.col, c);
+        self.col += c.len_utf8();
+    }
+
+    /// Insert text at the cursor, such as a paste. Tabs become four spaces
+    /// and other control characters are dropped.
+    pub fn insert_str(&mut self, text: &str) {
+        for c in text.chars() {
+            match c {
+                '\t' => (0..4).for_each(|_| self.insert_char(' ')),
+                '\n' => self.newline(),
+                c if c.is_control() => {}
+                c => self.insert_char(c),
+            }
+        }
+    }
+
+    /// Split the current line at the cursor.
+    pub fn newline(&mut self) {
+        let rest = self.lines[self.row].split_off(self.col);
+        self.row += 1;
+        self.col = 0;
+        self.lines.insert(self.row, rest);
+    }
+
+    /// Delete the grapheme before the cursor, joining lines at column 0.
+    pub fn backspace(&mut self) {
+        if self.col > 0 {
+            let start = self.previous_boundary();
+            self.lines[self.row].replace_range(start..self.col, "");
+            self.col = start;
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.line().len();
+            self.lines[self.row].push_str(&line);
+        }
+    }
+
+    /// Delete the grapheme under the cursor, joining the next line at the end.
+    pub fn delete(&mut self) {
+        if self.col < self.line().len() {
+            let end = self.next_boundary();
+            self.lines[self.row].replace_range(self.col..end, "");
+        } else if self.row + 1 < self.lines.len() {
+            let next = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&next);
+        }
+    }
+
+    pub fn move_left(&mut self) {
+        if self.col > 0 {
+            self.col = self.previous_boundary();
+        } else if self.row > 0 {
+            self.row -= 1;
+            self.col = self.line().len();
+        }
+    }
+
+    pub fn move_right(&mut self) {
+        if self.col < self.line().len() {
+            self.col = self.next_boundary();
+        } else if self.row + 1 < self.lines.len() {
+            self.row += 1;
+            self.col = 0;
+        }
+    }
+
+    pub fn move_up(&mut self) {
+        if self.row > 0 {
+            let column = self.cursor().1;
+            self.row -= 1;
+            self.col = self.offset_at_column(column);
+        }
+    }
+
+    pub fn move_down(&mut self) {
+        if self.row + 1 < self.lines.len() {
+            let column = self.cursor().1;
+            self.row += 1;
+            self.col = self.offset_at_column(column);
+        }
+    }
+
+    pub fn move_home(&mut self) {
+        self.col = 0;
+    }
+
+    pub fn move_end(&mut self) {
+        self.col = self.line().len();
+    }
+
+    fn move_to_end(&mut self) {
+        self.row = self.lines.len() - 1;
+        self.col = self.line().len();
+    }
+
+    /// Lines soft-wrapped to `width` display columns, with the cursor's
+    /// position in the wrapped output as (row, display column). Graphemes
+    /// are never split, and one wider than `width` gets a row of its own.
+    pub fn wrapped(&self, width: usize) -> (Vec<String>, (usize, usize)) {
+        let width = width.max(1);
+        let mut out = Vec::new();
+        let mut cursor = (0, 0);
+        for (row, line) in self.lines.iter().enumerate() {
+            let mut chunk = String::new();
+            let mut used = 0;
+            for (offset, grapheme) in line.grapheme_indices(true) {
+                let grapheme_width = grapheme.width();
+                if used + grapheme_width > width && !chunk.is_empty() {
+                    out.push(std::mem::take(&mut chunk));
+                    used = 0;
+                }
+                if row == self.row && offset == self.col {
+                    cursor = (out.len(), used);
+                }
+                chunk.push_str(grapheme);
+                used += grapheme_width;
+            }
+            if row == self.row && self.col == line.len() {
+                // A cursor at the very end of a full row sits on the next row
+                if used >= width {
+                    out.push(std::mem::take(&mut chunk));
+                    used = 0;
+                }
+                cursor = (out.len(), used);
+            }
+            out.push(chunk);
+        }
+        (out, cursor)
+    }
+
+    fn line(&self) -> &str {
+        &self.lines[self.row]
+    }
+
+    fn previous_boundary(&self) -> usize {
+        self.line()[..self.col]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    fn next_boundary(&self) -> usize {
+        self.line()[self.col..]
+            .graphemes(true)
+            .next()
+            .map_or(self.col, |g| self.col + g.len())
+    }
+
+    /// Byte offset of the last grapheme boundary in the current line that
+    /// is at most `column` display columns in.
+    fn offset_at_column(&self, column: usize) -> usize {
+        let mut used = 0;
+        for (offset, grapheme) in self.line().grapheme_indices(true) {
+            used += grapheme.width();
+            if used > column {
+                return offset;
+            }
+        }
+        self.line().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_text_places_cursor_at_end() {
+        let editor = TextEditor::from_text("one\ntwo");
+        assert_eq!(editor.cursor(), (1, 3));
+        assert_eq!(editor.text(), "one\ntwo");
+    }
+
+    #[test]
+    fn test_insert_and_delete_mid_text() {
+        let mut editor = TextEditor::from_text("helo");
+        editor.move_left();
+        editor.insert_char('l');
+        assert_eq!(editor.text(), "hello");
+        editor.move_home();
+        editor.delete();
+        assert_eq!(editor.text(), "ello");
+        editor.move_end();
+        editor.backspace();
+        assert_eq!(editor.text(), "ell");
+    }
+
+    #[test]
+    fn test_insert_str_multiline_paste() {
+        let mut editor = TextEditor::from_text("ab");
+        editor.move_left();
+        editor.insert_str("1\n\t2\x1b\n3");
+        assert_eq!(editor.text(), "a1\n    2\n3b");
+        assert_eq!(editor.cursor(), (2, 1));
+    }
+
+    #[test]
+    fn test_newline_splits_and_backspace_joins() {
+        let mut editor = TextEditor::from_text("ab");
+        editor.move_left();
+        editor.newline();
+        assert_eq!(editor.text(), "a\nb");
+        assert_eq!(editor.cursor(), (1, 0));
+        editor.backspace();
+        assert_eq!(editor.text(), "ab");
+        assert_eq!(editor.cursor(), (0, 1));
+    }
+
+    #[test]
+    fn test_vertical_movement_clamps_column() {
+        let mut editor = TextEditor::from_text("long line\nab");
+        editor.move_up();
+        assert_eq!(editor.cursor(), (0, 2));
+        editor.move_end();
+        editor.move_down();
+        assert_eq!(editor.cursor(), (1, 2));
+    }
+
+    #[test]
+    fn test_multibyte_characters() {
+        let mut editor = TextEditor::from_text("héllo");
+        editor.move_home();
+        editor.move_right();
+        editor.delete();
+        assert_eq!(editor.text(), "hllo");
+        editor.insert_char('ü');
+        assert_eq!(editor.text(), "hüllo");
+    }
+
+    #[test]
+    fn test_wide_and_combining_characters() {
+        // "e" plus a combining acute accent is one grapheme
+        let mut editor = TextEditor::from_text("日本e\u{301}x");
+        assert_eq!(editor.cursor(), (0, 6));
+        editor.move_left();
+        editor.move_left();
+        assert_eq!(editor.cursor(), (0, 4));
+        editor.backspace();
+        assert_eq!(editor.text(), "日e\u{301}x");
+        assert_eq!(editor.cursor(), (0, 2));
+
+        let (lines, cursor) = TextEditor::from_text("日本語x").wrapped(5);
+        assert_eq!(lines, vec!["日本", "語x"]);
+        assert_eq!(cursor, (1, 3));
+
+        editor.newline();
+        editor.insert_str("abc");
+        editor.move_up();
+        assert_eq!(editor.cursor(), (0, 2));
+    }
+
+    #[test]
+    fn test_wrapped_lines_and_cursor() {
+        let mut editor = TextEditor::from_text("abcdefg\nxy");
+        let (lines, cursor) = editor.wrapped(3);
+        assert_eq!(lines, vec!["abc", "def", "g", "xy"]);
+        assert_eq!(cursor, (3, 2));
+        editor.move_up();
+        editor.move_home();
+        editor.insert_str("123456");
+        let (lines, cursor) = editor.wrapped(3);
+        assert_eq!(lines[..4], ["123", "456", "abc", "def"]);
+        assert_eq!(cursor, (2, 0));
+    }
+
+    #[test]
+    fn test_empty_editor() {
+        let mut editor = TextEditor::default();
+        assert!(editor.is_empty());
+        editor.backspace();
+        editor.delete();
+        assert_eq!(editor.wrapped(10), (vec![String::new()], (0, 0)));
+    }
+}
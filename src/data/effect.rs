@@ -1,13 +1,37 @@
 //! Side-effect descriptions returned by transforms.
 
+use super::config::PaneLayout;
+
 /// Effects that the UI layer should execute.
 /// Transforms return these instead of performing side effects directly.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Effect {
     /// Save the current results to disk.
     SaveResults,
+    /// Save the current results once the autosave interval has passed,
+    /// backing up the previous file only if no save this session has.
+    Autosave,
     /// Quit the application.
     Quit,
     /// Insert a command string into the embedded terminal.
     InsertTerminalCommand(String),
+    /// Run a command in the embedded terminal and capture its exit code.
+    RunTerminalCommand(String),
+    /// Load the testlist file again after it changed on disk.
+    ReloadTestlist,
+    /// Persist the pane layout in the config file.
+    SaveLayout(PaneLayout),
+    /// Put text on the system clipboard.
+    CopyToClipboard(String),
+    /// Open a file or URL in the system's default viewer.
+    OpenExternal(String),
+    /// Suspend the TUI and edit a test's notes in the user's `$EDITOR`.
+    EditNotesExternally { test_id: String, notes: String },
+    /// Capture a screenshot to `path` and attach it to a test's result.
+    CaptureScreenshot {
+        test_id: String,
+        path: std::path::PathBuf,
+    },
+    /// File a GitHub issue for a failed test and link it as a defect.
+    CreateIssue { test_id: String },
 }
@@ -0,0 +1,398 @@
+//! Journal of result changes made since the last save, for crash recovery.
+//!
+//! Entries record the new value of a field rather than the action that
+//! produced it, so replaying a journal twice gives the same results.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::results::{
+    Attempt, CommandRun, ResultsMeta, Review, Severity, Status, TestResult, TestlistResults,
+};
+
+/// One change to the results.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JournalEntry {
+    Status {
+        test_id: String,
+        status: Status,
+        completed_at: Option<String>,
+        #[serde(default)]
+        severity: Option<Severity>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        blocked_reason: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        custom_status: Option<String>,
+    },
+    Notes {
+        test_id: String,
+        notes: Option<String>,
+    },
+    Checklist {
+        key: String,
+        checked: bool,
+    },
+    Defects {
+        test_id: String,
+        defects: Vec<String>,
+    },
+    Screenshots {
+        test_id: String,
+        screenshots: Vec<PathBuf>,
+    },
+    TimeSpent {
+        test_id: String,
+        time_spent_secs: u64,
+    },
+    CommandRuns {
+        test_id: String,
+        command_runs: Vec<CommandRun>,
+    },
+    CommandHistory {
+        test_id: String,
+        command_history: Vec<String>,
+    },
+    Attempts {
+        test_id: String,
+        attempts: Vec<Attempt>,
+    },
+    Review {
+        test_id: String,
+        review: Option<Review>,
+    },
+    Order {
+        test_order: Vec<String>,
+    },
+    /// The whole results metadata: sessions, environment, and git checkout.
+    Meta {
+        meta: ResultsMeta,
+    },
+    /// The entries before this one were saved with append-only saving, so
+    /// they are part of the results rather than unsaved changes.
+    Saved,
+}
+
+/// Entries that turn `before` into `after` for statuses, notes, defects,
+/// screenshots, time spent, commands run, earlier attempts, reviews,
+/// checklists, the test order, and the metadata.
+pub fn diff(before: &TestlistResults, after: &TestlistResults) -> Vec<JournalEntry> {
+    let mut entries = Vec::new();
+    for result in &after.results {
+        let old = before.results.iter().find(|r| r.test_id == result.test_id);
+        let status = |r: &TestResult| {
+            (
+                r.status,
+                r.completed_at.clone(),
+                r.severity,
+                r.blocked_reason.clone(),
+                r.custom_status.clone(),
+            )
+        };
+        if old.map(status) != Some(status(result)) {
+            entries.push(JournalEntry::Status {
+                test_id: result.test_id.clone(),
+                status: result.status,
+                completed_at: result.completed_at.clone(),
+                severity: result.severity,
+                blocked_reason: result.blocked_reason.clone(),
+                custom_status: result.custom_status.clone(),
+            });
+        }
+        if old.map(|r| &r.notes) != Some(&result.notes) {
+            entries.push(JournalEntry::Notes {
+                test_id: result.test_id.clone(),
+                notes: result.notes.clone(),
+            });
+        }
+        if old.map(|r| &r.defects) != Some(&result.defects) {
+            entries.push(JournalEntry::Defects {
+                test_id: result.test_id.clone(),
+                defects: result.defects.clone(),
+            });
+        }
+        if old.map(|r| &r.screenshots) != Some(&result.screenshots) {
+            entries.push(JournalEntry::Screenshots {
+                test_id: result.test_id.clone(),
+                screenshots: result.screenshots.clone(),
+            });
+        }
+        if old.map(|r| r.time_spent_secs) != Some(result.time_spent_secs) {
+            entries.push(JournalEntry::TimeSpent {
+                test_id: result.test_id.clone(),
+                time_spent_secs: result.time_spent_secs,
+            });
+        }
+        if old.map(|r| &r.command_runs) != Some(&result.command_runs) {
+            entries.push(JournalEntry::CommandRuns {
+                test_id: result.test_id.clone(),
+                command_runs: result.command_runs.clone(),
+            });
+        }
+        if old.map(|r| &r.command_history) != Some(&result.command_history) {
+            entries.push(JournalEntry::CommandHistory {
+                test_id: result.test_id.clone(),
+                command_history: result.command_history.clone(),
+            });
+        }
+        if old.map(|r| &r.attempts) != Some(&result.attempts) {
+            entries.push(JournalEntry::Attempts {
+                test_id: result.test_id.clone(),
+                attempts: result.attempts.clone(),
+            });
+        }
+        if old.map(|r| &r.review) != Some(&result.review) {
+            entries.push(JournalEntry::Review {
+                test_id: result.test_id.clone(),
+                review: result.review.clone(),
+            });
+        }
+    }
+
+    let mut keys: Vec<&String> = after
+        .checklist_results
+        .keys()
+        .chain(before.checklist_results.keys())
+        .collect();
+    keys.sort();
+    keys.dedup();
+    for key in keys {
+        let checked = after.checklist_results.get(key).copied().unwrap_or(false);
+        if before.checklist_results.get(key).copied().unwrap_or(false) != checked {
+            entries.push(JournalEntry::Checklist {
+                key: key.clone(),
+                checked,
+            });
+        }
+    }
+    if before.test_order != after.test_order {
+        entries.push(JournalEntry::Order {
+            test_order: after.test_order.clone(),
+        });
+    }
+    if before.meta != after.meta {
+        entries.push(JournalEntry::Meta {
+            meta: after.meta.clone(),
+        });
+    }
+    entries
+}
+
+/// Split a journal into the entries saved with append-only saving, up to
+/// the last [`JournalEntry::Saved`], and the unsaved changes after them.
+pub fn split_saved(entries: &[JournalEntry]) -> (&[JournalEntry], &[JournalEntry]) {
+    let saved = entries
+        .iter()
+        .rposition(|e| *e == JournalEntry::Saved)
+        .map_or(0, |i| i + 1);
+    entries.split_at(saved)
+}
+
+/// Apply journal entries to results. Entries for unknown tests are ignored.
+///
+/// Status changes are added to the audit trail, dated by `completed_at` when
+/// the entry has one and by the time of replay otherwise.
+pub fn replay(results: &mut TestlistResults, entries: &[JournalEntry]) {
+    let now = chrono::Utc::now().to_rfc3339();
+    let tester = results.current_tester().to_string();
+    for entry in entries {
+        match entry {
+            JournalEntry::Status {
+                test_id,
+                status,
+                completed_at,
+                severity,
+                blocked_reason,
+                custom_status,
+            } => {
+                if let Some(result) = results.get_result_mut(test_id) {
+                    let at = completed_at.as_deref().unwrap_or(&now);
+                    if (result.status, &result.custom_status) != (*status, custom_status) {
+                        result.change_status(*status, at, &tester);
+                    }
+                    result.completed_at = completed_at.clone();
+                    result.severity = *severity;
+                    result.blocked_reason = blocked_reason.clone();
+                    result.custom_status = custom_status.clone();
+                }
+            }
+            JournalEntry::Notes { test_id, notes } => {
+                if let Some(result) = results.get_result_mut(test_id) {
+                    result.notes = notes.clone();
+                }
+            }
+            JournalEntry::Checklist { key, checked } => {
+                results.checklist_results.insert(key.clone(), *checked);
+            }
+            JournalEntry::Defects { test_id, defects } => {
+                if let Some(result) = results.get_result_mut(test_id) {
+                    result.defects = defects.clone();
+                }
+            }
+            JournalEntry::Screenshots {
+                test_id,
+                screenshots,
+            } => {
+                if let Some(result) = results.get_result_mut(test_id) {
+                    result.screenshots = screenshots.clone();
+                }
+            }
+            JournalEntry::TimeSpent {
+                test_id,
+                time_spent_secs,
+            } => {
+                if let Some(result) = results.get_result_mut(test_id) {
+                    result.time_spent_secs = *time_spent_secs;
+                }
+            }
+            JournalEntry::CommandRuns {
+                test_id,
+                command_runs,
+            } => {
+                if let Some(result) = results.get_result_mut(test_id) {
+                    result.command_runs = command_runs.clone();
+                }
+            }
+            JournalEntry::CommandHistory {
+                test_id,
+                command_history,
+            } => {
+                if let Some(result) = results.get_result_mut(test_id) {
+                    result.command_history = command_history.clone();
+                }
+            }
+            JournalEntry::Attempts { test_id, attempts } => {
+                if let Some(result) = results.get_result_mut(test_id) {
+                    result.attempts = attempts.clone();
+                }
+            }
+            JournalEntry::Review { test_id, review } => {
+                if let Some(result) = results.get_result_mut(test_id) {
+                    result.review = review.clone();
+                }
+            }
+            JournalEntry::Order { test_order } => results.test_order = test_order.clone(),
+            JournalEntry::Meta { meta } => results.meta = meta.clone(),
+            JournalEntry::Saved => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::Test;
+    use crate::data::results::Verdict;
+    use crate::fixtures;
+
+    fn make_results() -> TestlistResults {
+        let test = |id: &str| Test {
+            id: id.to_string(),
+            title: id.to_string(),
+            ..Default::default()
+        };
+        let testlist = fixtures::testlist(vec![test("t1"), test("t2")]);
+        fixtures::results(&testlist)
+    }
+
+    #[test]
+    fn test_diff_records_changed_fields_only() {
+        let before = make_results();
+        let mut after = before.clone();
+        assert!(diff(&before, &after).is_empty());
+
+        after.results[1].status = Status::Failed;
+        after.results[1].completed_at = Some("2025-01-24T14:32:00Z".to_string());
+        after.results[1].severity = Some(Severity::Major);
+        after.results[0].notes = Some("slow".to_string());
+        after
+            .checklist_results
+            .insert("t1:verify:v0".to_string(), true);
+
+        assert_eq!(
+            diff(&before, &after),
+            vec![
+                JournalEntry::Notes {
+                    test_id: "t1".to_string(),
+                    notes: Some("slow".to_string()),
+                },
+                JournalEntry::Status {
+                    test_id: "t2".to_string(),
+                    status: Status::Failed,
+                    completed_at: Some("2025-01-24T14:32:00Z".to_string()),
+                    severity: Some(Severity::Major),
+                    blocked_reason: None,
+                    custom_status: None,
+                },
+                JournalEntry::Checklist {
+                    key: "t1:verify:v0".to_string(),
+                    checked: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cleared_checklist_is_recorded_unchecked() {
+        let mut before = make_results();
+        before
+            .checklist_results
+            .insert("t1:setup:s0".to_string(), true);
+        let mut after = before.clone();
+        after.clear_checklist("t1");
+        assert_eq!(
+            diff(&before, &after),
+            vec![JournalEntry::Checklist {
+                key: "t1:setup:s0".to_string(),
+                checked: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_replay_reproduces_changes() {
+        let before = make_results();
+        let mut after = before.clone();
+        after.results[0].status = Status::Passed;
+        after.results[0].notes = Some("ok".to_string());
+        after.results[1].defects.push("BUG-7".to_string());
+        after.results[1].attempts.push(Attempt {
+            status: Status::Failed,
+            notes: Some("crashed".to_string()),
+            severity: Some(Severity::Blocker),
+            time_spent_secs: 90,
+            completed_at: None,
+        });
+        after.results[1].status = Status::Retest;
+        after.results[0].review = Some(Review {
+            verdict: Verdict::Approved,
+            reviewer: "rita".to_string(),
+            at: "2025-01-24T16:00:00Z".to_string(),
+            comment: Some("ok".to_string()),
+        });
+        after
+            .checklist_results
+            .insert("t2:verify:v0".to_string(), true);
+        after.test_order = vec!["t2".to_string(), "t1".to_string()];
+
+        let mut replayed = before.clone();
+        replay(&mut replayed, &diff(&before, &after));
+        assert!(diff(&replayed, &after).is_empty());
+        assert_eq!(replayed.test_order, after.test_order);
+    }
+
+    #[test]
+    fn test_replay_keeps_blocked_reason() {
+        let before = make_results();
+        let mut after = before.clone();
+        after.results[0].status = Status::Blocked;
+        after.results[0].blocked_reason = Some("no device".to_string());
+
+        let mut replayed = before.clone();
+        replay(&mut replayed, &diff(&before, &after));
+        assert_eq!(
+            replayed.results[0].blocked_reason.as_deref(),
+            Some("no device")
+        );
+    }
+}
@@ -0,0 +1,282 @@
+//! Merging results files recorded by several testers into one.
+//!
+//! Each test takes its result from one source file. Open results (Pending
+//! or Retest) never override finished ones; when finished results disagree
+//! on status or notes, a conflict policy picks one.
+
+use crate::data::definition::{Test, Testlist};
+use crate::data::results::{Provenance, Status, TestResult, TestlistResults};
+use crate::error::Error;
+
+/// How to choose between conflicting results for a test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Keep the most severe status: Failed, Inconclusive, Skipped, Passed.
+    WorstStatus,
+    /// Keep the most recently completed result.
+    Latest,
+    /// Ask which result to keep.
+    Interactive,
+}
+
+impl MergePolicy {
+    /// Pick one of the candidates, or `None` when the user has to decide.
+    pub fn resolve(self, candidates: &[Candidate]) -> Option<usize> {
+        let latest = |i: &usize| completed_at(candidates[*i].result);
+        let indices = 0..candidates.len();
+        match self {
+            MergePolicy::WorstStatus => {
+                indices.max_by_key(|&i| (severity_rank(candidates[i].result.status), latest(&i)))
+            }
+            MergePolicy::Latest => indices.max_by_key(latest),
+            MergePolicy::Interactive => None,
+        }
+    }
+}
+
+impl std::str::FromStr for MergePolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> crate::error::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "worst-status" | "worst" => Ok(MergePolicy::WorstStatus),
+            "latest" => Ok(MergePolicy::Latest),
+            "interactive" => Ok(MergePolicy::Interactive),
+            _ => Err(Error::UnknownMergePolicy(s.to_string())),
+        }
+    }
+}
+
+/// A source's result for a test that is being merged.
+#[derive(Debug, Clone, Copy)]
+pub struct Candidate<'a> {
+    /// Name of the source, usually its file path.
+    pub source: &'a str,
+    pub tester: &'a str,
+    pub result: &'a TestResult,
+}
+
+/// Outcome of a merge.
+#[derive(Debug, Clone)]
+pub struct Merged {
+    pub results: TestlistResults,
+    /// Number of tests whose finished results disagreed.
+    pub conflicts: usize,
+}
+
+/// How severe a status is for [`MergePolicy::WorstStatus`]. Open results
+/// only compete when no source has finished the test, so a custom status
+/// ranks with Passed whether or not it counts as complete.
+fn severity_rank(status: Status) -> u8 {
+    match status {
+        Status::Failed => 4,
+        Status::Inconclusive => 3,
+        Status::Skipped => 2,
+        Status::Passed | Status::Custom => 1,
+        Status::Pending | Status::Retest | Status::InProgress | Status::Blocked => 0,
+    }
+}
+
+fn completed_at(result: &TestResult) -> Option<chrono::DateTime<chrono::Utc>> {
+    let at = chrono::DateTime::parse_from_rfc3339(result.completed_at.as_deref()?).ok()?;
+    Some(at.with_timezone(&chrono::Utc))
+}
+
+/// Merge named results files for `testlist`, calling `resolve` with the
+/// conflicting candidates of a test to get the index of the one to keep.
+///
+/// Every merged result records the source it came from. Sessions of all
+/// sources are combined, and the meta testers are listed comma-separated.
+pub fn merge(
+    testlist: &Testlist,
+    sources: &[(String, TestlistResults)],
+    mut resolve: impl FnMut(&Test, &[Candidate]) -> usize,
+) -> Merged {
+    let first = &sources.first().expect("merge needs at least one source").1;
+    let mut merged = TestlistResults::new_for_testlist(testlist, &first.meta.testlist, "");
+    let mut testers: Vec<&str> = Vec::new();
+    for (_, results) in sources {
+        for tester in std::iter::once(results.meta.tester.as_str())
+            .chain(results.meta.sessions.iter().map(|s| s.tester.as_str()))
+        {
+            if !testers.contains(&tester) {
+                testers.push(tester);
+            }
+        }
+        merged
+            .meta
+            .sessions
+            .extend(results.meta.sessions.iter().cloned());
+    }
+    merged.meta.tester = testers.join(", ");
+    merged
+        .meta
+        .sessions
+        .sort_by(|a, b| a.started.cmp(&b.started));
+    merged.meta.environment = sources.iter().find_map(|(_, r)| r.meta.environment.clone());
+
+    let mut conflicts = 0;
+    merged.results.clear();
+    for test in &testlist.tests {
+        let all: Vec<(&(String, TestlistResults), Candidate)> = sources
+            .iter()
+            .filter_map(|source| {
+                let result = source.1.results.iter().find(|r| r.test_id == test.id)?;
+                let candidate = Candidate {
+                    source: &source.0,
+                    tester: source.1.current_tester(),
+                    result,
+                };
+                Some((source, candidate))
+            })
+            .collect();
+        let finished: Vec<_> = all
+            .iter()
+            .filter(|(_, c)| !c.result.is_open(testlist))
+            .cloned()
+            .collect();
+        let pool = if finished.is_empty() { all } else { finished };
+        let Some(last) = pool.last() else {
+            merged.results.push(TestResult::new_pending(test));
+            continue;
+        };
+
+        let agree = pool.iter().all(|(_, c)| {
+            c.result.status == last.1.result.status && c.result.notes == last.1.result.notes
+        });
+        let chosen = if agree {
+            last
+        } else {
+            conflicts += 1;
+            let candidates: Vec<Candidate> = pool.iter().map(|(_, c)| *c).collect();
+            &pool[resolve(test, &candidates).min(pool.len() - 1)]
+        };
+
+        let (source, candidate) = chosen;
+        let mut result = candidate.result.clone();
+        result.merged_from = Some(Provenance {
+            file: source.0.clone(),
+            tester: candidate.tester.to_string(),
+        });
+        merged.results.push(result);
+        let prefix = format!("{}:", test.id);
+        merged.checklist_results.extend(
+            source
+                .1
+                .checklist_results
+                .iter()
+                .filter(|(key, _)| key.starts_with(&prefix))
+                .map(|(key, &checked)| (key.clone(), checked)),
+        );
+    }
+
+    Merged {
+        results: merged,
+        conflicts,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn make_testlist() -> Testlist {
+        fixtures::testlist(
+            (1..=3)
+                .map(|n| Test {
+                    id: format!("t{}", n),
+                    title: format!("Test {}", n),
+                    ..Default::default()
+                })
+                .collect(),
+        )
+    }
+
+    fn finish(results: &mut TestlistResults, index: usize, status: Status, at: &str) {
+        results.results[index].status = status;
+        results.results[index].completed_at = Some(at.to_string());
+    }
+
+    /// alice passes t1 and fails t2 early; bob passes t2 later and leaves t1 pending.
+    fn make_sources() -> (Testlist, Vec<(String, TestlistResults)>) {
+        let testlist = make_testlist();
+        let mut alice = TestlistResults::new_for_testlist(&testlist, "t.ron", "alice");
+        let mut bob = TestlistResults::new_for_testlist(&testlist, "t.ron", "bob");
+        finish(&mut alice, 0, Status::Passed, "2025-01-24T09:00:00Z");
+        finish(&mut alice, 1, Status::Failed, "2025-01-24T09:05:00Z");
+        finish(&mut bob, 1, Status::Passed, "2025-01-24T11:00:00Z");
+        alice
+            .checklist_results
+            .insert("t1:verify:v0".to_string(), true);
+        let sources = vec![
+            ("alice.ron".to_string(), alice),
+            ("bob.ron".to_string(), bob),
+        ];
+        (testlist, sources)
+    }
+
+    fn statuses(merged: &Merged) -> Vec<Status> {
+        merged.results.results.iter().map(|r| r.status).collect()
+    }
+
+    #[test]
+    fn test_merge_worst_status_wins() {
+        let (testlist, sources) = make_sources();
+        let policy = MergePolicy::WorstStatus;
+        let merged = merge(&testlist, &sources, |_, c| policy.resolve(c).unwrap());
+        assert_eq!(merged.conflicts, 1);
+        assert_eq!(
+            statuses(&merged),
+            vec![Status::Passed, Status::Failed, Status::Pending]
+        );
+        let provenance = merged.results.results[0].merged_from.as_ref().unwrap();
+        assert_eq!(
+            (provenance.file.as_str(), provenance.tester.as_str()),
+            ("alice.ron", "alice")
+        );
+        assert_eq!(
+            merged.results.checklist_results.get("t1:verify:v0"),
+            Some(&true)
+        );
+        assert_eq!(merged.results.meta.tester, "alice, bob");
+    }
+
+    #[test]
+    fn test_merge_latest_wins() {
+        let (testlist, sources) = make_sources();
+        let policy = MergePolicy::Latest;
+        let merged = merge(&testlist, &sources, |_, c| policy.resolve(c).unwrap());
+        assert_eq!(merged.results.results[1].status, Status::Passed);
+        assert_eq!(
+            merged.results.results[1].merged_from.as_ref().unwrap().file,
+            "bob.ron"
+        );
+    }
+
+    #[test]
+    fn test_merge_asks_on_conflict_only() {
+        let (testlist, sources) = make_sources();
+        let mut asked = Vec::new();
+        let merged = merge(&testlist, &sources, |test, candidates| {
+            asked.push((test.id.clone(), candidates.len()));
+            0
+        });
+        assert_eq!(asked, vec![("t2".to_string(), 2)]);
+        assert_eq!(merged.results.results[1].status, Status::Failed);
+        assert_eq!(MergePolicy::Interactive.resolve(&[]), None);
+    }
+
+    #[test]
+    fn test_merge_policy_from_str() {
+        assert_eq!(
+            "latest".parse::<MergePolicy>().unwrap(),
+            MergePolicy::Latest
+        );
+        assert_eq!(
+            "worst-status".parse::<MergePolicy>().unwrap(),
+            MergePolicy::WorstStatus
+        );
+        assert!("random".parse::<MergePolicy>().is_err());
+    }
+}
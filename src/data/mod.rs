@@ -1,6 +1,15 @@
 //! Data layer: pure data types with no behavior beyond serialization.
 
+pub mod config;
+pub mod copy_mode;
 pub mod definition;
+pub mod editor;
 pub mod effect;
+pub mod journal;
+pub mod merge;
 pub mod results;
+pub mod script;
 pub mod state;
+pub mod strict;
+pub mod template;
+pub mod theme;
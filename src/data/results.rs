@@ -1,10 +1,11 @@
 //! Types for testlist results files (.testlist.results.ron).
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
-use super::definition::{Test, Testlist};
+use super::definition::{Priority, StatusDef, Test, Testlist};
 
 /// Status of a test result.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
@@ -15,6 +16,126 @@ pub enum Status {
     Failed,
     Inconclusive,
     Skipped,
+    /// Failed earlier and waiting to be tested again; see `TestResult::attempts`.
+    Retest,
+    /// Being worked on; for long tests started in one sitting and finished later.
+    InProgress,
+    /// Can't be tested for now; see `TestResult::blocked_reason`.
+    Blocked,
+    /// A status declared by the testlist, named by `TestResult::custom_status`.
+    /// Whether it counts as complete is looked up in the testlist's `statuses`.
+    Custom,
+}
+
+impl Status {
+    /// Check whether a test with this built-in status still has to be done.
+    /// Whether a custom status does depends on the testlist; see
+    /// [`TestResult::is_open`].
+    pub fn is_open(self) -> bool {
+        matches!(
+            self,
+            Status::Pending | Status::Retest | Status::InProgress | Status::Blocked
+        )
+    }
+
+    /// The status in words, for text labels and announcements.
+    pub fn label(self) -> &'static str {
+        match self {
+            Status::Pending => "pending",
+            Status::Passed => "passed",
+            Status::Failed => "failed",
+            Status::Inconclusive => "inconclusive",
+            Status::Skipped => "skipped",
+            Status::Retest => "retest",
+            Status::InProgress => "in progress",
+            Status::Blocked => "blocked",
+            Status::Custom => "custom",
+        }
+    }
+}
+
+impl std::str::FromStr for Status {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> crate::error::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "pending" => Ok(Status::Pending),
+            "passed" | "pass" => Ok(Status::Passed),
+            "failed" | "fail" => Ok(Status::Failed),
+            "inconclusive" => Ok(Status::Inconclusive),
+            "skipped" | "skip" => Ok(Status::Skipped),
+            "retest" => Ok(Status::Retest),
+            "inprogress" | "in-progress" | "in_progress" => Ok(Status::InProgress),
+            "blocked" => Ok(Status::Blocked),
+            _ => Err(crate::error::Error::UnknownStatus(s.to_string())),
+        }
+    }
+}
+
+/// How serious a failure is, declared most serious first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Severity {
+    Blocker,
+    Major,
+    Minor,
+    Cosmetic,
+}
+
+impl Severity {
+    /// All severities in the order they are offered, numbered from 1.
+    pub const ALL: [Severity; 4] = [
+        Severity::Blocker,
+        Severity::Major,
+        Severity::Minor,
+        Severity::Cosmetic,
+    ];
+
+    /// Short lowercase label for display.
+    pub fn label(self) -> &'static str {
+        match self {
+            Severity::Blocker => "blocker",
+            Severity::Major => "major",
+            Severity::Minor => "minor",
+            Severity::Cosmetic => "cosmetic",
+        }
+    }
+}
+
+impl std::str::FromStr for Severity {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> crate::error::Result<Self> {
+        Severity::ALL
+            .into_iter()
+            .find(|severity| severity.label().eq_ignore_ascii_case(s))
+            .ok_or_else(|| crate::error::Error::UnknownSeverity(s.to_string()))
+    }
+}
+
+/// A reviewer's decision on a result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Verdict {
+    Approved,
+    Rejected,
+}
+
+impl Verdict {
+    pub fn label(self) -> &'static str {
+        match self {
+            Verdict::Approved => "approved",
+            Verdict::Rejected => "rejected",
+        }
+    }
+}
+
+/// A reviewer's sign-off on a result.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Review {
+    pub verdict: Verdict,
+    pub reviewer: String,
+    pub at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
 }
 
 /// Checklist section type for composite keys.
@@ -33,13 +154,113 @@ impl std::fmt::Display for ChecklistSection {
     }
 }
 
+impl std::str::FromStr for ChecklistSection {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> crate::error::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "setup" => Ok(ChecklistSection::Setup),
+            "verify" => Ok(ChecklistSection::Verify),
+            _ => Err(crate::error::Error::UnknownChecklistSection(s.to_string())),
+        }
+    }
+}
+
+/// The environment a testing session ran in.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Environment {
+    pub os: String,
+    pub arch: String,
+    #[serde(default)]
+    pub hostname: Option<String>,
+    #[serde(default)]
+    pub product_version: Option<String>,
+    /// Free-form key/value pairs supplied by the tester.
+    #[serde(default)]
+    pub custom: BTreeMap<String, String>,
+}
+
+impl Environment {
+    /// Record a key/value pair. The `product_version` key fills the dedicated field.
+    pub fn set(&mut self, key: &str, value: &str) {
+        if key == "product_version" {
+            self.product_version = Some(value.to_string());
+        } else {
+            self.custom.insert(key.to_string(), value.to_string());
+        }
+    }
+}
+
+/// The git checkout a testing session ran in.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitInfo {
+    /// Full SHA of the checked-out commit.
+    pub commit: String,
+    /// Checked-out branch; `None` for a detached HEAD.
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// Whether there were uncommitted changes or untracked files.
+    #[serde(default)]
+    pub dirty: bool,
+}
+
+impl GitInfo {
+    /// Short description such as `1a2b3c4 (main, dirty)`.
+    pub fn summary(&self) -> String {
+        let short: String = self.commit.chars().take(7).collect();
+        let mut details: Vec<&str> = self.branch.iter().map(String::as_str).collect();
+        if self.dirty {
+            details.push("dirty");
+        }
+        if details.is_empty() {
+            short
+        } else {
+            format!("{} ({})", short, details.join(", "))
+        }
+    }
+}
+
+/// Split a `key=value` string, trimming whitespace around both parts.
+pub fn parse_key_value(text: &str) -> Option<(String, String)> {
+    let (key, value) = text.split_once('=')?;
+    let key = key.trim();
+    if key.is_empty() {
+        return None;
+    }
+    Some((key.to_string(), value.trim().to_string()))
+}
+
 /// Metadata for a results file.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ResultsMeta {
     pub testlist: String,
+    /// SHA-256 of the testlist definition the results were last recorded
+    /// against (see `Testlist::sha256`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub testlist_sha256: Option<String>,
+    /// Tester who started the run; later sittings record their own tester.
     pub tester: String,
-    pub started: String,
+    /// Sittings spent on this run, oldest first.
+    #[serde(default)]
+    pub sessions: Vec<Session>,
     pub completed: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<Environment>,
+    /// Git checkout of the latest session, if it ran in one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitInfo>,
+}
+
+/// One sitting of a testing run, from launching the TUI to the last save.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    pub started: String,
+    /// When results were last saved in this session; `None` until then.
+    #[serde(default)]
+    pub ended: Option<String>,
+    pub tester: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
 }
 
 /// Result for a single test.
@@ -54,6 +275,42 @@ pub struct TestResult {
     #[serde(default)]
     pub screenshots: Vec<PathBuf>,
     pub completed_at: Option<String>,
+    /// How serious the failure is; only set while the status is Failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    /// Why the test can't be done, only kept while the status is `Blocked`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocked_reason: Option<String>,
+    /// Name of the testlist's custom status, only kept while the status is `Custom`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_status: Option<String>,
+    /// Bug tracker IDs or URLs linked to this test's failure.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub defects: Vec<String>,
+    /// Priority copied from the definition so reports can rank findings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    /// Seconds the test was selected in the TUI, accumulated across sessions.
+    #[serde(default)]
+    pub time_spent_secs: u64,
+    /// Suggested commands executed from the TUI, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub command_runs: Vec<CommandRun>,
+    /// Command lines entered in the embedded terminal while this test was selected.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub command_history: Vec<String>,
+    /// Earlier attempts set aside by marking the test for retest, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<Attempt>,
+    /// Status transitions, oldest first, for audits.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub status_history: Vec<StatusChange>,
+    /// Where the result came from when results files were merged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merged_from: Option<Provenance>,
+    /// Reviewer sign-off; cleared when the status changes afterwards.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review: Option<Review>,
     // Legacy fields for backward compatibility on load.
     // Always None when saving in new format.
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -62,7 +319,114 @@ pub struct TestResult {
     pub verify_checked: Option<Vec<bool>>,
 }
 
+/// Audit record of a command run in the embedded terminal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandRun {
+    pub command: String,
+    pub exit_code: i32,
+    pub started: String,
+    pub finished: String,
+    /// Last lines of output, with terminal escape sequences removed.
+    pub output_tail: String,
+}
+
+/// A finished attempt at a test, kept when the test is marked for retest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Attempt {
+    pub status: Status,
+    pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+    pub time_spent_secs: u64,
+    pub completed_at: Option<String>,
+}
+
+/// Source of a merged result: the results file it was taken from and that
+/// file's tester.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub file: String,
+    pub tester: String,
+}
+
+/// One change of a test's status.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusChange {
+    pub from: Status,
+    pub to: Status,
+    pub at: String,
+    pub tester: String,
+}
+
 impl TestResult {
+    /// Set the status, recording the transition in `status_history`.
+    /// Setting the current status again records nothing; leaving Failed
+    /// clears the severity, leaving Blocked clears the reason, leaving a
+    /// custom status clears its name, and any change clears the review.
+    pub fn change_status(&mut self, status: Status, at: &str, tester: &str) {
+        if status == self.status && status != Status::Custom {
+            return;
+        }
+        if status != Status::Failed {
+            self.severity = None;
+        }
+        if status != Status::Blocked {
+            self.blocked_reason = None;
+        }
+        if status != Status::Custom {
+            self.custom_status = None;
+        }
+        self.review = None;
+        self.status_history.push(StatusChange {
+            from: self.status,
+            to: status,
+            at: at.to_string(),
+            tester: tester.to_string(),
+        });
+        self.status = status;
+    }
+
+    /// Set one of the testlist's custom statuses; setting the current one
+    /// again records nothing.
+    pub fn change_custom_status(&mut self, def: &StatusDef, at: &str, tester: &str) {
+        if self.custom_status.as_deref() == Some(def.name.as_str()) {
+            return;
+        }
+        self.change_status(Status::Custom, at, tester);
+        self.custom_status = Some(def.name.clone());
+    }
+
+    /// Check whether the test still has to be done. A custom status is open
+    /// unless the testlist declares it as counting as complete, so one the
+    /// testlist no longer declares is open.
+    pub fn is_open(&self, testlist: &Testlist) -> bool {
+        match self.status {
+            Status::Custom => !self
+                .custom_status
+                .as_deref()
+                .and_then(|name| testlist.custom_status(name))
+                .is_some_and(|def| def.counts_as_complete),
+            status => status.is_open(),
+        }
+    }
+
+    /// Name of the status for display: the custom status's name, or the
+    /// built-in status's.
+    pub fn status_name(&self) -> String {
+        match (&self.custom_status, self.status) {
+            (Some(name), Status::Custom) => name.clone(),
+            (_, status) => format!("{:?}", status),
+        }
+    }
+
+    /// Add a line to the end of the notes.
+    pub fn append_note(&mut self, text: &str) {
+        self.notes = Some(match self.notes.take() {
+            Some(notes) => format!("{}\n{}", notes, text),
+            None => text.to_string(),
+        });
+    }
+
     /// Create a new pending result for a test.
     pub fn new_pending(test: &Test) -> Self {
         Self {
@@ -71,72 +435,304 @@ impl TestResult {
             notes: None,
             screenshots: Vec::new(),
             completed_at: None,
+            severity: None,
+            blocked_reason: None,
+            custom_status: None,
+            defects: Vec::new(),
+            priority: test.priority,
+            time_spent_secs: 0,
+            command_runs: Vec::new(),
+            command_history: Vec::new(),
+            attempts: Vec::new(),
+            status_history: Vec::new(),
+            merged_from: None,
+            review: None,
             setup_checked: None,
             verify_checked: None,
         }
     }
 }
 
+/// Directory for a results file's attachments: `<results file stem>.assets`
+/// next to the results file.
+pub fn assets_dir(results_path: &std::path::Path) -> PathBuf {
+    let stem = results_path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy();
+    results_path.with_file_name(format!("{}.assets", stem))
+}
+
+/// Temporary file a save is written to before it is renamed into place.
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.tmp", name))
+}
+
+/// Write `content` to a temporary file next to `path`, flush it to disk, and
+/// rename it over `path`. On failure the temporary file is removed and
+/// `path` is left untouched.
+pub fn write_atomic(path: &Path, content: &[u8]) -> crate::error::Result<()> {
+    let temp = temp_path(path);
+    let written = (|| {
+        let mut file = std::fs::File::create(&temp)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+        std::fs::rename(&temp, path)
+    })();
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    Ok(written?)
+}
+
 /// Builds a composite key for the checklist_results HashMap.
 pub fn checklist_key(test_id: &str, section: ChecklistSection, item_id: &str) -> String {
     format!("{}:{}:{}", test_id, section, item_id)
 }
 
+/// Schema version of results files written by this build.
+///
+/// Version 1 kept checklist states as `setup_checked`/`verify_checked` lists
+/// on each result; version 2 moved them into `checklist_results`; version 3
+/// replaced `meta.started` with a list of sessions; version 4 added the
+/// `InProgress` and `Blocked` statuses, so version 3 files load unchanged;
+/// version 5 added custom statuses declared by the testlist.
+pub const SCHEMA_VERSION: u32 = 5;
+
 /// Root type for results files.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TestlistResults {
+    /// Format version, see [`SCHEMA_VERSION`]. Files written before the
+    /// field existed are version 1 or 2, told apart by their shape.
+    #[serde(default)]
+    pub schema_version: u32,
     pub meta: ResultsMeta,
     pub results: Vec<TestResult>,
     /// Checklist item states with composite keys: "test-id:setup:item-id" or "test-id:verify:item-id"
     #[serde(default)]
     pub checklist_results: HashMap<String, bool>,
+    /// Test IDs in the order the tester arranged the tests pane; empty keeps
+    /// the testlist's order. Tests missing from it follow in testlist order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub test_order: Vec<String>,
 }
 
 impl TestlistResults {
-    /// Load results from a RON file, migrating old format if needed.
+    /// Load results from a RON file, migrating older schema versions.
     pub fn load(path: &std::path::Path, testlist: &Testlist) -> crate::error::Result<Self> {
         let content = std::fs::read_to_string(path)?;
+        Self::parse(&content, testlist)
+    }
 
-        // Try loading as new format first
-        if let Ok(results) = ron::from_str::<TestlistResults>(&content) {
-            return Ok(results);
+    /// Load results like [`TestlistResults::load`], failing on fields the
+    /// current schema doesn't have. Older schema versions are migrated
+    /// without the check.
+    pub fn load_strict(path: &std::path::Path, testlist: &Testlist) -> crate::error::Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        if schema_version(&content)? == SCHEMA_VERSION {
+            super::strict::reject_unknown(path, super::strict::unknown_results_fields(&content)?)?;
         }
+        Self::parse(&content, testlist)
+    }
 
-        // Fall back to old format and migrate
-        let old: OldResults = ron::from_str(&content)?;
-        Ok(Self::migrate_from_old(old, testlist))
+    /// Parse results of any supported schema version, migrating them step by
+    /// step to the current one.
+    ///
+    /// Each version has a type it parses into and a migration to the next
+    /// version, so a new version adds one arm and one migration.
+    pub fn parse(content: &str, testlist: &Testlist) -> crate::error::Result<Self> {
+        let version = schema_version(content)?;
+        let mut results = match version {
+            1 => Self::migrate_from_v2(Self::migrate_from_old(ron::from_str(content)?, testlist)),
+            2 => Self::migrate_from_v2(ron::from_str(content)?),
+            3 | 4 | SCHEMA_VERSION => ron::from_str(content)?,
+            _ => {
+                return Err(crate::error::Error::UnsupportedSchemaVersion(
+                    version,
+                    SCHEMA_VERSION,
+                ))
+            }
+        };
+        results.schema_version = SCHEMA_VERSION;
+        Ok(results)
     }
 
     /// Save results to a RON file.
+    ///
+    /// The file is replaced atomically, so a crash mid-save leaves the
+    /// previous results intact.
     pub fn save(&self, path: &std::path::Path) -> crate::error::Result<()> {
         let content = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?;
-        std::fs::write(path, content)?;
-        Ok(())
+        write_atomic(path, content.as_bytes())
     }
 
     /// Create initial results for a testlist.
+    ///
+    /// No session is recorded until [`start_session`](Self::start_session).
     pub fn new_for_testlist(testlist: &Testlist, testlist_path: &str, tester: &str) -> Self {
-        let now = chrono::Utc::now().to_rfc3339();
         Self {
+            schema_version: SCHEMA_VERSION,
             meta: ResultsMeta {
                 testlist: testlist_path.to_string(),
                 tester: tester.to_string(),
-                started: now,
+                sessions: Vec::new(),
                 completed: None,
+                environment: None,
+                git: None,
+                testlist_sha256: testlist.sha256().map(str::to_string),
             },
             results: testlist.tests.iter().map(TestResult::new_pending).collect(),
             checklist_results: HashMap::new(),
+            test_order: Vec::new(),
         }
     }
 
+    /// Record the start of a new sitting.
+    pub fn start_session(&mut self, tester: &str, note: Option<String>) {
+        self.meta.sessions.push(Session {
+            started: chrono::Utc::now().to_rfc3339(),
+            ended: None,
+            tester: tester.to_string(),
+            note,
+        });
+    }
+
+    /// Mark the current sitting as ending now; called whenever results are saved.
+    ///
+    /// Also records the run as completed once no test is open, keeping the
+    /// first completion time, and clears it again if a test was reopened.
+    pub fn end_session(&mut self, testlist: &Testlist) {
+        let now = chrono::Utc::now().to_rfc3339();
+        if self.results.iter().any(|r| r.is_open(testlist)) {
+            self.meta.completed = None;
+        } else if self.meta.completed.is_none() {
+            self.meta.completed = Some(now.clone());
+        }
+        if let Some(session) = self.meta.sessions.last_mut() {
+            session.ended = Some(now);
+        }
+    }
+
+    /// Tester of the current session, or the run's tester before any session.
+    pub fn current_tester(&self) -> &str {
+        self.meta
+            .sessions
+            .last()
+            .map_or(&self.meta.tester, |s| &s.tester)
+    }
+
     /// Get mutable reference to result for a test by ID.
     pub fn get_result_mut(&mut self, test_id: &str) -> Option<&mut TestResult> {
         self.results.iter_mut().find(|r| r.test_id == test_id)
     }
 
+    /// Set a test's status as the current tester, stamping its completion
+    /// time. Returns `false` if there is no result for the test.
+    pub fn set_status(&mut self, test_id: &str, status: Status) -> bool {
+        let tester = self.current_tester().to_string();
+        match self.get_result_mut(test_id) {
+            Some(result) => {
+                let now = chrono::Utc::now().to_rfc3339();
+                result.change_status(status, &now, &tester);
+                result.completed_at = (!status.is_open()).then_some(now);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Number of a test's verify items not checked yet.
+    pub fn unchecked_verify_count(&self, test: &Test) -> usize {
+        test.verify
+            .iter()
+            .filter(|item| {
+                let key = checklist_key(&test.id, ChecklistSection::Verify, &item.id);
+                !self.checklist_results.get(&key).copied().unwrap_or(false)
+            })
+            .count()
+    }
+
+    /// Check whether a test may be marked Passed: always, unless
+    /// `require_checks` is set and some of its verify items are unchecked.
+    pub fn may_pass(&self, test: &Test, require_checks: bool) -> bool {
+        !require_checks || self.unchecked_verify_count(test) == 0
+    }
+
+    /// Set a test's status to one of the testlist's custom statuses as the
+    /// current tester, stamping its completion time if the status counts as
+    /// complete. Returns `false` if there is no result for the test.
+    pub fn set_custom_status(&mut self, test_id: &str, def: &StatusDef) -> bool {
+        let tester = self.current_tester().to_string();
+        match self.get_result_mut(test_id) {
+            Some(result) => {
+                let now = chrono::Utc::now().to_rfc3339();
+                result.change_custom_status(def, &now, &tester);
+                result.completed_at = def.counts_as_complete.then_some(now);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Whether the results were recorded against a different version of the
+    /// testlist. Results that don't record its hash are taken to match.
+    pub fn testlist_changed(&self, testlist: &Testlist) -> bool {
+        match (&self.meta.testlist_sha256, testlist.sha256()) {
+            (Some(recorded), Some(current)) => recorded != current,
+            _ => false,
+        }
+    }
+
+    /// Add pending results for tests that have none, e.g. after tests were
+    /// added to the testlist. Results of removed tests are kept.
+    pub fn reconcile(&mut self, testlist: &Testlist) {
+        for test in &testlist.tests {
+            if !self.results.iter().any(|r| r.test_id == test.id) {
+                self.results.push(TestResult::new_pending(test));
+            }
+        }
+    }
+
+    /// Remove all checklist item states recorded for a test.
+    pub fn clear_checklist(&mut self, test_id: &str) {
+        let prefixes = [ChecklistSection::Setup, ChecklistSection::Verify]
+            .map(|section| format!("{}:{}:", test_id, section));
+        self.checklist_results
+            .retain(|key, _| !prefixes.iter().any(|p| key.starts_with(p.as_str())));
+    }
+
+    /// Migrate version 2 results by turning `meta.started` into the first session.
+    fn migrate_from_v2(old: ResultsV2) -> Self {
+        let session = Session {
+            started: old.meta.started,
+            ended: None,
+            tester: old.meta.tester.clone(),
+            note: None,
+        };
+        TestlistResults {
+            schema_version: 3,
+            meta: ResultsMeta {
+                testlist: old.meta.testlist,
+                tester: old.meta.tester,
+                sessions: vec![session],
+                completed: old.meta.completed,
+                environment: old.meta.environment,
+                git: None,
+                testlist_sha256: None,
+            },
+            results: old.results,
+            checklist_results: old.checklist_results,
+            test_order: Vec::new(),
+        }
+    }
+
     /// Migrate from old Results format (with setup_checked/verify_checked on each TestResult)
-    /// to new format with centralized checklist_results HashMap.
-    fn migrate_from_old(old: OldResults, testlist: &Testlist) -> Self {
+    /// to version 2 with centralized checklist_results HashMap.
+    fn migrate_from_old(old: OldResults, testlist: &Testlist) -> ResultsV2 {
         let mut checklist_results = HashMap::new();
 
         for old_result in &old.results {
@@ -176,17 +772,33 @@ impl TestlistResults {
             .results
             .into_iter()
             .map(|r| TestResult {
+                priority: testlist
+                    .tests
+                    .iter()
+                    .find(|t| t.id == r.test_id)
+                    .and_then(|t| t.priority),
                 test_id: r.test_id,
                 status: r.status,
                 notes: r.notes,
                 screenshots: r.screenshots,
                 completed_at: r.completed_at,
+                severity: None,
+                blocked_reason: None,
+                defects: Vec::new(),
+                time_spent_secs: 0,
+                command_runs: Vec::new(),
+                command_history: Vec::new(),
+                attempts: Vec::new(),
+                status_history: Vec::new(),
+                merged_from: None,
+                review: None,
                 setup_checked: None,
                 verify_checked: None,
+                custom_status: None,
             })
             .collect();
 
-        TestlistResults {
+        ResultsV2 {
             meta: old.meta,
             results,
             checklist_results,
@@ -194,11 +806,73 @@ impl TestlistResults {
     }
 }
 
-/// Old format for backward compatibility loading.
+/// Schema version of a results file: the `schema_version` field if present,
+/// otherwise 2 when it has a `checklist_results` map and 1 when it doesn't.
+fn schema_version(content: &str) -> crate::error::Result<u32> {
+    let value: ron::Value = ron::from_str(content)?;
+    let ron::Value::Map(fields) = value else {
+        return Ok(1);
+    };
+    let field = |name: &str| {
+        let key = ron::Value::String(name.to_string());
+        fields.iter().find(|(k, _)| **k == key).map(|(_, v)| v)
+    };
+    Ok(match field("schema_version") {
+        Some(ron::Value::Number(n)) => n.as_i64().and_then(|v| u32::try_from(v).ok()).unwrap_or(0),
+        Some(_) => 0,
+        None if field("checklist_results").is_some() => 2,
+        None => 1,
+    })
+}
+
+/// The testlist path recorded in results of any schema version.
+pub fn recorded_testlist(content: &str) -> crate::error::Result<Option<String>> {
+    let value: ron::Value = ron::from_str(content)?;
+    let field = |value: &ron::Value, name: &str| {
+        let ron::Value::Map(fields) = value else {
+            return None;
+        };
+        let key = ron::Value::String(name.to_string());
+        fields
+            .iter()
+            .find(|(k, _)| **k == key)
+            .map(|(_, v)| v.clone())
+    };
+    Ok(
+        match field(&value, "meta").and_then(|meta| field(&meta, "testlist")) {
+            Some(ron::Value::String(path)) => Some(path),
+            _ => None,
+        },
+    )
+}
+
+/// Version 2 results format, loaded for migration.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename = "TestlistResults")]
+struct ResultsV2 {
+    meta: ResultsMetaV2,
+    results: Vec<TestResult>,
+    #[serde(default)]
+    checklist_results: HashMap<String, bool>,
+}
+
+/// Results metadata of versions 1 and 2, with a single start time.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename = "ResultsMeta")]
+struct ResultsMetaV2 {
+    testlist: String,
+    tester: String,
+    started: String,
+    completed: Option<String>,
+    #[serde(default)]
+    environment: Option<Environment>,
+}
+
+/// Version 1 results format, loaded for migration.
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename = "Results")]
 struct OldResults {
-    meta: ResultsMeta,
+    meta: ResultsMetaV2,
     results: Vec<OldTestResult>,
 }
 
@@ -218,38 +892,45 @@ struct OldTestResult {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data::definition::{ChecklistItem, Meta};
+    use crate::data::definition::{ChecklistItem, Format};
+    use crate::fixtures;
 
     fn make_testlist() -> Testlist {
-        Testlist {
-            meta: Meta {
-                title: "Test".to_string(),
-                description: "".to_string(),
-                created: "".to_string(),
-                version: "1".to_string(),
-            },
-            tests: vec![Test {
-                id: "t1".to_string(),
-                title: "Test 1".to_string(),
-                description: "".to_string(),
-                setup: vec![
-                    ChecklistItem {
-                        id: "setup-0".to_string(),
-                        text: "Step A".to_string(),
-                    },
-                    ChecklistItem {
-                        id: "setup-1".to_string(),
-                        text: "Step B".to_string(),
-                    },
-                ],
-                action: "Do it".to_string(),
-                verify: vec![ChecklistItem {
-                    id: "verify-0".to_string(),
-                    text: "Check A".to_string(),
-                }],
-                suggested_command: None,
+        fixtures::testlist(vec![Test {
+            id: "t1".to_string(),
+            title: "Test 1".to_string(),
+            description: "".to_string(),
+            setup: vec![
+                ChecklistItem {
+                    id: "setup-0".to_string(),
+                    text: "Step A".to_string(),
+                },
+                ChecklistItem {
+                    id: "setup-1".to_string(),
+                    text: "Step B".to_string(),
+                },
+            ],
+            action: "Do it".to_string(),
+            verify: vec![ChecklistItem {
+                id: "verify-0".to_string(),
+                text: "Check A".to_string(),
             }],
-        }
+            suggested_command: None,
+            ..Default::default()
+        }])
+    }
+
+    #[test]
+    fn test_git_info_summary() {
+        let mut git = GitInfo {
+            commit: "1a2b3c4d5e6f".to_string(),
+            branch: Some("main".to_string()),
+            dirty: true,
+        };
+        assert_eq!(git.summary(), "1a2b3c4 (main, dirty)");
+        git.branch = None;
+        git.dirty = false;
+        assert_eq!(git.summary(), "1a2b3c4");
     }
 
     #[test]
@@ -257,6 +938,16 @@ mod tests {
         assert_eq!(Status::default(), Status::Pending);
     }
 
+    #[test]
+    fn test_recorded_testlist() {
+        let content = r#"(meta: (testlist: "release.testlist.ron", tester: "alice"), results: [])"#;
+        assert_eq!(
+            recorded_testlist(content).unwrap().as_deref(),
+            Some("release.testlist.ron")
+        );
+        assert_eq!(recorded_testlist("(results: [])").unwrap(), None);
+    }
+
     #[test]
     fn test_new_pending_result() {
         let testlist = make_testlist();
@@ -267,6 +958,39 @@ mod tests {
         assert!(result.verify_checked.is_none());
     }
 
+    #[test]
+    fn test_reconcile_adds_missing_results() {
+        let mut testlist = make_testlist();
+        let mut results = TestlistResults::new_for_testlist(&testlist, "test.ron", "alice");
+        results.results[0].status = Status::Passed;
+        testlist.tests.push(Test {
+            id: "t2".to_string(),
+            title: "Second".to_string(),
+            ..Default::default()
+        });
+        results.reconcile(&testlist);
+        results.reconcile(&testlist);
+        assert_eq!(results.results.len(), 2);
+        assert_eq!(results.results[0].status, Status::Passed);
+        assert_eq!(results.results[1].test_id, "t2");
+        assert_eq!(results.results[1].status, Status::Pending);
+    }
+
+    #[test]
+    fn test_testlist_changed() {
+        let content = make_testlist().to_string_as(Format::Ron).unwrap();
+        let testlist = Testlist::parse(&content, Format::Ron).unwrap();
+        let mut results = TestlistResults::new_for_testlist(&testlist, "test.ron", "alice");
+        assert!(!results.testlist_changed(&testlist));
+
+        let edited = Testlist::parse(&format!("// Edited\n{}", content), Format::Ron).unwrap();
+        assert!(results.testlist_changed(&edited));
+        assert!(!results.testlist_changed(&make_testlist()));
+
+        results.meta.testlist_sha256 = None;
+        assert!(!results.testlist_changed(&testlist));
+    }
+
     #[test]
     fn test_new_for_testlist() {
         let testlist = make_testlist();
@@ -279,7 +1003,7 @@ mod tests {
     #[test]
     fn test_get_result_mut() {
         let testlist = make_testlist();
-        let mut results = TestlistResults::new_for_testlist(&testlist, "test.ron", "tester");
+        let mut results = fixtures::results(&testlist);
         assert!(results.get_result_mut("t1").is_some());
         assert!(results.get_result_mut("nonexistent").is_none());
         results.get_result_mut("t1").unwrap().status = Status::Passed;
@@ -321,11 +1045,13 @@ Results(
     ],
 )
 "#;
-        let old: OldResults = ron::from_str(ron_str).unwrap();
+        assert_eq!(schema_version(ron_str).unwrap(), 1);
         let testlist = make_testlist();
-        let migrated = TestlistResults::migrate_from_old(old, &testlist);
+        let migrated = TestlistResults::parse(ron_str, &testlist).unwrap();
 
+        assert_eq!(migrated.schema_version, SCHEMA_VERSION);
         assert_eq!(migrated.meta.tester, "alice");
+        assert_eq!(migrated.meta.sessions[0].started, "2025-01-24T14:30:00Z");
         assert_eq!(migrated.results[0].status, Status::Passed);
         assert!(migrated.results[0].setup_checked.is_none()); // Cleared
         assert_eq!(
@@ -367,8 +1093,19 @@ TestlistResults(
     },
 )
 "#;
-        let results: TestlistResults = ron::from_str(ron_str).unwrap();
+        assert_eq!(schema_version(ron_str).unwrap(), 2);
+        let results = TestlistResults::parse(ron_str, &make_testlist()).unwrap();
+        assert_eq!(results.schema_version, SCHEMA_VERSION);
         assert_eq!(results.meta.tester, "bob");
+        assert_eq!(
+            results.meta.sessions,
+            vec![Session {
+                started: "2025-01-24".to_string(),
+                ended: None,
+                tester: "bob".to_string(),
+                note: None,
+            }]
+        );
         assert_eq!(
             results.checklist_results.get("t1:setup:setup-0"),
             Some(&true)
@@ -379,6 +1116,104 @@ TestlistResults(
         );
     }
 
+    #[test]
+    fn test_sessions_are_appended() {
+        let testlist = make_testlist();
+        let mut results = TestlistResults::new_for_testlist(&testlist, "test.ron", "alice");
+        assert!(results.meta.sessions.is_empty());
+        results.end_session(&testlist);
+
+        results.start_session("alice", None);
+        results.end_session(&testlist);
+        results.start_session("bob", Some("retest after fix".to_string()));
+        let sessions = &results.meta.sessions;
+        assert_eq!(sessions.len(), 2);
+        assert!(sessions[0].ended.is_some());
+        assert_eq!(sessions[1].tester, "bob");
+        assert_eq!(sessions[1].note.as_deref(), Some("retest after fix"));
+        assert_eq!(sessions[1].ended, None);
+    }
+
+    #[test]
+    fn test_parse_status_and_severity() {
+        assert_eq!("Passed".parse::<Status>().unwrap(), Status::Passed);
+        assert_eq!("fail".parse::<Status>().unwrap(), Status::Failed);
+        assert!("done".parse::<Status>().is_err());
+        assert_eq!("MAJOR".parse::<Severity>().unwrap(), Severity::Major);
+        assert!("critical".parse::<Severity>().is_err());
+    }
+
+    #[test]
+    fn test_end_session_records_completion() {
+        let testlist = make_testlist();
+        let mut results = TestlistResults::new_for_testlist(&testlist, "test.ron", "alice");
+        results.start_session("alice", None);
+        results.end_session(&testlist);
+        assert_eq!(results.meta.completed, None);
+
+        for result in &mut results.results {
+            result.status = Status::Passed;
+        }
+        results.end_session(&testlist);
+        let completed = results.meta.completed.clone();
+        assert!(completed.is_some());
+        results.end_session(&testlist);
+        assert_eq!(results.meta.completed, completed);
+
+        results.results[0].status = Status::Retest;
+        results.end_session(&testlist);
+        assert_eq!(results.meta.completed, None);
+    }
+
+    #[test]
+    fn test_change_status_records_transitions() {
+        let mut results = TestlistResults::new_for_testlist(&make_testlist(), "test.ron", "alice");
+        assert_eq!(results.current_tester(), "alice");
+        results.start_session("bob", None);
+        let tester = results.current_tester().to_string();
+        let result = &mut results.results[0];
+        result.change_status(Status::Failed, "2025-01-24T14:00:00Z", &tester);
+        result.change_status(Status::Failed, "2025-01-24T14:05:00Z", &tester);
+        result.change_status(Status::Passed, "2025-01-25T09:00:00Z", &tester);
+        assert_eq!(result.status, Status::Passed);
+        assert_eq!(
+            result.status_history,
+            vec![
+                StatusChange {
+                    from: Status::Pending,
+                    to: Status::Failed,
+                    at: "2025-01-24T14:00:00Z".to_string(),
+                    tester: "bob".to_string(),
+                },
+                StatusChange {
+                    from: Status::Failed,
+                    to: Status::Passed,
+                    at: "2025-01-25T09:00:00Z".to_string(),
+                    tester: "bob".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_newer_schema() {
+        let ron_str = r#"
+TestlistResults(
+    schema_version: 99,
+    meta: ResultsMeta(testlist: "test.ron", tester: "bob", started: "2025-01-24", completed: None),
+    results: [],
+)
+"#;
+        assert_eq!(schema_version(ron_str).unwrap(), 99);
+        assert!(matches!(
+            TestlistResults::parse(ron_str, &make_testlist()),
+            Err(crate::error::Error::UnsupportedSchemaVersion(
+                99,
+                SCHEMA_VERSION
+            ))
+        ));
+    }
+
     #[test]
     fn test_parse_all_statuses() {
         let ron_str = r#"
@@ -407,12 +1242,98 @@ TestlistResults(
         assert_eq!(results.results[4].status, Status::Skipped);
     }
 
+    #[test]
+    fn test_parse_v3_and_blocked_status() {
+        let ron_str = r#"
+TestlistResults(
+    schema_version: 3,
+    meta: ResultsMeta(testlist: "test.ron", tester: "bob", sessions: [], completed: None),
+    results: [
+        TestResult(test_id: "t1", status: Passed, notes: None, screenshots: [], completed_at: None),
+    ],
+)
+"#;
+        let mut results = TestlistResults::parse(ron_str, &make_testlist()).unwrap();
+        assert_eq!(results.schema_version, SCHEMA_VERSION);
+
+        results.results[0].status = Status::Blocked;
+        results.results[0].blocked_reason = Some("no device".to_string());
+        let saved = ron::to_string(&results).unwrap();
+        let reloaded = TestlistResults::parse(&saved, &make_testlist()).unwrap();
+        assert_eq!(reloaded.results[0].status, Status::Blocked);
+        assert_eq!(
+            reloaded.results[0].blocked_reason.as_deref(),
+            Some("no device")
+        );
+        assert_eq!("in-progress".parse::<Status>().unwrap(), Status::InProgress);
+    }
+
+    #[test]
+    fn test_custom_status_round_trip() {
+        let def = StatusDef {
+            name: "Needs info".to_string(),
+            icon: "…".to_string(),
+            color: None,
+            counts_as_complete: false,
+        };
+        let mut results = TestlistResults::new_for_testlist(&make_testlist(), "test.ron", "bob");
+        assert!(results.set_custom_status("t1", &def));
+        let saved = ron::to_string(&results).unwrap();
+        let reloaded = TestlistResults::parse(&saved, &make_testlist()).unwrap();
+        let result = &reloaded.results[0];
+        assert_eq!(result.status, Status::Custom);
+        assert_eq!(result.custom_status.as_deref(), Some("Needs info"));
+        assert!(result.is_open(&make_testlist()));
+        assert!(result.completed_at.is_none());
+        assert_eq!(result.status_history.len(), 1);
+    }
+
+    #[test]
+    fn test_clear_checklist_only_affects_one_test() {
+        let mut results = TestlistResults::new_for_testlist(&make_testlist(), "test.ron", "tester");
+        for key in ["t1:setup:s0", "t1:verify:v0", "t10:setup:s0"] {
+            results.checklist_results.insert(key.to_string(), true);
+        }
+        results.clear_checklist("t1");
+        let keys: Vec<&String> = results.checklist_results.keys().collect();
+        assert_eq!(keys, vec!["t10:setup:s0"]);
+    }
+
+    #[test]
+    fn test_environment_set() {
+        let mut env = Environment::default();
+        env.set("product_version", "1.2.3");
+        env.set("browser", "firefox");
+        assert_eq!(env.product_version, Some("1.2.3".to_string()));
+        assert_eq!(env.custom.get("browser"), Some(&"firefox".to_string()));
+    }
+
+    #[test]
+    fn test_parse_key_value() {
+        assert_eq!(
+            parse_key_value(" db = postgres 16 "),
+            Some(("db".to_string(), "postgres 16".to_string()))
+        );
+        assert_eq!(
+            parse_key_value("url=http://x?a=b"),
+            Some(("url".to_string(), "http://x?a=b".to_string()))
+        );
+        assert_eq!(parse_key_value("novalue"), None);
+        assert_eq!(parse_key_value("=x"), None);
+    }
+
     #[test]
     fn test_results_save_load_roundtrip() {
         let testlist = make_testlist();
         let mut results = TestlistResults::new_for_testlist(&testlist, "test.ron", "alice");
         results.results[0].status = Status::Passed;
         results.results[0].notes = Some("This worked!".to_string());
+        let mut env = Environment {
+            os: "linux".to_string(),
+            ..Default::default()
+        };
+        env.set("browser", "firefox");
+        results.meta.environment = Some(env.clone());
         results
             .checklist_results
             .insert("t1:setup:setup-0".to_string(), true);
@@ -424,11 +1345,14 @@ TestlistResults(
         let temp_path = temp_file.path().to_path_buf();
 
         results.save(&temp_path).unwrap();
+        let content = std::fs::read_to_string(&temp_path).unwrap();
+        assert_eq!(schema_version(&content).unwrap(), SCHEMA_VERSION);
         let loaded = TestlistResults::load(&temp_path, &testlist).unwrap();
 
         assert_eq!(loaded.meta.tester, "alice");
         assert_eq!(loaded.results[0].status, Status::Passed);
         assert_eq!(loaded.results[0].notes, Some("This worked!".to_string()));
+        assert_eq!(loaded.meta.environment, Some(env));
         assert_eq!(
             loaded.checklist_results.get("t1:setup:setup-0"),
             Some(&true)
@@ -438,4 +1362,37 @@ TestlistResults(
             Some(&true)
         );
     }
+
+    #[test]
+    fn test_save_replaces_file_and_cleans_up() {
+        let testlist = make_testlist();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("t.results.ron");
+        std::fs::write(&path, "old").unwrap();
+        // A temp file left behind by a crashed save is overwritten
+        std::fs::write(temp_path(&path), "partial").unwrap();
+
+        let results = TestlistResults::new_for_testlist(&testlist, "test.ron", "alice");
+        results.save(&path).unwrap();
+
+        assert!(TestlistResults::load(&path, &testlist).is_ok());
+        assert!(!temp_path(&path).exists());
+    }
+
+    #[test]
+    fn test_failed_save_keeps_previous_results() {
+        let testlist = make_testlist();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("t.results.ron");
+        let mut results = TestlistResults::new_for_testlist(&testlist, "test.ron", "alice");
+        results.save(&path).unwrap();
+
+        // Make the temp file impossible to create, interrupting the save
+        std::fs::create_dir(temp_path(&path)).unwrap();
+        results.results[0].status = Status::Failed;
+        assert!(results.save(&path).is_err());
+
+        let loaded = TestlistResults::load(&path, &testlist).unwrap();
+        assert_eq!(loaded.results[0].status, Status::Pending);
+    }
 }
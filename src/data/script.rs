@@ -0,0 +1,153 @@
+//! Changes requested by testlist scripts.
+
+use super::definition::Testlist;
+use super::results::{checklist_key, ChecklistSection, Status, TestlistResults};
+
+/// A change a script asked for, applied once the script has finished.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptAction {
+    SetStatus {
+        test_id: String,
+        status: Status,
+    },
+    SetChecked {
+        test_id: String,
+        section: ChecklistSection,
+        item_id: String,
+        checked: bool,
+    },
+    AddNote {
+        test_id: String,
+        text: String,
+    },
+}
+
+/// Apply script actions to `results`, skipping ones that name a test or
+/// checklist item the testlist doesn't have. With `require_checks`, a test
+/// isn't passed while any of its verify items is unchecked. Returns how many
+/// were applied.
+pub fn apply_actions(
+    testlist: &Testlist,
+    results: &mut TestlistResults,
+    actions: &[ScriptAction],
+    require_checks: bool,
+) -> usize {
+    actions
+        .iter()
+        .filter(|action| apply(testlist, results, action, require_checks))
+        .count()
+}
+
+fn apply(
+    testlist: &Testlist,
+    results: &mut TestlistResults,
+    action: &ScriptAction,
+    require_checks: bool,
+) -> bool {
+    match action {
+        ScriptAction::SetStatus { test_id, status } => {
+            let unchecked = *status == Status::Passed
+                && testlist
+                    .tests
+                    .iter()
+                    .find(|t| &t.id == test_id)
+                    .is_some_and(|test| !results.may_pass(test, require_checks));
+            !unchecked && results.set_status(test_id, *status)
+        }
+        ScriptAction::SetChecked {
+            test_id,
+            section,
+            item_id,
+            checked,
+        } => {
+            let Some(test) = testlist.tests.iter().find(|t| &t.id == test_id) else {
+                return false;
+            };
+            let items = match section {
+                ChecklistSection::Setup => &test.setup,
+                ChecklistSection::Verify => &test.verify,
+            };
+            if !items.iter().any(|item| &item.id == item_id) {
+                return false;
+            }
+            results
+                .checklist_results
+                .insert(checklist_key(test_id, *section, item_id), *checked);
+            true
+        }
+        ScriptAction::AddNote { test_id, text } => match results.get_result_mut(test_id) {
+            Some(result) => {
+                result.append_note(text);
+                true
+            }
+            None => false,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::{ChecklistItem, Test};
+    use crate::fixtures;
+
+    #[test]
+    fn test_apply_actions() {
+        let testlist = fixtures::testlist(vec![Test {
+            id: "t1".to_string(),
+            verify: vec![ChecklistItem {
+                id: "v0".to_string(),
+                text: "Check".to_string(),
+            }],
+            ..Default::default()
+        }]);
+        let mut results = TestlistResults::new_for_testlist(&testlist, "t.ron", "alice");
+        let checked = |test_id: &str, item_id: &str| ScriptAction::SetChecked {
+            test_id: test_id.to_string(),
+            section: ChecklistSection::Verify,
+            item_id: item_id.to_string(),
+            checked: true,
+        };
+        let actions = vec![
+            checked("t1", "v0"),
+            checked("t1", "v9"),
+            ScriptAction::SetStatus {
+                test_id: "t1".to_string(),
+                status: Status::Passed,
+            },
+            ScriptAction::SetStatus {
+                test_id: "t2".to_string(),
+                status: Status::Passed,
+            },
+            ScriptAction::AddNote {
+                test_id: "t1".to_string(),
+                text: "ok".to_string(),
+            },
+        ];
+        assert_eq!(apply_actions(&testlist, &mut results, &actions, true), 3);
+        assert_eq!(results.checklist_results.get("t1:verify:v0"), Some(&true));
+        assert_eq!(results.results[0].status, Status::Passed);
+        assert_eq!(results.results[0].notes.as_deref(), Some("ok"));
+    }
+
+    #[test]
+    fn test_apply_actions_requires_checks_to_pass() {
+        let testlist = fixtures::testlist(vec![Test {
+            id: "t1".to_string(),
+            verify: vec![ChecklistItem {
+                id: "v0".to_string(),
+                text: "Check".to_string(),
+            }],
+            ..Default::default()
+        }]);
+        let mut results = TestlistResults::new_for_testlist(&testlist, "t.ron", "alice");
+        let pass = [ScriptAction::SetStatus {
+            test_id: "t1".to_string(),
+            status: Status::Passed,
+        }];
+        assert_eq!(apply_actions(&testlist, &mut results, &pass, true), 0);
+        assert_eq!(results.results[0].status, Status::Pending);
+        assert_eq!(apply_actions(&testlist, &mut results, &pass, false), 1);
+        assert_eq!(results.results[0].status, Status::Passed);
+    }
+}
@@ -1,12 +1,17 @@
 //! Pure data types for application state.
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::net::{IpAddr, Ipv4Addr};
 use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
 
-use ratatui::style::Color;
-
-use super::definition::Testlist;
-use super::results::TestlistResults;
+use super::config::{Hooks, PaneLayout, Signing, Webhook, DEFAULT_BACKUPS};
+use super::copy_mode::CopyMode;
+use super::definition::{Format, Testlist};
+use super::editor::TextEditor;
+use super::results::ChecklistSection;
+use super::results::{Severity, Status, TestResult, TestlistResults, Verdict};
+use super::theme::Theme;
 
 /// Which pane is currently focused.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -27,77 +32,320 @@ impl FocusedPane {
     }
 }
 
-/// Theme for the TUI.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-pub enum Theme {
+/// A selectable header row in the tests pane.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaneEntry {
+    /// A section header, identified by its title.
+    Section(String),
+    /// A test header, identified by its index into `testlist.tests`.
+    Test(usize),
+}
+
+/// A pane border that can be dragged with the mouse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaneBorder {
+    /// Between the tests and notes panes.
+    TestsNotes,
+    /// Above the terminal pane.
+    Terminal,
+}
+
+/// What a row of the tests pane shows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaneRow {
+    /// A section or test header.
+    Header(PaneEntry),
+    /// A setup or verify item of an expanded test: (test index, section, item index).
+    Checklist(usize, ChecklistSection, usize),
+    /// Any other content row of an expanded test.
+    Detail(usize),
+}
+
+/// A suggested command running in the embedded terminal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunningCommand {
+    pub test_id: String,
+    pub command: String,
+    pub started: String,
+    pub started_at: Instant,
+}
+
+/// A status filter applied at launch with `--filter status=...`.
+///
+/// The matching tests are fixed when the filter is applied, so a failed test
+/// stays listed after it is re-run and passes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusFilter {
+    pub statuses: Vec<Status>,
+    pub test_ids: Vec<String>,
+}
+
+/// Which tests the tests pane shows by status, cycled with `v`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StatusView {
     #[default]
-    Dark,
-    Light,
+    All,
+    /// Tests still to be done: Pending, Retest, In progress, or Blocked.
+    Pending,
+    Failed,
+    Blocked,
 }
 
-impl Theme {
-    pub fn toggle(self) -> Self {
+impl StatusView {
+    /// The view after this one: All, Pending, Failed, Blocked, then All again.
+    pub fn next(self) -> Self {
         match self {
-            Theme::Dark => Theme::Light,
-            Theme::Light => Theme::Dark,
+            StatusView::All => StatusView::Pending,
+            StatusView::Pending => StatusView::Failed,
+            StatusView::Failed => StatusView::Blocked,
+            StatusView::Blocked => StatusView::All,
         }
     }
 
-    pub fn bg(self) -> Color {
+    /// Check whether a test with `result` is shown in this view.
+    pub fn shows(self, testlist: &Testlist, result: &TestResult) -> bool {
         match self {
-            Theme::Dark => Color::Black,
-            Theme::Light => Color::White,
+            StatusView::All => true,
+            StatusView::Pending => result.is_open(testlist),
+            StatusView::Failed => result.status == Status::Failed,
+            StatusView::Blocked => result.status == Status::Blocked,
         }
     }
+}
 
-    pub fn fg(self) -> Color {
-        match self {
-            Theme::Dark => Color::White,
-            Theme::Light => Color::Black,
+/// Field of the failure dialog that has focus.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CaptureField {
+    #[default]
+    Note,
+    Severity,
+    Defect,
+    Screenshot,
+}
+
+/// A failure being recorded in the failure dialog: the status the test
+/// will get once it's confirmed, and the details entered so far. Empty
+/// fields are left out of the result.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FailureCapture {
+    /// `Failed` or `Inconclusive`.
+    pub status: Status,
+    pub field: CaptureField,
+    pub note: String,
+    /// Only asked for a failure.
+    pub severity: Option<Severity>,
+    /// Bug tracker ID or URL; only asked for a failure.
+    pub defect: String,
+    pub screenshot: String,
+}
+
+impl FailureCapture {
+    pub fn new(status: Status) -> Self {
+        Self {
+            status,
+            ..Default::default()
         }
     }
 
-    pub fn dim(self) -> Color {
-        match self {
-            Theme::Dark => Color::DarkGray,
-            Theme::Light => Color::Gray,
+    /// The fields the dialog shows, in order.
+    pub fn fields(&self) -> &'static [CaptureField] {
+        if self.status == Status::Failed {
+            &[
+                CaptureField::Note,
+                CaptureField::Severity,
+                CaptureField::Defect,
+                CaptureField::Screenshot,
+            ]
+        } else {
+            &[CaptureField::Note, CaptureField::Screenshot]
         }
     }
 
-    pub fn selection_bg(self) -> Color {
-        match self {
-            Theme::Dark => Color::DarkGray,
-            Theme::Light => Color::LightBlue,
+    /// The text of the focused field, unless it's the severity.
+    pub fn input_mut(&mut self) -> Option<&mut String> {
+        match self.field {
+            CaptureField::Note => Some(&mut self.note),
+            CaptureField::Severity => None,
+            CaptureField::Defect => Some(&mut self.defect),
+            CaptureField::Screenshot => Some(&mut self.screenshot),
         }
     }
+}
 
-    pub fn accent(self) -> Color {
-        match self {
-            Theme::Dark => Color::Cyan,
-            Theme::Light => Color::Blue,
+/// A filter given on the command line with `--filter KEY=VALUE`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LaunchFilter {
+    /// `status=failed,retest`: tests with any of the statuses.
+    Status(Vec<Status>),
+    /// `tag=smoke`: tests with the tag.
+    Tag(String),
+}
+
+impl std::str::FromStr for LaunchFilter {
+    type Err = crate::error::Error;
+
+    fn from_str(s: &str) -> crate::error::Result<Self> {
+        let unknown = || crate::error::Error::UnknownFilter(s.to_string());
+        let (key, value) = s.split_once('=').ok_or_else(unknown)?;
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(unknown());
+        }
+        match key.trim().to_ascii_lowercase().as_str() {
+            "status" => value
+                .split(',')
+                .map(|status| status.trim().parse())
+                .collect::<crate::error::Result<_>>()
+                .map(LaunchFilter::Status),
+            "tag" => Ok(LaunchFilter::Tag(value.to_string())),
+            _ => Err(unknown()),
         }
     }
 }
 
+/// Results of an earlier run shown next to the current ones by `testlist compare`.
+#[derive(Debug, Clone)]
+pub struct Comparison {
+    /// Name shown in the UI, usually the results file name.
+    pub label: String,
+    pub results: TestlistResults,
+}
+
 /// Pure application state — no methods with side effects.
 pub struct AppState {
     pub testlist: Testlist,
     pub results: TestlistResults,
     pub testlist_path: PathBuf,
     pub results_path: PathBuf,
+    // Format the testlist file is read in, for reloading it
+    pub testlist_format: Format,
+    // Modification time of the testlist file when it was last loaded
+    pub testlist_modified: Option<SystemTime>,
+    // Show the reload dialog after the testlist changed on disk
+    pub confirm_reload: bool,
+    // Baseline results shown alongside the current ones in compare mode
+    pub comparison: Option<Comparison>,
     pub selected_test: usize,
     pub focused_pane: FocusedPane,
     pub expanded_tests: HashSet<String>,
+    // Section titles whose tests are hidden in the tests pane
+    pub collapsed_sections: HashSet<String>,
+    // Section header under the cursor; when set it takes precedence over selected_test
+    pub selected_section: Option<String>,
     pub should_quit: bool,
     // Notes editing state
     pub editing_notes: bool,
-    pub notes_input: String,
+    pub notes_input: TextEditor,
     pub adding_screenshot: bool,
     pub screenshot_input: String,
+    // Tab-completion candidates and validation error for the screenshot path
+    pub screenshot_completions: Vec<String>,
+    pub screenshot_error: Option<String>,
+    // Screenshot tool command (`{path}` is replaced by the output file); auto-detected when unset
+    pub screenshot_command: Option<String>,
+    // Copy attachments into the results assets directory and store relative paths
+    pub copy_attachments: bool,
+    pub adding_env: bool,
+    pub env_input: String,
+    // Entering a bug tracker ID or URL for the selected failed test
+    pub adding_defect: bool,
+    pub defect_input: String,
+    // Blocked reason input mode
+    pub adding_blocked_reason: bool,
+    pub blocked_reason_input: String,
+    // Review mode: the reviewer signing off results, and the verdict whose
+    // comment is being entered
+    pub reviewer: Option<String>,
+    pub review_verdict: Option<Verdict>,
+    pub review_input: String,
+    // Jump-to-test prompt, accepting a test ID or 1-based number
+    pub goto_active: bool,
+    pub goto_input: String,
+    // Waiting for a digit to pick which reference to open
+    pub choosing_reference: bool,
+    // Waiting for i/t/c to pick what of the selected test `y` copies
+    pub choosing_copy: bool,
+    // Waiting for a digit to pick one of the testlist's custom statuses
+    pub choosing_custom_status: bool,
+    // Offering to pass the selected test after its last verify item was checked
+    pub suggesting_pass: bool,
+    // Failure dialog asking for the details of a failure before it's marked
+    pub failure_capture: Option<FailureCapture>,
+    // Command line typed into the embedded terminal since the last Enter;
+    // `terminal_line_edited` is set once it was changed by keys we can't track
+    pub terminal_line: String,
+    pub terminal_line_edited: bool,
+    // Backups kept when overwriting the results file (from the config file)
+    pub backup_count: usize,
+    // Whether a save this session has backed up the results file; autosave
+    // only makes the first backup
+    pub results_backed_up: bool,
+    // Reject unknown fields when reloading the testlist (`--strict` or config)
+    pub strict: bool,
+    // Require all verify items checked before Passed (config), on top of the
+    // testlist's own `require_checks`
+    pub require_checks: bool,
+    // Require a note for failures, and a severity for Failed (config), on
+    // top of the testlist's own settings
+    pub require_failure_notes: bool,
+    pub require_severity: bool,
+    // Note templates from the config file, on top of the testlist's own
+    pub note_templates: BTreeMap<String, String>,
+    // Waiting for a digit to pick the note template to insert
+    pub choosing_note_template: bool,
+    // Shell for the embedded terminal (None = default shell)
+    pub shell: Option<String>,
+    // Save results this often while dirty (None = only on `w` and quit)
+    pub autosave_interval: Option<Duration>,
+    // Save by appending to the journal; the results file is written on exit
+    pub append_only: bool,
+    // A save was made to the journal, to be marked there once this
+    // iteration's changes are journaled
+    pub journal_save_pending: bool,
+    // Normal-mode key remapping from the config file
+    pub keymap: BTreeMap<char, char>,
+    // Commands run on session events (from the config file)
+    pub hooks: Hooks,
+    // Endpoint notified of failures and session completion (from the config file)
+    pub webhook: Option<Webhook>,
+    // Signs the results file after each save (from the config file)
+    pub signing: Option<Signing>,
+    // Repository GitHub issues are filed in (None = the origin remote's)
+    pub github_repo: Option<String>,
+    // Port the progress page is served on with `--serve` (None = not served)
+    pub serve_port: Option<u16>,
+    // Address the progress page is served on (`--serve-addr`, default localhost)
+    pub serve_addr: IpAddr,
+    // Observer mode (`testlist watch`): results are reloaded from disk and can't be changed
+    pub read_only: bool,
+    // Accessibility mode: statuses are spelled out next to their icons
+    pub accessible: bool,
+    // File that state changes are announced to for screen readers (None = off)
+    pub announce_path: Option<PathBuf>,
+    // Which optional panes are shown (from the config file)
+    pub layout: PaneLayout,
+    // Width of the tests pane in percent when the notes pane is shown
+    pub tests_width_percent: u16,
+    // Height of the terminal pane in rows, borders included
+    pub terminal_height: u16,
+    // Border being dragged with the mouse
+    pub dragging: Option<PaneBorder>,
+    // Terminal pane temporarily fills the screen
+    pub terminal_zoomed: bool,
+    // Cursor and selection while the terminal pane is in copy mode
+    pub copy_mode: Option<CopyMode>,
+    // Suggested command whose exit code is awaited from the terminal
+    pub running_command: Option<RunningCommand>,
+    // Run commands still going after this long are interrupted
+    pub command_timeout: Option<Duration>,
+    // Set after the TUI was suspended so the next frame repaints everything
+    pub needs_full_redraw: bool,
     // Terminal size tracking
     pub terminal_size: (u16, u16),
     // Scroll offset for tests pane
     pub tests_scroll_offset: usize,
+    // Scroll offset of the notes view; reset when the selection changes
+    pub notes_scroll: usize,
     // Visible height of tests pane (updated during draw)
     pub tests_visible_height: usize,
     // Track unsaved changes
@@ -106,12 +354,44 @@ pub struct AppState {
     pub confirm_quit: bool,
     // Show help popup
     pub show_help: bool,
+    pub show_stats: bool,
     // UI theme
     pub theme: Theme,
+    // Themes cycled by `t`: the user theme (if any) followed by the built-ins
+    pub themes: Vec<Theme>,
     // Quit dialog selection: 0 = Yes (save+quit), 1 = No (quit without saving)
     pub quit_selection: u8,
     // Skip saving on quit
     pub skip_save: bool,
+    // Active tag filter for the tests pane (None = show all tests)
+    pub tag_filter: Option<String>,
+    // Test IDs the session is restricted to with `--only` (None = all tests)
+    pub only_tests: Option<Vec<String>>,
+    // Status filter applied at launch with `--filter` (None = show all tests)
+    pub status_filter: Option<StatusFilter>,
+    // Tag filter input mode
+    pub filtering_tags: bool,
+    pub tag_filter_input: String,
+    // Incremental search: typing mode, active query, and where the search started
+    pub searching: bool,
+    pub search_query: String,
+    pub search_origin: Option<PaneEntry>,
+    // Results snapshots for undo (oldest first) and redo
+    pub undo_stack: Vec<TestlistResults>,
+    pub redo_stack: Vec<TestlistResults>,
+    // Visual mode: the entry where the range selection started
+    pub visual_anchor: Option<PaneEntry>,
+    // Tests shown by status in the tests pane (`h` and `v`)
+    pub status_view: StatusView,
+    // Order the tests pane by priority instead of definition order
+    pub sort_by_priority: bool,
+    // One-shot message shown in the status bar, cleared on the next key press
+    pub status_message: Option<String>,
+    // Test currently being timed and when its uncredited time started
+    pub timer_test_id: Option<String>,
+    pub timer_started: Option<Instant>,
+    // When this app session started, for the statistics popup
+    pub session_started: Instant,
 }
 
 impl AppState {
@@ -124,25 +404,104 @@ impl AppState {
         Self {
             testlist,
             results,
+            testlist_format: Format::from_path(&testlist_path),
+            testlist_modified: None,
+            confirm_reload: false,
+            comparison: None,
             testlist_path,
             results_path,
             selected_test: 0,
             focused_pane: FocusedPane::Tests,
             expanded_tests: HashSet::new(),
+            collapsed_sections: HashSet::new(),
+            selected_section: None,
             should_quit: false,
             editing_notes: false,
-            notes_input: String::new(),
+            notes_input: TextEditor::default(),
             adding_screenshot: false,
             screenshot_input: String::new(),
+            screenshot_completions: Vec::new(),
+            screenshot_error: None,
+            screenshot_command: None,
+            copy_attachments: false,
+            adding_env: false,
+            env_input: String::new(),
+            adding_defect: false,
+            defect_input: String::new(),
+            adding_blocked_reason: false,
+            blocked_reason_input: String::new(),
+            reviewer: None,
+            review_verdict: None,
+            review_input: String::new(),
+            goto_active: false,
+            goto_input: String::new(),
+            choosing_reference: false,
+            choosing_copy: false,
+            choosing_custom_status: false,
+            suggesting_pass: false,
+            failure_capture: None,
+            terminal_line: String::new(),
+            terminal_line_edited: false,
+            backup_count: DEFAULT_BACKUPS,
+            results_backed_up: false,
+            strict: false,
+            require_checks: false,
+            require_failure_notes: false,
+            require_severity: false,
+            note_templates: BTreeMap::new(),
+            choosing_note_template: false,
+            shell: None,
+            autosave_interval: None,
+            append_only: false,
+            journal_save_pending: false,
+            keymap: BTreeMap::new(),
+            hooks: Hooks::default(),
+            webhook: None,
+            signing: None,
+            github_repo: None,
+            serve_port: None,
+            serve_addr: Ipv4Addr::LOCALHOST.into(),
+            read_only: false,
+            accessible: false,
+            announce_path: None,
+            layout: PaneLayout::default(),
+            tests_width_percent: 50,
+            terminal_height: 8,
+            dragging: None,
+            terminal_zoomed: false,
+            copy_mode: None,
+            running_command: None,
+            command_timeout: None,
+            needs_full_redraw: false,
             terminal_size: (24, 80),
             tests_scroll_offset: 0,
+            notes_scroll: 0,
             tests_visible_height: 20,
             dirty: false,
             confirm_quit: false,
             show_help: false,
-            theme: Theme::Dark,
+            show_stats: false,
+            theme: Theme::DARK,
+            themes: Theme::BUILTIN.to_vec(),
             quit_selection: 0,
             skip_save: false,
+            tag_filter: None,
+            only_tests: None,
+            status_filter: None,
+            status_view: StatusView::All,
+            filtering_tags: false,
+            tag_filter_input: String::new(),
+            searching: false,
+            search_query: String::new(),
+            search_origin: None,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            visual_anchor: None,
+            sort_by_priority: false,
+            status_message: None,
+            timer_test_id: None,
+            timer_started: None,
+            session_started: Instant::now(),
         }
     }
 }
@@ -151,24 +510,6 @@ impl AppState {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_theme_default_is_dark() {
-        assert_eq!(Theme::default(), Theme::Dark);
-    }
-
-    #[test]
-    fn test_theme_toggle() {
-        assert_eq!(Theme::Dark.toggle(), Theme::Light);
-        assert_eq!(Theme::Light.toggle(), Theme::Dark);
-    }
-
-    #[test]
-    fn test_theme_colors_differ() {
-        assert_ne!(Theme::Dark.bg(), Theme::Light.bg());
-        assert_ne!(Theme::Dark.fg(), Theme::Light.fg());
-        assert_ne!(Theme::Dark.selection_bg(), Theme::Light.selection_bg());
-    }
-
     #[test]
     fn test_focused_pane_next() {
         assert_eq!(FocusedPane::Tests.next(), FocusedPane::Notes);
@@ -181,4 +522,19 @@ mod tests {
         assert_eq!(FocusedPane::default(), FocusedPane::Tests);
     }
 
+    #[test]
+    fn test_parse_launch_filter() {
+        assert_eq!(
+            "status=failed,Retest".parse::<LaunchFilter>().unwrap(),
+            LaunchFilter::Status(vec![Status::Failed, Status::Retest])
+        );
+        assert_eq!(
+            "tag=smoke".parse::<LaunchFilter>().unwrap(),
+            LaunchFilter::Tag("smoke".to_string())
+        );
+        assert!("status=broken".parse::<LaunchFilter>().is_err());
+        assert!("owner=alice".parse::<LaunchFilter>().is_err());
+        assert!("tag=".parse::<LaunchFilter>().is_err());
+        assert!("smoke".parse::<LaunchFilter>().is_err());
+    }
 }
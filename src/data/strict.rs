@@ -0,0 +1,214 @@
+//! Strict parsing: finding fields the definition and results types don't
+//! know, which serde otherwise drops silently.
+//!
+//! The known fields of each type are taken from its derived `Deserialize`
+//! impl, so they stay in sync with the types. Maps with free-form keys
+//! (`matrix`, `parameters`, `custom`, `checklist_results`) are not checked.
+
+use std::path::Path;
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde_json::Value;
+
+use super::definition::{ChecklistItem, Format, Meta, Section, StatusDef, Test, Testlist};
+use super::results::{
+    Attempt, CommandRun, Environment, GitInfo, Provenance, ResultsMeta, Review, Session,
+    StatusChange, TestResult, TestlistResults,
+};
+
+/// Deserializer that records the field names passed to `deserialize_struct`
+/// and fails every other request.
+struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+impl<'de> Deserializer<'de> for FieldNames<'_> {
+    type Error = de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(de::Error::custom("not a struct"))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        *self.0 = fields;
+        Err(de::Error::custom("field names recorded"))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
+
+/// The fields a struct accepts, and the shapes of those holding structs
+/// (directly, in an `Option`, or in a list).
+struct Shape {
+    fields: &'static [&'static str],
+    nested: Vec<(&'static str, Shape)>,
+}
+
+fn shape<'de, T: Deserialize<'de>>(nested: Vec<(&'static str, Shape)>) -> Shape {
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldNames(&mut fields));
+    Shape { fields, nested }
+}
+
+fn test_shape() -> Shape {
+    shape::<Test>(vec![
+        ("setup", shape::<ChecklistItem>(vec![])),
+        ("verify", shape::<ChecklistItem>(vec![])),
+    ])
+}
+
+fn testlist_shape() -> Shape {
+    shape::<Testlist>(vec![
+        ("meta", shape::<Meta>(vec![])),
+        ("tests", test_shape()),
+        ("sections", shape::<Section>(vec![("tests", test_shape())])),
+        ("statuses", shape::<StatusDef>(vec![])),
+    ])
+}
+
+fn results_shape() -> Shape {
+    shape::<TestlistResults>(vec![
+        (
+            "meta",
+            shape::<ResultsMeta>(vec![
+                ("sessions", shape::<Session>(vec![])),
+                ("environment", shape::<Environment>(vec![])),
+                ("git", shape::<GitInfo>(vec![])),
+            ]),
+        ),
+        (
+            "results",
+            shape::<TestResult>(vec![
+                ("command_runs", shape::<CommandRun>(vec![])),
+                ("attempts", shape::<Attempt>(vec![])),
+                ("status_history", shape::<StatusChange>(vec![])),
+                ("merged_from", shape::<Provenance>(vec![])),
+                ("review", shape::<Review>(vec![])),
+            ]),
+        ),
+    ])
+}
+
+/// Collect the paths of unknown fields in `value`, e.g. `tests[2].sugested_command`.
+fn collect_unknown(value: &Value, shape: &Shape, path: &str, unknown: &mut Vec<String>) {
+    match value {
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                collect_unknown(item, shape, &format!("{}[{}]", path, i), unknown);
+            }
+        }
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                let field_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                if !shape.fields.contains(&key.as_str()) {
+                    unknown.push(field_path);
+                } else if let Some((_, nested)) = shape.nested.iter().find(|(name, _)| name == key)
+                {
+                    collect_unknown(value, nested, &field_path, unknown);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn ron_value(content: &str) -> crate::error::Result<Value> {
+    let value: ron::Value = ron::from_str(content)?;
+    Ok(serde_json::to_value(value)?)
+}
+
+/// Unknown fields in a testlist definition, ordered by field name.
+pub fn unknown_testlist_fields(content: &str, format: Format) -> crate::error::Result<Vec<String>> {
+    let value = match format {
+        Format::Ron => ron_value(content)?,
+        Format::Yaml => serde_yaml::from_str(content)?,
+        Format::Json => serde_json::from_str(content)?,
+    };
+    let mut unknown = Vec::new();
+    collect_unknown(&value, &testlist_shape(), "", &mut unknown);
+    Ok(unknown)
+}
+
+/// Unknown fields in a results file of the current schema version.
+pub fn unknown_results_fields(content: &str) -> crate::error::Result<Vec<String>> {
+    let mut unknown = Vec::new();
+    collect_unknown(&ron_value(content)?, &results_shape(), "", &mut unknown);
+    Ok(unknown)
+}
+
+/// Fail with [`Error::UnknownFields`](crate::error::Error::UnknownFields)
+/// if any unknown fields were found in `path`.
+pub fn reject_unknown(path: &Path, unknown: Vec<String>) -> crate::error::Result<()> {
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    Err(crate::error::Error::UnknownFields(
+        path.to_path_buf(),
+        unknown.join(", "),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::results::Status;
+    use crate::fixtures;
+
+    #[test]
+    fn test_unknown_testlist_fields() {
+        let yaml = r#"
+meta: { title: T, description: "", created: "", version: "1" }
+tests:
+  - id: a
+    title: A
+    description: ""
+    action: Do it
+    sugested_command: make
+    setup: ["Plain step", { id: s1, text: Step, done: true }]
+    matrix: { browser: [firefox] }
+sections:
+  - title: S
+    tests: []
+    colour: red
+"#;
+        assert_eq!(
+            unknown_testlist_fields(yaml, Format::Yaml).unwrap(),
+            vec![
+                "sections[0].colour",
+                "tests[0].setup[1].done",
+                "tests[0].sugested_command",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_results_without_unknown_fields() {
+        let testlist = fixtures::testlist(vec![Test {
+            id: "a".to_string(),
+            ..Default::default()
+        }]);
+        let mut results = TestlistResults::new_for_testlist(&testlist, "t.ron", "alice");
+        results.start_session("alice", None);
+        results.results[0].change_status(Status::Failed, "now", "alice");
+        let content =
+            ron::ser::to_string_pretty(&results, ron::ser::PrettyConfig::default()).unwrap();
+        assert!(unknown_results_fields(&content).unwrap().is_empty());
+
+        let typo = content.replacen("notes:", "note:", 1);
+        assert_eq!(
+            unknown_results_fields(&typo).unwrap(),
+            vec!["results[0].note"]
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! Built-in testlist templates for `--new`.
+
+use crate::error::{Error, Result};
+
+/// A named starting point for a new testlist, in RON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Template {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub source: &'static str,
+}
+
+/// Template used when `--template` isn't given.
+pub const DEFAULT_TEMPLATE: &str = "cargo";
+
+/// All built-in templates, in the order `--list-templates` shows them.
+pub const TEMPLATES: &[Template] = &[
+    Template {
+        name: "cargo",
+        description: "Build, test, and check a Rust project by hand",
+        source: include_str!("../templates/cargo.testlist.ron"),
+    },
+    Template {
+        name: "release",
+        description: "Release checklist: changelog, CI, artifacts, publishing",
+        source: include_str!("../templates/release.testlist.ron"),
+    },
+    Template {
+        name: "ui-smoke",
+        description: "Quick pass over the main screens of an application",
+        source: include_str!("../templates/ui-smoke.testlist.ron"),
+    },
+    Template {
+        name: "api",
+        description: "Manual HTTP API checks with curl commands",
+        source: include_str!("../templates/api.testlist.ron"),
+    },
+    Template {
+        name: "accessibility",
+        description: "Keyboard, screen reader, contrast, and zoom audit",
+        source: include_str!("../templates/accessibility.testlist.ron"),
+    },
+];
+
+/// A built-in template by name.
+pub fn find(name: &str) -> Result<&'static Template> {
+    TEMPLATES
+        .iter()
+        .find(|t| t.name.eq_ignore_ascii_case(name))
+        .ok_or_else(|| Error::UnknownTemplate(name.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::{Format, Testlist};
+
+    #[test]
+    fn test_templates_parse() {
+        for template in TEMPLATES {
+            let testlist = Testlist::parse(template.source, Format::Ron)
+                .unwrap_or_else(|e| panic!("{}: {}", template.name, e));
+            assert!(!testlist.tests.is_empty(), "{}", template.name);
+        }
+        assert_eq!(find(DEFAULT_TEMPLATE).unwrap().name, "cargo");
+        assert_eq!(find("Release").unwrap().name, "release");
+        assert!(matches!(find("mobile"), Err(Error::UnknownTemplate(_))));
+    }
+}
@@ -0,0 +1,342 @@
+//! Color themes, built in or loaded from a user theme file.
+
+use std::str::FromStr;
+
+use ratatui::style::Color;
+use serde::Deserialize;
+
+use super::definition::{Priority, StatusDef};
+use super::results::Status;
+use crate::error::{Error, Result};
+
+/// Colors used by the TUI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    bg: Color,
+    fg: Color,
+    dim: Color,
+    selection_bg: Color,
+    accent: Color,
+    passed: Color,
+    failed: Color,
+    inconclusive: Color,
+    skipped: Color,
+    critical: Color,
+    high: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::DARK
+    }
+}
+
+impl Theme {
+    pub const DARK: Theme = Theme {
+        bg: Color::Black,
+        fg: Color::White,
+        dim: Color::DarkGray,
+        selection_bg: Color::DarkGray,
+        accent: Color::Cyan,
+        passed: Color::LightGreen,
+        failed: Color::LightRed,
+        inconclusive: Color::Yellow,
+        skipped: Color::DarkGray,
+        critical: Color::Red,
+        high: Color::Yellow,
+    };
+
+    pub const LIGHT: Theme = Theme {
+        bg: Color::White,
+        fg: Color::Black,
+        dim: Color::Gray,
+        selection_bg: Color::LightBlue,
+        accent: Color::Blue,
+        passed: Color::Green,
+        failed: Color::Red,
+        inconclusive: Color::Rgb(0xb8, 0x86, 0x0b),
+        skipped: Color::Gray,
+        critical: Color::Red,
+        high: Color::Rgb(0xb8, 0x86, 0x0b),
+    };
+
+    /// Bright colors on black, with a selection that stands out from every
+    /// status color; the default in accessibility mode.
+    pub const HIGH_CONTRAST: Theme = Theme {
+        bg: Color::Black,
+        fg: Color::White,
+        dim: Color::Gray,
+        selection_bg: Color::Blue,
+        accent: Color::LightCyan,
+        passed: Color::LightGreen,
+        failed: Color::LightRed,
+        inconclusive: Color::LightYellow,
+        skipped: Color::Gray,
+        critical: Color::LightRed,
+        high: Color::LightYellow,
+    };
+
+    /// The built-in themes, in the order `t` cycles through them.
+    pub const BUILTIN: [Theme; 3] = [Theme::DARK, Theme::LIGHT, Theme::HIGH_CONTRAST];
+
+    /// The theme after this one in `themes`, wrapping around to the first;
+    /// the first theme if this one isn't listed.
+    pub fn next_in(self, themes: &[Theme]) -> Option<Theme> {
+        let next = themes
+            .iter()
+            .position(|t| *t == self)
+            .map_or(0, |i| (i + 1) % themes.len());
+        themes.get(next).copied()
+    }
+
+    /// A built-in theme by name: `"dark"`, `"light"`, or `"high-contrast"`.
+    pub fn builtin(name: &str) -> Result<Theme> {
+        match name {
+            "dark" => Ok(Theme::DARK),
+            "light" => Ok(Theme::LIGHT),
+            "high-contrast" => Ok(Theme::HIGH_CONTRAST),
+            other => Err(Error::InvalidTheme(format!(
+                "unknown base theme '{}' (expected dark, light, or high-contrast)",
+                other
+            ))),
+        }
+    }
+
+    pub fn bg(self) -> Color {
+        self.bg
+    }
+
+    pub fn fg(self) -> Color {
+        self.fg
+    }
+
+    pub fn dim(self) -> Color {
+        self.dim
+    }
+
+    pub fn selection_bg(self) -> Color {
+        self.selection_bg
+    }
+
+    pub fn accent(self) -> Color {
+        self.accent
+    }
+
+    pub fn passed(self) -> Color {
+        self.passed
+    }
+
+    pub fn failed(self) -> Color {
+        self.failed
+    }
+
+    pub fn inconclusive(self) -> Color {
+        self.inconclusive
+    }
+
+    pub fn skipped(self) -> Color {
+        self.skipped
+    }
+
+    pub fn critical(self) -> Color {
+        self.critical
+    }
+
+    pub fn high(self) -> Color {
+        self.high
+    }
+
+    /// Color for a test status marker.
+    pub fn status_color(self, status: Status) -> Color {
+        match status {
+            Status::Pending => self.fg(),
+            Status::Passed => self.passed(),
+            Status::Failed => self.failed(),
+            Status::Inconclusive => self.inconclusive(),
+            Status::Skipped => self.skipped(),
+            Status::Retest | Status::InProgress | Status::Custom => self.accent(),
+            Status::Blocked => self.inconclusive(),
+        }
+    }
+
+    /// Color for a priority marker.
+    pub fn priority_color(self, priority: Priority) -> Color {
+        match priority {
+            Priority::Critical => self.critical(),
+            Priority::High => self.high(),
+            Priority::Medium => self.accent(),
+            Priority::Low => self.dim(),
+        }
+    }
+
+    /// Color for a custom status's icon: its own color if it has a valid
+    /// one, the accent color otherwise.
+    pub fn custom_status_color(self, def: &StatusDef) -> Color {
+        def.color
+            .as_deref()
+            .and_then(|text| Color::from_str(text).ok())
+            .unwrap_or(self.accent())
+    }
+}
+
+/// On-disk theme definition (`~/.config/testlist/theme.ron`).
+///
+/// Every color is optional and falls back to the `base` theme (`"dark"` by
+/// default). Colors are names like `"cyan"`, `"#rrggbb"` truecolor values,
+/// or 256-color indices like `"208"`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename = "Theme", default)]
+pub struct ThemeFile {
+    pub base: Option<String>,
+    pub bg: Option<String>,
+    pub fg: Option<String>,
+    pub dim: Option<String>,
+    pub selection_bg: Option<String>,
+    pub accent: Option<String>,
+    pub passed: Option<String>,
+    pub failed: Option<String>,
+    pub inconclusive: Option<String>,
+    pub skipped: Option<String>,
+    pub critical: Option<String>,
+    pub high: Option<String>,
+}
+
+impl ThemeFile {
+    /// Parse a theme definition from RON.
+    pub fn parse(content: &str) -> Result<Self> {
+        Ok(ron::from_str(content)?)
+    }
+
+    /// Resolve the definition into a theme, validating every color.
+    pub fn into_theme(self) -> Result<Theme> {
+        let base = match self.base.as_deref() {
+            Some(name) => Theme::builtin(name)?,
+            None => Theme::DARK,
+        };
+        let color = |value: Option<String>, default: Color| -> Result<Color> {
+            match value {
+                Some(text) => Color::from_str(&text)
+                    .map_err(|_| Error::InvalidTheme(format!("invalid color '{}'", text))),
+                None => Ok(default),
+            }
+        };
+        Ok(Theme {
+            bg: color(self.bg, base.bg)?,
+            fg: color(self.fg, base.fg)?,
+            dim: color(self.dim, base.dim)?,
+            selection_bg: color(self.selection_bg, base.selection_bg)?,
+            accent: color(self.accent, base.accent)?,
+            passed: color(self.passed, base.passed)?,
+            failed: color(self.failed, base.failed)?,
+            inconclusive: color(self.inconclusive, base.inconclusive)?,
+            skipped: color(self.skipped, base.skipped)?,
+            critical: color(self.critical, base.critical)?,
+            high: color(self.high, base.high)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_default_is_dark() {
+        assert_eq!(Theme::default(), Theme::DARK);
+    }
+
+    #[test]
+    fn test_builtin_theme_by_name() {
+        assert_eq!(Theme::builtin("light").unwrap(), Theme::LIGHT);
+        assert_eq!(Theme::builtin("dark").unwrap(), Theme::DARK);
+        assert_eq!(
+            Theme::builtin("high-contrast").unwrap(),
+            Theme::HIGH_CONTRAST
+        );
+        assert!(Theme::builtin("solarized").is_err());
+    }
+
+    #[test]
+    fn test_next_in_cycles_through_every_theme() {
+        let user = ThemeFile::parse(r##"Theme(accent: Some("#ff8800"))"##)
+            .unwrap()
+            .into_theme()
+            .unwrap();
+        let mut themes = vec![user];
+        themes.extend(Theme::BUILTIN);
+
+        let mut theme = themes[0];
+        for expected in &themes[1..] {
+            theme = theme.next_in(&themes).unwrap();
+            assert_eq!(theme, *expected);
+        }
+        assert_eq!(theme.next_in(&themes), Some(user));
+        assert_eq!(user.next_in(&Theme::BUILTIN), Some(Theme::DARK));
+        assert_eq!(Theme::DARK.next_in(&[]), None);
+    }
+
+    #[test]
+    fn test_theme_colors_differ() {
+        assert_ne!(Theme::DARK.bg(), Theme::LIGHT.bg());
+        assert_ne!(Theme::DARK.fg(), Theme::LIGHT.fg());
+        assert_ne!(Theme::DARK.selection_bg(), Theme::LIGHT.selection_bg());
+    }
+
+    #[test]
+    fn test_status_colors_distinct() {
+        for theme in [Theme::DARK, Theme::LIGHT, Theme::HIGH_CONTRAST] {
+            let passed = theme.status_color(Status::Passed);
+            let failed = theme.status_color(Status::Failed);
+            let inconclusive = theme.status_color(Status::Inconclusive);
+            assert_ne!(passed, failed);
+            assert_ne!(failed, inconclusive);
+            assert_ne!(passed, inconclusive);
+            assert_eq!(theme.status_color(Status::Skipped), theme.skipped());
+        }
+    }
+
+    #[test]
+    fn test_priority_colors_follow_theme() {
+        assert_ne!(
+            Theme::DARK.priority_color(Priority::High),
+            Theme::LIGHT.priority_color(Priority::High)
+        );
+        let theme = ThemeFile::parse(r#"Theme(critical: Some("magenta"))"#)
+            .unwrap()
+            .into_theme()
+            .unwrap();
+        assert_eq!(theme.priority_color(Priority::Critical), Color::Magenta);
+        assert_eq!(theme.priority_color(Priority::High), Theme::DARK.high());
+        assert_eq!(theme.priority_color(Priority::Low), theme.dim());
+    }
+
+    #[test]
+    fn test_theme_file_overrides_base() {
+        let file = ThemeFile::parse(
+            r##"Theme(base: Some("light"), accent: Some("#ff8800"), passed: Some("cyan"))"##,
+        )
+        .unwrap();
+        let theme = file.into_theme().unwrap();
+        assert_eq!(theme.accent(), Color::Rgb(0xff, 0x88, 0x00));
+        assert_eq!(theme.passed(), Color::Cyan);
+        assert_eq!(theme.bg(), Theme::LIGHT.bg());
+    }
+
+    #[test]
+    fn test_theme_file_defaults_to_dark() {
+        let theme = ThemeFile::parse("Theme()").unwrap().into_theme().unwrap();
+        assert_eq!(theme, Theme::DARK);
+    }
+
+    #[test]
+    fn test_theme_file_rejects_bad_color() {
+        let file = ThemeFile::parse(r#"Theme(fg: Some("not-a-color"))"#).unwrap();
+        assert!(matches!(file.into_theme(), Err(Error::InvalidTheme(_))));
+    }
+
+    #[test]
+    fn test_theme_file_rejects_unknown_base() {
+        let file = ThemeFile::parse(r#"Theme(base: Some("solarized"))"#).unwrap();
+        assert!(matches!(file.into_theme(), Err(Error::InvalidTheme(_))));
+    }
+}
@@ -15,14 +15,109 @@ pub enum Error {
     #[error("Failed to serialize RON: {0}")]
     Serialize(#[from] ron::Error),
 
+    #[error("YAML error: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("Invalid column map: {0}")]
+    InvalidColumnMap(String),
+
+    #[error("XML error: {0}")]
+    Xml(#[from] roxmltree::Error),
+
+    #[error("Not a TestRail export: {0}")]
+    InvalidTestRail(String),
+
+    #[error("Unknown format: {0} (expected ron, yaml, or json)")]
+    UnknownFormat(String),
+
+    #[error("Unknown merge policy: {0} (expected worst-status, latest, or interactive)")]
+    UnknownMergePolicy(String),
+
+    #[error("Unknown report format: {0} (expected markdown or html)")]
+    UnknownReportFormat(String),
+
+    #[error("Unknown export format: {0} (expected junit, json, tap, or testrail)")]
+    UnknownExportFormat(String),
+
+    #[error(
+        "Unknown status: {0} (expected pending, passed, failed, inconclusive, skipped, retest, in-progress, or blocked)"
+    )]
+    UnknownStatus(String),
+
+    #[error("Unknown filter: {0} (expected status=STATUS or tag=TAG)")]
+    UnknownFilter(String),
+
+    #[error("Unknown severity: {0} (expected blocker, major, minor, or cosmetic)")]
+    UnknownSeverity(String),
+
+    #[error("Unknown field(s) in {0}: {1}")]
+    UnknownFields(PathBuf, String),
+
     #[error("Testlist file not found: {0}")]
     TestlistNotFound(PathBuf),
 
     #[error("Invalid test ID: {0}")]
     InvalidTestId(String),
 
+    #[error("Unknown checklist item: {0}")]
+    UnknownChecklistItem(String),
+
+    #[error("Can't pass {0}: {1} verify item(s) unchecked")]
+    UncheckedVerifyItems(String, usize),
+
+    #[error("Unknown checklist section: {0} (expected setup or verify)")]
+    UnknownChecklistSection(String),
+
+    #[error("Results file has schema version {0}; this build supports up to {1}")]
+    UnsupportedSchemaVersion(u32, u32),
+
     #[error("Results file not found: {0}")]
     ResultsNotFound(PathBuf),
+
+    #[error("Include cycle detected at: {0}")]
+    IncludeCycle(PathBuf),
+
+    #[error("Duplicate test ID '{0}' in {1}")]
+    DuplicateTestId(String, PathBuf),
+
+    #[error("Unknown template: {0} (see --list-templates)")]
+    UnknownTemplate(String),
+
+    #[error("Invalid custom status: {0}")]
+    InvalidStatus(String),
+
+    #[error("Invalid theme: {0}")]
+    InvalidTheme(String),
+
+    #[error("External editor failed: {0}")]
+    Editor(String),
+
+    #[error("Clipboard error: {0}")]
+    Clipboard(String),
+
+    #[error("Screenshot failed: {0}")]
+    Screenshot(String),
+
+    #[error("GitHub issue failed: {0}")]
+    GitHub(String),
+
+    #[error("Could not signal process: {0}")]
+    Signal(String),
+
+    #[error("Script failed: {0}")]
+    Script(String),
+
+    #[error("Signing failed: {0}")]
+    Signing(String),
+
+    #[error("Bad signature: {0}")]
+    BadSignature(String),
 }
 
 /// Result type alias using our custom Error.
@@ -63,6 +158,22 @@ mod tests {
         assert!(display.contains("Results file not found"));
     }
 
+    #[test]
+    fn test_error_display_unknown_format() {
+        let err = Error::UnknownFormat("toml".to_string());
+        let display = format!("{}", err);
+        assert!(display.contains("Unknown format"));
+        assert!(display.contains("toml"));
+    }
+
+    #[test]
+    fn test_error_display_duplicate_test_id() {
+        let err = Error::DuplicateTestId("build".to_string(), PathBuf::from("common.ron"));
+        let display = format!("{}", err);
+        assert!(display.contains("Duplicate test ID 'build'"));
+        assert!(display.contains("common.ron"));
+    }
+
     #[test]
     fn test_io_error_from() {
         let io_err = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "access denied");
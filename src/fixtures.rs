@@ -0,0 +1,38 @@
+//! Builders shared by the unit tests.
+
+use std::path::PathBuf;
+
+use crate::data::definition::{Meta, Test, Testlist};
+use crate::data::results::TestlistResults;
+use crate::data::state::AppState;
+
+/// A testlist titled "Test" with the given tests.
+pub fn testlist(tests: Vec<Test>) -> Testlist {
+    Testlist {
+        meta: Meta {
+            title: "Test".to_string(),
+            version: "1".to_string(),
+            ..Default::default()
+        },
+        tests,
+        statuses: Vec::new(),
+        source_sha256: None,
+    }
+}
+
+/// Fresh results for `testlist`, recorded by "tester".
+pub fn results(testlist: &Testlist) -> TestlistResults {
+    TestlistResults::new_for_testlist(testlist, "test.ron", "tester")
+}
+
+/// A session on `testlist` with fresh results; nothing is read from or
+/// written to its paths unless a test saves.
+pub fn state(testlist: Testlist) -> AppState {
+    let results = results(&testlist);
+    AppState::new(
+        testlist,
+        results,
+        PathBuf::from("test.testlist.ron"),
+        PathBuf::from("test.testlist.results.ron"),
+    )
+}
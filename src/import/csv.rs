@@ -0,0 +1,268 @@
+//! Import testlists from spreadsheet test cases saved as CSV.
+//!
+//! Conversion rules:
+//! - The first row is a header. Every later row with a title becomes a test.
+//! - A column map (`title=B,action=C,verify=D`) says which column holds
+//!   which field; without one, header names that match a field are used.
+//! - Setup and verify cells hold one checklist item per line, with any
+//!   leading bullet removed. Tags are separated by commas or semicolons.
+//! - IDs come from the ID column, or else from the title, made unique.
+//! - A test without an action uses its title as the action.
+
+use std::str::FromStr;
+
+use crate::data::definition::{ChecklistItem, Meta, Test, Testlist};
+use crate::error::{Error, Result};
+use crate::import::{slugify, unique_id};
+
+/// A test field that can be read from a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Id,
+    Title,
+    Description,
+    Setup,
+    Action,
+    Verify,
+    Command,
+    Section,
+    Tags,
+}
+
+impl FromStr for Field {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().replace(' ', "_").as_str() {
+            "id" => Ok(Field::Id),
+            "title" => Ok(Field::Title),
+            "description" => Ok(Field::Description),
+            "setup" => Ok(Field::Setup),
+            "action" => Ok(Field::Action),
+            "verify" => Ok(Field::Verify),
+            "command" | "suggested_command" => Ok(Field::Command),
+            "section" => Ok(Field::Section),
+            "tags" => Ok(Field::Tags),
+            _ => Err(Error::InvalidColumnMap(format!("unknown field '{}'", s))),
+        }
+    }
+}
+
+/// Turn a column letter (`A`, `B`, …, `AA`) into a zero-based index.
+fn column_index(letters: &str) -> Option<usize> {
+    if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let number = letters
+        .to_ascii_uppercase()
+        .bytes()
+        .try_fold(0usize, |n, b| {
+            n.checked_mul(26)?.checked_add((b - b'A' + 1) as usize)
+        })?;
+    Some(number - 1)
+}
+
+/// Which column each field is read from.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnMap {
+    pub columns: Vec<(Field, usize)>,
+}
+
+impl FromStr for ColumnMap {
+    type Err = Error;
+
+    /// Parse `FIELD=COLUMN` pairs separated by commas, e.g. `title=B,action=C`.
+    fn from_str(s: &str) -> Result<Self> {
+        let mut columns = Vec::new();
+        for pair in s.split(',').filter(|p| !p.trim().is_empty()) {
+            let (field, column) = pair.split_once('=').ok_or_else(|| {
+                Error::InvalidColumnMap(format!("'{}' is not FIELD=COLUMN", pair))
+            })?;
+            let field: Field = field.parse()?;
+            let column = column_index(column.trim()).ok_or_else(|| {
+                Error::InvalidColumnMap(format!("'{}' is not a column letter", column))
+            })?;
+            columns.push((field, column));
+        }
+        Ok(ColumnMap { columns })
+    }
+}
+
+impl ColumnMap {
+    /// Map every header whose name is a field to its column.
+    pub fn from_headers(headers: &csv::StringRecord) -> Self {
+        let columns = headers
+            .iter()
+            .enumerate()
+            .filter_map(|(i, name)| Some((name.parse().ok()?, i)))
+            .collect();
+        ColumnMap { columns }
+    }
+
+    fn column(&self, field: Field) -> Option<usize> {
+        self.columns
+            .iter()
+            .find(|(f, _)| *f == field)
+            .map(|(_, column)| *column)
+    }
+}
+
+/// Split a checklist cell into one item per non-empty line.
+fn checklist(prefix: &str, cell: &str) -> Vec<ChecklistItem> {
+    cell.lines()
+        .map(|line| {
+            let line = line.trim();
+            ["- ", "* ", "• "]
+                .iter()
+                .find_map(|bullet| line.strip_prefix(bullet))
+                .unwrap_or(line)
+                .trim()
+        })
+        .filter(|text| !text.is_empty())
+        .enumerate()
+        .map(|(i, text)| ChecklistItem {
+            id: format!("{}-{}", prefix, i),
+            text: text.to_string(),
+        })
+        .collect()
+}
+
+/// Convert CSV test cases into a testlist definition.
+///
+/// Without a `map`, columns are found by their header names. `source_name`
+/// becomes the testlist title and is recorded in its description.
+pub fn from_csv(data: &str, map: Option<&ColumnMap>, source_name: &str) -> Result<Testlist> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(data.as_bytes());
+    let map = match map {
+        Some(map) => map.clone(),
+        None => ColumnMap::from_headers(reader.headers()?),
+    };
+    if map.column(Field::Title).is_none() {
+        return Err(Error::InvalidColumnMap(
+            "no title column (map one with title=COLUMN)".to_string(),
+        ));
+    }
+
+    let mut tests: Vec<Test> = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let cell = |field| {
+            map.column(field)
+                .and_then(|column| record.get(column))
+                .unwrap_or("")
+                .trim()
+        };
+        let title = cell(Field::Title);
+        if title.is_empty() {
+            continue;
+        }
+        let id = match cell(Field::Id) {
+            "" => slugify(title),
+            id => id.to_string(),
+        };
+        let taken: Vec<String> = tests.iter().map(|t| t.id.clone()).collect();
+        let action = match cell(Field::Action) {
+            "" => title,
+            action => action,
+        };
+        let optional = |text: &str| (!text.is_empty()).then(|| text.to_string());
+        tests.push(Test {
+            id: unique_id(id, &taken),
+            title: title.to_string(),
+            description: cell(Field::Description).to_string(),
+            setup: checklist("setup", cell(Field::Setup)),
+            action: action.to_string(),
+            verify: checklist("verify", cell(Field::Verify)),
+            suggested_command: optional(cell(Field::Command)),
+            section: optional(cell(Field::Section)),
+            tags: cell(Field::Tags)
+                .split([',', ';'])
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect(),
+            ..Default::default()
+        });
+    }
+
+    Ok(Testlist {
+        meta: Meta {
+            title: source_name.to_string(),
+            description: format!("Imported from {}", source_name),
+            created: chrono::Utc::now().to_rfc3339(),
+            version: "1".to_string(),
+            ..Default::default()
+        },
+        tests,
+        statuses: Vec::new(),
+        source_sha256: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_map() {
+        let map: ColumnMap = "title=B, action=c,verify=AA".parse().unwrap();
+        assert_eq!(
+            map.columns,
+            vec![(Field::Title, 1), (Field::Action, 2), (Field::Verify, 26)]
+        );
+        assert!(matches!(
+            "title".parse::<ColumnMap>(),
+            Err(Error::InvalidColumnMap(_))
+        ));
+        assert!(matches!(
+            "owner=B".parse::<ColumnMap>(),
+            Err(Error::InvalidColumnMap(_))
+        ));
+        assert!(matches!(
+            "title=2".parse::<ColumnMap>(),
+            Err(Error::InvalidColumnMap(_))
+        ));
+    }
+
+    #[test]
+    fn test_mapped_columns_become_tests() {
+        let data = "\
+Case,Name,Steps,Expected
+TC-1,Login works,Open /login and sign in,\"- Redirects to dashboard
+- Shows welcome message\"
+TC-2,Login works,,
+TC-3,,ignored,
+";
+        let map = "title=B,action=C,verify=D".parse().unwrap();
+        let testlist = from_csv(data, Some(&map), "cases.csv").unwrap();
+        assert_eq!(testlist.meta.title, "cases.csv");
+        assert_eq!(testlist.tests.len(), 2);
+        let test = &testlist.tests[0];
+        assert_eq!(test.id, "login-works");
+        assert_eq!(test.action, "Open /login and sign in");
+        assert_eq!(test.verify.len(), 2);
+        assert_eq!(test.verify[0].id, "verify-0");
+        assert_eq!(test.verify[1].text, "Shows welcome message");
+        assert_eq!(testlist.tests[1].id, "login-works-2");
+        assert_eq!(testlist.tests[1].action, "Login works");
+    }
+
+    #[test]
+    fn test_header_names_without_map() {
+        let data = "ID,Title,Suggested Command,Tags,Section\n\
+                    build,Build it,cargo build,\"smoke; ci\",Core\n";
+        let testlist = from_csv(data, None, "cases.csv").unwrap();
+        let test = &testlist.tests[0];
+        assert_eq!(test.id, "build");
+        assert_eq!(test.suggested_command.as_deref(), Some("cargo build"));
+        assert_eq!(test.tags, vec!["smoke", "ci"]);
+        assert_eq!(test.section.as_deref(), Some("Core"));
+
+        assert!(matches!(
+            from_csv("Name,Steps\nx,y\n", None, "cases.csv"),
+            Err(Error::InvalidColumnMap(_))
+        ));
+    }
+}
@@ -0,0 +1,43 @@
+//! Import testlists from the checklists of GitHub issues and pull requests.
+//!
+//! The issue body is converted like a Markdown file (see
+//! [`crate::import::markdown`]), so its `- [ ]` checkboxes become tests.
+//! The issue title becomes the testlist title, and every test references
+//! the issue, so failures can be reported back to it with
+//! `testlist issue --comment`.
+
+use crate::actions::github::Issue;
+use crate::data::definition::Testlist;
+use crate::import::markdown::from_markdown;
+
+/// Convert an issue's checklist into a testlist definition.
+pub fn from_issue(issue: &Issue) -> Testlist {
+    let mut testlist = from_markdown(&issue.body, &issue.title);
+    testlist.meta.title = issue.title.clone();
+    testlist.meta.description = format!("Imported from {}", issue.url);
+    for test in &mut testlist.tests {
+        test.references.push(issue.url.clone());
+    }
+    testlist
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkboxes_become_tests() {
+        let issue = Issue {
+            title: "Release 1.2 checklist".to_string(),
+            body: "Before tagging:\r\n\r\n- [ ] Changelog updated\r\n- [x] CI green\r\n  - [ ] All platforms\r\n"
+                .to_string(),
+            url: "https://github.com/o/r/issues/7".to_string(),
+        };
+        let testlist = from_issue(&issue);
+        assert_eq!(testlist.meta.title, "Release 1.2 checklist");
+        assert_eq!(testlist.tests.len(), 2);
+        assert_eq!(testlist.tests[0].id, "changelog-updated");
+        assert_eq!(testlist.tests[1].verify[0].text, "All platforms");
+        assert_eq!(testlist.tests[1].references, vec![issue.url]);
+    }
+}
@@ -0,0 +1,197 @@
+//! Import testlists from Markdown task lists.
+//!
+//! Conversion rules:
+//! - A level-1 heading before any other heading becomes the testlist title.
+//! - Every other heading starts a test; paragraph text below it becomes the
+//!   description and every list item below it becomes a verify item.
+//! - A top-level task item (`- [ ] ...`) outside any heading starts a test of
+//!   its own, and the bullets nested under it become its verify items.
+
+use crate::data::definition::{ChecklistItem, Meta, Test, Testlist};
+use crate::import::{slugify, unique_id};
+
+/// A parsed Markdown list item.
+struct ListItem<'a> {
+    indent: usize,
+    text: &'a str,
+}
+
+/// Parse a list item (`-`, `*`, or `+`, optionally with a `[ ]`/`[x]` task box).
+fn parse_list_item(line: &str) -> Option<ListItem<'_>> {
+    let trimmed = line.trim_start();
+    let indent = line.len() - trimmed.len();
+    let rest = trimmed
+        .strip_prefix("- ")
+        .or_else(|| trimmed.strip_prefix("* "))
+        .or_else(|| trimmed.strip_prefix("+ "))?;
+    let text = ["[ ] ", "[x] ", "[X] "]
+        .iter()
+        .find_map(|box_| rest.strip_prefix(box_))
+        .unwrap_or(rest);
+    Some(ListItem {
+        indent,
+        text: text.trim(),
+    })
+}
+
+/// Parse a heading, returning its level and text.
+fn parse_heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if level == 0 || level > 6 {
+        return None;
+    }
+    let text = line[level..].strip_prefix(' ')?;
+    Some((level, text.trim()))
+}
+
+fn new_test(title: &str, tests: &[Test]) -> Test {
+    let taken: Vec<String> = tests.iter().map(|t| t.id.clone()).collect();
+    Test {
+        id: unique_id(slugify(title), &taken),
+        title: title.to_string(),
+        action: title.to_string(),
+        ..Default::default()
+    }
+}
+
+fn push_verify(test: &mut Test, text: &str) {
+    let id = format!("verify-{}", test.verify.len());
+    test.verify.push(ChecklistItem {
+        id,
+        text: text.to_string(),
+    });
+}
+
+/// Convert Markdown into a testlist definition.
+///
+/// `source_name` is recorded in the testlist description.
+pub fn from_markdown(markdown: &str, source_name: &str) -> Testlist {
+    let mut title = None;
+    let mut tests: Vec<Test> = Vec::new();
+    // Whether the current test was started by a heading (vs. a task item)
+    let mut under_heading = false;
+    let mut in_code_block = false;
+
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            continue;
+        }
+
+        if let Some((level, text)) = parse_heading(line) {
+            if level == 1 && title.is_none() && tests.is_empty() {
+                title = Some(text.to_string());
+            } else {
+                tests.push(new_test(text, &tests));
+                under_heading = true;
+            }
+            continue;
+        }
+
+        if let Some(item) = parse_list_item(line) {
+            if item.text.is_empty() {
+                continue;
+            }
+            if !under_heading && item.indent == 0 {
+                tests.push(new_test(item.text, &tests));
+            } else if let Some(test) = tests.last_mut() {
+                push_verify(test, item.text);
+            }
+            continue;
+        }
+
+        let text = line.trim();
+        if under_heading && !text.is_empty() {
+            if let Some(test) = tests.last_mut() {
+                if !test.description.is_empty() {
+                    test.description.push('\n');
+                }
+                test.description.push_str(text);
+            }
+        }
+    }
+
+    Testlist {
+        meta: Meta {
+            title: title.unwrap_or_else(|| source_name.to_string()),
+            description: format!("Imported from {}", source_name),
+            created: chrono::Utc::now().to_rfc3339(),
+            version: "1".to_string(),
+            ..Default::default()
+        },
+        tests,
+        statuses: Vec::new(),
+        source_sha256: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headings_become_tests() {
+        let md = "\
+# Release plan
+
+## Build
+Make sure the release build works.
+
+- [ ] Build completes
+- [x] No warnings
+
+## Build
+- Second build item
+";
+        let testlist = from_markdown(md, "plan.md");
+        assert_eq!(testlist.meta.title, "Release plan");
+        assert_eq!(testlist.tests.len(), 2);
+        assert_eq!(testlist.tests[0].id, "build");
+        assert_eq!(
+            testlist.tests[0].description,
+            "Make sure the release build works."
+        );
+        assert_eq!(testlist.tests[0].verify.len(), 2);
+        assert_eq!(testlist.tests[0].verify[0].id, "verify-0");
+        assert_eq!(testlist.tests[0].verify[0].text, "Build completes");
+        assert_eq!(testlist.tests[0].verify[1].text, "No warnings");
+        assert_eq!(testlist.tests[1].id, "build-2");
+        assert_eq!(testlist.tests[1].verify[0].text, "Second build item");
+    }
+
+    #[test]
+    fn test_top_level_tasks_become_tests() {
+        let md = "\
+- [ ] Login works
+  - [ ] Redirects to dashboard
+  - Shows welcome message
+- [ ] Logout works
+";
+        let testlist = from_markdown(md, "tasks.md");
+        assert_eq!(testlist.meta.title, "tasks.md");
+        assert_eq!(testlist.tests.len(), 2);
+        assert_eq!(testlist.tests[0].id, "login-works");
+        assert_eq!(testlist.tests[0].verify.len(), 2);
+        assert_eq!(testlist.tests[0].verify[1].text, "Shows welcome message");
+        assert!(testlist.tests[1].verify.is_empty());
+    }
+
+    #[test]
+    fn test_code_blocks_ignored() {
+        let md = "\
+## Run
+```
+# not a heading
+- not an item
+```
+- [ ] Real item
+";
+        let testlist = from_markdown(md, "x.md");
+        assert_eq!(testlist.tests.len(), 1);
+        assert_eq!(testlist.tests[0].verify.len(), 1);
+        assert_eq!(testlist.tests[0].verify[0].text, "Real item");
+    }
+}
@@ -0,0 +1,54 @@
+//! Importers that convert other document formats into testlist definitions.
+
+pub mod csv;
+pub mod github;
+pub mod markdown;
+pub mod testrail;
+
+/// Turn a title into a lowercase, dash-separated test ID.
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.ends_with('-') && !slug.is_empty() {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-').to_string();
+    if slug.is_empty() {
+        "test".to_string()
+    } else {
+        slug
+    }
+}
+
+/// Make `id` unique among `taken`, appending `-2`, `-3`, … as needed.
+pub fn unique_id(id: String, taken: &[String]) -> String {
+    if !taken.contains(&id) {
+        return id;
+    }
+    (2..)
+        .map(|n| format!("{}-{}", id, n))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("User login flow"), "user-login-flow");
+        assert_eq!(slugify("  Build (release) ! "), "build-release");
+        assert_eq!(slugify("???"), "test");
+    }
+
+    #[test]
+    fn test_unique_id() {
+        let taken = vec!["login".to_string(), "login-2".to_string()];
+        assert_eq!(unique_id("login".to_string(), &taken), "login-3");
+        assert_eq!(unique_id("logout".to_string(), &taken), "logout");
+    }
+}
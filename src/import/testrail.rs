@@ -0,0 +1,300 @@
+//! Import testlists from TestRail suite XML exports.
+//!
+//! Conversion rules, the reverse of [`crate::report::testrail`]:
+//! - The suite name becomes the testlist title and every `<case>` a test.
+//! - A case's innermost `<section>` becomes its section, unless it is named
+//!   after the suite.
+//! - The case `<id>` (`C123`) becomes the test ID, so results can be
+//!   exported back; cases without one get an ID from their title.
+//! - Bullet lines of `preconds` become setup items and the rest the
+//!   description.
+//! - Step contents become the action (numbered when there are several) and
+//!   each line of their expected results a verify item. The text template's
+//!   `steps` and `expected` are read the same way.
+//! - `priority`, `estimate`, and `references` are kept when they match the
+//!   testlist's own fields.
+
+use roxmltree::Node;
+
+use crate::data::definition::{parse_duration, ChecklistItem, Meta, Priority, Test, Testlist};
+use crate::error::{Error, Result};
+use crate::import::{slugify, unique_id};
+use crate::report::testrail::case_id;
+
+/// Text of the first child element called `name`, trimmed.
+fn child_text<'a>(node: Node<'a, '_>, name: &str) -> &'a str {
+    node.children()
+        .find(|n| n.has_tag_name(name))
+        .and_then(|n| n.text())
+        .map(str::trim)
+        .unwrap_or("")
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name(name))
+}
+
+/// Strip a leading `-`, `*`, or `•` bullet from a line.
+fn strip_bullet(line: &str) -> Option<&str> {
+    ["- ", "* ", "• "]
+        .iter()
+        .find_map(|bullet| line.strip_prefix(bullet))
+        .map(str::trim)
+}
+
+fn checklist(prefix: &str, lines: &[&str]) -> Vec<ChecklistItem> {
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, text)| ChecklistItem {
+            id: format!("{}-{}", prefix, i),
+            text: text.to_string(),
+        })
+        .collect()
+}
+
+fn parse_priority(name: &str) -> Option<Priority> {
+    match name.to_ascii_lowercase().as_str() {
+        "critical" => Some(Priority::Critical),
+        "high" => Some(Priority::High),
+        "medium" => Some(Priority::Medium),
+        "low" => Some(Priority::Low),
+        _ => None,
+    }
+}
+
+fn parse_case(case: Node, section: Option<&str>, tests: &[Test]) -> Option<Test> {
+    let title = child_text(case, "title");
+    if title.is_empty() {
+        return None;
+    }
+    let custom = child(case, "custom");
+    let custom_text = |name| custom.map_or("", |custom| child_text(custom, name));
+
+    let mut description = Vec::new();
+    let mut setup = Vec::new();
+    for line in custom_text("preconds").lines() {
+        match strip_bullet(line.trim()) {
+            Some(item) if !item.is_empty() => setup.push(item),
+            _ => description.push(line),
+        }
+    }
+
+    // Steps template, falling back to the text template
+    let steps: Vec<(&str, &str)> = custom
+        .and_then(|custom| child(custom, "steps_separated"))
+        .map(|steps| {
+            steps
+                .children()
+                .filter(|n| n.has_tag_name("step"))
+                .map(|step| (child_text(step, "content"), child_text(step, "expected")))
+                .collect()
+        })
+        .unwrap_or_else(|| vec![(custom_text("steps"), custom_text("expected"))]);
+    let contents: Vec<&str> = steps
+        .iter()
+        .map(|(content, _)| *content)
+        .filter(|content| !content.is_empty())
+        .collect();
+    let action = match contents.as_slice() {
+        [] => title.to_string(),
+        [content] => content.to_string(),
+        _ => contents
+            .iter()
+            .enumerate()
+            .map(|(i, content)| format!("{}. {}", i + 1, content))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+    let verify: Vec<&str> = steps
+        .iter()
+        .flat_map(|(_, expected)| expected.lines())
+        .map(|line| strip_bullet(line.trim()).unwrap_or(line.trim()))
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    let taken: Vec<String> = tests.iter().map(|t| t.id.clone()).collect();
+    let id = match child_text(case, "id") {
+        id if case_id(id).is_some() => id.to_ascii_uppercase(),
+        _ => slugify(title),
+    };
+    let estimate = child_text(case, "estimate");
+    Some(Test {
+        id: unique_id(id, &taken),
+        title: title.to_string(),
+        description: description.join("\n").trim().to_string(),
+        setup: checklist("setup", &setup),
+        action,
+        verify: checklist("verify", &verify),
+        priority: parse_priority(child_text(case, "priority")),
+        section: section.map(str::to_string),
+        references: child_text(case, "references")
+            .split(',')
+            .map(str::trim)
+            .filter(|r| !r.is_empty())
+            .map(str::to_string)
+            .collect(),
+        expected_duration: parse_duration(estimate).map(|_| estimate.to_string()),
+        ..Default::default()
+    })
+}
+
+/// Add the cases of `section` and its subsections to `tests`.
+fn collect_section(section: Node, suite_name: &str, tests: &mut Vec<Test>) {
+    let name = child_text(section, "name");
+    let name = (!name.is_empty() && name != suite_name).then_some(name);
+    if let Some(cases) = child(section, "cases") {
+        for case in cases.children().filter(|n| n.has_tag_name("case")) {
+            if let Some(test) = parse_case(case, name, tests) {
+                tests.push(test);
+            }
+        }
+    }
+    if let Some(sections) = child(section, "sections") {
+        for sub in sections.children().filter(|n| n.has_tag_name("section")) {
+            collect_section(sub, suite_name, tests);
+        }
+    }
+}
+
+/// Convert a TestRail suite XML export into a testlist definition.
+///
+/// `source_name` is recorded in the testlist description.
+pub fn from_xml(xml: &str, source_name: &str) -> Result<Testlist> {
+    let document = roxmltree::Document::parse(xml)?;
+    let suite = document.root_element();
+    if !suite.has_tag_name("suite") {
+        return Err(Error::InvalidTestRail(format!(
+            "expected a <suite> root element, found <{}>",
+            suite.tag_name().name()
+        )));
+    }
+    let suite_name = child_text(suite, "name");
+
+    let mut tests = Vec::new();
+    if let Some(sections) = child(suite, "sections") {
+        for section in sections.children().filter(|n| n.has_tag_name("section")) {
+            collect_section(section, suite_name, &mut tests);
+        }
+    }
+
+    let title = if suite_name.is_empty() {
+        source_name
+    } else {
+        suite_name
+    };
+    Ok(Testlist {
+        meta: Meta {
+            title: title.to_string(),
+            description: format!("Imported from {}", source_name),
+            created: chrono::Utc::now().to_rfc3339(),
+            version: "1".to_string(),
+            ..Default::default()
+        },
+        tests,
+        statuses: Vec::new(),
+        source_sha256: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+    use crate::report::testrail::render_cases;
+
+    #[test]
+    fn test_cases_become_tests() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<suite>
+  <name>Web app</name>
+  <sections>
+    <section>
+      <name>Auth</name>
+      <cases>
+        <case>
+          <id>C42</id>
+          <title>Login</title>
+          <priority>Critical</priority>
+          <estimate>2m</estimate>
+          <references>JIRA-1, JIRA-2</references>
+          <custom>
+            <preconds>Fresh browser
+- Account exists</preconds>
+            <steps_separated>
+              <step><index>1</index><content>Open /login</content><expected>Form shown</expected></step>
+              <step><index>2</index><content>Sign in</content><expected>- Dashboard
+- Welcome</expected></step>
+            </steps_separated>
+          </custom>
+        </case>
+      </cases>
+      <sections>
+        <section>
+          <name>Password</name>
+          <cases>
+            <case>
+              <title>Reset</title>
+              <custom><steps>Click "forgot"</steps><expected>Mail sent</expected></custom>
+            </case>
+          </cases>
+        </section>
+      </sections>
+    </section>
+  </sections>
+</suite>"#;
+        let testlist = from_xml(xml, "suite.xml").unwrap();
+        assert_eq!(testlist.meta.title, "Web app");
+        assert_eq!(testlist.tests.len(), 2);
+
+        let login = &testlist.tests[0];
+        assert_eq!(login.id, "C42");
+        assert_eq!(login.section.as_deref(), Some("Auth"));
+        assert_eq!(login.description, "Fresh browser");
+        assert_eq!(login.setup[0].text, "Account exists");
+        assert_eq!(login.action, "1. Open /login\n2. Sign in");
+        let verify: Vec<&str> = login.verify.iter().map(|i| i.text.as_str()).collect();
+        assert_eq!(verify, vec!["Form shown", "Dashboard", "Welcome"]);
+        assert_eq!(login.priority, Some(Priority::Critical));
+        assert_eq!(login.expected_duration.as_deref(), Some("2m"));
+        assert_eq!(login.references, vec!["JIRA-1", "JIRA-2"]);
+
+        let reset = &testlist.tests[1];
+        assert_eq!(reset.id, "reset");
+        assert_eq!(reset.section.as_deref(), Some("Password"));
+        assert_eq!(reset.action, "Click \"forgot\"");
+        assert_eq!(reset.verify[0].text, "Mail sent");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut original = fixtures::testlist(vec![Test {
+            id: "C7".to_string(),
+            title: "Build".to_string(),
+            description: "Clean checkout".to_string(),
+            setup: checklist("setup", &["Rust installed"]),
+            action: "cargo build".to_string(),
+            verify: checklist("verify", &["No errors", "No <warnings>"]),
+            ..Default::default()
+        }]);
+        original.meta.title = "Release".to_string();
+        let imported = from_xml(&render_cases(&original), "x.xml").unwrap();
+        let (a, b) = (&original.tests[0], &imported.tests[0]);
+        assert_eq!(imported.meta.title, "Release");
+        assert_eq!(b.id, a.id);
+        assert_eq!(b.section, None);
+        assert_eq!(b.description, a.description);
+        assert_eq!(b.action, a.action);
+        assert_eq!(b.setup[0].text, a.setup[0].text);
+        assert_eq!(b.verify[1].text, a.verify[1].text);
+    }
+
+    #[test]
+    fn test_rejects_other_xml() {
+        assert!(matches!(
+            from_xml("<testsuites/>", "x.xml"),
+            Err(Error::InvalidTestRail(_))
+        ));
+        assert!(matches!(from_xml("<suite>", "x.xml"), Err(Error::Xml(_))));
+    }
+}
@@ -3,6 +3,11 @@
 pub mod actions;
 pub mod data;
 pub mod error;
+#[cfg(test)]
+mod fixtures;
+pub mod import;
 pub mod queries;
+pub mod report;
+pub mod session;
 pub mod transforms;
 pub mod ui;
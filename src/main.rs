@@ -1,16 +1,45 @@
-use clap::Parser;
-use std::path::PathBuf;
+use clap::{ArgGroup, Parser, Subcommand};
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
 
+use testlist::actions::config;
+use testlist::actions::environment::capture_environment;
+use testlist::actions::export;
 use testlist::actions::files;
-use testlist::data::results::TestlistResults;
-use testlist::data::state::AppState;
+use testlist::actions::git::capture_git;
+use testlist::actions::github;
+use testlist::actions::journal;
+use testlist::actions::logging;
+use testlist::actions::signing;
+use testlist::data::config::{Config, Signing};
+use testlist::data::definition::{Format, Test, Testlist};
+use testlist::data::journal::{replay, split_saved};
+use testlist::data::merge::{merge, Candidate, MergePolicy};
+use testlist::data::results::{parse_key_value, Severity, Status, TestlistResults};
+use testlist::data::state::{AppState, Comparison, LaunchFilter};
+use testlist::data::template::{self, DEFAULT_TEMPLATE, TEMPLATES};
+use testlist::data::theme::Theme;
+use testlist::import::csv::ColumnMap;
+use testlist::queries::diff::{diff_results, format_diff};
+use testlist::queries::history::sort_runs;
+use testlist::queries::tests::{resolve_results_path, result_for_test};
+use testlist::report::{
+    history, html, issue, junit, markdown, tap, testrail, ExportFormat, ReportFormat,
+};
+use testlist::session::{default_results_path, Session};
+use testlist::transforms::filter as filter_transforms;
+use testlist::transforms::navigation;
+use testlist::transforms::ui as ui_transforms;
 
 /// Structured human feedback collection tool
 #[derive(Parser, Debug)]
 #[command(name = "testlist")]
 #[command(version, about, long_about = None)]
 struct Args {
-    /// Path to testlist definition file
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Path to testlist definition file, or - to read it from stdin (requires --results)
     #[arg(value_name = "TESTLIST")]
     testlist: Option<PathBuf>,
 
@@ -18,25 +47,1044 @@ struct Args {
     #[arg(long, value_name = "PATH")]
     new: Option<PathBuf>,
 
-    /// Set tester name for results (default: $USER)
+    /// Template for --new, e.g. release or ui-smoke (default: cargo)
+    #[arg(long, value_name = "NAME", requires = "new")]
+    template: Option<String>,
+
+    /// List the templates available to --new --template and exit
+    #[arg(long)]
+    list_templates: bool,
+
+    /// Set tester name for results (default: from the config file, or $USER)
     #[arg(long, value_name = "NAME")]
     tester: Option<String>,
 
+    /// Built-in theme to start with: dark, light, or high-contrast
+    #[arg(long, value_name = "NAME")]
+    theme: Option<String>,
+
+    /// Accessibility mode: the high-contrast theme and statuses spelled out in words
+    #[arg(long)]
+    accessible: bool,
+
+    /// Append plain-text announcements of selection and status changes to FILE, for screen readers
+    #[arg(long, value_name = "FILE")]
+    announce: Option<PathBuf>,
+
+    /// Shell for the embedded terminal (default: $SHELL, or PowerShell on Windows)
+    #[arg(long, value_name = "PATH")]
+    shell: Option<String>,
+
+    /// Save results every SECS seconds while there are unsaved changes (0 disables)
+    #[arg(long, value_name = "SECS")]
+    autosave: Option<u64>,
+
     /// Custom path for results file (default: <testlist>.results.ron)
     #[arg(long, value_name = "PATH")]
     results: Option<PathBuf>,
 
-    /// Continue from existing results file
-    #[arg(long, name = "continue")]
+    /// Continue from existing results file, recovering unsaved changes from its journal
+    #[arg(long = "continue")]
     continue_from: bool,
+
+    /// Continue like --continue and expand the first test still to be done
+    #[arg(long)]
+    resume: bool,
+
+    /// Continue even if the testlist has changed since the results were recorded
+    #[arg(long)]
+    allow_changed_testlist: bool,
+
+    /// Fail on unknown fields in the testlist and results instead of ignoring them
+    #[arg(long)]
+    strict: bool,
+
+    /// Record an environment detail in the results (repeatable), e.g. --env browser=firefox
+    #[arg(long = "env", value_name = "KEY=VALUE", value_parser = parse_env_arg)]
+    env: Vec<(String, String)>,
+
+    /// Don't record the commit, branch, and dirty flag of the git checkout in the results
+    #[arg(long)]
+    no_git: bool,
+
+    /// Note recorded with this session, e.g. --session-note "retest after fix"
+    #[arg(long, value_name = "TEXT")]
+    session_note: Option<String>,
+
+    /// Screenshot tool for `A`, with `{path}` for the output file (default: screencapture, grim, or scrot)
+    #[arg(long, value_name = "CMD")]
+    screenshot_command: Option<String>,
+
+    /// Show only these tests (comma-separated IDs); results still go to the shared results file
+    #[arg(long, value_name = "IDS", value_delimiter = ',')]
+    only: Vec<String>,
+
+    /// Open with the tests pane filtered, e.g. --filter status=failed or --filter tag=smoke (repeatable)
+    #[arg(long, value_name = "KEY=VALUE")]
+    filter: Vec<LaunchFilter>,
+
+    /// Write a diagnostic log of loads, saves, status changes, and terminal errors to this file
+    #[arg(long, value_name = "PATH")]
+    log_file: Option<PathBuf>,
+
+    /// Archive a copy of the results here when the run is completed, for `testlist stats`
+    #[arg(long, value_name = "DIR")]
+    archive_dir: Option<PathBuf>,
+
+    /// Copy attached screenshots into <results>.assets/ and store paths relative to the results file
+    #[arg(long)]
+    copy_attachments: bool,
+
+    /// Interrupt commands run with `C` after this many seconds
+    #[arg(long, value_name = "SECS")]
+    command_timeout: Option<u64>,
+
+    /// Serve a read-only progress page and JSON status on this port while the TUI runs
+    #[arg(long, value_name = "PORT")]
+    serve: Option<u16>,
+
+    /// Address to serve the progress page on, e.g. 0.0.0.0 for all interfaces
+    #[arg(
+        long,
+        value_name = "ADDR",
+        default_value = "127.0.0.1",
+        requires = "serve"
+    )]
+    serve_addr: std::net::IpAddr,
+
+    /// Testlist file format: ron, yaml, or json (default: from file extension)
+    #[arg(long, value_name = "FORMAT")]
+    format: Option<Format>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Convert another document format into a testlist definition
+    #[command(group(ArgGroup::new("input").required(true)))]
+    Import {
+        /// Markdown file whose headings and task items become tests
+        #[arg(long, value_name = "PATH", group = "input")]
+        from_markdown: Option<PathBuf>,
+
+        /// CSV file whose rows become tests
+        #[arg(long, value_name = "PATH", group = "input")]
+        from_csv: Option<PathBuf>,
+
+        /// CSV columns per field, e.g. title=B,action=C,verify=D (default: header names)
+        #[arg(
+            long,
+            value_name = "FIELD=COLUMN,...",
+            conflicts_with_all = ["from_markdown", "from_testrail"]
+        )]
+        map: Option<ColumnMap>,
+
+        /// TestRail suite XML export whose cases become tests
+        #[arg(long, value_name = "PATH", group = "input")]
+        from_testrail: Option<PathBuf>,
+
+        /// GitHub issue or PR whose checkboxes become tests: NUMBER, OWNER/NAME#NUMBER, or its URL
+        #[arg(long, value_name = "ISSUE", group = "input")]
+        from_github: Option<String>,
+
+        /// Repository of a --from-github NUMBER (default: github_repo in the config file, or the origin remote's)
+        #[arg(
+            long,
+            value_name = "OWNER/NAME",
+            conflicts_with_all = ["from_markdown", "from_csv", "from_testrail"]
+        )]
+        repo: Option<String>,
+
+        /// Output path (default: <input>.testlist.ron, or issue-<NUMBER>.testlist.ron)
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+    /// Compare two results files recorded against the same testlist
+    Diff {
+        /// Testlist definition both results files belong to
+        #[arg(value_name = "TESTLIST")]
+        testlist: PathBuf,
+
+        /// Earlier results file
+        #[arg(value_name = "OLD")]
+        old: PathBuf,
+
+        /// Later results file
+        #[arg(value_name = "NEW")]
+        new: PathBuf,
+    },
+    /// Open results in the TUI next to an earlier run's results
+    Compare {
+        /// Earlier results file, shown as the baseline
+        #[arg(value_name = "OLD")]
+        old: PathBuf,
+
+        /// Results file to open and continue
+        #[arg(value_name = "NEW")]
+        new: PathBuf,
+
+        /// Testlist definition (default: the one recorded in NEW)
+        #[arg(long, value_name = "PATH")]
+        testlist: Option<PathBuf>,
+    },
+    /// Open a results file in review mode to approve or reject results
+    Review {
+        /// Results file to review
+        #[arg(value_name = "RESULTS")]
+        results: PathBuf,
+
+        /// Testlist definition (default: the one recorded in RESULTS)
+        #[arg(long, value_name = "PATH")]
+        testlist: Option<PathBuf>,
+
+        /// Reviewer name recorded with each sign-off (default: --tester or $USER)
+        #[arg(long, value_name = "NAME")]
+        reviewer: Option<String>,
+    },
+    /// Follow another tester's session read-only, reloading the results as they change
+    Watch {
+        /// Results file to watch
+        #[arg(value_name = "RESULTS")]
+        results: PathBuf,
+
+        /// Testlist definition (default: the one recorded in RESULTS)
+        #[arg(long, value_name = "PATH")]
+        testlist: Option<PathBuf>,
+    },
+    /// Combine results files from several testers into one
+    Merge {
+        /// Testlist definition the results files belong to
+        #[arg(value_name = "TESTLIST")]
+        testlist: PathBuf,
+
+        /// Results files to merge, at least two
+        #[arg(value_name = "RESULTS", num_args = 2.., required = true)]
+        inputs: Vec<PathBuf>,
+
+        /// Output path for the merged results
+        #[arg(short, long, value_name = "PATH")]
+        output: PathBuf,
+
+        /// Conflict policy: worst-status, latest, or interactive
+        #[arg(long, value_name = "POLICY", default_value = "worst-status")]
+        policy: MergePolicy,
+    },
+    /// Render a results file as a Markdown or HTML report
+    Report {
+        /// Results file to report on
+        #[arg(value_name = "RESULTS")]
+        results: PathBuf,
+
+        /// Testlist definition (default: the one recorded in RESULTS)
+        #[arg(long, value_name = "PATH")]
+        testlist: Option<PathBuf>,
+
+        /// Write the report here instead of to stdout
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<PathBuf>,
+
+        /// Report format: markdown or html (default: from the output extension)
+        #[arg(long, value_name = "FORMAT")]
+        format: Option<ReportFormat>,
+
+        /// Copy screenshots into <output>.assets/ instead of embedding them in HTML
+        #[arg(long, requires = "output")]
+        copy_screenshots: bool,
+    },
+    /// Set test statuses in a results file without opening the TUI
+    Mark {
+        /// Results file to update
+        #[arg(value_name = "RESULTS")]
+        results: PathBuf,
+
+        /// Test ID to mark (repeatable, or comma-separated)
+        #[arg(
+            long = "test",
+            value_name = "ID",
+            required = true,
+            value_delimiter = ','
+        )]
+        tests: Vec<String>,
+
+        /// New status: pending, passed, failed, inconclusive, skipped, retest,
+        /// in-progress, blocked, or a custom status declared by the testlist
+        #[arg(long, value_name = "STATUS")]
+        status: String,
+
+        /// Replace the tests' notes; an empty note clears them
+        #[arg(long, value_name = "TEXT")]
+        note: Option<String>,
+
+        /// Severity of a failure: blocker, major, minor, or cosmetic
+        #[arg(long, value_name = "SEVERITY")]
+        severity: Option<Severity>,
+
+        /// Tester recorded with the change (default: $USER)
+        #[arg(long, value_name = "NAME")]
+        tester: Option<String>,
+
+        /// Testlist definition (default: the one recorded in RESULTS)
+        #[arg(long, value_name = "PATH")]
+        testlist: Option<PathBuf>,
+    },
+    /// Run a Rhai script that updates a results file
+    Script {
+        /// Results file to update
+        #[arg(value_name = "RESULTS")]
+        results: PathBuf,
+
+        /// Rhai script to run
+        #[arg(value_name = "SCRIPT")]
+        script: PathBuf,
+
+        /// Tester recorded with the changes (default: $USER)
+        #[arg(long, value_name = "NAME")]
+        tester: Option<String>,
+
+        /// Testlist definition (default: the one recorded in RESULTS)
+        #[arg(long, value_name = "PATH")]
+        testlist: Option<PathBuf>,
+    },
+    /// File a GitHub issue for a failed test and link it in the results file
+    Issue {
+        /// Results file holding the failure
+        #[arg(value_name = "RESULTS")]
+        results: PathBuf,
+
+        /// ID of the failed test
+        #[arg(long = "test", value_name = "ID")]
+        test: String,
+
+        /// Repository as OWNER/NAME (default: github_repo in the config file, or the origin remote's)
+        #[arg(long, value_name = "OWNER/NAME")]
+        repo: Option<String>,
+
+        /// Comment on the GitHub issue or PR the test references (e.g. the one it
+        /// was imported from) instead of filing a new issue
+        #[arg(long, conflicts_with = "repo")]
+        comment: bool,
+
+        /// Print the issue instead of filing it
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Testlist definition (default: the one recorded in RESULTS)
+        #[arg(long, value_name = "PATH")]
+        testlist: Option<PathBuf>,
+    },
+    /// Check a results file's signature, and that its testlist hasn't changed
+    Verify {
+        /// Signed results file
+        #[arg(value_name = "RESULTS")]
+        results: PathBuf,
+
+        /// Testlist definition (default: the one recorded in RESULTS)
+        #[arg(long, value_name = "PATH")]
+        testlist: Option<PathBuf>,
+    },
+    /// Summarize archived runs: pass rates, frequent failures, and durations
+    Stats {
+        /// Directory of archived results files (see --archive-dir)
+        #[arg(value_name = "ARCHIVE_DIR")]
+        archive_dir: PathBuf,
+
+        /// Testlist definition (default: the one recorded in the newest run)
+        #[arg(long, value_name = "PATH")]
+        testlist: Option<PathBuf>,
+    },
+    /// Export a results file or testlist definition for other tools
+    Export {
+        /// Results file, or testlist definition for JSON and TestRail
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Export format: junit, json, tap, or testrail
+        #[arg(long, value_name = "FORMAT")]
+        format: ExportFormat,
+
+        /// Testlist definition for a results FILE (default: the one recorded in it)
+        #[arg(long, value_name = "PATH")]
+        testlist: Option<PathBuf>,
+
+        /// Write the export here instead of to stdout
+        #[arg(short, long, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+}
+
+fn parse_env_arg(text: &str) -> Result<(String, String), String> {
+    parse_key_value(text).ok_or_else(|| format!("expected KEY=VALUE, got '{}'", text))
+}
+
+fn run_command(command: Command) {
+    match command {
+        Command::Import {
+            from_markdown,
+            from_csv,
+            map,
+            from_testrail,
+            from_github,
+            repo,
+            output,
+        } => {
+            if let Some(issue_ref) = from_github {
+                import_github_issue(&issue_ref, repo, output);
+                return;
+            }
+            let output_for =
+                |input: &Path| output.unwrap_or_else(|| input.with_extension("testlist.ron"));
+            let (kind, output, imported) = match (from_markdown, from_csv, from_testrail) {
+                (Some(input), _, _) => {
+                    let output = output_for(&input);
+                    let imported = files::import_markdown(&input, &output);
+                    ("Markdown", output, imported)
+                }
+                (_, Some(input), _) => {
+                    let output = output_for(&input);
+                    let imported = files::import_csv(&input, map.as_ref(), &output);
+                    ("CSV", output, imported)
+                }
+                (_, _, Some(input)) => {
+                    let output = output_for(&input);
+                    let imported = files::import_testrail(&input, &output);
+                    ("TestRail", output, imported)
+                }
+                (None, None, None) => unreachable!("clap requires an input"),
+            };
+            match imported {
+                Ok(testlist) => println!(
+                    "Imported {} tests into: {}",
+                    testlist.tests.len(),
+                    output.display()
+                ),
+                Err(e) => {
+                    eprintln!("Error importing {}: {}", kind, e);
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Diff { testlist, old, new } => {
+            let testlist = files::load_testlist(&testlist).unwrap_or_else(|e| {
+                eprintln!("Error loading testlist: {}", e);
+                std::process::exit(1);
+            });
+            let load = |path: &PathBuf| {
+                files::load_results(path, &testlist).unwrap_or_else(|e| {
+                    eprintln!("Error loading results {}: {}", path.display(), e);
+                    std::process::exit(1);
+                })
+            };
+            let changes = diff_results(&testlist, &load(&old), &load(&new));
+            println!("Comparing {} → {}\n", old.display(), new.display());
+            print!("{}", format_diff(&changes, std::io::stdout().is_terminal()));
+        }
+        Command::Compare { .. } | Command::Review { .. } | Command::Watch { .. } => {
+            unreachable!("compare, review, and watch run the TUI")
+        }
+        Command::Merge {
+            testlist,
+            inputs,
+            output,
+            policy,
+        } => {
+            let testlist = files::load_testlist(&testlist).unwrap_or_else(|e| {
+                eprintln!("Error loading testlist: {}", e);
+                std::process::exit(1);
+            });
+            let sources: Vec<(String, TestlistResults)> = inputs
+                .iter()
+                .map(|path| match files::load_results(path, &testlist) {
+                    Ok(results) => (path.display().to_string(), results),
+                    Err(e) => {
+                        eprintln!("Error loading results {}: {}", path.display(), e);
+                        std::process::exit(1);
+                    }
+                })
+                .collect();
+            let merged = merge(&testlist, &sources, |test, candidates| {
+                policy
+                    .resolve(candidates)
+                    .unwrap_or_else(|| ask_merge_choice(test, candidates))
+            });
+            if let Err(e) = files::save_results(&merged.results, &output, 0) {
+                eprintln!("Error saving results: {}", e);
+                std::process::exit(1);
+            }
+            println!(
+                "Merged {} results files into: {} ({} conflict(s) resolved)",
+                sources.len(),
+                output.display(),
+                merged.conflicts
+            );
+        }
+        Command::Report {
+            results,
+            testlist,
+            output,
+            format,
+            copy_screenshots,
+        } => {
+            let (testlist, loaded) = load_existing_results(&results, testlist);
+            let format = format
+                .or_else(|| output.as_deref().map(ReportFormat::from_path))
+                .unwrap_or_default();
+            let report = match format {
+                ReportFormat::Markdown => markdown::render(&testlist, &loaded),
+                ReportFormat::Html => html::render(&testlist, &loaded, |path| {
+                    let source = resolve_results_path(&results, path);
+                    match output.as_deref() {
+                        Some(output) if copy_screenshots => {
+                            let copied = files::copy_attachment(&source, output).ok()?;
+                            Some(copied.to_string_lossy().into_owned())
+                        }
+                        _ => files::image_data_uri(&source).ok(),
+                    }
+                }),
+            };
+            write_output(output.as_deref(), &report, "report");
+        }
+        Command::Mark {
+            results,
+            tests,
+            status,
+            note,
+            severity,
+            tester,
+            testlist,
+        } => {
+            let builtin = status.parse::<Status>().ok();
+            if severity.is_some() && builtin != Some(Status::Failed) {
+                eprintln!("Error: --severity only applies to --status failed");
+                std::process::exit(1);
+            }
+            if journal::journal_path(&results).exists() {
+                eprintln!(
+                    "Error: {} has unsaved changes from a TUI session; open it with --continue first",
+                    results.display()
+                );
+                std::process::exit(1);
+            }
+            let testlist_path = existing_results_testlist(&results, testlist);
+            let (testlist, mut loaded) =
+                load_existing_results(&results, Some(testlist_path.clone()));
+            loaded.reconcile(&testlist);
+            let unknown: Vec<&str> = tests
+                .iter()
+                .filter(|id| !loaded.results.iter().any(|r| &r.test_id == *id))
+                .map(String::as_str)
+                .collect();
+            if !unknown.is_empty() {
+                eprintln!("Error: unknown test ID(s): {}", unknown.join(", "));
+                std::process::exit(1);
+            }
+
+            let config = load_config_or_exit();
+            let needs_note = config.require_failure_notes || testlist.meta.require_failure_notes;
+            let needs_severity = config.require_severity || testlist.meta.require_severity;
+            let has_note = note.as_deref().is_some_and(|n| !n.trim().is_empty());
+            if needs_note
+                && !has_note
+                && matches!(builtin, Some(Status::Failed | Status::Inconclusive))
+            {
+                eprintln!(
+                    "Error: this testlist requires a --note for failed and inconclusive tests"
+                );
+                std::process::exit(1);
+            }
+            if needs_severity && severity.is_none() && builtin == Some(Status::Failed) {
+                eprintln!("Error: this testlist requires a --severity for failed tests");
+                std::process::exit(1);
+            }
+
+            let mut session = Session::new(testlist, loaded, results.clone());
+            session.backups = config.backups;
+            session.signing = config.signing.clone();
+            session.require_checks = config.require_checks;
+            let tester = tester.unwrap_or_else(|| default_tester(&config));
+            session
+                .results
+                .start_session(&tester, Some("mark".to_string()));
+            for id in &tests {
+                let marked = match builtin {
+                    Some(builtin) => session.set_status(id, builtin),
+                    None => session.set_custom_status(id, &status),
+                };
+                let marked = marked.and_then(|()| {
+                    if let Some(ref note) = note {
+                        session.set_notes(id, Some(note))?;
+                    }
+                    match severity {
+                        Some(severity) => session.set_severity(id, severity),
+                        None => Ok(()),
+                    }
+                });
+                if let Err(e) = marked {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            if let Err(e) = session.save() {
+                eprintln!("Error saving results: {}", e);
+                std::process::exit(1);
+            }
+            let status = builtin.map_or(status, |s| format!("{:?}", s));
+            println!(
+                "Marked {} {} in: {}",
+                tests.join(", "),
+                status,
+                results.display()
+            );
+        }
+        Command::Script {
+            results,
+            script,
+            tester,
+            testlist,
+        } => {
+            if journal::journal_path(&results).exists() {
+                eprintln!(
+                    "Error: {} has unsaved changes from a TUI session; open it with --continue first",
+                    results.display()
+                );
+                std::process::exit(1);
+            }
+            let source = std::fs::read_to_string(&script).unwrap_or_else(|e| {
+                eprintln!("Error reading {}: {}", script.display(), e);
+                std::process::exit(1);
+            });
+            let (testlist, mut loaded) = load_existing_results(&results, testlist);
+            loaded.reconcile(&testlist);
+            let mut session = Session::new(testlist, loaded, results.clone());
+            let config = load_config_or_exit();
+            session.backups = config.backups;
+            session.signing = config.signing.clone();
+            session.require_checks = config.require_checks;
+            let tester = tester.unwrap_or_else(|| default_tester(&config));
+            session
+                .results
+                .start_session(&tester, Some("script".to_string()));
+            let applied = session.run_script(&source).unwrap_or_else(|e| {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            });
+            if let Err(e) = session.save() {
+                eprintln!("Error saving results: {}", e);
+                std::process::exit(1);
+            }
+            println!("Applied {} change(s) to: {}", applied, results.display());
+        }
+        Command::Issue {
+            results,
+            test,
+            repo,
+            comment,
+            dry_run,
+            testlist,
+        } => {
+            if journal::journal_path(&results).exists() {
+                eprintln!(
+                    "Error: {} has unsaved changes from a TUI session; open it with --continue first",
+                    results.display()
+                );
+                std::process::exit(1);
+            }
+            let testlist_path = existing_results_testlist(&results, testlist);
+            let (testlist, loaded) = load_existing_results(&results, Some(testlist_path.clone()));
+            let failed = testlist
+                .tests
+                .iter()
+                .find(|t| t.id == test)
+                .zip(result_for_test(&loaded, &test))
+                .filter(|(_, result)| result.status == Status::Failed);
+            let Some((definition, result)) = failed else {
+                eprintln!(
+                    "Error: {} is not a failed test in {}",
+                    test,
+                    results.display()
+                );
+                std::process::exit(1);
+            };
+            let title = issue::title(definition);
+            let body = issue::render(&testlist, &loaded, definition, result);
+            let source =
+                definition
+                    .references
+                    .iter()
+                    .find_map(|r| match github::parse_issue_ref(r) {
+                        Some((Some(repo), number)) => Some((repo, number)),
+                        _ => None,
+                    });
+            if comment && source.is_none() {
+                eprintln!("Error: {} does not reference a GitHub issue or PR", test);
+                std::process::exit(1);
+            }
+            if dry_run {
+                if let (true, Some((repo, number))) = (comment, &source) {
+                    println!("Comment on {}#{}:\n", repo, number);
+                }
+                println!("{}\n\n{}", title, body);
+                return;
+            }
+
+            let config = load_config_or_exit();
+            let filed = match source {
+                Some((repo, number)) if comment => {
+                    let text = format!("### {}\n\n{}", title, body);
+                    github::comment_on_issue(&repo, number, &text)
+                }
+                _ => {
+                    let Some(repo) = repo.or(config.github_repo).or_else(github::detect_repo)
+                    else {
+                        eprintln!("Error: pass --repo OWNER/NAME; no github_repo is configured and origin is not on GitHub");
+                        std::process::exit(1);
+                    };
+                    github::create_issue(&repo, &title, &body)
+                }
+            };
+            let url = filed.unwrap_or_else(|e| {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            });
+            let mut state = AppState::new(testlist, loaded, testlist_path, results.clone());
+            ui_transforms::link_defect(&mut state, &test, &url);
+            if let Err(e) = files::save_results(&state.results, &results, config.backups) {
+                eprintln!("Filed {} but could not save results: {}", url, e);
+                std::process::exit(1);
+            }
+            sign_saved_results(&results, config.signing.as_ref());
+            let verb = if comment { "Commented" } else { "Filed" };
+            println!("{} {} and linked it to {}", verb, url, test);
+        }
+        Command::Verify { results, testlist } => {
+            let (testlist, loaded) = load_existing_results(&results, testlist);
+            let Some(signing) = load_config_or_exit().signing else {
+                eprintln!("Error: no signing is configured in the config file");
+                std::process::exit(1);
+            };
+            if let Err(e) = signing::verify(&results, &signing) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            println!(
+                "Signature: good ({})",
+                signing::signature_path(&results, signing.tool).display()
+            );
+            let current = testlist.sha256().unwrap_or_default();
+            match loaded.meta.testlist_sha256 {
+                Some(recorded) if recorded == current => {
+                    println!("Testlist: unchanged (sha256 {})", current)
+                }
+                Some(recorded) => {
+                    eprintln!(
+                        "Error: the testlist has changed since the results were recorded (sha256 {}, now {})",
+                        recorded, current
+                    );
+                    std::process::exit(1);
+                }
+                None => {
+                    eprintln!(
+                        "Error: {} does not record a testlist hash",
+                        results.display()
+                    );
+                    std::process::exit(1);
+                }
+            }
+        }
+        Command::Stats {
+            archive_dir,
+            testlist,
+        } => {
+            let testlist = testlist.unwrap_or_else(|| archive_testlist(&archive_dir));
+            let testlist = files::load_testlist(&testlist).unwrap_or_else(|e| {
+                eprintln!("Error loading testlist: {}", e);
+                std::process::exit(1);
+            });
+            let mut runs = files::load_archive(&archive_dir, &testlist).unwrap_or_else(|e| {
+                eprintln!("Error loading archive {}: {}", archive_dir.display(), e);
+                std::process::exit(1);
+            });
+            sort_runs(&mut runs);
+            print!("{}", history::render(&testlist, &runs));
+        }
+        Command::Export {
+            file,
+            format,
+            testlist,
+            output,
+        } => {
+            let is_results = export::is_results_file(&file).unwrap_or_else(|e| {
+                eprintln!("Error reading {}: {}", file.display(), e);
+                std::process::exit(1);
+            });
+            let exported = if is_results {
+                let (testlist, loaded) = load_existing_results(&file, testlist);
+                match format {
+                    ExportFormat::Junit => Ok(junit::render(&testlist, &loaded)),
+                    ExportFormat::Json => export::results_json(&testlist, &loaded),
+                    ExportFormat::Tap => Ok(tap::render(&testlist, &loaded)),
+                    ExportFormat::Testrail => testrail::render_results(&testlist, &loaded),
+                }
+            } else {
+                let testlist = files::load_testlist(&file).unwrap_or_else(|e| {
+                    eprintln!("Error loading testlist: {}", e);
+                    std::process::exit(1);
+                });
+                match format {
+                    ExportFormat::Junit | ExportFormat::Tap => {
+                        eprintln!("Error: {:?} export needs a results file", format);
+                        std::process::exit(1);
+                    }
+                    ExportFormat::Json => export::testlist_json(&testlist),
+                    ExportFormat::Testrail => Ok(testrail::render_cases(&testlist)),
+                }
+            };
+            let exported = exported.unwrap_or_else(|e| {
+                eprintln!("Error exporting {}: {}", file.display(), e);
+                std::process::exit(1);
+            });
+            write_output(output.as_deref(), &exported, "export");
+        }
+    }
+}
+
+/// Import the checklist of a GitHub issue or PR as a testlist. Exits on errors.
+fn import_github_issue(issue_ref: &str, repo: Option<String>, output: Option<PathBuf>) {
+    let Some((ref_repo, number)) = github::parse_issue_ref(issue_ref) else {
+        eprintln!(
+            "Error: '{}' is not an issue number, OWNER/NAME#NUMBER, or issue URL",
+            issue_ref
+        );
+        std::process::exit(1);
+    };
+    let repo = ref_repo
+        .or(repo)
+        .or_else(|| load_config_or_exit().github_repo)
+        .or_else(github::detect_repo);
+    let Some(repo) = repo else {
+        eprintln!("Error: pass --repo OWNER/NAME; no github_repo is configured and origin is not on GitHub");
+        std::process::exit(1);
+    };
+    let issue = github::fetch_issue(&repo, number).unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    let testlist = testlist::import::github::from_issue(&issue);
+    let output = output.unwrap_or_else(|| PathBuf::from(format!("issue-{}.testlist.ron", number)));
+    if let Err(e) = testlist.save(&output, Format::from_path(&output)) {
+        eprintln!("Error writing {}: {}", output.display(), e);
+        std::process::exit(1);
+    }
+    println!(
+        "Imported {} tests from {} into: {}",
+        testlist.tests.len(),
+        issue.url,
+        output.display()
+    );
+}
+
+/// Load an existing results file and its testlist, resolved as by
+/// [`existing_results_testlist`]. Exits on errors.
+fn load_existing_results(results: &Path, testlist: Option<PathBuf>) -> (Testlist, TestlistResults) {
+    let testlist = existing_results_testlist(results, testlist);
+    let testlist = files::load_testlist(&testlist).unwrap_or_else(|e| {
+        eprintln!("Error loading testlist: {}", e);
+        std::process::exit(1);
+    });
+    let loaded = files::load_results(results, &testlist).unwrap_or_else(|e| {
+        eprintln!("Error loading results {}: {}", results.display(), e);
+        std::process::exit(1);
+    });
+    (testlist, loaded)
+}
+
+/// The testlist recorded in the newest run of an archive. Exits if there is none.
+fn archive_testlist(archive_dir: &Path) -> PathBuf {
+    let newest = std::fs::read_dir(archive_dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|e| e == "ron"))
+        .max();
+    match newest.map(|path| files::results_testlist(&path)) {
+        Some(Ok(Some(testlist))) => testlist,
+        _ => {
+            eprintln!(
+                "Error: no archived run in {} records its testlist; pass --testlist",
+                archive_dir.display()
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Write generated output to `output`, or to stdout when not given.
+fn write_output(output: Option<&Path>, content: &str, what: &str) {
+    match output {
+        Some(output) => {
+            if let Err(e) = std::fs::write(output, content) {
+                eprintln!("Error writing {} {}: {}", what, output.display(), e);
+                std::process::exit(1);
+            }
+            println!("Wrote {} to: {}", what, output.display());
+        }
+        None => print!("{}", content),
+    }
+}
+
+/// Load the config file, or its defaults if there is none.
+fn load_config_or_exit() -> Config {
+    let Some(config_path) = config::config_path() else {
+        return Config::default();
+    };
+    config::load_config(&config_path).unwrap_or_else(|e| {
+        eprintln!("Error loading config {}: {}", config_path.display(), e);
+        std::process::exit(1);
+    })
+}
+
+/// Sign a results file that was just saved, if signing is configured,
+/// reporting a failure without exiting: the results themselves are saved.
+fn sign_saved_results(results: &Path, signing: Option<&Signing>) {
+    let Some(signing) = signing else { return };
+    match signing::sign(results, signing) {
+        Ok(signature) => {
+            log::info!("Signed results to {}", signature.display());
+            println!("Results signed: {}", signature.display());
+        }
+        Err(e) => {
+            log::error!("Could not sign results: {}", e);
+            eprintln!("Error signing results: {}", e);
+        }
+    }
+}
+
+/// Tester name when none is given: the config's, then `$USER`.
+fn default_tester(config: &Config) -> String {
+    config
+        .tester
+        .clone()
+        .unwrap_or_else(|| std::env::var("USER").unwrap_or_else(|_| "unknown".to_string()))
+}
+
+/// The testlist for an existing results file: `testlist` when given,
+/// otherwise the one recorded in the file. Exits if neither is available.
+fn existing_results_testlist(results: &std::path::Path, testlist: Option<PathBuf>) -> PathBuf {
+    if !results.exists() {
+        eprintln!("Error: Results file not found: {}", results.display());
+        std::process::exit(1);
+    }
+    if let Some(testlist) = testlist {
+        return testlist;
+    }
+    match files::results_testlist(results) {
+        Ok(Some(testlist)) => testlist,
+        Ok(None) => {
+            eprintln!(
+                "Error: {} does not record its testlist; pass --testlist",
+                results.display()
+            );
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("Error loading results {}: {}", results.display(), e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Ask on the terminal which conflicting result to keep for a test.
+fn ask_merge_choice(test: &Test, candidates: &[Candidate]) -> usize {
+    println!("\nConflict for {} ({}):", test.id, test.title);
+    for (i, candidate) in candidates.iter().enumerate() {
+        println!(
+            "  {}) {:?} by {} in {}",
+            i + 1,
+            candidate.result.status,
+            candidate.tester,
+            candidate.source
+        );
+        for line in candidate.result.notes.iter().flat_map(|n| n.lines()) {
+            println!("       {}", line);
+        }
+    }
+    let stdin = std::io::stdin();
+    loop {
+        print!("Keep which result? [1-{}] ", candidates.len());
+        let _ = std::io::stdout().flush();
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+            eprintln!("Error: no answer for {}", test.id);
+            std::process::exit(1);
+        }
+        match line.trim().parse::<usize>() {
+            Ok(n) if (1..=candidates.len()).contains(&n) => return n - 1,
+            _ => println!("Enter a number between 1 and {}", candidates.len()),
+        }
+    }
 }
 
 fn main() {
-    let args = Args::parse();
+    let mut args = Args::parse();
+
+    // Compare, review, and watch continue an existing results file: compare
+    // with older results as a baseline, review with sign-off keys enabled,
+    // watch read-only without starting a session
+    let mut baseline_path = None;
+    let mut review = false;
+    let mut watch = false;
+    args.continue_from |= args.resume;
+    match args.command.take() {
+        Some(Command::Compare { old, new, testlist }) => {
+            args.testlist = Some(existing_results_testlist(&new, testlist));
+            args.results = Some(new);
+            args.continue_from = true;
+            baseline_path = Some(old);
+        }
+        Some(Command::Review {
+            results,
+            testlist,
+            reviewer,
+        }) => {
+            args.testlist = Some(existing_results_testlist(&results, testlist));
+            args.results = Some(results);
+            args.continue_from = true;
+            args.tester = reviewer.or(args.tester);
+            args.session_note = args.session_note.or(Some("review".to_string()));
+            review = true;
+        }
+        Some(Command::Watch { results, testlist }) => {
+            if !results.exists() {
+                eprintln!(
+                    "Error: {}",
+                    testlist::error::Error::ResultsNotFound(results)
+                );
+                std::process::exit(1);
+            }
+            args.testlist = Some(existing_results_testlist(&results, testlist));
+            args.results = Some(results);
+            args.continue_from = true;
+            watch = true;
+        }
+        Some(command) => {
+            run_command(command);
+            return;
+        }
+        None => {}
+    }
+
+    if args.list_templates {
+        for template in TEMPLATES {
+            println!("{:<15} {}", template.name, template.description);
+        }
+        return;
+    }
 
     // Handle --new flag: create template and exit
     if let Some(path) = args.new {
-        if let Err(e) = files::create_template(&path) {
+        let format = args.format.unwrap_or_else(|| Format::from_path(&path));
+        let name = args.template.as_deref().unwrap_or(DEFAULT_TEMPLATE);
+        let created = template::find(name)
+            .and_then(|template| files::create_template(&path, format, template));
+        if let Err(e) = created {
             eprintln!("Error creating template: {}", e);
             std::process::exit(1);
         }
@@ -51,56 +1099,357 @@ fn main() {
         std::process::exit(1);
     };
 
+    if let Some(ref log_file) = args.log_file {
+        if let Err(e) = logging::init(log_file) {
+            eprintln!("Error opening log file {}: {}", log_file.display(), e);
+            std::process::exit(1);
+        }
+    }
+
+    let config = load_config_or_exit();
+    let strict = args.strict || config.strict;
+
     // Get tester name
-    let tester = args
-        .tester
-        .unwrap_or_else(|| std::env::var("USER").unwrap_or_else(|_| "unknown".to_string()));
+    let tester = args.tester.unwrap_or_else(|| default_tester(&config));
+
+    let from_stdin = files::is_stdin(&testlist_path);
+    if from_stdin && args.results.is_none() {
+        eprintln!("Error: --results is required when reading the testlist from stdin");
+        std::process::exit(1);
+    }
 
     // Determine results path
-    let results_path = args.results.unwrap_or_else(|| {
-        let mut path = testlist_path.clone();
-        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
-        let new_name = format!("{}.results.ron", stem);
-        path.set_file_name(new_name);
-        path
-    });
+    let results_path = args
+        .results
+        .unwrap_or_else(|| default_results_path(&testlist_path));
 
     // Load testlist
-    let testlist = match files::load_testlist(&testlist_path) {
+    let format = args
+        .format
+        .unwrap_or_else(|| Format::from_path(&testlist_path));
+    let loaded = if from_stdin {
+        files::read_testlist_stdin(format, strict)
+    } else if strict {
+        files::load_testlist_strict(&testlist_path, format)
+    } else {
+        files::load_testlist_as(&testlist_path, format)
+    };
+    let testlist = match loaded {
         Ok(t) => t,
         Err(e) => {
+            log::error!("Could not load testlist {}: {}", testlist_path.display(), e);
             eprintln!("Error loading testlist: {}", e);
             std::process::exit(1);
         }
     };
+    log::info!(
+        "Loaded testlist {} ({} tests, {:?})",
+        testlist_path.display(),
+        testlist.tests.len(),
+        format
+    );
+
+    let comparison = baseline_path.map(|path| match files::load_results(&path, &testlist) {
+        Ok(results) => Comparison {
+            label: path.file_name().map_or(path.display().to_string(), |n| {
+                n.to_string_lossy().to_string()
+            }),
+            results,
+        },
+        Err(e) => {
+            eprintln!("Error loading results {}: {}", path.display(), e);
+            std::process::exit(1);
+        }
+    });
 
     // Load or create results
-    let results = if args.continue_from && results_path.exists() {
-        match files::load_results(&results_path, &testlist) {
-            Ok(r) => r,
+    let mut results = if args.continue_from && results_path.exists() {
+        let loaded = if strict {
+            files::load_results_strict(&results_path, &testlist)
+        } else {
+            files::load_results(&results_path, &testlist)
+        };
+        match loaded {
+            Ok(r) => {
+                log::info!("Loaded results {}", results_path.display());
+                r
+            }
             Err(e) => {
+                log::error!("Could not load results {}: {}", results_path.display(), e);
                 eprintln!("Error loading results: {}", e);
                 std::process::exit(1);
             }
         }
     } else {
+        log::info!("Starting new results {}", results_path.display());
         TestlistResults::new_for_testlist(&testlist, &testlist_path.to_string_lossy(), &tester)
     };
 
+    // Results continued against an edited testlist would mix two checklists
+    if results.testlist_changed(&testlist) && !watch {
+        if !args.allow_changed_testlist {
+            eprintln!(
+                "Error: {} has changed since the results in {} were recorded",
+                testlist_path.display(),
+                results_path.display()
+            );
+            eprintln!("Run with --allow-changed-testlist to continue against the changed testlist");
+            std::process::exit(1);
+        }
+        log::warn!(
+            "Continuing {} against a changed testlist",
+            results_path.display()
+        );
+        eprintln!(
+            "Warning: {} has changed since the results were recorded",
+            testlist_path.display()
+        );
+    }
+
+    // Tests added to the testlist since the results were saved start out pending
+    results.reconcile(&testlist);
+
+    // Replay changes a crashed session made after its last save
+    let journal_path = journal::journal_path(&results_path);
+    let mut recovered = false;
+    let unsaved = journal::has_unsaved_changes(&journal_path, &results_path);
+    if unsaved && watch {
+        // An observer also sees the changes the tester hasn't saved yet
+        if let Ok(entries) = journal::load(&journal_path) {
+            replay(&mut results, &entries);
+        }
+    } else if unsaved {
+        if !args.continue_from {
+            eprintln!(
+                "Error: Unsaved changes from a previous session found in {}",
+                journal_path.display()
+            );
+            eprintln!("Run with --continue to recover them, or delete the journal to discard them");
+            std::process::exit(1);
+        }
+        match journal::load(&journal_path) {
+            Ok(entries) => {
+                replay(&mut results, &entries);
+                // Drop a line torn by the crash, so this session's entries follow whole ones
+                if let Err(e) = journal::rewrite(&journal_path, &entries) {
+                    eprintln!("Error rewriting journal {}: {}", journal_path.display(), e);
+                    std::process::exit(1);
+                }
+                // Changes saved with append-only saving were not lost
+                let (_, unsaved) = split_saved(&entries);
+                recovered = !unsaved.is_empty();
+                log::info!(
+                    "Recovered {} unsaved change(s) from {}",
+                    unsaved.len(),
+                    journal_path.display()
+                );
+                if recovered {
+                    println!(
+                        "Recovered {} unsaved change(s) from {}",
+                        unsaved.len(),
+                        journal_path.display()
+                    );
+                }
+            }
+            Err(e) => {
+                eprintln!("Error loading journal {}: {}", journal_path.display(), e);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    // A replayed journal may carry the metadata from before the testlist was edited
+    results.meta.testlist_sha256 = testlist.sha256().map(str::to_string);
+
+    // Record the session environment; a continued session keeps what it captured first
+    match results.meta.environment {
+        _ if watch => {}
+        Some(ref mut env) => {
+            for (key, value) in &args.env {
+                env.set(key, value);
+            }
+        }
+        None => results.meta.environment = Some(capture_environment(&args.env)),
+    }
+    // Record the git checkout being tested; it replaces one from an earlier session
+    if !args.no_git && !watch {
+        if let Some(git) = capture_git(Path::new(".")) {
+            log::info!("Testing git checkout {}", git.summary());
+            results.meta.git = Some(git);
+        }
+    }
+    if !watch {
+        results.start_session(&tester, args.session_note);
+    }
+
     // Create app state and run TUI
     let mut state = AppState::new(testlist, results, testlist_path, results_path.clone());
+    state.testlist_format = format;
+    state.comparison = comparison;
+    state.reviewer = review.then(|| tester.clone());
+    if !args.only.is_empty() {
+        let unknown: Vec<&str> = args
+            .only
+            .iter()
+            .filter(|id| !state.testlist.tests.iter().any(|t| &t.id == *id))
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            eprintln!(
+                "Error: unknown test ID(s) in --only: {}",
+                unknown.join(", ")
+            );
+            std::process::exit(1);
+        }
+        filter_transforms::focus_tests(&mut state, args.only.clone());
+    }
+    if !args.filter.is_empty() {
+        filter_transforms::apply_launch_filters(&mut state, args.filter);
+    }
+    if args.continue_from && !review {
+        navigation::resume(&mut state, args.resume);
+    }
+    state.testlist_modified = files::modified_time(&state.testlist_path);
+    state.screenshot_command = args.screenshot_command;
+    state.copy_attachments = args.copy_attachments;
+    state.command_timeout = args.command_timeout.map(std::time::Duration::from_secs);
+    state.dirty = recovered;
+
+    state.layout = config.layout;
+    state.backup_count = config.backups;
+    state.strict = strict;
+    state.require_checks = config.require_checks;
+    state.require_failure_notes = config.require_failure_notes;
+    state.require_severity = config.require_severity;
+    state.note_templates = config.note_templates.clone();
+    state.shell = args.shell.or(config.shell);
+    state.autosave_interval = args
+        .autosave
+        .or(config.autosave_secs)
+        .filter(|&secs| secs > 0 && !watch)
+        .map(std::time::Duration::from_secs);
+    state.append_only = config.append_only;
+    state.keymap = config.keymap;
+    state.hooks = config.hooks;
+    state.webhook = config.webhook;
+    state.signing = config.signing;
+    state.github_repo = config.github_repo;
+    state.serve_port = args.serve;
+    state.serve_addr = args.serve_addr;
+    state.read_only = watch;
+    state.accessible = args.accessible || config.accessible;
+    state.announce_path = args.announce.or(config.announce);
+    let builtin_theme = |name: &str| {
+        Theme::builtin(name).unwrap_or_else(|e| {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        })
+    };
+    if let Some(name) = &config.theme {
+        state.theme = builtin_theme(name);
+    } else if state.accessible {
+        state.theme = Theme::HIGH_CONTRAST;
+    }
+
+    // Apply the user theme, if one is configured
+    if let Some(theme_path) = config::theme_path() {
+        match config::load_theme(&theme_path) {
+            Ok(Some(theme)) => ui_transforms::set_user_theme(&mut state, theme),
+            Ok(None) => {}
+            Err(e) => {
+                eprintln!("Error loading theme {}: {}", theme_path.display(), e);
+                std::process::exit(1);
+            }
+        }
+    }
+    if let Some(name) = &args.theme {
+        state.theme = builtin_theme(name);
+    }
 
+    if watch {
+        log::info!("Watching {}", results_path.display());
+    } else {
+        log::info!("Session started by {}", tester);
+    }
     if let Err(e) = testlist::ui::app::run(&mut state) {
+        log::error!("TUI error: {}", e);
         eprintln!("Error running TUI: {}", e);
         std::process::exit(1);
     }
+    if watch {
+        // The results and journal belong to the tester being watched
+        return;
+    }
 
     // Save results on exit (unless user chose to quit without saving)
     if !state.skip_save {
-        if let Err(e) = files::save_results(&state.results, &results_path) {
+        state.results.end_session(&state.testlist);
+        if let Err(e) = files::save_results(&state.results, &results_path, state.backup_count) {
+            log::error!(
+                "Could not save results to {}: {}",
+                results_path.display(),
+                e
+            );
             eprintln!("Error saving results: {}", e);
             std::process::exit(1);
         }
+        log::info!("Saved results to {} on exit", results_path.display());
         println!("Results saved to: {}", results_path.display());
+        sign_saved_results(&results_path, state.signing.as_ref());
+        if let Some(ref archive_dir) = args.archive_dir {
+            if state.results.meta.completed.is_some() {
+                match files::archive_results(&state.results, &results_path, archive_dir) {
+                    Ok(path) => {
+                        log::info!("Archived run to {}", path.display());
+                        println!("Run archived to: {}", path.display())
+                    }
+                    Err(e) => {
+                        log::error!("Could not archive results: {}", e);
+                        eprintln!("Error archiving results: {}", e)
+                    }
+                }
+            }
+        }
+        if let Err(e) = journal::clear(&journal_path) {
+            eprintln!("Error removing journal {}: {}", journal_path.display(), e);
+        }
+    } else {
+        log::info!("Quit without saving");
+        // Changes saved to the journal stay for the next session
+        if let Err(e) = journal::discard_unsaved(&journal_path) {
+            eprintln!("Error removing journal {}: {}", journal_path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_continue_flag() {
+        let args = Args::try_parse_from(["testlist", "t.testlist.ron", "--continue"]).unwrap();
+        assert!(args.continue_from);
+        assert!(Args::try_parse_from(["testlist", "t.testlist.ron", "--continue-from"]).is_err());
+    }
+
+    #[test]
+    fn test_serve_addr_defaults_to_localhost() {
+        let args = Args::try_parse_from(["testlist", "t.testlist.ron", "--serve", "8080"]).unwrap();
+        assert!(args.serve_addr.is_loopback());
+        let args = Args::try_parse_from([
+            "testlist",
+            "t.testlist.ron",
+            "--serve",
+            "8080",
+            "--serve-addr",
+            "0.0.0.0",
+        ])
+        .unwrap();
+        assert!(args.serve_addr.is_unspecified());
+        assert!(
+            Args::try_parse_from(["testlist", "t.testlist.ron", "--serve-addr", "0.0.0.0"])
+                .is_err()
+        );
     }
 }
@@ -0,0 +1,155 @@
+//! Queries describing state changes as plain-text announcements, which
+//! `--announce` writes for screen readers to speak.
+
+use std::collections::BTreeMap;
+
+use crate::data::definition::Test;
+use crate::data::state::AppState;
+use crate::queries::tests::{result_for_test, status_label};
+
+/// What was true when the tester was last told about the state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Announced {
+    /// The selected test's ID, or the selected section's title.
+    selection: Option<String>,
+    /// Each test's spoken status, by test ID.
+    statuses: BTreeMap<String, String>,
+    status_message: Option<String>,
+}
+
+/// A test's status as spoken: its label, with the severity of a failure or
+/// the reason a test is blocked.
+fn spoken_status(state: &AppState, test: &Test) -> String {
+    let label = status_label(&state.testlist, &state.results, test);
+    let result = result_for_test(&state.results, &test.id);
+    if let Some(severity) = result.and_then(|r| r.severity) {
+        return format!("{} ({})", label, severity.label());
+    }
+    match result.and_then(|r| r.blocked_reason.as_ref()) {
+        Some(reason) => format!("{}: {}", label, reason),
+        None => label,
+    }
+}
+
+/// Take note of the current selection, statuses, and status message.
+pub fn snapshot(state: &AppState) -> Announced {
+    let selection = match &state.selected_section {
+        Some(title) => Some(title.clone()),
+        None => state
+            .testlist
+            .tests
+            .get(state.selected_test)
+            .map(|t| t.id.clone()),
+    };
+    Announced {
+        selection,
+        statuses: state
+            .testlist
+            .tests
+            .iter()
+            .map(|t| (t.id.clone(), spoken_status(state, t)))
+            .collect(),
+        status_message: state.status_message.clone(),
+    }
+}
+
+/// What is known at the start of a session: everything but the selection,
+/// so that the starting selection is announced.
+pub fn initial(state: &AppState) -> Announced {
+    Announced {
+        selection: None,
+        ..snapshot(state)
+    }
+}
+
+/// Lines announcing what changed since `before`: tests whose status
+/// changed, in testlist order, then a new selection, then a new status
+/// message.
+pub fn announcements(state: &AppState, before: &Announced) -> Vec<String> {
+    let now = snapshot(state);
+    let mut lines = Vec::new();
+    for test in &state.testlist.tests {
+        let status = &now.statuses[&test.id];
+        // A test that was added by a reload is announced with the selection
+        if before.statuses.get(&test.id).is_some_and(|s| s != status) {
+            lines.push(format!("{}: {}", test.title, status));
+        }
+    }
+    if now.selection != before.selection {
+        if let Some(title) = &state.selected_section {
+            lines.push(format!("Section {}", title));
+        } else if let Some(test) = state.testlist.tests.get(state.selected_test) {
+            lines.push(format!(
+                "Test {} of {}: {}, {}",
+                state.selected_test + 1,
+                state.testlist.tests.len(),
+                test.title,
+                now.statuses[&test.id]
+            ));
+        }
+    }
+    if now.status_message != before.status_message {
+        if let Some(message) = &now.status_message {
+            lines.push(message.clone());
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    use crate::data::results::{Severity, Status};
+
+    fn make_state() -> AppState {
+        let mut testlist = fixtures::testlist(
+            ["build", "login"]
+                .iter()
+                .map(|id| Test {
+                    id: id.to_string(),
+                    title: format!("Check {}", id),
+                    depends_on: if *id == "login" {
+                        vec!["build".to_string()]
+                    } else {
+                        Vec::new()
+                    },
+                    ..Default::default()
+                })
+                .collect(),
+        );
+        testlist.meta.title = "Release".to_string();
+        fixtures::state(testlist)
+    }
+
+    #[test]
+    fn test_announcements() {
+        let mut state = make_state();
+        let before = snapshot(&state);
+        assert!(announcements(&state, &before).is_empty());
+
+        state.selected_test = 1;
+        assert_eq!(
+            announcements(&state, &before),
+            vec!["Test 2 of 2: Check login, waiting"]
+        );
+
+        // Passing the dependency changes how the dependent test is spoken too
+        let before = snapshot(&state);
+        state.results.results[0].status = Status::Passed;
+        state.status_message = Some("Saved".to_string());
+        assert_eq!(
+            announcements(&state, &before),
+            vec!["Check build: passed", "Check login: pending", "Saved"]
+        );
+
+        let before = snapshot(&state);
+        state.results.results[1].status = Status::Failed;
+        state.results.results[1].severity = Some(Severity::Major);
+        assert_eq!(
+            announcements(&state, &before),
+            vec!["Check login: failed (major)"]
+        );
+    }
+}
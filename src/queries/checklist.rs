@@ -1,6 +1,7 @@
 //! Queries related to checklist item states.
 
 use crate::data::results::{checklist_key, ChecklistSection, TestlistResults};
+use crate::data::state::AppState;
 
 /// Check if a checklist item is checked.
 pub fn is_checked(
@@ -31,43 +32,43 @@ pub fn checklist_progress(
     (checked, item_ids.len())
 }
 
+/// Check whether tests may only pass once all their verify items are
+/// checked, by the testlist or the config file.
+pub fn checks_required(state: &AppState) -> bool {
+    state.require_checks || state.testlist.meta.require_checks
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data::definition::{ChecklistItem, Meta, Test, Testlist};
+    use crate::data::definition::{ChecklistItem, Test};
     use crate::data::results::TestlistResults;
+    use crate::fixtures;
 
     fn make_results() -> TestlistResults {
-        let testlist = Testlist {
-            meta: Meta {
-                title: "Test".to_string(),
-                description: "".to_string(),
-                created: "".to_string(),
-                version: "1".to_string(),
-            },
-            tests: vec![Test {
-                id: "t1".to_string(),
-                title: "Test".to_string(),
-                description: "".to_string(),
-                setup: vec![
-                    ChecklistItem {
-                        id: "s0".to_string(),
-                        text: "Step".to_string(),
-                    },
-                    ChecklistItem {
-                        id: "s1".to_string(),
-                        text: "Step".to_string(),
-                    },
-                ],
-                action: "Act".to_string(),
-                verify: vec![ChecklistItem {
-                    id: "v0".to_string(),
-                    text: "Check".to_string(),
-                }],
-                suggested_command: None,
+        let testlist = fixtures::testlist(vec![Test {
+            id: "t1".to_string(),
+            title: "Test".to_string(),
+            description: "".to_string(),
+            setup: vec![
+                ChecklistItem {
+                    id: "s0".to_string(),
+                    text: "Step".to_string(),
+                },
+                ChecklistItem {
+                    id: "s1".to_string(),
+                    text: "Step".to_string(),
+                },
+            ],
+            action: "Act".to_string(),
+            verify: vec![ChecklistItem {
+                id: "v0".to_string(),
+                text: "Check".to_string(),
             }],
-        };
-        let mut results = TestlistResults::new_for_testlist(&testlist, "test.ron", "tester");
+            suggested_command: None,
+            ..Default::default()
+        }]);
+        let mut results = fixtures::results(&testlist);
         results
             .checklist_results
             .insert("t1:setup:s0".to_string(), true);
@@ -0,0 +1,234 @@
+//! Comparing two results files recorded against the same testlist.
+
+use crate::data::definition::{Test, Testlist};
+use crate::data::results::{Status, TestlistResults};
+use crate::data::state::AppState;
+use crate::queries::tests::result_for_test;
+
+/// How one test differs between two results files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestChange {
+    pub test_id: String,
+    pub title: String,
+    /// Status in the older results, or `None` if the test is missing there.
+    pub old_status: Option<Status>,
+    pub new_status: Option<Status>,
+    pub old_notes: Option<String>,
+    pub new_notes: Option<String>,
+}
+
+impl TestChange {
+    pub fn status_changed(&self) -> bool {
+        self.old_status != self.new_status
+    }
+
+    pub fn notes_changed(&self) -> bool {
+        self.old_notes != self.new_notes
+    }
+
+    /// A test that passed before and fails now.
+    pub fn is_regression(&self) -> bool {
+        self.old_status == Some(Status::Passed) && self.new_status == Some(Status::Failed)
+    }
+
+    /// A test that passes now but did not before.
+    pub fn is_newly_passing(&self) -> bool {
+        self.new_status == Some(Status::Passed) && self.old_status != Some(Status::Passed)
+    }
+
+    /// A test that is skipped now but was not before.
+    pub fn is_newly_skipped(&self) -> bool {
+        self.new_status == Some(Status::Skipped) && self.old_status != Some(Status::Skipped)
+    }
+}
+
+/// Tests whose status or notes differ, in testlist order.
+pub fn diff_results(
+    testlist: &Testlist,
+    old: &TestlistResults,
+    new: &TestlistResults,
+) -> Vec<TestChange> {
+    testlist
+        .tests
+        .iter()
+        .map(|test| test_change(test, old, new))
+        .filter(|change| change.status_changed() || change.notes_changed())
+        .collect()
+}
+
+/// Compare one test's results.
+pub fn test_change(test: &Test, old: &TestlistResults, new: &TestlistResults) -> TestChange {
+    let old = result_for_test(old, &test.id);
+    let new = result_for_test(new, &test.id);
+    TestChange {
+        test_id: test.id.clone(),
+        title: test.title.clone(),
+        old_status: old.map(|r| r.status),
+        new_status: new.map(|r| r.status),
+        old_notes: old.and_then(|r| r.notes.clone()),
+        new_notes: new.and_then(|r| r.notes.clone()),
+    }
+}
+
+/// How a test differs from the baseline in compare mode, if comparing.
+pub fn compared_change(state: &AppState, test: &Test) -> Option<TestChange> {
+    let comparison = state.comparison.as_ref()?;
+    Some(test_change(test, &comparison.results, &state.results))
+}
+
+/// Render a diff as plain text, with regressions in red when `color` is set.
+pub fn format_diff(changes: &[TestChange], color: bool) -> String {
+    let status = |s: Option<Status>| s.map_or("—".to_string(), |s| format!("{:?}", s));
+    let transition = |c: &TestChange| {
+        format!(
+            "  {}  {}: {} → {}\n",
+            c.test_id,
+            c.title,
+            status(c.old_status),
+            status(c.new_status)
+        )
+    };
+
+    let regressions: Vec<&TestChange> = changes.iter().filter(|c| c.is_regression()).collect();
+    let skipped: Vec<&TestChange> = changes.iter().filter(|c| c.is_newly_skipped()).collect();
+    let other: Vec<&TestChange> = changes
+        .iter()
+        .filter(|c| c.status_changed() && !c.is_regression() && !c.is_newly_skipped())
+        .collect();
+    let notes: Vec<&TestChange> = changes.iter().filter(|c| c.notes_changed()).collect();
+
+    let mut out = String::new();
+    if !regressions.is_empty() {
+        out.push_str("Regressions:\n");
+        for change in &regressions {
+            if color {
+                out.push_str(&format!("\x1b[31m{}\x1b[0m", transition(change)));
+            } else {
+                out.push_str(&transition(change));
+            }
+        }
+        out.push('\n');
+    }
+    if !skipped.is_empty() {
+        out.push_str("Newly skipped:\n");
+        skipped.iter().for_each(|c| out.push_str(&transition(c)));
+        out.push('\n');
+    }
+    if !other.is_empty() {
+        out.push_str("Status changes:\n");
+        other.iter().for_each(|c| out.push_str(&transition(c)));
+        out.push('\n');
+    }
+    if !notes.is_empty() {
+        out.push_str("Notes changed:\n");
+        for change in &notes {
+            out.push_str(&format!("  {}  {}\n", change.test_id, change.title));
+            for line in change.old_notes.iter().flat_map(|n| n.lines()) {
+                out.push_str(&format!("    - {}\n", line));
+            }
+            for line in change.new_notes.iter().flat_map(|n| n.lines()) {
+                out.push_str(&format!("    + {}\n", line));
+            }
+        }
+        out.push('\n');
+    }
+
+    let plural = |n: usize, word: &str| format!("{} {}{}", n, word, if n == 1 { "" } else { "s" });
+    out.push_str(&format!(
+        "{}, {} newly skipped, {}, {}\n",
+        plural(regressions.len(), "regression"),
+        skipped.len(),
+        plural(other.len(), "other status change"),
+        plural(notes.len(), "note change"),
+    ));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    fn make_testlist() -> Testlist {
+        fixtures::testlist(
+            (1..=4)
+                .map(|n| Test {
+                    id: format!("t{}", n),
+                    title: format!("Test {}", n),
+                    ..Default::default()
+                })
+                .collect(),
+        )
+    }
+
+    fn make_pair() -> (Testlist, TestlistResults, TestlistResults) {
+        let testlist = make_testlist();
+        let mut old = TestlistResults::new_for_testlist(&testlist, "test.ron", "alice");
+        let mut new = TestlistResults::new_for_testlist(&testlist, "test.ron", "bob");
+        for results in [&mut old, &mut new] {
+            for result in &mut results.results {
+                result.status = Status::Passed;
+            }
+        }
+        new.results[1].status = Status::Failed;
+        new.results[2].status = Status::Skipped;
+        old.results[3].status = Status::Failed;
+        new.results[3].notes = Some("fixed in rc2".to_string());
+        (testlist, old, new)
+    }
+
+    #[test]
+    fn test_diff_results_classifies_changes() {
+        let (testlist, old, new) = make_pair();
+        let changes = diff_results(&testlist, &old, &new);
+        let ids: Vec<&str> = changes.iter().map(|c| c.test_id.as_str()).collect();
+        assert_eq!(ids, vec!["t2", "t3", "t4"]);
+        assert!(changes[0].is_regression());
+        assert!(changes[1].is_newly_skipped());
+        assert!(!changes[2].is_regression());
+        assert!(changes[2].is_newly_passing());
+        assert!(changes[2].status_changed() && changes[2].notes_changed());
+    }
+
+    #[test]
+    fn test_diff_results_handles_missing_tests() {
+        let (testlist, old, mut new) = make_pair();
+        new.results.retain(|r| r.test_id != "t1");
+        let changes = diff_results(&testlist, &old, &new);
+        assert_eq!(changes[0].test_id, "t1");
+        assert_eq!(changes[0].new_status, None);
+    }
+
+    #[test]
+    fn test_compared_change_needs_comparison() {
+        let (testlist, old, new) = make_pair();
+        let mut state = AppState::new(
+            testlist,
+            new,
+            std::path::PathBuf::from("test.testlist.ron"),
+            std::path::PathBuf::from("rc2.results.ron"),
+        );
+        let test = state.testlist.tests[1].clone();
+        assert_eq!(compared_change(&state, &test), None);
+        state.comparison = Some(crate::data::state::Comparison {
+            label: "rc1.results.ron".to_string(),
+            results: old,
+        });
+        assert!(compared_change(&state, &test).unwrap().is_regression());
+    }
+
+    #[test]
+    fn test_format_diff() {
+        let (testlist, old, new) = make_pair();
+        let text = format_diff(&diff_results(&testlist, &old, &new), false);
+        assert_eq!(
+            text,
+            "Regressions:\n  t2  Test 2: Passed → Failed\n\n\
+             Newly skipped:\n  t3  Test 3: Passed → Skipped\n\n\
+             Status changes:\n  t4  Test 4: Failed → Passed\n\n\
+             Notes changed:\n  t4  Test 4\n    + fixed in rc2\n\n\
+             1 regression, 1 newly skipped, 1 other status change, 1 note change\n"
+        );
+        assert!(format_diff(&diff_results(&testlist, &old, &new), true).contains("\x1b[31m"));
+    }
+}
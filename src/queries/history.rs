@@ -0,0 +1,169 @@
+//! Statistics over a history of archived runs.
+
+use crate::data::definition::Testlist;
+use crate::data::results::{Status, TestResult, TestlistResults};
+
+/// How one test fared across runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestHistory {
+    pub test_id: String,
+    /// Status in each run that includes the test, oldest first.
+    pub outcomes: Vec<Status>,
+    /// Time spent in each run that recorded any, earlier attempts included.
+    pub durations: Vec<u64>,
+}
+
+impl TestHistory {
+    pub fn count(&self, status: Status) -> usize {
+        self.outcomes.iter().filter(|&&s| s == status).count()
+    }
+
+    /// Share of runs with a verdict (passed, failed, or inconclusive) in
+    /// which the test passed; `None` if it never got a verdict.
+    pub fn pass_rate(&self) -> Option<f64> {
+        let verdicts = self
+            .outcomes
+            .iter()
+            .filter(|s| matches!(s, Status::Passed | Status::Failed | Status::Inconclusive))
+            .count();
+        (verdicts > 0).then(|| self.count(Status::Passed) as f64 / verdicts as f64)
+    }
+
+    /// Mean time spent per run, over the runs that recorded time.
+    pub fn average_secs(&self) -> Option<u64> {
+        let total: u64 = self.durations.iter().sum();
+        total.checked_div(self.durations.len() as u64)
+    }
+}
+
+/// When a run started: its first session, or its completion time.
+pub fn run_started(run: &TestlistResults) -> Option<&str> {
+    run.meta
+        .sessions
+        .first()
+        .map(|s| s.started.as_str())
+        .or(run.meta.completed.as_deref())
+}
+
+/// Sort runs oldest first.
+pub fn sort_runs(runs: &mut [TestlistResults]) {
+    runs.sort_by_cached_key(|run| {
+        run_started(run).and_then(|at| chrono::DateTime::parse_from_rfc3339(at).ok())
+    });
+}
+
+fn total_secs(result: &TestResult) -> u64 {
+    result.time_spent_secs
+        + result
+            .attempts
+            .iter()
+            .map(|a| a.time_spent_secs)
+            .sum::<u64>()
+}
+
+/// History of every test in the testlist, in testlist order, followed by
+/// tests that only appear in the archived runs. `runs` should be sorted.
+pub fn test_history(testlist: &Testlist, runs: &[TestlistResults]) -> Vec<TestHistory> {
+    let mut ids: Vec<&str> = testlist.tests.iter().map(|t| t.id.as_str()).collect();
+    for result in runs.iter().flat_map(|run| &run.results) {
+        if !ids.contains(&result.test_id.as_str()) {
+            ids.push(&result.test_id);
+        }
+    }
+    ids.into_iter()
+        .map(|id| {
+            let results: Vec<&TestResult> = runs
+                .iter()
+                .filter_map(|run| run.results.iter().find(|r| r.test_id == id))
+                .collect();
+            TestHistory {
+                test_id: id.to_string(),
+                outcomes: results.iter().map(|r| r.status).collect(),
+                durations: results
+                    .iter()
+                    .map(|r| total_secs(r))
+                    .filter(|&secs| secs > 0)
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+/// Tests that failed at least once, most failures first.
+pub fn most_failing(history: &[TestHistory], limit: usize) -> Vec<&TestHistory> {
+    let mut failing: Vec<&TestHistory> = history
+        .iter()
+        .filter(|h| h.count(Status::Failed) > 0)
+        .collect();
+    failing.sort_by_key(|h| std::cmp::Reverse(h.count(Status::Failed)));
+    failing.truncate(limit);
+    failing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::Test;
+    use crate::fixtures;
+
+    fn make_testlist() -> Testlist {
+        let mut testlist = fixtures::testlist(
+            ["build", "login", "docs"]
+                .iter()
+                .map(|id| Test {
+                    id: id.to_string(),
+                    title: id.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+        );
+        testlist.meta.title = "Release".to_string();
+        testlist
+    }
+
+    fn make_run(started: &str, statuses: [Status; 3]) -> TestlistResults {
+        let mut run = TestlistResults::new_for_testlist(&make_testlist(), "t.ron", "alice");
+        run.start_session("alice", None);
+        run.meta.sessions[0].started = started.to_string();
+        for (result, status) in run.results.iter_mut().zip(statuses) {
+            result.status = status;
+            result.time_spent_secs = 60;
+        }
+        run
+    }
+
+    #[test]
+    fn test_history_across_runs() {
+        use Status::*;
+        let mut runs = vec![
+            make_run("2025-02-01T10:00:00Z", [Passed, Failed, Skipped]),
+            make_run("2025-01-01T10:00:00Z", [Passed, Passed, Skipped]),
+            make_run("2025-03-01T10:00:00Z", [Failed, Failed, Skipped]),
+        ];
+        runs[0].results[0].time_spent_secs = 120;
+        sort_runs(&mut runs);
+        let history = test_history(&make_testlist(), &runs);
+
+        assert_eq!(history[1].outcomes, vec![Passed, Failed, Failed]);
+        assert!((history[1].pass_rate().unwrap() - 1.0 / 3.0).abs() < 1e-9);
+        assert_eq!(history[2].pass_rate(), None);
+        assert_eq!(history[0].average_secs(), Some(80));
+
+        let failing: Vec<&str> = most_failing(&history, 5)
+            .iter()
+            .map(|h| h.test_id.as_str())
+            .collect();
+        assert_eq!(failing, vec!["login", "build"]);
+    }
+
+    #[test]
+    fn test_history_includes_removed_tests() {
+        let mut run = make_run("2025-01-01T10:00:00Z", [Status::Passed; 3]);
+        run.results[2].test_id = "old".to_string();
+        let history = test_history(&make_testlist(), &[run]);
+        let ids: Vec<&str> = history.iter().map(|h| h.test_id.as_str()).collect();
+        assert_eq!(ids, vec!["build", "login", "docs", "old"]);
+        assert!(history[2].outcomes.is_empty());
+        assert_eq!(history[2].average_secs(), None);
+    }
+}
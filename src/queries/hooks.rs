@@ -0,0 +1,320 @@
+//! Queries deciding which configured hooks and webhook notifications to send
+//! and what they are told.
+
+use serde::Serialize;
+
+use crate::data::results::{Severity, Status, TestlistResults};
+use crate::data::state::AppState;
+use crate::queries::stats::{status_counts, StatusCounts};
+use crate::queries::tests::result_for_test;
+
+/// The test a hook event is about.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HookTest {
+    pub id: String,
+    pub title: String,
+    pub section: Option<String>,
+    pub status: Status,
+    pub severity: Option<Severity>,
+    pub notes: Option<String>,
+    pub defects: Vec<String>,
+}
+
+/// What a hook is told about an event; sent to it as JSON on stdin.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HookEvent {
+    /// Name of the hook, e.g. `on_test_failed`.
+    pub event: &'static str,
+    pub testlist: String,
+    pub results_file: String,
+    pub tester: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub test: Option<HookTest>,
+    pub counts: StatusCounts,
+}
+
+impl HookEvent {
+    /// The event as `TESTLIST_*` environment variables.
+    pub fn env_vars(&self) -> Vec<(&'static str, String)> {
+        let mut vars = vec![
+            ("TESTLIST_EVENT", self.event.to_string()),
+            ("TESTLIST_TITLE", self.testlist.clone()),
+            ("TESTLIST_RESULTS", self.results_file.clone()),
+            ("TESTLIST_TESTER", self.tester.clone()),
+            ("TESTLIST_PASSED", self.counts.passed.to_string()),
+            ("TESTLIST_FAILED", self.counts.failed.to_string()),
+        ];
+        if let Some(test) = &self.test {
+            vars.extend([
+                ("TESTLIST_TEST_ID", test.id.clone()),
+                ("TESTLIST_TEST_TITLE", test.title.clone()),
+                ("TESTLIST_STATUS", format!("{:?}", test.status)),
+                ("TESTLIST_NOTES", test.notes.clone().unwrap_or_default()),
+            ]);
+        }
+        vars
+    }
+}
+
+fn event(state: &AppState, name: &'static str, test: Option<HookTest>) -> HookEvent {
+    HookEvent {
+        event: name,
+        testlist: state.testlist.meta.title.clone(),
+        results_file: state.results_path.display().to_string(),
+        tester: state
+            .results
+            .meta
+            .sessions
+            .last()
+            .map_or_else(|| state.results.meta.tester.clone(), |s| s.tester.clone()),
+        test,
+        counts: status_counts(&state.testlist, &state.results),
+    }
+}
+
+/// Events for the changes from `before` to the current results: one per test
+/// that became Passed or Failed, in testlist order, then
+/// `on_session_complete` if the last open test was just finished.
+fn session_events(state: &AppState, before: &TestlistResults) -> Vec<HookEvent> {
+    let mut events = Vec::new();
+    for test in &state.testlist.tests {
+        let Some(result) = result_for_test(&state.results, &test.id) else {
+            continue;
+        };
+        if result_for_test(before, &test.id).map(|r| r.status) == Some(result.status) {
+            continue;
+        }
+        let name = match result.status {
+            Status::Passed => "on_test_passed",
+            Status::Failed => "on_test_failed",
+            _ => continue,
+        };
+        let test = HookTest {
+            id: test.id.clone(),
+            title: test.title.clone(),
+            section: test.section.clone(),
+            status: result.status,
+            severity: result.severity,
+            notes: result.notes.clone(),
+            defects: result.defects.clone(),
+        };
+        events.push(event(state, name, Some(test)));
+    }
+    let open =
+        |results: &TestlistResults| results.results.iter().any(|r| r.is_open(&state.testlist));
+    if open(before) && !open(&state.results) {
+        events.push(event(state, "on_session_complete", None));
+    }
+    events
+}
+
+/// Hooks to run for the changes from `before` to the current results, as
+/// (command, event) pairs in the order of [`session_events`].
+pub fn triggered_hooks(state: &AppState, before: &TestlistResults) -> Vec<(String, HookEvent)> {
+    let hooks = &state.hooks;
+    if hooks.is_empty() {
+        return Vec::new();
+    }
+    session_events(state, before)
+        .into_iter()
+        .filter_map(|event| {
+            let command = match event.event {
+                "on_test_passed" => &hooks.on_test_passed,
+                "on_test_failed" => &hooks.on_test_failed,
+                _ => &hooks.on_session_complete,
+            };
+            command.clone().map(|command| (command, event))
+        })
+        .collect()
+}
+
+/// Requests to send to the configured webhook for the changes from `before`
+/// to the current results, as (url, JSON body) pairs: one per failed test and
+/// one when the session completes.
+pub fn webhook_requests(state: &AppState, before: &TestlistResults) -> Vec<(String, String)> {
+    let Some(webhook) = &state.webhook else {
+        return Vec::new();
+    };
+    session_events(state, before)
+        .into_iter()
+        .filter(|event| event.event != "on_test_passed")
+        .map(|event| {
+            let body = webhook_payload(&event, webhook.template.as_deref());
+            (webhook.url.clone(), body)
+        })
+        .collect()
+}
+
+/// One-line description of the event, e.g. `Release: login failed (Check login)`.
+pub fn event_summary(event: &HookEvent) -> String {
+    match &event.test {
+        Some(test) => format!(
+            "{}: {} {} ({})",
+            event.testlist,
+            test.id,
+            format!("{:?}", test.status).to_lowercase(),
+            test.title
+        ),
+        None => format!(
+            "{}: session complete, {} passed, {} failed",
+            event.testlist, event.counts.passed, event.counts.failed
+        ),
+    }
+}
+
+/// The webhook body for an event: `template` with its `{placeholders}` filled
+/// in as JSON-escaped strings, or the event as JSON with a `text` summary.
+pub fn webhook_payload(event: &HookEvent, template: Option<&str>) -> String {
+    let summary = event_summary(event);
+    let Some(template) = template else {
+        let mut value = serde_json::to_value(event).unwrap_or_default();
+        if let Some(fields) = value.as_object_mut() {
+            fields.insert("text".to_string(), summary.into());
+        }
+        return value.to_string();
+    };
+    let test = event.test.as_ref();
+    let values = [
+        ("event", event.event.to_string()),
+        ("testlist", event.testlist.clone()),
+        ("results_file", event.results_file.clone()),
+        ("tester", event.tester.clone()),
+        ("summary", summary),
+        ("passed", event.counts.passed.to_string()),
+        ("failed", event.counts.failed.to_string()),
+        ("test_id", test.map(|t| t.id.clone()).unwrap_or_default()),
+        (
+            "test_title",
+            test.map(|t| t.title.clone()).unwrap_or_default(),
+        ),
+        (
+            "status",
+            test.map(|t| format!("{:?}", t.status)).unwrap_or_default(),
+        ),
+        (
+            "notes",
+            test.and_then(|t| t.notes.clone()).unwrap_or_default(),
+        ),
+    ];
+    // One pass, so placeholders inside the values are left as they are
+    let placeholder = regex::Regex::new(r"\{(\w+)\}").expect("valid placeholder pattern");
+    placeholder
+        .replace_all(template, |caps: &regex::Captures| {
+            match values.iter().find(|(name, _)| *name == &caps[1]) {
+                Some((_, value)) => {
+                    let escaped = serde_json::to_string(value).unwrap_or_default();
+                    escaped[1..escaped.len() - 1].to_string()
+                }
+                None => caps[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::config::{Hooks, Webhook};
+    use crate::data::definition::Test;
+    use crate::fixtures;
+
+    fn make_state() -> AppState {
+        let mut testlist = fixtures::testlist(
+            ["build", "login"]
+                .iter()
+                .map(|id| Test {
+                    id: id.to_string(),
+                    title: format!("Check {}", id),
+                    ..Default::default()
+                })
+                .collect(),
+        );
+        testlist.meta.title = "Release".to_string();
+        let mut state = fixtures::state(testlist);
+        state.hooks = Hooks {
+            on_test_failed: Some("notify failed".to_string()),
+            on_session_complete: Some("notify done".to_string()),
+            ..Default::default()
+        };
+        state
+    }
+
+    #[test]
+    fn test_hooks_for_status_changes() {
+        let mut state = make_state();
+        let before = state.results.clone();
+        state.results.results[0].status = Status::Passed;
+        state.results.results[1].status = Status::Failed;
+        state.results.results[1].notes = Some("Spinner hangs".to_string());
+
+        let triggered = triggered_hooks(&state, &before);
+        let commands: Vec<&str> = triggered.iter().map(|(c, _)| c.as_str()).collect();
+        assert_eq!(commands, vec!["notify failed", "notify done"]);
+
+        let failed = &triggered[0].1;
+        assert_eq!(failed.event, "on_test_failed");
+        assert_eq!(failed.test.as_ref().unwrap().id, "login");
+        let vars = failed.env_vars();
+        assert!(vars.contains(&("TESTLIST_STATUS", "Failed".to_string())));
+        assert!(vars.contains(&("TESTLIST_NOTES", "Spinner hangs".to_string())));
+        assert_eq!(triggered[1].1.counts.passed, 1);
+
+        // Setting a severity later doesn't fire the hook again
+        let before = state.results.clone();
+        state.results.results[1].severity = Some(Severity::Major);
+        assert!(triggered_hooks(&state, &before).is_empty());
+    }
+
+    #[test]
+    fn test_webhook_requests() {
+        let mut state = make_state();
+        state.webhook = Some(Webhook {
+            url: "https://hooks.example.com/T1".to_string(),
+            template: Some(r#"{"text": "{summary}", "notes": "{notes}"}"#.to_string()),
+        });
+        let before = state.results.clone();
+        state.results.results[0].status = Status::Passed;
+        state.results.results[1].status = Status::Failed;
+        state.results.results[1].notes = Some("Says \"oops\"".to_string());
+
+        let requests = webhook_requests(&state, &before);
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].0, "https://hooks.example.com/T1");
+        let failed: serde_json::Value = serde_json::from_str(&requests[0].1).unwrap();
+        assert_eq!(failed["text"], "Release: login failed (Check login)");
+        assert_eq!(failed["notes"], "Says \"oops\"");
+        let complete: serde_json::Value = serde_json::from_str(&requests[1].1).unwrap();
+        assert_eq!(
+            complete["text"],
+            "Release: session complete, 1 passed, 1 failed"
+        );
+
+        state.webhook.as_mut().unwrap().template = None;
+        let requests = webhook_requests(&state, &before);
+        let failed: serde_json::Value = serde_json::from_str(&requests[0].1).unwrap();
+        assert_eq!(failed["event"], "on_test_failed");
+        assert_eq!(failed["test"]["id"], "login");
+        assert_eq!(failed["text"], "Release: login failed (Check login)");
+    }
+
+    #[test]
+    fn test_webhook_template_ignores_placeholders_in_values() {
+        let mut state = make_state();
+        state.webhook = Some(Webhook {
+            url: "https://hooks.example.com/T1".to_string(),
+            template: Some(
+                r#"{"notes": "{notes}", "tester": "{tester}", "x": "{unknown}"}"#.to_string(),
+            ),
+        });
+        let before = state.results.clone();
+        state.results.results[1].status = Status::Failed;
+        state.results.results[1].notes = Some("Expected {tester} in {notes}".to_string());
+        state.results.meta.tester = "qa {notes}".to_string();
+
+        let requests = webhook_requests(&state, &before);
+        let failed: serde_json::Value = serde_json::from_str(&requests[0].1).unwrap();
+        assert_eq!(failed["notes"], "Expected {tester} in {notes}");
+        assert_eq!(failed["tester"], "qa {notes}");
+        assert_eq!(failed["x"], "{unknown}");
+    }
+}
@@ -0,0 +1,38 @@
+//! Queries for which panes are on screen.
+
+use crate::data::state::{AppState, FocusedPane};
+
+/// Check whether the notes pane is drawn. A hidden notes pane still appears
+/// while notes or a screenshot path are being entered.
+pub fn notes_visible(state: &AppState) -> bool {
+    state.layout.notes || state.editing_notes || state.adding_screenshot
+}
+
+/// Check whether the terminal pane is drawn. A hidden terminal still
+/// appears while it has focus, e.g. after running a command.
+pub fn terminal_visible(state: &AppState) -> bool {
+    state.layout.terminal || state.focused_pane == FocusedPane::Terminal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::config::PaneLayout;
+    use crate::fixtures;
+
+    fn make_state() -> AppState {
+        fixtures::state(fixtures::testlist(vec![]))
+    }
+
+    #[test]
+    fn test_hidden_panes_show_while_in_use() {
+        let mut state = make_state();
+        state.layout = PaneLayout::TESTS_ONLY;
+        assert!(!notes_visible(&state));
+        assert!(!terminal_visible(&state));
+        state.editing_notes = true;
+        state.focused_pane = FocusedPane::Terminal;
+        assert!(notes_visible(&state));
+        assert!(terminal_visible(&state));
+    }
+}
@@ -1,4 +1,12 @@
 //! Query layer: read-only functions operating on AppState.
 
+pub mod announce;
 pub mod checklist;
+pub mod diff;
+pub mod history;
+pub mod hooks;
+pub mod layout;
+pub mod progress;
+pub mod sections;
+pub mod stats;
 pub mod tests;
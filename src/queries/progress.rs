@@ -0,0 +1,59 @@
+//! Query building the live progress published by `--serve`.
+
+use serde::Serialize;
+
+use crate::data::results::{Severity, Status};
+use crate::data::state::AppState;
+use crate::queries::stats::{status_counts, StatusCounts};
+use crate::queries::tests::{current_test, result_for_test};
+
+/// One test's row of the progress page.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProgressTest {
+    pub id: String,
+    pub title: String,
+    pub section: Option<String>,
+    pub status: Status,
+    /// Name of the custom status while `status` is `Custom`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_status: Option<String>,
+    pub severity: Option<Severity>,
+}
+
+/// Snapshot of a running session for observers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Progress {
+    pub testlist: String,
+    pub tester: String,
+    /// Test the tester has selected, if any.
+    pub current_test: Option<String>,
+    pub counts: StatusCounts,
+    pub tests: Vec<ProgressTest>,
+}
+
+/// Progress of the session in `state`, with tests in testlist order.
+pub fn progress(state: &AppState) -> Progress {
+    let tests = state
+        .testlist
+        .tests
+        .iter()
+        .map(|test| {
+            let result = result_for_test(&state.results, &test.id);
+            ProgressTest {
+                id: test.id.clone(),
+                title: test.title.clone(),
+                section: test.section.clone(),
+                status: result.map(|r| r.status).unwrap_or_default(),
+                custom_status: result.and_then(|r| r.custom_status.clone()),
+                severity: result.and_then(|r| r.severity),
+            }
+        })
+        .collect();
+    Progress {
+        testlist: state.testlist.meta.title.clone(),
+        tester: state.results.current_tester().to_string(),
+        current_test: current_test(state).map(|t| t.id.clone()),
+        counts: status_counts(&state.testlist, &state.results),
+        tests,
+    }
+}
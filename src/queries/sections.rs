@@ -0,0 +1,106 @@
+//! Queries related to test sections.
+
+use crate::data::definition::Testlist;
+use crate::data::state::AppState;
+use crate::queries::tests::result_for_test;
+
+/// Group test indices by section, in order of each section's first appearance.
+///
+/// Tests without a section form a group keyed by `None`.
+pub fn section_groups(testlist: &Testlist) -> Vec<(Option<&str>, Vec<usize>)> {
+    let mut groups: Vec<(Option<&str>, Vec<usize>)> = Vec::new();
+    for (i, test) in testlist.tests.iter().enumerate() {
+        let key = test.section.as_deref();
+        match groups.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, indices)) => indices.push(i),
+            None => groups.push((key, vec![i])),
+        }
+    }
+    groups
+}
+
+/// Progress for a section: (completed_count, total_count).
+pub fn section_progress(state: &AppState, title: &str) -> (usize, usize) {
+    let tests: Vec<_> = state
+        .testlist
+        .tests
+        .iter()
+        .filter(|t| t.section.as_deref() == Some(title))
+        .collect();
+    let completed = tests
+        .iter()
+        .filter(|t| {
+            result_for_test(&state.results, &t.id).is_some_and(|r| !r.is_open(&state.testlist))
+        })
+        .count();
+    (completed, tests.len())
+}
+
+/// Title of the section under the cursor, or the section of the selected test.
+pub fn current_section(state: &AppState) -> Option<&str> {
+    match &state.selected_section {
+        Some(title) => Some(title.as_str()),
+        None => state
+            .testlist
+            .tests
+            .get(state.selected_test)
+            .and_then(|t| t.section.as_deref()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::Test;
+    use crate::data::results::Status;
+    use crate::fixtures;
+
+    fn make_state() -> AppState {
+        let test = |id: &str, section: Option<&str>| Test {
+            id: id.to_string(),
+            title: id.to_string(),
+            action: "Do it".to_string(),
+            section: section.map(|s| s.to_string()),
+            ..Default::default()
+        };
+        fixtures::state(fixtures::testlist(vec![
+            test("a1", Some("A")),
+            test("top", None),
+            test("b1", Some("B")),
+            test("a2", Some("A")),
+        ]))
+    }
+
+    #[test]
+    fn test_section_groups_first_appearance_order() {
+        let state = make_state();
+        let groups = section_groups(&state.testlist);
+        assert_eq!(
+            groups,
+            vec![
+                (Some("A"), vec![0, 3]),
+                (None, vec![1]),
+                (Some("B"), vec![2]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_section_progress() {
+        let mut state = make_state();
+        assert_eq!(section_progress(&state, "A"), (0, 2));
+        state.results.results[3].status = Status::Failed;
+        assert_eq!(section_progress(&state, "A"), (1, 2));
+        assert_eq!(section_progress(&state, "B"), (0, 1));
+    }
+
+    #[test]
+    fn test_current_section() {
+        let mut state = make_state();
+        assert_eq!(current_section(&state), Some("A"));
+        state.selected_test = 1;
+        assert_eq!(current_section(&state), None);
+        state.selected_section = Some("B".to_string());
+        assert_eq!(current_section(&state), Some("B"));
+    }
+}
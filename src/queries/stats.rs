@@ -0,0 +1,475 @@
+//! Queries summarizing progress across the whole run.
+
+use crate::data::definition::{StatusDef, Testlist};
+use crate::data::results::{Severity, Status, TestlistResults, Verdict};
+use crate::data::state::AppState;
+use crate::queries::tests::result_for_test;
+
+/// Number of tests in each status.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct StatusCounts {
+    pub passed: usize,
+    pub failed: usize,
+    pub inconclusive: usize,
+    pub skipped: usize,
+    pub pending: usize,
+    pub retest: usize,
+    pub in_progress: usize,
+    pub blocked: usize,
+    /// Custom statuses that count as complete.
+    pub custom: usize,
+    /// Custom statuses that leave the test open.
+    pub custom_open: usize,
+}
+
+impl StatusCounts {
+    /// Total number of tests counted.
+    pub fn total(&self) -> usize {
+        self.passed + self.failed + self.inconclusive + self.skipped + self.custom + self.open()
+    }
+
+    /// Tests still to be done: pending, awaiting retest, in progress,
+    /// blocked, or in a custom status that doesn't count as complete.
+    pub fn open(&self) -> usize {
+        self.pending + self.retest + self.in_progress + self.blocked + self.custom_open
+    }
+
+    /// Counts paired with their status, in display order; custom statuses
+    /// are counted together.
+    pub fn by_status(&self) -> [(Status, usize); 9] {
+        [
+            (Status::Passed, self.passed),
+            (Status::Failed, self.failed),
+            (Status::Inconclusive, self.inconclusive),
+            (Status::Skipped, self.skipped),
+            (Status::Custom, self.custom + self.custom_open),
+            (Status::Blocked, self.blocked),
+            (Status::InProgress, self.in_progress),
+            (Status::Retest, self.retest),
+            (Status::Pending, self.pending),
+        ]
+    }
+}
+
+/// Count results by status, looking up in the testlist whether each custom
+/// status counts as complete.
+pub fn status_counts(testlist: &Testlist, results: &TestlistResults) -> StatusCounts {
+    let mut counts = StatusCounts::default();
+    for result in &results.results {
+        match result.status {
+            Status::Passed => counts.passed += 1,
+            Status::Failed => counts.failed += 1,
+            Status::Inconclusive => counts.inconclusive += 1,
+            Status::Skipped => counts.skipped += 1,
+            Status::Pending => counts.pending += 1,
+            Status::Retest => counts.retest += 1,
+            Status::InProgress => counts.in_progress += 1,
+            Status::Blocked => counts.blocked += 1,
+            Status::Custom if result.is_open(testlist) => counts.custom_open += 1,
+            Status::Custom => counts.custom += 1,
+        }
+    }
+    counts
+}
+
+/// Number of tests in each of the testlist's custom statuses, in
+/// declaration order, leaving out statuses no test has.
+pub fn custom_status_counts<'a>(
+    testlist: &'a Testlist,
+    results: &TestlistResults,
+) -> Vec<(&'a StatusDef, usize)> {
+    testlist
+        .statuses
+        .iter()
+        .map(|def| {
+            let count = results
+                .results
+                .iter()
+                .filter(|r| {
+                    r.status == Status::Custom
+                        && r.custom_status.as_deref() == Some(def.name.as_str())
+                })
+                .count();
+            (def, count)
+        })
+        .filter(|&(_, count)| count > 0)
+        .collect()
+}
+
+/// Number of failed tests of each severity, most serious first, leaving
+/// out severities with no failures and failures without a severity.
+pub fn severity_counts(results: &TestlistResults) -> Vec<(Severity, usize)> {
+    Severity::ALL
+        .into_iter()
+        .map(|severity| {
+            let count = results
+                .results
+                .iter()
+                .filter(|r| r.status == Status::Failed && r.severity == Some(severity))
+                .count();
+            (severity, count)
+        })
+        .filter(|&(_, count)| count > 0)
+        .collect()
+}
+
+/// Number of results approved and rejected by a reviewer.
+pub fn review_counts(results: &TestlistResults) -> (usize, usize) {
+    let verdicts = results
+        .results
+        .iter()
+        .filter_map(|r| r.review.as_ref().map(|review| review.verdict));
+    verdicts.fold((0, 0), |(approved, rejected), verdict| match verdict {
+        Verdict::Approved => (approved + 1, rejected),
+        Verdict::Rejected => (approved, rejected + 1),
+    })
+}
+
+/// How results split between first attempts and retests.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetestSummary {
+    /// Tests that passed without an earlier attempt.
+    pub passed_first_time: usize,
+    /// Tests with at least one earlier attempt.
+    pub retested: usize,
+    /// Retested tests that have since passed.
+    pub passed_after_retest: usize,
+}
+
+/// Separate first-pass results from results after a retest.
+pub fn retest_summary(results: &TestlistResults) -> RetestSummary {
+    let mut summary = RetestSummary::default();
+    for result in &results.results {
+        let passed = result.status == Status::Passed;
+        if result.attempts.is_empty() {
+            summary.passed_first_time += passed as usize;
+        } else {
+            summary.retested += 1;
+            summary.passed_after_retest += passed as usize;
+        }
+    }
+    summary
+}
+
+/// Timing summary for the statistics popup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimingStats {
+    /// Total time recorded against all tests, across sessions.
+    pub time_spent_secs: u64,
+    /// Mean time spent per completed test, if any are completed.
+    pub average_secs: Option<u64>,
+    /// Estimated time for the open tests, if an estimate is possible.
+    pub remaining_secs: Option<u64>,
+}
+
+/// Summarize time spent and estimate the time left.
+///
+/// Each open test is estimated by its `expected_duration` when set and by
+/// the average of completed tests otherwise; time already spent on it is
+/// subtracted. No estimate is given when an open test has neither. Time
+/// spent on earlier attempts counts towards the total.
+pub fn timing_stats(state: &AppState) -> TimingStats {
+    let results = &state.results.results;
+    let time_spent_secs = results
+        .iter()
+        .map(|r| r.time_spent_secs + r.attempts.iter().map(|a| a.time_spent_secs).sum::<u64>())
+        .sum();
+
+    let completed: Vec<u64> = results
+        .iter()
+        .filter(|r| !r.is_open(&state.testlist))
+        .map(|r| r.time_spent_secs)
+        .collect();
+    let average_secs = (completed.iter().sum::<u64>()).checked_div(completed.len() as u64);
+
+    let remaining_secs = state
+        .testlist
+        .tests
+        .iter()
+        .filter_map(|test| {
+            let result = result_for_test(&state.results, &test.id)?;
+            result
+                .is_open(&state.testlist)
+                .then_some((test, result.time_spent_secs))
+        })
+        .map(|(test, spent)| {
+            test.expected_secs()
+                .or(average_secs)
+                .map(|estimate| estimate.saturating_sub(spent))
+        })
+        .sum();
+
+    TimingStats {
+        time_spent_secs,
+        average_secs,
+        remaining_secs,
+    }
+}
+
+/// How a run was spread over sittings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SessionSummary {
+    pub sessions: usize,
+    /// Distinct local calendar days on which sessions started.
+    pub days: usize,
+    /// Testers in order of their first session.
+    pub testers: Vec<String>,
+}
+
+/// Summarize the sessions recorded in the results.
+pub fn session_summary(results: &TestlistResults) -> SessionSummary {
+    let sessions = &results.meta.sessions;
+    let mut days: Vec<chrono::NaiveDate> = sessions
+        .iter()
+        .filter_map(|s| chrono::DateTime::parse_from_rfc3339(&s.started).ok())
+        .map(|t| t.with_timezone(&chrono::Local).date_naive())
+        .collect();
+    days.sort();
+    days.dedup();
+    let mut testers: Vec<String> = Vec::new();
+    for session in sessions {
+        if !testers.contains(&session.tester) {
+            testers.push(session.tester.clone());
+        }
+    }
+    SessionSummary {
+        sessions: sessions.len(),
+        days: days.len(),
+        testers,
+    }
+}
+
+/// Split `width` cells between segments in proportion to their counts.
+///
+/// Uses largest-remainder rounding so the widths always sum to `width`
+/// (when any count is non-zero) and every non-empty segment that can fit
+/// gets at least one cell.
+pub fn segment_widths(counts: &[usize], width: usize) -> Vec<usize> {
+    let total: usize = counts.iter().sum();
+    if total == 0 {
+        return vec![0; counts.len()];
+    }
+    let mut widths: Vec<usize> = counts.iter().map(|&c| c * width / total).collect();
+    let mut remainders: Vec<(usize, usize)> = counts
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| (c * width % total, i))
+        .collect();
+    remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+    let assigned: usize = widths.iter().sum();
+    for &(_, i) in remainders.iter().take(width - assigned) {
+        widths[i] += 1;
+    }
+    // Give tiny segments a visible cell, taken from the widest one
+    for i in 0..counts.len() {
+        if counts[i] > 0 && widths[i] == 0 {
+            let widest = (0..widths.len()).max_by_key(|&j| widths[j]).unwrap_or(i);
+            if widths[widest] > 1 {
+                widths[widest] -= 1;
+                widths[i] = 1;
+            }
+        }
+    }
+    widths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::Test;
+    use crate::data::results::Attempt;
+    use crate::fixtures;
+
+    fn make_testlist() -> Testlist {
+        fixtures::testlist(
+            (1..=5)
+                .map(|n| Test {
+                    id: format!("t{}", n),
+                    title: format!("Test {}", n),
+                    ..Default::default()
+                })
+                .collect(),
+        )
+    }
+
+    fn make_results() -> TestlistResults {
+        fixtures::results(&make_testlist())
+    }
+
+    #[test]
+    fn test_status_counts() {
+        let mut results = make_results();
+        results.results[0].status = Status::Passed;
+        results.results[1].status = Status::Passed;
+        results.results[2].status = Status::Failed;
+        let counts = status_counts(&make_testlist(), &results);
+        assert_eq!(counts.passed, 2);
+        assert_eq!(counts.failed, 1);
+        assert_eq!(counts.pending, 2);
+        assert_eq!(counts.total(), 5);
+    }
+
+    #[test]
+    fn test_status_counts_look_up_custom_statuses() {
+        let mut testlist = make_testlist();
+        let def = |name: &str, counts_as_complete| StatusDef {
+            name: name.to_string(),
+            icon: "*".to_string(),
+            color: None,
+            counts_as_complete,
+        };
+        testlist.statuses = vec![def("Deviation", true), def("Needs info", false)];
+        let mut results = fixtures::results(&testlist);
+        results.set_custom_status("t1", &testlist.statuses[0]);
+        results.set_custom_status("t2", &testlist.statuses[1]);
+        let counts = status_counts(&testlist, &results);
+        assert_eq!((counts.custom, counts.custom_open), (1, 1));
+        assert_eq!(counts.open(), 4);
+
+        // Declaring a status complete later finishes the tests that have it.
+        testlist.statuses[1].counts_as_complete = true;
+        let counts = status_counts(&testlist, &results);
+        assert_eq!((counts.custom, counts.custom_open), (2, 0));
+        assert_eq!(counts.open(), 3);
+    }
+
+    #[test]
+    fn test_retest_summary() {
+        let mut results = make_results();
+        results.results[0].status = Status::Passed;
+        for i in [1, 2] {
+            results.results[i].attempts.push(Attempt {
+                status: Status::Failed,
+                notes: None,
+                severity: None,
+                time_spent_secs: 0,
+                completed_at: None,
+            });
+        }
+        results.results[1].status = Status::Passed;
+        results.results[2].status = Status::Retest;
+        let counts = status_counts(&make_testlist(), &results);
+        assert_eq!((counts.retest, counts.open()), (1, 3));
+        assert_eq!(
+            retest_summary(&results),
+            RetestSummary {
+                passed_first_time: 1,
+                retested: 2,
+                passed_after_retest: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_review_counts() {
+        let mut results = make_results();
+        for (i, verdict) in [Verdict::Approved, Verdict::Rejected, Verdict::Approved]
+            .into_iter()
+            .enumerate()
+        {
+            results.results[i].review = Some(crate::data::results::Review {
+                verdict,
+                reviewer: "rita".to_string(),
+                at: "2025-01-24T09:00:00Z".to_string(),
+                comment: None,
+            });
+        }
+        assert_eq!(review_counts(&results), (2, 1));
+    }
+
+    #[test]
+    fn test_severity_counts() {
+        let mut results = make_results();
+        for (i, severity) in [
+            Some(Severity::Minor),
+            Some(Severity::Blocker),
+            None,
+            Some(Severity::Minor),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            results.results[i].status = Status::Failed;
+            results.results[i].severity = severity;
+        }
+        assert_eq!(
+            severity_counts(&results),
+            vec![(Severity::Blocker, 1), (Severity::Minor, 2)]
+        );
+    }
+
+    fn make_state() -> AppState {
+        let results = make_results();
+        let testlist = fixtures::testlist(
+            results
+                .results
+                .iter()
+                .map(|r| Test {
+                    id: r.test_id.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+        );
+        AppState::new(
+            testlist,
+            results,
+            std::path::PathBuf::from("test.testlist.ron"),
+            std::path::PathBuf::from("test.testlist.results.ron"),
+        )
+    }
+
+    #[test]
+    fn test_timing_stats_estimates_remaining() {
+        let mut state = make_state();
+        state.results.results[0].status = Status::Passed;
+        state.results.results[0].time_spent_secs = 60;
+        state.results.results[1].status = Status::Failed;
+        state.results.results[1].time_spent_secs = 120;
+        state.results.results[2].time_spent_secs = 30;
+        state.testlist.tests[3].expected_duration = Some("5m".to_string());
+
+        let stats = timing_stats(&state);
+        assert_eq!(stats.time_spent_secs, 210);
+        assert_eq!(stats.average_secs, Some(90));
+        // t3: 90 - 30, t4: 300, t5: 90
+        assert_eq!(stats.remaining_secs, Some(450));
+    }
+
+    #[test]
+    fn test_timing_stats_without_history() {
+        let state = make_state();
+        let stats = timing_stats(&state);
+        assert_eq!(stats.average_secs, None);
+        assert_eq!(stats.remaining_secs, None);
+    }
+
+    #[test]
+    fn test_session_summary() {
+        let mut results = make_results();
+        for (started, tester) in [
+            ("2025-01-24T09:00:00+00:00", "alice"),
+            ("2025-01-24T09:30:00+00:00", "bob"),
+            ("2025-01-27T09:00:00+00:00", "alice"),
+        ] {
+            results.start_session(tester, None);
+            results.meta.sessions.last_mut().unwrap().started = started.to_string();
+        }
+        let summary = session_summary(&results);
+        assert_eq!(summary.sessions, 3);
+        assert_eq!(summary.days, 2);
+        assert_eq!(summary.testers, vec!["alice", "bob"]);
+    }
+
+    #[test]
+    fn test_segment_widths_fill_width() {
+        assert_eq!(segment_widths(&[1, 1, 1], 10), vec![4, 3, 3]);
+        assert_eq!(segment_widths(&[2, 0, 2], 9), vec![5, 0, 4]);
+        assert_eq!(segment_widths(&[0, 0], 10), vec![0, 0]);
+    }
+
+    #[test]
+    fn test_segment_widths_keep_small_segments_visible() {
+        let widths = segment_widths(&[1, 999], 20);
+        assert_eq!(widths, vec![1, 19]);
+    }
+}
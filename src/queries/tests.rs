@@ -1,12 +1,28 @@
 //! Queries related to tests and results.
 
-use crate::data::definition::Test;
-use crate::data::results::{Status, TestResult, TestlistResults};
-use crate::data::state::AppState;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use crate::data::definition::{StatusDef, Test, Testlist};
+use crate::data::results::{self, ChecklistSection, Status, TestResult, TestlistResults};
+use crate::data::state::{AppState, FailureCapture, PaneEntry, PaneRow};
+use crate::queries::sections::section_groups;
 
 /// Get the currently selected test definition.
+///
+/// Returns `None` when the cursor is on a section header or the selection is
+/// hidden by the active filter, so status keys never act on a test the user
+/// cannot see.
 pub fn current_test(state: &AppState) -> Option<&Test> {
-    state.testlist.tests.get(state.selected_test)
+    if state.selected_section.is_some() {
+        return None;
+    }
+    state
+        .testlist
+        .tests
+        .get(state.selected_test)
+        .filter(|t| is_test_visible(state, t))
 }
 
 /// Get the result for the currently selected test.
@@ -19,116 +35,595 @@ pub fn result_for_test<'a>(results: &'a TestlistResults, test_id: &str) -> Optio
     results.results.iter().find(|r| r.test_id == test_id)
 }
 
-/// Count completed (non-pending) tests.
+/// The testlist's declaration of a result's custom status, if it has one
+/// the testlist still declares.
+pub fn custom_status_def<'a>(testlist: &'a Testlist, result: &TestResult) -> Option<&'a StatusDef> {
+    match result.status {
+        Status::Custom => testlist.custom_status(result.custom_status.as_deref()?),
+        _ => None,
+    }
+}
+
+/// Check whether marking a test with `status` needs a note: Failed and
+/// Inconclusive do when the testlist or the config file requires it.
+pub fn note_required(state: &AppState, status: Status) -> bool {
+    matches!(status, Status::Failed | Status::Inconclusive)
+        && (state.require_failure_notes || state.testlist.meta.require_failure_notes)
+}
+
+/// Check whether marking a test with `status` needs a severity: Failed does
+/// when the testlist or the config file requires it.
+pub fn severity_required(state: &AppState, status: Status) -> bool {
+    status == Status::Failed && (state.require_severity || state.testlist.meta.require_severity)
+}
+
+/// Note templates by name: the config file's, with the testlist's added
+/// and taking precedence.
+pub fn note_templates(state: &AppState) -> Vec<(&str, &str)> {
+    let mut templates: BTreeMap<&str, &str> = state
+        .note_templates
+        .iter()
+        .map(|(name, text)| (name.as_str(), text.as_str()))
+        .collect();
+    templates.extend(
+        state
+            .testlist
+            .meta
+            .note_templates
+            .iter()
+            .map(|(name, text)| (name.as_str(), text.as_str())),
+    );
+    templates.into_iter().collect()
+}
+
+/// The first detail the failure dialog still needs before it can mark the
+/// test, such as "a note", or None once everything required is filled in.
+/// A note template inserted without changes doesn't count as a note.
+pub fn missing_capture_detail(state: &AppState, capture: &FailureCapture) -> Option<&'static str> {
+    let note = capture.note.trim();
+    let no_note = note.is_empty()
+        || note_templates(state)
+            .iter()
+            .any(|(_, text)| text.trim() == note);
+    if no_note && note_required(state, capture.status) {
+        Some("a note")
+    } else if capture.severity.is_none() && severity_required(state, capture.status) {
+        Some("a severity")
+    } else {
+        None
+    }
+}
+
+/// IDs of the dependencies of `test` that have not passed yet.
+///
+/// Dependencies that do not exist in the testlist are reported as unmet so a
+/// typo in `depends_on` is visible rather than silently ignored.
+pub fn unmet_dependencies<'a>(
+    testlist: &Testlist,
+    results: &TestlistResults,
+    test: &'a Test,
+) -> Vec<&'a str> {
+    test.depends_on
+        .iter()
+        .filter(|dep| {
+            !testlist.tests.iter().any(|t| &t.id == *dep)
+                || result_for_test(results, dep).map(|r| r.status) != Some(Status::Passed)
+        })
+        .map(|dep| dep.as_str())
+        .collect()
+}
+
+/// Check whether a test is waiting on a dependency that has not passed.
+pub fn is_waiting(testlist: &Testlist, results: &TestlistResults, test: &Test) -> bool {
+    !unmet_dependencies(testlist, results, test).is_empty()
+}
+
+/// A test's status in words, for accessibility mode: a custom status's
+/// name, `waiting` for a pending test waiting on its dependencies, or the
+/// built-in status's label.
+pub fn status_label(testlist: &Testlist, results: &TestlistResults, test: &Test) -> String {
+    let result = result_for_test(results, &test.id);
+    if let Some(def) = result.and_then(|r| custom_status_def(testlist, r)) {
+        return def.name.clone();
+    }
+    match result.map(|r| r.status).unwrap_or_default() {
+        Status::Pending if is_waiting(testlist, results, test) => "waiting".to_string(),
+        status => status.label().to_string(),
+    }
+}
+
+/// Resolve a test reference for opening: URLs are returned unchanged and
+/// relative paths are made relative to the testlist file's directory.
+pub fn resolve_reference(state: &AppState, reference: &str) -> String {
+    if reference.contains("://") || reference.starts_with("mailto:") {
+        return reference.to_string();
+    }
+    let path = std::path::Path::new(reference);
+    if path.is_absolute() {
+        return reference.to_string();
+    }
+    state
+        .testlist_path
+        .parent()
+        .unwrap_or(std::path::Path::new(""))
+        .join(path)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// How many times its expected duration a test may take before it is flagged.
+pub const OVER_BUDGET_FACTOR: f64 = 1.5;
+
+/// Check whether a test took far longer than its expected duration.
+pub fn is_over_budget(test: &Test, result: &TestResult) -> bool {
+    match test.expected_secs() {
+        Some(expected) => result.time_spent_secs as f64 > expected as f64 * OVER_BUDGET_FACTOR,
+        None => false,
+    }
+}
+
+/// Check whether the running command has exceeded the command timeout.
+pub fn command_timed_out(state: &AppState, now: Instant) -> bool {
+    match (&state.running_command, state.command_timeout) {
+        (Some(running), Some(timeout)) => now.duration_since(running.started_at) >= timeout,
+        _ => false,
+    }
+}
+
+/// Count completed tests, i.e. neither pending nor awaiting retest.
 pub fn completed_count(state: &AppState) -> usize {
     state
         .results
         .results
         .iter()
-        .filter(|r| r.status != Status::Pending)
+        .filter(|r| !r.is_open(&state.testlist))
         .count()
 }
 
-/// Calculate the line number of the current selection (header) in the tests pane.
-pub fn selected_line_number(state: &AppState) -> usize {
-    let mut line = 0;
+/// Find a test by exact ID, falling back to its 1-based position in the testlist.
+pub fn find_test(state: &AppState, query: &str) -> Option<usize> {
+    let query = query.trim();
+    let tests = &state.testlist.tests;
+    tests.iter().position(|t| t.id == query).or_else(|| {
+        query
+            .parse::<usize>()
+            .ok()
+            .filter(|&n| n >= 1 && n <= tests.len())
+            .map(|n| n - 1)
+    })
+}
 
-    for (i, test) in state.testlist.tests.iter().enumerate() {
-        if i == state.selected_test {
-            return line;
+/// Directory holding captured and copied attachments.
+pub fn assets_dir(state: &AppState) -> PathBuf {
+    results::assets_dir(&state.results_path)
+}
+
+/// Locate an attachment on disk.
+///
+/// Relative paths are tried against the results file's directory first (where
+/// copied attachments are stored relative to), then the working directory.
+pub fn resolve_attachment(state: &AppState, path: &Path) -> PathBuf {
+    resolve_results_path(&state.results_path, path)
+}
+
+/// Locate a path recorded in the results file at `results_path`, as
+/// [`resolve_attachment`] does.
+pub fn resolve_results_path(results_path: &Path, path: &Path) -> PathBuf {
+    if path.is_relative() {
+        if let Some(dir) = results_path.parent() {
+            let candidate = dir.join(path);
+            if candidate.exists() {
+                return candidate;
+            }
         }
-        line += 1;
+    }
+    path.to_path_buf()
+}
 
-        if state.expanded_tests.contains(&test.id) {
-            if !test.setup.is_empty() {
-                line += 1 + test.setup.len(); // "Setup:" + items
+/// Path for a new screenshot of a test, named by test ID and timestamp.
+pub fn screenshot_path(state: &AppState, test_id: &str, timestamp: &str) -> PathBuf {
+    assets_dir(state).join(format!("{}-{}.png", test_id, timestamp))
+}
+
+/// Check whether a test passes the active tag and status filters.
+///
+/// Tests whose status is outside the [`StatusView`] are hidden too, unless
+/// selected: a test just marked stays put until the cursor moves on.
+pub fn is_test_visible(state: &AppState, test: &Test) -> bool {
+    passes_filters(state, test) && !is_hidden_by_view(state, test)
+}
+
+/// Number of tests hidden only by the status view.
+pub fn hidden_by_view_count(state: &AppState) -> usize {
+    state
+        .testlist
+        .tests
+        .iter()
+        .filter(|t| passes_filters(state, t) && is_hidden_by_view(state, t))
+        .count()
+}
+
+/// Check whether a test is hidden by the status view.
+fn is_hidden_by_view(state: &AppState, test: &Test) -> bool {
+    let selected = state.selected_section.is_none()
+        && state
+            .testlist
+            .tests
+            .get(state.selected_test)
+            .is_some_and(|t| t.id == test.id);
+    !selected
+        && result_for_test(&state.results, &test.id)
+            .is_some_and(|r| !state.status_view.shows(&state.testlist, r))
+}
+
+fn passes_filters(state: &AppState, test: &Test) -> bool {
+    if !is_in_focus(state, test) {
+        return false;
+    }
+    if let Some(filter) = &state.status_filter {
+        if !filter.test_ids.contains(&test.id) {
+            return false;
+        }
+    }
+    match &state.tag_filter {
+        Some(tag) => test.tags.iter().any(|t| t == tag),
+        None => true,
+    }
+}
+
+/// The active filters as shown in the status bar, e.g.
+/// `status=failed tag=smoke`; `None` without filters.
+pub fn filter_label(state: &AppState) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some(filter) = &state.status_filter {
+        let statuses: Vec<String> = filter
+            .statuses
+            .iter()
+            .map(|s| format!("{:?}", s).to_lowercase())
+            .collect();
+        parts.push(format!("status={}", statuses.join(",")));
+    }
+    if let Some(tag) = &state.tag_filter {
+        parts.push(format!("tag={}", tag));
+    }
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+/// Check whether a test is among those the session is restricted to with
+/// `--only`; every test is when there is no restriction.
+pub fn is_in_focus(state: &AppState, test: &Test) -> bool {
+    state
+        .only_tests
+        .as_ref()
+        .is_none_or(|ids| ids.contains(&test.id))
+}
+
+/// Indices of all tests, in the tester's custom order (`results.test_order`).
+///
+/// Tests missing from the custom order follow in definition order.
+pub fn custom_order(state: &AppState) -> Vec<usize> {
+    let tests = &state.testlist.tests;
+    let mut order: Vec<usize> = (0..tests.len()).collect();
+    if !state.results.test_order.is_empty() {
+        let rank: HashMap<&str, usize> = state
+            .results
+            .test_order
+            .iter()
+            .enumerate()
+            .map(|(n, id)| (id.as_str(), n))
+            .collect();
+        order.sort_by_key(|&i| {
+            rank.get(tests[i].id.as_str())
+                .copied()
+                .unwrap_or(usize::MAX)
+        });
+    }
+    order
+}
+
+/// Tests passing the filter, grouped by section in display order.
+///
+/// Sections with no matching tests are omitted. Within a section, tests
+/// follow the [`custom_order`]. When sorting by priority, tests are ordered
+/// within their section, tests without a priority come last, and ties keep
+/// that order.
+fn display_groups(state: &AppState) -> Vec<(Option<&str>, Vec<usize>)> {
+    let tests = &state.testlist.tests;
+    let position: Vec<usize> = {
+        let mut position = vec![0; tests.len()];
+        for (n, i) in custom_order(state).into_iter().enumerate() {
+            position[i] = n;
+        }
+        position
+    };
+    section_groups(&state.testlist)
+        .into_iter()
+        .filter_map(|(section, indices)| {
+            let mut matching: Vec<usize> = indices
+                .into_iter()
+                .filter(|&i| is_test_visible(state, &tests[i]))
+                .collect();
+            matching.sort_by_key(|&i| position[i]);
+            if state.sort_by_priority {
+                matching.sort_by_key(|&i| (tests[i].priority.is_none(), tests[i].priority));
             }
-            line += 1; // Action
-            if !test.verify.is_empty() {
-                line += 1 + test.verify.len(); // "Verify:" + items
+            (!matching.is_empty()).then_some((section, matching))
+        })
+        .collect()
+}
+
+/// Selectable header rows of the tests pane, in display order.
+///
+/// A section header is only shown when at least one of its tests passes the
+/// filter, and a collapsed section contributes its header alone.
+pub fn pane_entries(state: &AppState) -> Vec<PaneEntry> {
+    let mut entries = Vec::new();
+
+    for (section, indices) in display_groups(state) {
+        if let Some(title) = section {
+            entries.push(PaneEntry::Section(title.to_string()));
+            if state.collapsed_sections.contains(title) {
+                continue;
             }
         }
+        entries.extend(indices.into_iter().map(PaneEntry::Test));
+    }
+
+    entries
+}
+
+/// Tests passing the filter in display order, including those inside collapsed sections.
+pub fn display_order(state: &AppState) -> Vec<usize> {
+    display_groups(state)
+        .into_iter()
+        .flat_map(|(_, indices)| indices)
+        .collect()
+}
+
+/// The first test in display order that still has to be done.
+pub fn first_open_test(state: &AppState) -> Option<usize> {
+    display_order(state).into_iter().find(|&i| {
+        result_for_test(&state.results, &state.testlist.tests[i].id)
+            .is_some_and(|r| r.is_open(&state.testlist))
+    })
+}
+
+/// Tests covered by the visual-mode range, in display order.
+///
+/// The range runs from the anchor to the cursor and skips section headers.
+/// If the anchor is no longer visible, only the cursor's test is selected.
+pub fn visual_selection(state: &AppState) -> Vec<usize> {
+    let Some(anchor) = &state.visual_anchor else {
+        return Vec::new();
+    };
+    let entries = pane_entries(state);
+    let cursor = entries.iter().position(|e| *e == selected_entry(state));
+    let (start, end) = match (entries.iter().position(|e| e == anchor), cursor) {
+        (Some(a), Some(c)) => (a.min(c), a.max(c)),
+        (None, Some(c)) => (c, c),
+        _ => return Vec::new(),
+    };
+    entries[start..=end]
+        .iter()
+        .filter_map(|e| match e {
+            PaneEntry::Test(i) => Some(*i),
+            PaneEntry::Section(_) => None,
+        })
+        .collect()
+}
+
+/// Check whether a test matches a search query (case-insensitive substring
+/// of its title, ID, or description).
+pub fn matches_search(test: &Test, query: &str) -> bool {
+    let query = query.to_lowercase();
+    !query.is_empty()
+        && [&test.title, &test.id, &test.description]
+            .iter()
+            .any(|field| field.to_lowercase().contains(&query))
+}
+
+/// Tests matching the active search query, in display order.
+pub fn search_matches(state: &AppState) -> Vec<usize> {
+    display_order(state)
+        .into_iter()
+        .filter(|&i| matches_search(&state.testlist.tests[i], &state.search_query))
+        .collect()
+}
+
+/// Indices (into `testlist.tests`) of the tests shown in the tests pane, in display order.
+pub fn visible_tests(state: &AppState) -> Vec<usize> {
+    pane_entries(state)
+        .into_iter()
+        .filter_map(|entry| match entry {
+            PaneEntry::Test(i) => Some(i),
+            PaneEntry::Section(_) => None,
+        })
+        .collect()
+}
+
+/// The pane entry under the cursor.
+pub fn selected_entry(state: &AppState) -> PaneEntry {
+    match &state.selected_section {
+        Some(title) => PaneEntry::Section(title.clone()),
+        None => PaneEntry::Test(state.selected_test),
+    }
+}
+
+/// All distinct tags used in the testlist, sorted alphabetically.
+pub fn all_tags(state: &AppState) -> Vec<String> {
+    let mut tags: Vec<String> = state
+        .testlist
+        .tests
+        .iter()
+        .flat_map(|t| t.tags.iter().cloned())
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Total number of rows in the tests pane.
+pub fn pane_line_count(state: &AppState) -> usize {
+    pane_entries(state)
+        .iter()
+        .map(|entry| entry_row_count(state, entry))
+        .sum()
+}
+
+/// Number of rows an entry occupies in the tests pane (header plus expanded content).
+fn entry_row_count(state: &AppState, entry: &PaneEntry) -> usize {
+    let test = match entry {
+        PaneEntry::Section(_) => return 1,
+        PaneEntry::Test(i) => &state.testlist.tests[*i],
+    };
+    let mut rows = 1;
+
+    if state.expanded_tests.contains(&test.id) {
+        if !test.setup.is_empty() {
+            rows += 1 + test.setup.len(); // "Setup:" + items
+        }
+        if !test.depends_on.is_empty() {
+            rows += 1; // "Depends on:"
+        }
+        rows += 1; // Action
+        if !test.verify.is_empty() {
+            rows += 1 + test.verify.len(); // "Verify:" + items
+        }
+        if !test.references.is_empty() {
+            rows += 1 + test.references.len(); // "References:" + items
+        }
+    }
+
+    rows
+}
+
+/// Calculate the line number of the current selection (header) in the tests pane.
+pub fn selected_line_number(state: &AppState) -> usize {
+    let selected = selected_entry(state);
+    let mut line = 0;
+
+    for entry in pane_entries(state) {
+        if entry == selected {
+            return line;
+        }
+        line += entry_row_count(state, &entry);
     }
 
     line
 }
 
-/// Map a y-coordinate in the tests pane to a test index.
+/// Map a y-coordinate in the tests pane to a pane entry.
 /// Clicks on expanded content rows map to the parent test.
-pub fn map_y_to_test_index(state: &AppState, y: usize) -> Option<usize> {
+pub fn map_y_to_entry(state: &AppState, y: usize) -> Option<PaneEntry> {
     let mut current_y = 0;
 
-    for (i, test) in state.testlist.tests.iter().enumerate() {
+    for entry in pane_entries(state) {
         let header_y = current_y;
-        current_y += 1;
+        current_y += entry_row_count(state, &entry);
 
-        if state.expanded_tests.contains(&test.id) {
+        // y falls within this entry's range (header + expanded content)
+        if y >= header_y && y < current_y {
+            return Some(entry);
+        }
+    }
+
+    None
+}
+
+/// Map a y-coordinate in the tests pane to the row drawn there, following
+/// the order rows are drawn for an expanded test.
+pub fn map_y_to_row(state: &AppState, y: usize) -> Option<PaneRow> {
+    let mut header_y = 0;
+    for entry in pane_entries(state) {
+        let rows = entry_row_count(state, &entry);
+        if y == header_y {
+            return Some(PaneRow::Header(entry));
+        }
+        if y < header_y + rows {
+            let PaneEntry::Test(i) = entry else {
+                return None;
+            };
+            let test = &state.testlist.tests[i];
+            let mut offset = y - header_y - 1;
             if !test.setup.is_empty() {
-                current_y += 1 + test.setup.len();
+                if offset <= test.setup.len() {
+                    return Some(checklist_row(i, ChecklistSection::Setup, offset));
+                }
+                offset -= 1 + test.setup.len();
+            }
+            // "Depends on:" and "Action:" rows
+            let detail_rows = usize::from(!test.depends_on.is_empty()) + 1;
+            if offset < detail_rows {
+                return Some(PaneRow::Detail(i));
             }
-            current_y += 1; // Action
-            if !test.verify.is_empty() {
-                current_y += 1 + test.verify.len();
+            offset -= detail_rows;
+            if !test.verify.is_empty() && offset <= test.verify.len() {
+                return Some(checklist_row(i, ChecklistSection::Verify, offset));
             }
+            return Some(PaneRow::Detail(i));
         }
+        header_y += rows;
+    }
+    None
+}
 
-        // y falls within this test's range (header + expanded content)
-        if y >= header_y && y < current_y {
-            return Some(i);
-        }
+/// Row `offset` of a checklist block, where row 0 is the block's title.
+fn checklist_row(test: usize, section: ChecklistSection, offset: usize) -> PaneRow {
+    match offset.checked_sub(1) {
+        Some(item) => PaneRow::Checklist(test, section, item),
+        None => PaneRow::Detail(test),
     }
+}
 
-    None
+/// Map a y-coordinate in the tests pane to a test index.
+/// Clicks on expanded content rows map to the parent test; section headers map to `None`.
+pub fn map_y_to_test_index(state: &AppState, y: usize) -> Option<usize> {
+    match map_y_to_entry(state, y)? {
+        PaneEntry::Test(i) => Some(i),
+        PaneEntry::Section(_) => None,
+    }
 }
 
 #[cfg(test)]
 mod tests_mod {
     use super::*;
-    use crate::data::definition::{ChecklistItem, Meta, Testlist};
-    use crate::data::results::TestlistResults;
+    use crate::data::definition::ChecklistItem;
+    use crate::fixtures;
+
+    use crate::data::state::RunningCommand;
+    use std::time::Duration;
 
     fn make_state() -> AppState {
-        let testlist = Testlist {
-            meta: Meta {
-                title: "Test".to_string(),
+        fixtures::state(fixtures::testlist(vec![
+            Test {
+                id: "t1".to_string(),
+                title: "Test 1".to_string(),
+                description: "".to_string(),
+                setup: vec![ChecklistItem {
+                    id: "s0".to_string(),
+                    text: "Step A".to_string(),
+                }],
+                action: "Do it".to_string(),
+                verify: vec![],
+                suggested_command: None,
+                tags: vec!["smoke".to_string()],
+                ..Default::default()
+            },
+            Test {
+                id: "t2".to_string(),
+                title: "Test 2".to_string(),
                 description: "".to_string(),
-                created: "".to_string(),
-                version: "1".to_string(),
+                setup: vec![],
+                action: "Do it".to_string(),
+                verify: vec![ChecklistItem {
+                    id: "v0".to_string(),
+                    text: "Check".to_string(),
+                }],
+                suggested_command: Some("echo hi".to_string()),
+                tags: vec!["regression".to_string(), "smoke".to_string()],
+                ..Default::default()
             },
-            tests: vec![
-                Test {
-                    id: "t1".to_string(),
-                    title: "Test 1".to_string(),
-                    description: "".to_string(),
-                    setup: vec![ChecklistItem {
-                        id: "s0".to_string(),
-                        text: "Step A".to_string(),
-                    }],
-                    action: "Do it".to_string(),
-                    verify: vec![],
-                    suggested_command: None,
-                },
-                Test {
-                    id: "t2".to_string(),
-                    title: "Test 2".to_string(),
-                    description: "".to_string(),
-                    setup: vec![],
-                    action: "Do it".to_string(),
-                    verify: vec![ChecklistItem {
-                        id: "v0".to_string(),
-                        text: "Check".to_string(),
-                    }],
-                    suggested_command: Some("echo hi".to_string()),
-                },
-            ],
-        };
-        let results = TestlistResults::new_for_testlist(&testlist, "test.ron", "tester");
-        AppState::new(
-            testlist,
-            results,
-            std::path::PathBuf::from("test.testlist.ron"),
-            std::path::PathBuf::from("test.testlist.results.ron"),
-        )
+        ]))
     }
 
     #[test]
@@ -166,4 +661,274 @@ mod tests_mod {
         assert_eq!(map_y_to_test_index(&state, 3), Some(0)); // Action
         assert_eq!(map_y_to_test_index(&state, 4), Some(1)); // t2 header
     }
+
+    #[test]
+    fn test_map_y_to_row_finds_checklist_items() {
+        let mut state = make_state();
+        state.testlist.tests[0].depends_on = vec!["t2".to_string()];
+        state.testlist.tests[0].verify = vec![ChecklistItem {
+            id: "v0".to_string(),
+            text: "Check".to_string(),
+        }];
+        state.expanded_tests.insert("t1".to_string());
+        // header(0), Setup:(1), Step A(2), Depends on(3), Action(4), Verify:(5), Check(6)
+        assert_eq!(
+            map_y_to_row(&state, 0),
+            Some(PaneRow::Header(PaneEntry::Test(0)))
+        );
+        assert_eq!(map_y_to_row(&state, 1), Some(PaneRow::Detail(0)));
+        assert_eq!(
+            map_y_to_row(&state, 2),
+            Some(PaneRow::Checklist(0, ChecklistSection::Setup, 0))
+        );
+        assert_eq!(map_y_to_row(&state, 3), Some(PaneRow::Detail(0)));
+        assert_eq!(map_y_to_row(&state, 4), Some(PaneRow::Detail(0)));
+        assert_eq!(map_y_to_row(&state, 5), Some(PaneRow::Detail(0)));
+        assert_eq!(
+            map_y_to_row(&state, 6),
+            Some(PaneRow::Checklist(0, ChecklistSection::Verify, 0))
+        );
+        assert_eq!(
+            map_y_to_row(&state, 7),
+            Some(PaneRow::Header(PaneEntry::Test(1)))
+        );
+        assert_eq!(map_y_to_row(&state, 99), None);
+    }
+
+    #[test]
+    fn test_visible_tests_respects_tag_filter() {
+        let mut state = make_state();
+        assert_eq!(visible_tests(&state), vec![0, 1]);
+        state.tag_filter = Some("regression".to_string());
+        assert_eq!(visible_tests(&state), vec![1]);
+        state.tag_filter = Some("nope".to_string());
+        assert!(visible_tests(&state).is_empty());
+    }
+
+    #[test]
+    fn test_current_test_hidden_by_filter() {
+        let mut state = make_state();
+        state.tag_filter = Some("regression".to_string());
+        assert!(current_test(&state).is_none());
+        state.selected_test = 1;
+        assert_eq!(current_test(&state).unwrap().id, "t2");
+    }
+
+    #[test]
+    fn test_visible_tests_sorted_by_priority() {
+        use crate::data::definition::Priority;
+
+        let mut state = make_state();
+        state.testlist.tests[1].priority = Some(Priority::High);
+        assert_eq!(visible_tests(&state), vec![0, 1]);
+        state.sort_by_priority = true;
+        assert_eq!(visible_tests(&state), vec![1, 0]);
+        state.testlist.tests[0].priority = Some(Priority::Critical);
+        assert_eq!(visible_tests(&state), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_is_waiting_until_dependency_passes() {
+        let mut state = make_state();
+        state.testlist.tests[1].depends_on = vec!["t1".to_string()];
+        let t2 = state.testlist.tests[1].clone();
+        assert!(is_waiting(&state.testlist, &state.results, &t2));
+        assert_eq!(
+            unmet_dependencies(&state.testlist, &state.results, &t2),
+            vec!["t1"]
+        );
+
+        state.results.results[0].status = Status::Failed;
+        assert!(is_waiting(&state.testlist, &state.results, &t2));
+
+        state.results.results[0].status = Status::Passed;
+        assert!(!is_waiting(&state.testlist, &state.results, &t2));
+    }
+
+    #[test]
+    fn test_unknown_dependency_waits() {
+        let mut state = make_state();
+        state.testlist.tests[0].depends_on = vec!["missing".to_string()];
+        let t1 = state.testlist.tests[0].clone();
+        assert!(is_waiting(&state.testlist, &state.results, &t1));
+    }
+
+    #[test]
+    fn test_is_over_budget() {
+        let mut state = make_state();
+        state.testlist.tests[0].expected_duration = Some("1m".to_string());
+        let test = state.testlist.tests[0].clone();
+        state.results.results[0].time_spent_secs = 90;
+        assert!(!is_over_budget(&test, &state.results.results[0]));
+        state.results.results[0].time_spent_secs = 91;
+        assert!(is_over_budget(&test, &state.results.results[0]));
+        // Tests without a budget are never flagged
+        assert!(!is_over_budget(
+            &state.testlist.tests[1],
+            &state.results.results[1]
+        ));
+    }
+
+    #[test]
+    fn test_resolve_reference() {
+        let mut state = make_state();
+        state.testlist_path = std::path::PathBuf::from("plans/app.testlist.ron");
+        assert_eq!(
+            resolve_reference(&state, "https://example.com/spec"),
+            "https://example.com/spec"
+        );
+        assert_eq!(
+            resolve_reference(&state, "docs/mockup.png"),
+            std::path::Path::new("plans/docs/mockup.png").to_string_lossy()
+        );
+    }
+
+    #[test]
+    fn test_expanded_references_add_rows() {
+        let mut state = make_state();
+        state.testlist.tests[0].references = vec!["a.pdf".to_string(), "b.png".to_string()];
+        state.expanded_tests.insert("t1".to_string());
+        // header, Setup:, Step A, Action, References:, a.pdf, b.png
+        assert_eq!(map_y_to_test_index(&state, 6), Some(0));
+        assert_eq!(map_y_to_test_index(&state, 7), Some(1));
+    }
+
+    #[test]
+    fn test_search_matches_title_id_description() {
+        let mut state = make_state();
+        state.testlist.tests[1].description = "Checks the greeting".to_string();
+        state.search_query = "TEST".to_string();
+        assert_eq!(search_matches(&state), vec![0, 1]);
+        state.search_query = "t2".to_string();
+        assert_eq!(search_matches(&state), vec![1]);
+        state.search_query = "greeting".to_string();
+        assert_eq!(search_matches(&state), vec![1]);
+        state.search_query.clear();
+        assert!(search_matches(&state).is_empty());
+    }
+
+    #[test]
+    fn test_search_includes_collapsed_sections() {
+        let mut state = make_state();
+        state.testlist.tests[1].section = Some("Auth".to_string());
+        state.collapsed_sections.insert("Auth".to_string());
+        state.search_query = "Test 2".to_string();
+        assert_eq!(search_matches(&state), vec![1]);
+    }
+
+    #[test]
+    fn test_command_timed_out() {
+        let mut state = make_state();
+        let start = Instant::now();
+        state.running_command = Some(RunningCommand {
+            test_id: "t1".to_string(),
+            command: "sleep 60".to_string(),
+            started: String::new(),
+            started_at: start,
+        });
+        assert!(!command_timed_out(
+            &state,
+            start + Duration::from_secs(3600)
+        ));
+        state.command_timeout = Some(Duration::from_secs(30));
+        assert!(!command_timed_out(&state, start + Duration::from_secs(29)));
+        assert!(command_timed_out(&state, start + Duration::from_secs(30)));
+        state.running_command = None;
+        assert!(!command_timed_out(&state, start + Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn test_find_test_by_id_or_number() {
+        let state = make_state();
+        assert_eq!(find_test(&state, "t2"), Some(1));
+        assert_eq!(find_test(&state, " 1 "), Some(0));
+        assert_eq!(find_test(&state, "0"), None);
+        assert_eq!(find_test(&state, "99"), None);
+        assert_eq!(find_test(&state, "missing"), None);
+    }
+
+    #[test]
+    fn test_visual_selection_spans_anchor_to_cursor() {
+        let mut state = make_state();
+        assert!(visual_selection(&state).is_empty());
+        state.selected_test = 1;
+        state.visual_anchor = Some(PaneEntry::Test(1));
+        assert_eq!(visual_selection(&state), vec![1]);
+        state.selected_test = 0;
+        assert_eq!(visual_selection(&state), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_visual_selection_skips_section_headers() {
+        let mut state = make_state();
+        state.testlist.tests[1].section = Some("Auth".to_string());
+        state.visual_anchor = Some(PaneEntry::Test(0));
+        state.selected_test = 1;
+        assert_eq!(visual_selection(&state), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_screenshot_path_in_assets_dir() {
+        let mut state = make_state();
+        state.results_path = PathBuf::from("qa/login.testlist.results.ron");
+        assert_eq!(
+            screenshot_path(&state, "t1", "20260101-120000"),
+            PathBuf::from("qa/login.testlist.results.assets/t1-20260101-120000.png")
+        );
+    }
+
+    #[test]
+    fn test_all_tags_sorted_and_deduped() {
+        let state = make_state();
+        assert_eq!(all_tags(&state), vec!["regression", "smoke"]);
+    }
+
+    #[test]
+    fn test_map_y_skips_filtered_tests() {
+        let mut state = make_state();
+        state.tag_filter = Some("regression".to_string());
+        assert_eq!(map_y_to_test_index(&state, 0), Some(1));
+        assert_eq!(map_y_to_test_index(&state, 1), None);
+    }
+
+    #[test]
+    fn test_pane_entries_with_sections() {
+        let mut state = make_state();
+        state.testlist.tests[1].section = Some("Auth".to_string());
+        assert_eq!(
+            pane_entries(&state),
+            vec![
+                PaneEntry::Test(0),
+                PaneEntry::Section("Auth".to_string()),
+                PaneEntry::Test(1),
+            ]
+        );
+
+        state.collapsed_sections.insert("Auth".to_string());
+        assert_eq!(
+            pane_entries(&state),
+            vec![PaneEntry::Test(0), PaneEntry::Section("Auth".to_string())]
+        );
+        assert_eq!(visible_tests(&state), vec![0]);
+        assert_eq!(
+            map_y_to_entry(&state, 1),
+            Some(PaneEntry::Section("Auth".to_string()))
+        );
+        assert_eq!(map_y_to_test_index(&state, 1), None);
+    }
+
+    #[test]
+    fn test_section_header_hidden_when_filtered_out() {
+        let mut state = make_state();
+        state.testlist.tests[0].section = Some("Auth".to_string());
+        state.tag_filter = Some("regression".to_string());
+        assert_eq!(pane_entries(&state), vec![PaneEntry::Test(1)]);
+    }
+
+    #[test]
+    fn test_current_test_none_on_section_header() {
+        let mut state = make_state();
+        state.selected_section = Some("Auth".to_string());
+        assert!(current_test(&state).is_none());
+    }
 }
@@ -0,0 +1,121 @@
+//! Plain-text summary of archived runs, printed by `testlist stats`.
+
+use std::fmt::Write;
+
+use crate::data::definition::{format_duration, Testlist};
+use crate::data::results::{Status, TestlistResults};
+use crate::queries::history::{most_failing, run_started, test_history};
+use crate::report::{format_timestamp, status_icon};
+
+/// Runs shown in a test's trend, most recent last.
+const TREND_RUNS: usize = 20;
+
+/// Failing tests listed at the top.
+const MOST_FAILING: usize = 5;
+
+/// Summarize archived runs, which must be sorted oldest first.
+pub fn render(testlist: &Testlist, runs: &[TestlistResults]) -> String {
+    let mut out = String::new();
+    let date = |run: &TestlistResults| {
+        run_started(run).map_or("?".to_string(), |at| {
+            format_timestamp(at).chars().take(10).collect()
+        })
+    };
+    let (Some(first), Some(last)) = (runs.first(), runs.last()) else {
+        return "No archived runs\n".to_string();
+    };
+    let _ = writeln!(
+        out,
+        "{}: {} run{} from {} to {}\n",
+        testlist.meta.title,
+        runs.len(),
+        if runs.len() == 1 { "" } else { "s" },
+        date(first),
+        date(last)
+    );
+
+    let history = test_history(testlist, runs);
+    let title = |id: &str| {
+        testlist
+            .tests
+            .iter()
+            .find(|t| t.id == id)
+            .map_or("(not in testlist)", |t| t.title.as_str())
+    };
+    let id_width = history.iter().map(|h| h.test_id.len()).max().unwrap_or(0);
+
+    let failing = most_failing(&history, MOST_FAILING);
+    if !failing.is_empty() {
+        out.push_str("Most frequently failing:\n");
+        for h in failing {
+            let _ = writeln!(
+                out,
+                "  {:id_width$}  {} of {} runs failed  {}",
+                h.test_id,
+                h.count(Status::Failed),
+                h.outcomes.len(),
+                title(&h.test_id)
+            );
+        }
+        out.push('\n');
+    }
+
+    out.push_str("Pass rate per test (trend oldest to newest):\n");
+    for h in &history {
+        let rate = h
+            .pass_rate()
+            .map_or("   —".to_string(), |r| format!("{:3.0}%", r * 100.0));
+        let trend: String = h
+            .outcomes
+            .iter()
+            .skip(h.outcomes.len().saturating_sub(TREND_RUNS))
+            .map(|&s| status_icon(s))
+            .collect();
+        let average = h.average_secs().map_or(String::new(), |secs| {
+            format!("  avg {}", format_duration(secs))
+        });
+        let _ = writeln!(
+            out,
+            "  {:id_width$}  {}  {:TREND_RUNS$}{}  {}",
+            h.test_id,
+            rate,
+            trend,
+            average,
+            title(&h.test_id)
+        );
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::Test;
+    use crate::fixtures;
+
+    #[test]
+    fn test_render_history() {
+        let mut testlist = fixtures::testlist(vec![Test {
+            id: "login".to_string(),
+            title: "Log in".to_string(),
+            ..Default::default()
+        }]);
+        testlist.meta.title = "Release".to_string();
+        let runs: Vec<TestlistResults> = [Status::Passed, Status::Failed]
+            .into_iter()
+            .map(|status| {
+                let mut run = TestlistResults::new_for_testlist(&testlist, "t.ron", "alice");
+                run.results[0].status = status;
+                run.results[0].time_spent_secs = 90;
+                run.meta.completed = Some("not a date".to_string());
+                run
+            })
+            .collect();
+        let text = render(&testlist, &runs);
+        assert!(text.starts_with("Release: 2 runs from not a date to not a date\n"));
+        assert!(text.contains("Most frequently failing:\n  login  1 of 2 runs failed  Log in\n"));
+        assert!(text.contains("  login   50%  ✓✗"));
+        assert!(text.contains("avg 1:30  Log in"));
+        assert_eq!(render(&testlist, &[]), "No archived runs\n");
+    }
+}
@@ -0,0 +1,306 @@
+//! Self-contained HTML report of a testing run.
+//!
+//! Holds the same content as the Markdown report in a single file with
+//! inline CSS. Each test is a collapsible `<details>` element, open for
+//! failures. Screenshots are shown as images where the caller supplies an
+//! image source for them (a data URI or a copied file), and linked otherwise.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::data::definition::{format_duration, Testlist};
+use crate::data::results::{Status, TestlistResults};
+use crate::queries::stats::{severity_counts, status_counts};
+use crate::queries::tests::result_for_test;
+use crate::report::{escape, format_timestamp, result_icon, run_details, summary_rows};
+
+const STYLE: &str = "\
+body { font-family: system-ui, sans-serif; max-width: 60rem; margin: 2rem auto; padding: 0 1rem; color: #222; }
+table { border-collapse: collapse; margin: 1rem 0; }
+th, td { border: 1px solid #ccc; padding: 0.3rem 0.6rem; text-align: left; }
+td.count { text-align: right; }
+details { border: 1px solid #ddd; border-radius: 4px; margin: 0.5rem 0; padding: 0.4rem 0.8rem; }
+summary { cursor: pointer; font-weight: 600; }
+code { background: #f3f3f3; padding: 0 0.2rem; }
+blockquote { border-left: 3px solid #ccc; margin: 0.5rem 0; padding-left: 0.8rem; white-space: pre-wrap; }
+img { max-width: 100%; border: 1px solid #ccc; margin: 0.4rem 0; }
+.passed { color: #1a7f37; }
+.failed { color: #cf222e; }
+.inconclusive, .retest, .blocked { color: #9a6700; }
+.skipped, .pending, .inprogress { color: #6e7781; }
+.custom { color: #8250df; }
+.facts { color: #555; }
+";
+
+/// CSS class for a status.
+fn status_class(status: Status) -> String {
+    match status {
+        Status::Custom => "custom".to_string(),
+        status => format!("{:?}", status).to_ascii_lowercase(),
+    }
+}
+
+/// Render a results file and its testlist as an HTML document.
+///
+/// `image_src` gives the `src` to show a screenshot with, or `None` to only
+/// link to the recorded path.
+pub fn render(
+    testlist: &Testlist,
+    results: &TestlistResults,
+    image_src: impl Fn(&Path) -> Option<String>,
+) -> String {
+    let mut out = String::new();
+    let meta = &testlist.meta;
+    let _ = writeln!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{}</title>\n<style>\n{}</style>\n</head>\n<body>",
+        escape(&meta.title),
+        STYLE
+    );
+    let _ = writeln!(out, "<h1>{}</h1>", escape(&meta.title));
+    if !meta.description.trim().is_empty() {
+        let _ = writeln!(out, "<p>{}</p>", escape(meta.description.trim()));
+    }
+
+    // Run metadata
+    let _ = writeln!(out, "<table>");
+    for (label, value) in run_details(testlist, results) {
+        let _ = writeln!(
+            out,
+            "<tr><th>{}</th><td>{}</td></tr>",
+            escape(&label),
+            escape(&value)
+        );
+    }
+    let _ = writeln!(out, "</table>");
+
+    // Summary
+    let counts = status_counts(testlist, results);
+    let _ = writeln!(
+        out,
+        "<h2>Summary</h2>\n<table>\n<tr><th>Status</th><th>Tests</th></tr>"
+    );
+    for (status, icon, name, count) in summary_rows(testlist, results) {
+        let _ = writeln!(
+            out,
+            "<tr><td class=\"{}\">{} {}</td><td class=\"count\">{}</td></tr>",
+            status_class(status),
+            escape(icon),
+            escape(&name),
+            count
+        );
+    }
+    let _ = writeln!(
+        out,
+        "<tr><th>Total</th><th class=\"count\">{}</th></tr>\n</table>",
+        counts.total()
+    );
+    let severities: Vec<String> = severity_counts(results)
+        .into_iter()
+        .map(|(severity, n)| format!("{} {}", n, severity.label()))
+        .collect();
+    if !severities.is_empty() {
+        let _ = writeln!(
+            out,
+            "<p>Failures by severity: {}.</p>",
+            severities.join(", ")
+        );
+    }
+
+    // Failures at a glance
+    let failed: Vec<_> = testlist
+        .tests
+        .iter()
+        .filter_map(|test| Some((test, result_for_test(results, &test.id)?)))
+        .filter(|(_, result)| result.status == Status::Failed)
+        .collect();
+    if !failed.is_empty() {
+        let _ = writeln!(
+            out,
+            "<h2>Failures</h2>\n<table>\n<tr><th>Test</th><th>Severity</th><th>Defects</th></tr>"
+        );
+        for (test, result) in &failed {
+            let _ = writeln!(
+                out,
+                "<tr><td><a href=\"#test-{}\">{}</a></td><td>{}</td><td>{}</td></tr>",
+                escape(&test.id),
+                escape(&test.title),
+                result.severity.map_or("—", |s| s.label()),
+                escape(&result.defects.join(", "))
+            );
+        }
+        let _ = writeln!(out, "</table>");
+    }
+
+    // Every test
+    let _ = writeln!(out, "<h2>Results</h2>");
+    let mut section: Option<&str> = None;
+    for test in &testlist.tests {
+        if test.section.as_deref() != section {
+            section = test.section.as_deref();
+            if let Some(title) = section {
+                let _ = writeln!(out, "<h3>{}</h3>", escape(title));
+            }
+        }
+        let Some(result) = result_for_test(results, &test.id) else {
+            continue;
+        };
+        let _ = writeln!(
+            out,
+            "<details id=\"test-{}\"{}>\n<summary><span class=\"{}\">{}</span> {} <code>{}</code></summary>",
+            escape(&test.id),
+            if result.status == Status::Failed { " open" } else { "" },
+            status_class(result.status),
+            escape(result_icon(testlist, result)),
+            escape(&test.title),
+            escape(&test.id)
+        );
+        let mut facts = vec![format!(
+            "<strong>{}</strong>",
+            escape(&result.status_name())
+        )];
+        if let Some(severity) = result.severity {
+            facts.push(severity.label().to_string());
+        }
+        if let Some(ref reason) = result.blocked_reason {
+            facts.push(format!("blocked: {}", escape(reason)));
+        }
+        if let Some(ref at) = result.completed_at {
+            facts.push(format!("completed {}", escape(&format_timestamp(at))));
+        }
+        if result.time_spent_secs > 0 {
+            facts.push(format!("time {}", format_duration(result.time_spent_secs)));
+        }
+        let _ = writeln!(out, "<p class=\"facts\">{}</p>", facts.join(" · "));
+
+        if let Some(ref notes) = result.notes {
+            let _ = writeln!(out, "<blockquote>{}</blockquote>", escape(notes));
+        }
+        for path in &result.screenshots {
+            let href = escape(&path.to_string_lossy());
+            let name = path
+                .file_name()
+                .map_or(path.to_string_lossy(), |n| n.to_string_lossy());
+            match image_src(path) {
+                Some(src) => {
+                    let _ = writeln!(
+                        out,
+                        "<figure><img src=\"{}\" alt=\"{}\"><figcaption>{}</figcaption></figure>",
+                        escape(&src),
+                        escape(&name),
+                        escape(&name)
+                    );
+                }
+                None => {
+                    let _ = writeln!(
+                        out,
+                        "<p>Screenshot: <a href=\"{}\">{}</a></p>",
+                        href,
+                        escape(&name)
+                    );
+                }
+            }
+        }
+        if !result.defects.is_empty() {
+            let _ = writeln!(
+                out,
+                "<p>Defects: {}</p>",
+                escape(&result.defects.join(", "))
+            );
+        }
+        if !result.attempts.is_empty() {
+            let _ = writeln!(out, "<p>Earlier attempts:</p>\n<ul>");
+            for attempt in &result.attempts {
+                let mut line = format!("{:?}", attempt.status);
+                if let Some(ref at) = attempt.completed_at {
+                    let _ = write!(line, " ({})", format_timestamp(at));
+                }
+                if let Some(ref notes) = attempt.notes {
+                    let _ = write!(line, ": {}", notes.lines().collect::<Vec<_>>().join(" "));
+                }
+                let _ = writeln!(out, "<li>{}</li>", escape(&line));
+            }
+            let _ = writeln!(out, "</ul>");
+        }
+        if let Some(ref review) = result.review {
+            let mut line = format!(
+                "Review: {} by {} ({})",
+                review.verdict.label(),
+                review.reviewer,
+                format_timestamp(&review.at)
+            );
+            if let Some(ref comment) = review.comment {
+                let _ = write!(line, ": {}", comment);
+            }
+            let _ = writeln!(out, "<p>{}</p>", escape(&line));
+        }
+        let _ = writeln!(out, "</details>");
+    }
+    let _ = writeln!(out, "</body>\n</html>");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::Test;
+    use crate::fixtures;
+
+    fn make_run() -> (Testlist, TestlistResults) {
+        let mut testlist = fixtures::testlist(vec![
+            Test {
+                id: "build".to_string(),
+                title: "Build".to_string(),
+                ..Default::default()
+            },
+            Test {
+                id: "login".to_string(),
+                title: "Log in & out".to_string(),
+                section: Some("Auth".to_string()),
+                ..Default::default()
+            },
+        ]);
+        testlist.meta.title = "Release <1.0>".to_string();
+        let mut results = TestlistResults::new_for_testlist(&testlist, "release.ron", "alice");
+        results.results[0].status = Status::Passed;
+        results.results[0]
+            .screenshots
+            .push("shots/build.png".into());
+        let login = &mut results.results[1];
+        login.status = Status::Failed;
+        login.notes = Some("Error: <script>".to_string());
+        login.screenshots.push("shots/login.png".into());
+        (testlist, results)
+    }
+
+    #[test]
+    fn test_render_escapes_and_collapses() {
+        let (testlist, results) = make_run();
+        let html = render(&testlist, &results, |_| None);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>Release &lt;1.0&gt;</title>"));
+        assert!(html.contains("<details id=\"test-build\">"));
+        assert!(html.contains("<details id=\"test-login\" open>"));
+        assert!(html.contains("Log in &amp; out"));
+        assert!(html.contains("<blockquote>Error: &lt;script&gt;</blockquote>"));
+        assert!(html.contains("<a href=\"#test-login\">"));
+        assert!(html.contains("<h3>Auth</h3>"));
+        assert!(html.trim_end().ends_with("</html>"));
+    }
+
+    #[test]
+    fn test_render_screenshots() {
+        let (testlist, results) = make_run();
+        let html = render(&testlist, &results, |path| {
+            (path.file_name()? == "login.png").then(|| "data:image/png;base64,AAAA".to_string())
+        });
+        assert!(html.contains("<img src=\"data:image/png;base64,AAAA\" alt=\"login.png\">"));
+        assert!(html.contains("Screenshot: <a href=\"shots/build.png\">build.png</a>"));
+    }
+
+    #[test]
+    fn test_status_class() {
+        assert_eq!(status_class(Status::Inconclusive), "inconclusive");
+    }
+}
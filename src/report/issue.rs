@@ -0,0 +1,117 @@
+//! Bug report for a failed test, used as the body of a GitHub issue.
+
+use std::fmt::Write;
+
+use crate::data::definition::{Test, Testlist};
+use crate::data::results::{ChecklistSection, TestResult, TestlistResults};
+use crate::queries::checklist::is_checked;
+use crate::report::run_details;
+
+/// Issue title for a failed test.
+pub fn title(test: &Test) -> String {
+    format!("{} failed", test.title)
+}
+
+/// Render the steps, expectations, notes, and run details of a failed test
+/// as a Markdown issue body.
+pub fn render(
+    testlist: &Testlist,
+    results: &TestlistResults,
+    test: &Test,
+    result: &TestResult,
+) -> String {
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "Test `{}` of {} failed.\n",
+        test.id, testlist.meta.title
+    );
+    if let Some(severity) = result.severity {
+        let _ = writeln!(out, "**Severity:** {}\n", severity.label());
+    }
+    if !test.description.trim().is_empty() {
+        let _ = writeln!(out, "{}\n", test.description.trim());
+    }
+
+    let _ = writeln!(out, "### Steps\n");
+    for item in &test.setup {
+        let _ = writeln!(out, "1. {}", item.text);
+    }
+    if !test.action.trim().is_empty() {
+        let _ = writeln!(out, "1. {}", test.action.trim());
+    }
+    if let Some(ref command) = test.suggested_command {
+        let _ = writeln!(out, "\n```sh\n{}\n```", command);
+    }
+    out.push('\n');
+
+    if !test.verify.is_empty() {
+        let _ = writeln!(out, "### Expected\n");
+        for item in &test.verify {
+            let checked = is_checked(results, &test.id, ChecklistSection::Verify, &item.id);
+            let _ = writeln!(out, "- [{}] {}", if checked { "x" } else { " " }, item.text);
+        }
+        out.push('\n');
+    }
+
+    if let Some(ref notes) = result.notes {
+        let _ = writeln!(out, "### Notes\n\n{}\n", notes.trim());
+    }
+    if !result.screenshots.is_empty() {
+        let _ = writeln!(out, "### Screenshots\n");
+        for path in &result.screenshots {
+            let _ = writeln!(out, "- `{}`", path.display());
+        }
+        out.push('\n');
+    }
+
+    let _ = writeln!(out, "### Run\n\n| | |\n|---|---|");
+    for (label, value) in run_details(testlist, results) {
+        let _ = writeln!(out, "| {} | {} |", label, value.replace('|', "\\|"));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::ChecklistItem;
+    use crate::data::results::{checklist_key, Status};
+    use crate::fixtures;
+
+    #[test]
+    fn test_render_issue() {
+        let item = |id: &str, text: &str| ChecklistItem {
+            id: id.to_string(),
+            text: text.to_string(),
+        };
+        let mut testlist = fixtures::testlist(vec![Test {
+            id: "login".to_string(),
+            title: "Log in".to_string(),
+            setup: vec![item("s1", "Open the app")],
+            action: "Log in with SSO".to_string(),
+            verify: vec![item("v1", "Dashboard shows"), item("v2", "Name shown")],
+            ..Default::default()
+        }]);
+        testlist.meta.title = "Release".to_string();
+        let mut results = TestlistResults::new_for_testlist(&testlist, "t.ron", "alice");
+        results
+            .checklist_results
+            .insert(checklist_key("login", ChecklistSection::Verify, "v1"), true);
+        let result = &mut results.results[0];
+        result.status = Status::Failed;
+        result.notes = Some("Spinner never stops".to_string());
+        result.screenshots.push("shots/login.png".into());
+        let result = results.results[0].clone();
+
+        let test = &testlist.tests[0];
+        assert_eq!(title(test), "Log in failed");
+        let body = render(&testlist, &results, test, &result);
+        assert!(body.starts_with("Test `login` of Release failed.\n"));
+        assert!(body.contains("1. Open the app\n1. Log in with SSO\n"));
+        assert!(body.contains("- [x] Dashboard shows\n- [ ] Name shown\n"));
+        assert!(body.contains("### Notes\n\nSpinner never stops\n"));
+        assert!(body.contains("- `shots/login.png`"));
+        assert!(body.contains("| Testers | alice |"));
+    }
+}
@@ -0,0 +1,259 @@
+//! JUnit XML export of a testing run, for CI test dashboards.
+//!
+//! Each testlist section becomes a `<testsuite>` (tests outside a section go
+//! in one named after the testlist) and each test a `<testcase>` named by its
+//! ID, so dashboards can track it across runs. Statuses map to JUnit as:
+//!
+//! - Passed: a plain test case
+//! - Failed: `<failure>`, typed by severity, with the notes as its message
+//! - Inconclusive: `<error>`, since the result needs attention but is not a failure
+//! - Skipped, Pending, Retest, InProgress: `<skipped>`
+//! - Blocked: `<skipped>` with the reason
+//! - Custom: a plain test case if the status counts as complete, otherwise
+//!   `<skipped>` with its name
+//!
+//! The title, custom status name, notes, and defects go in `<system-out>`.
+
+use std::fmt::Write;
+
+use crate::data::definition::Testlist;
+use crate::data::results::{Status, TestResult, TestlistResults};
+use crate::queries::tests::result_for_test;
+use crate::report::escape_xml;
+
+/// First line of the notes, or `fallback`.
+fn message(result: &TestResult, fallback: &str) -> String {
+    result
+        .notes
+        .as_deref()
+        .and_then(|n| n.lines().next())
+        .filter(|line| !line.trim().is_empty())
+        .unwrap_or(fallback)
+        .to_string()
+}
+
+#[derive(Default)]
+struct Suite<'a> {
+    name: String,
+    cases: Vec<(&'a str, &'a str, &'a TestResult)>,
+}
+
+impl Suite<'_> {
+    fn count(&self, f: impl Fn(&TestResult) -> bool) -> usize {
+        self.cases.iter().filter(|(_, _, r)| f(r)).count()
+    }
+
+    fn time(&self) -> u64 {
+        self.cases.iter().map(|(_, _, r)| r.time_spent_secs).sum()
+    }
+}
+
+fn is_skipped(testlist: &Testlist, result: &TestResult) -> bool {
+    result.status == Status::Skipped || result.is_open(testlist)
+}
+
+/// Render a results file and its testlist as JUnit XML.
+pub fn render(testlist: &Testlist, results: &TestlistResults) -> String {
+    let mut suites: Vec<Suite> = Vec::new();
+    for test in &testlist.tests {
+        let Some(result) = result_for_test(results, &test.id) else {
+            continue;
+        };
+        let name = test
+            .section
+            .clone()
+            .unwrap_or_else(|| testlist.meta.title.clone());
+        let index = match suites.iter().position(|s| s.name == name) {
+            Some(index) => index,
+            None => {
+                suites.push(Suite {
+                    name,
+                    ..Default::default()
+                });
+                suites.len() - 1
+            }
+        };
+        suites[index].cases.push((&test.id, &test.title, result));
+    }
+
+    let timestamp = results
+        .meta
+        .sessions
+        .first()
+        .map(|s| format!(" timestamp=\"{}\"", escape_xml(&s.started)))
+        .unwrap_or_default();
+    let all = |f: &dyn Fn(&Suite) -> usize| suites.iter().map(f).sum::<usize>();
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{}\">",
+        escape_xml(&testlist.meta.title),
+        all(&|s| s.cases.len()),
+        all(&|s| s.count(|r| r.status == Status::Failed)),
+        all(&|s| s.count(|r| r.status == Status::Inconclusive)),
+        all(&|s| s.count(|r| is_skipped(testlist, r))),
+        suites.iter().map(Suite::time).sum::<u64>()
+    );
+    for suite in &suites {
+        let _ = writeln!(
+            out,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\" time=\"{}\"{}>",
+            escape_xml(&suite.name),
+            suite.cases.len(),
+            suite.count(|r| r.status == Status::Failed),
+            suite.count(|r| r.status == Status::Inconclusive),
+            suite.count(|r| is_skipped(testlist, r)),
+            suite.time(),
+            timestamp
+        );
+        for (id, title, result) in &suite.cases {
+            let _ = writeln!(
+                out,
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\">",
+                escape_xml(id),
+                escape_xml(&suite.name),
+                result.time_spent_secs
+            );
+            let notes = result.notes.as_deref().unwrap_or("");
+            match result.status {
+                Status::Passed => {}
+                Status::Custom if !result.is_open(testlist) => {}
+                Status::Custom => {
+                    let _ = writeln!(
+                        out,
+                        "      <skipped message=\"{}\"/>",
+                        escape_xml(&result.status_name())
+                    );
+                }
+                Status::Failed => {
+                    let _ = writeln!(
+                        out,
+                        "      <failure message=\"{}\" type=\"{}\">{}</failure>",
+                        escape_xml(&message(result, "Failed")),
+                        result.severity.map_or("failed", |s| s.label()),
+                        escape_xml(notes)
+                    );
+                }
+                Status::Inconclusive => {
+                    let _ = writeln!(
+                        out,
+                        "      <error message=\"{}\" type=\"inconclusive\">{}</error>",
+                        escape_xml(&message(result, "Inconclusive")),
+                        escape_xml(notes)
+                    );
+                }
+                Status::Skipped => {
+                    let _ = writeln!(
+                        out,
+                        "      <skipped message=\"{}\"/>",
+                        escape_xml(&message(result, "Skipped"))
+                    );
+                }
+                Status::Blocked => {
+                    let reason = result
+                        .blocked_reason
+                        .as_deref()
+                        .unwrap_or("no reason given");
+                    let _ = writeln!(
+                        out,
+                        "      <skipped message=\"{}\"/>",
+                        escape_xml(&format!("Blocked: {}", reason))
+                    );
+                }
+                Status::Pending | Status::Retest | Status::InProgress => {
+                    let _ = writeln!(
+                        out,
+                        "      <skipped message=\"{}\"/>",
+                        match result.status {
+                            Status::Retest => "Awaiting retest",
+                            Status::InProgress => "In progress",
+                            _ => "Not run",
+                        }
+                    );
+                }
+            }
+            let mut system_out = title.to_string();
+            if let Some(name) = &result.custom_status {
+                let _ = write!(system_out, "\n\nStatus: {}", name);
+            }
+            if !notes.is_empty() {
+                let _ = write!(system_out, "\n\n{}", notes);
+            }
+            if !result.defects.is_empty() {
+                let _ = write!(system_out, "\n\nDefects: {}", result.defects.join(", "));
+            }
+            let _ = writeln!(
+                out,
+                "      <system-out>{}</system-out>",
+                escape_xml(&system_out)
+            );
+            let _ = writeln!(out, "    </testcase>");
+        }
+        let _ = writeln!(out, "  </testsuite>");
+    }
+    let _ = writeln!(out, "</testsuites>");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::Test;
+    use crate::data::results::Severity;
+    use crate::fixtures;
+
+    fn make_run() -> (Testlist, TestlistResults) {
+        let test = |id: &str, section: Option<&str>| Test {
+            id: id.to_string(),
+            title: format!("Test {}", id),
+            section: section.map(str::to_string),
+            ..Default::default()
+        };
+        let mut testlist = fixtures::testlist(vec![
+            test("a", None),
+            test("b", Some("Auth")),
+            test("c", Some("Auth")),
+            test("d", None),
+        ]);
+        testlist.meta.title = "Release".to_string();
+        let mut results = TestlistResults::new_for_testlist(&testlist, "release.ron", "alice");
+        results.results[0].status = Status::Passed;
+        results.results[0].time_spent_secs = 30;
+        let b = &mut results.results[1];
+        b.status = Status::Failed;
+        b.severity = Some(Severity::Blocker);
+        b.notes = Some("Crash <on> submit\n\x1b[1mtrace".to_string());
+        b.defects.push("BUG-1".to_string());
+        results.results[2].status = Status::Inconclusive;
+        (testlist, results)
+    }
+
+    #[test]
+    fn test_render_suites_and_counts() {
+        let (testlist, results) = make_run();
+        let xml = render(&testlist, &results);
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+        assert!(xml.contains(
+            "<testsuites name=\"Release\" tests=\"4\" failures=\"1\" errors=\"1\" skipped=\"1\" time=\"30\">"
+        ));
+        assert!(xml.contains(
+            "<testsuite name=\"Release\" tests=\"2\" failures=\"0\" errors=\"0\" skipped=\"1\" time=\"30\""
+        ));
+        assert!(xml.contains("<testsuite name=\"Auth\" tests=\"2\" failures=\"1\" errors=\"1\""));
+        assert_eq!(xml.matches("<testsuite ").count(), 2);
+    }
+
+    #[test]
+    fn test_render_status_mapping() {
+        let (testlist, results) = make_run();
+        let xml = render(&testlist, &results);
+        assert!(xml.contains(
+            "<failure message=\"Crash &lt;on&gt; submit\" type=\"blocker\">Crash &lt;on&gt; submit\n[1mtrace</failure>"
+        ));
+        assert!(xml.contains("<error message=\"Inconclusive\" type=\"inconclusive\"></error>"));
+        assert!(xml.contains("<skipped message=\"Not run\"/>"));
+        assert!(xml.contains("Defects: BUG-1</system-out>"));
+        assert!(!xml.contains('\x1b'));
+    }
+}
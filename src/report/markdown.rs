@@ -0,0 +1,222 @@
+//! Markdown report of a testing run.
+//!
+//! The report opens with the run's metadata and a summary table, lists the
+//! failures with their severity and defects, and then gives every test's
+//! result in testlist order, grouped under section headings.
+
+use std::fmt::Write;
+
+use crate::data::definition::{format_duration, Testlist};
+use crate::data::results::{Status, TestlistResults};
+use crate::queries::stats::{severity_counts, status_counts};
+use crate::queries::tests::result_for_test;
+use crate::report::{format_timestamp, result_icon, run_details, summary_rows};
+
+/// Escape characters that would break a Markdown table cell.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Render a results file and its testlist as a Markdown document.
+pub fn render(testlist: &Testlist, results: &TestlistResults) -> String {
+    let mut out = String::new();
+    let meta = &testlist.meta;
+    let _ = writeln!(out, "# {}\n", meta.title);
+    if !meta.description.trim().is_empty() {
+        let _ = writeln!(out, "{}\n", meta.description.trim());
+    }
+
+    // Run metadata
+    let _ = writeln!(out, "| | |\n|---|---|");
+    for (label, value) in run_details(testlist, results) {
+        let _ = writeln!(out, "| {} | {} |", cell(&label), cell(&value));
+    }
+    out.push('\n');
+
+    // Summary
+    let counts = status_counts(testlist, results);
+    let _ = writeln!(out, "## Summary\n\n| Status | Tests |\n|---|---:|");
+    for (_, icon, name, count) in summary_rows(testlist, results) {
+        let _ = writeln!(out, "| {} {} | {} |", icon, cell(&name), count);
+    }
+    let _ = writeln!(out, "| **Total** | **{}** |\n", counts.total());
+    let severities: Vec<String> = severity_counts(results)
+        .into_iter()
+        .map(|(severity, n)| format!("{} {}", n, severity.label()))
+        .collect();
+    if !severities.is_empty() {
+        let _ = writeln!(out, "Failures by severity: {}.\n", severities.join(", "));
+    }
+
+    // Failures at a glance
+    let failed: Vec<_> = testlist
+        .tests
+        .iter()
+        .filter_map(|test| Some((test, result_for_test(results, &test.id)?)))
+        .filter(|(_, result)| result.status == Status::Failed)
+        .collect();
+    if !failed.is_empty() {
+        let _ = writeln!(
+            out,
+            "## Failures\n\n| Test | Severity | Defects |\n|---|---|---|"
+        );
+        for (test, result) in &failed {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} |",
+                cell(&test.title),
+                result.severity.map_or("—", |s| s.label()),
+                cell(&result.defects.join(", "))
+            );
+        }
+        out.push('\n');
+    }
+
+    // Every test
+    let _ = writeln!(out, "## Results\n");
+    let mut section: Option<&str> = None;
+    for test in &testlist.tests {
+        if test.section.as_deref() != section {
+            section = test.section.as_deref();
+            if let Some(title) = section {
+                let _ = writeln!(out, "### {}\n", title);
+            }
+        }
+        let Some(result) = result_for_test(results, &test.id) else {
+            continue;
+        };
+        let _ = writeln!(
+            out,
+            "#### {} {} (`{}`)\n",
+            result_icon(testlist, result),
+            test.title,
+            test.id
+        );
+        let mut facts = vec![format!("**{}**", result.status_name())];
+        if let Some(severity) = result.severity {
+            facts.push(severity.label().to_string());
+        }
+        if let Some(ref reason) = result.blocked_reason {
+            facts.push(format!("blocked: {}", reason));
+        }
+        if let Some(ref at) = result.completed_at {
+            facts.push(format!("completed {}", format_timestamp(at)));
+        }
+        if result.time_spent_secs > 0 {
+            facts.push(format!("time {}", format_duration(result.time_spent_secs)));
+        }
+        let _ = writeln!(out, "{}\n", facts.join(" · "));
+
+        if let Some(ref notes) = result.notes {
+            for line in notes.lines() {
+                let _ = writeln!(out, "> {}", line);
+            }
+            out.push('\n');
+        }
+        if !result.screenshots.is_empty() {
+            let _ = writeln!(out, "Screenshots:\n");
+            for path in &result.screenshots {
+                let name = path
+                    .file_name()
+                    .map_or(path.to_string_lossy(), |n| n.to_string_lossy());
+                let _ = writeln!(out, "- [{}]({})", name, path.display());
+            }
+            out.push('\n');
+        }
+        if !result.defects.is_empty() {
+            let _ = writeln!(out, "Defects: {}\n", result.defects.join(", "));
+        }
+        if !result.attempts.is_empty() {
+            let _ = writeln!(out, "Earlier attempts:\n");
+            for attempt in &result.attempts {
+                let mut line = format!("- {:?}", attempt.status);
+                if let Some(ref at) = attempt.completed_at {
+                    let _ = write!(line, " ({})", format_timestamp(at));
+                }
+                if let Some(ref notes) = attempt.notes {
+                    let _ = write!(line, ": {}", notes.lines().collect::<Vec<_>>().join(" "));
+                }
+                let _ = writeln!(out, "{}", line);
+            }
+            out.push('\n');
+        }
+        if let Some(ref review) = result.review {
+            let mut line = format!(
+                "Review: {} by {} ({})",
+                review.verdict.label(),
+                review.reviewer,
+                format_timestamp(&review.at)
+            );
+            if let Some(ref comment) = review.comment {
+                let _ = write!(line, ": {}", comment);
+            }
+            let _ = writeln!(out, "{}\n", line);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::Test;
+    use crate::data::results::Severity;
+    use crate::fixtures;
+
+    fn make_run() -> (Testlist, TestlistResults) {
+        let test = |id: &str, title: &str, section: Option<&str>| Test {
+            id: id.to_string(),
+            title: title.to_string(),
+            section: section.map(str::to_string),
+            ..Default::default()
+        };
+        let mut testlist = fixtures::testlist(vec![
+            test("build", "Build", None),
+            test("login", "Log in", Some("Auth")),
+            test("logout", "Log out", Some("Auth")),
+        ]);
+        testlist.meta.title = "Release 1.0".to_string();
+        testlist.meta.description = "Checks before tagging.".to_string();
+        testlist.meta.version = "3".to_string();
+        let mut results =
+            TestlistResults::new_for_testlist(&testlist, "release.testlist.ron", "alice");
+        results.results[0].status = Status::Passed;
+        results.results[0].time_spent_secs = 90;
+        let login = &mut results.results[1];
+        login.status = Status::Failed;
+        login.severity = Some(Severity::Major);
+        login.notes = Some("Spinner never stops\nafter submit".to_string());
+        login.defects.push("BUG-7".to_string());
+        login.screenshots.push("shots/login.png".into());
+        (testlist, results)
+    }
+
+    #[test]
+    fn test_render_summary_and_failures() {
+        let (testlist, results) = make_run();
+        let report = render(&testlist, &results);
+        assert!(report.starts_with("# Release 1.0\n\nChecks before tagging.\n"));
+        assert!(report.contains("| Testlist | release.testlist.ron (version 3) |"));
+        assert!(report.contains("| ✓ Passed | 1 |\n| ✗ Failed | 1 |\n| ○ Pending | 1 |"));
+        assert!(report.contains("| **Total** | **3** |"));
+        assert!(report.contains("Failures by severity: 1 major."));
+        assert!(report.contains("| Log in | major | BUG-7 |"));
+    }
+
+    #[test]
+    fn test_render_results_per_test() {
+        let (testlist, results) = make_run();
+        let report = render(&testlist, &results);
+        assert!(report.contains("#### ✓ Build (`build`)\n\n**Passed** · time 1:30\n"));
+        assert!(report.contains("### Auth\n\n#### ✗ Log in (`login`)"));
+        assert!(report.contains("> Spinner never stops\n> after submit\n"));
+        assert!(report.contains("- [login.png](shots/login.png)"));
+        assert!(report.contains("Defects: BUG-7"));
+        assert_eq!(report.matches("### Auth").count(), 1);
+    }
+
+    #[test]
+    fn test_cell_escapes_table_syntax() {
+        assert_eq!(cell("a|b\nc"), "a\\|b c");
+    }
+}
@@ -0,0 +1,242 @@
+//! Reports that render a results file and its testlist for readers without
+//! testlist.
+
+pub mod history;
+pub mod html;
+pub mod issue;
+pub mod junit;
+pub mod markdown;
+pub mod progress;
+pub mod tap;
+pub mod testrail;
+
+use std::str::FromStr;
+
+use crate::data::definition::Testlist;
+use crate::data::results::{Environment, Status, TestResult, TestlistResults};
+use crate::error::Error;
+use crate::queries::stats::{custom_status_counts, session_summary, status_counts};
+use crate::queries::tests::custom_status_def;
+
+/// Output format of a report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    /// Detect the format from an output file extension, defaulting to Markdown.
+    pub fn from_path(path: &std::path::Path) -> Self {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("html") | Some("htm") => ReportFormat::Html,
+            _ => ReportFormat::Markdown,
+        }
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Ok(ReportFormat::Markdown),
+            "html" => Ok(ReportFormat::Html),
+            _ => Err(Error::UnknownReportFormat(s.to_string())),
+        }
+    }
+}
+
+/// Icon for a status in reports.
+pub fn status_icon(status: Status) -> &'static str {
+    match status {
+        Status::Pending => "○",
+        Status::Passed => "✓",
+        Status::Failed => "✗",
+        Status::Inconclusive => "?",
+        Status::Skipped => "–",
+        Status::Retest => "↻",
+        Status::InProgress => "◐",
+        Status::Blocked => "!",
+        Status::Custom => "*",
+    }
+}
+
+/// Icon for a result in reports: its custom status's icon if it has one.
+pub fn result_icon<'a>(testlist: &'a Testlist, result: &TestResult) -> &'a str {
+    custom_status_def(testlist, result).map_or(status_icon(result.status), |def| &def.icon)
+}
+
+/// Rows of the summary table: status, icon, name, and number of tests, in
+/// display order and leaving out statuses no test has. Custom statuses get
+/// a row each, after the finished built-in ones.
+pub fn summary_rows<'a>(
+    testlist: &'a Testlist,
+    results: &TestlistResults,
+) -> Vec<(Status, &'a str, String, usize)> {
+    let mut rows = Vec::new();
+    for (status, count) in status_counts(testlist, results).by_status() {
+        if status == Status::Custom {
+            rows.extend(
+                custom_status_counts(testlist, results)
+                    .into_iter()
+                    .map(|(def, n)| (status, def.icon.as_str(), def.name.clone(), n)),
+            );
+        } else if count > 0 {
+            rows.push((status, status_icon(status), format!("{:?}", status), count));
+        }
+    }
+    rows
+}
+
+/// Escape text for use in HTML or XML content and attribute values.
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Escape text for XML, dropping control characters XML 1.0 does not allow.
+pub fn escape_xml(text: &str) -> String {
+    let allowed: String = text
+        .chars()
+        .filter(|&c| !c.is_control() || matches!(c, '\t' | '\n' | '\r'))
+        .collect();
+    escape(&allowed)
+}
+
+/// Machine-readable export format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Junit,
+    Json,
+    Tap,
+    Testrail,
+}
+
+impl FromStr for ExportFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "junit" => Ok(ExportFormat::Junit),
+            "json" => Ok(ExportFormat::Json),
+            "tap" => Ok(ExportFormat::Tap),
+            "testrail" => Ok(ExportFormat::Testrail),
+            _ => Err(Error::UnknownExportFormat(s.to_string())),
+        }
+    }
+}
+
+/// Details of the run shown at the top of a report, as `(label, value)` rows.
+pub fn run_details(testlist: &Testlist, results: &TestlistResults) -> Vec<(String, String)> {
+    let sessions = session_summary(results);
+    let testers = if sessions.testers.is_empty() {
+        results.meta.tester.clone()
+    } else {
+        sessions.testers.join(", ")
+    };
+    let mut rows = vec![
+        (
+            "Testlist".to_string(),
+            format!(
+                "{} (version {})",
+                results.meta.testlist, testlist.meta.version
+            ),
+        ),
+        ("Testers".to_string(), testers),
+    ];
+    if let Some(first) = results.meta.sessions.first() {
+        rows.push(("Started".to_string(), format_timestamp(&first.started)));
+    }
+    rows.push((
+        "Sessions".to_string(),
+        format!(
+            "{} over {} day{}",
+            sessions.sessions,
+            sessions.days,
+            if sessions.days == 1 { "" } else { "s" }
+        ),
+    ));
+    if let Some(ref env) = results.meta.environment {
+        rows.extend(environment_fields(env));
+    }
+    if let Some(ref git) = results.meta.git {
+        rows.push(("Commit".to_string(), git.summary()));
+    }
+    rows
+}
+
+/// Environment details as `key: value` pairs, in display order.
+pub fn environment_fields(env: &Environment) -> Vec<(String, String)> {
+    let mut fields = vec![("OS".to_string(), format!("{} ({})", env.os, env.arch))];
+    if let Some(ref hostname) = env.hostname {
+        fields.push(("Host".to_string(), hostname.clone()));
+    }
+    if let Some(ref version) = env.product_version {
+        fields.push(("Product version".to_string(), version.clone()));
+    }
+    fields.extend(env.custom.iter().map(|(k, v)| (k.clone(), v.clone())));
+    fields
+}
+
+/// Local date and time of an RFC 3339 timestamp, or the timestamp as is.
+pub fn format_timestamp(at: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(at)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_else(|_| at.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_report_and_export_formats() {
+        assert_eq!(
+            ReportFormat::from_path(Path::new("r.HTML")),
+            ReportFormat::Html
+        );
+        assert_eq!(
+            ReportFormat::from_path(Path::new("r.md")),
+            ReportFormat::Markdown
+        );
+        assert_eq!("html".parse::<ReportFormat>().unwrap(), ReportFormat::Html);
+        assert_eq!(
+            "md".parse::<ReportFormat>().unwrap(),
+            ReportFormat::Markdown
+        );
+        assert!("pdf".parse::<ReportFormat>().is_err());
+        assert_eq!(
+            "JUnit".parse::<ExportFormat>().unwrap(),
+            ExportFormat::Junit
+        );
+        assert_eq!("json".parse::<ExportFormat>().unwrap(), ExportFormat::Json);
+        assert_eq!("TAP".parse::<ExportFormat>().unwrap(), ExportFormat::Tap);
+        assert_eq!(
+            "testrail".parse::<ExportFormat>().unwrap(),
+            ExportFormat::Testrail
+        );
+        assert!("csv".parse::<ExportFormat>().is_err());
+    }
+}
@@ -0,0 +1,139 @@
+//! Read-only HTML page showing the live progress of a session, served by
+//! `--serve`. It reloads itself every few seconds.
+
+use std::fmt::Write;
+
+use crate::data::results::Status;
+use crate::queries::progress::Progress;
+use crate::report::{escape, status_icon};
+
+/// Seconds between reloads of the progress page.
+const REFRESH_SECS: u32 = 5;
+
+const STYLE: &str = "\
+body { font-family: system-ui, sans-serif; max-width: 60rem; margin: 2rem auto; padding: 0 1rem; color: #222; }
+table { border-collapse: collapse; margin: 1rem 0; }
+th, td { border: 1px solid #ccc; padding: 0.3rem 0.6rem; text-align: left; }
+progress { width: 100%; height: 1.2rem; }
+tr.current { background: #fff8c5; }
+.passed { color: #1a7f37; }
+.failed { color: #cf222e; }
+.inconclusive, .retest { color: #9a6700; }
+.skipped, .pending, .inprogress { color: #6e7781; }
+.blocked { color: #9a6700; }
+.custom { color: #8250df; }
+";
+
+/// Render the progress page.
+pub fn render(progress: &Progress) -> String {
+    let mut out = String::new();
+    let counts = &progress.counts;
+    let done = counts.total() - counts.open();
+    let _ = writeln!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta http-equiv=\"refresh\" content=\"{}\">\n<title>{}</title>\n\
+         <style>\n{}</style>\n</head>\n<body>",
+        REFRESH_SECS,
+        escape(&progress.testlist),
+        STYLE
+    );
+    let _ = writeln!(out, "<h1>{}</h1>", escape(&progress.testlist));
+    let _ = writeln!(
+        out,
+        "<p>Tester: {} — {} of {} done</p>\n<progress value=\"{}\" max=\"{}\"></progress>",
+        escape(&progress.tester),
+        done,
+        counts.total(),
+        done,
+        counts.total().max(1)
+    );
+
+    out.push_str("<table>\n<tr>");
+    for (status, _) in counts.by_status() {
+        let _ = write!(out, "<th>{:?}</th>", status);
+    }
+    out.push_str("</tr>\n<tr>");
+    for (_, count) in counts.by_status() {
+        let _ = write!(out, "<td>{}</td>", count);
+    }
+    out.push_str("</tr>\n</table>\n");
+
+    out.push_str("<table>\n<tr><th></th><th>Test</th><th>Section</th><th>Status</th></tr>\n");
+    for test in &progress.tests {
+        let current = progress.current_test.as_deref() == Some(test.id.as_str());
+        let class = match test.status {
+            Status::Custom => "custom".to_string(),
+            status => format!("{:?}", status).to_ascii_lowercase(),
+        };
+        let severity = test
+            .severity
+            .map(|s| format!(" ({})", s.label()))
+            .unwrap_or_default();
+        let _ = writeln!(
+            out,
+            "<tr{}><td class=\"{}\">{}</td><td>{} <code>{}</code></td><td>{}</td><td class=\"{}\">{}{}</td></tr>",
+            if current { " class=\"current\"" } else { "" },
+            class,
+            status_icon(test.status),
+            escape(&test.title),
+            escape(&test.id),
+            escape(test.section.as_deref().unwrap_or("")),
+            class,
+            escape(
+                &test
+                    .custom_status
+                    .clone()
+                    .unwrap_or_else(|| format!("{:?}", test.status))
+            ),
+            severity
+        );
+    }
+    out.push_str("</table>\n</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::results::Severity;
+    use crate::queries::progress::ProgressTest;
+    use crate::queries::stats::StatusCounts;
+
+    #[test]
+    fn test_render_progress() {
+        let progress = Progress {
+            testlist: "Release <1.0>".to_string(),
+            tester: "alice".to_string(),
+            current_test: Some("login".to_string()),
+            counts: StatusCounts {
+                failed: 1,
+                pending: 1,
+                ..Default::default()
+            },
+            tests: vec![
+                ProgressTest {
+                    id: "build".to_string(),
+                    title: "Build".to_string(),
+                    section: None,
+                    status: Status::Failed,
+                    severity: Some(Severity::Major),
+                    custom_status: None,
+                },
+                ProgressTest {
+                    id: "login".to_string(),
+                    title: "Log in".to_string(),
+                    section: Some("Auth".to_string()),
+                    status: Status::Pending,
+                    severity: None,
+                    custom_status: None,
+                },
+            ],
+        };
+        let html = render(&progress);
+        assert!(html.contains("<title>Release &lt;1.0&gt;</title>"));
+        assert!(html.contains("1 of 2 done"));
+        assert!(html.contains("Failed (major)"));
+        assert!(html.contains("<tr class=\"current\"><td class=\"pending\">"));
+    }
+}
@@ -0,0 +1,184 @@
+//! TAP version 13 output of a testing run, for TAP harnesses and aggregators.
+//!
+//! Each test is one test point described by its ID. Statuses map to TAP as:
+//!
+//! - Passed: `ok`
+//! - Failed, Inconclusive: `not ok`
+//! - Skipped: `ok ... # SKIP` with the first line of the notes
+//! - Pending, Retest, In progress: `not ok ... # TODO`, since the test still
+//!   has to be run
+//! - Blocked: `not ok ... # TODO blocked` with the reason
+//! - Custom statuses: `ok` if they count as complete, otherwise
+//!   `not ok ... # TODO` with the status's name
+//!
+//! Test points other than a plain pass are followed by a YAML diagnostic
+//! block with the title, status, notes, severity, blocked reason, and defects.
+
+use std::fmt::Write;
+
+use serde::Serialize;
+
+use crate::data::definition::{Test, Testlist};
+use crate::data::results::{Status, TestResult, TestlistResults};
+use crate::queries::tests::result_for_test;
+
+#[derive(Serialize)]
+struct Diagnostics<'a> {
+    title: &'a str,
+    status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    severity: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blocked_reason: Option<&'a str>,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    defects: &'a [String],
+}
+
+/// Escape `#` and `\` so a description isn't read as a directive.
+fn description(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('#', "\\#")
+}
+
+/// First line of the notes, if any.
+fn first_line(result: &TestResult) -> Option<&str> {
+    result
+        .notes
+        .as_deref()
+        .and_then(|n| n.lines().next())
+        .filter(|line| !line.trim().is_empty())
+}
+
+/// The YAML diagnostic block for a test point, indented by two spaces.
+fn diagnostics(test: &Test, result: &TestResult) -> String {
+    let yaml = serde_yaml::to_string(&Diagnostics {
+        title: &test.title,
+        status: result.status_name(),
+        message: result.notes.as_deref(),
+        severity: result.severity.map(|s| s.label()),
+        blocked_reason: result.blocked_reason.as_deref(),
+        defects: &result.defects,
+    })
+    .unwrap_or_default();
+    let mut out = String::from("  ---\n");
+    for line in yaml.lines() {
+        let _ = writeln!(out, "  {}", line);
+    }
+    out.push_str("  ...\n");
+    out
+}
+
+/// Render a results file and its testlist as TAP version 13.
+pub fn render(testlist: &Testlist, results: &TestlistResults) -> String {
+    let points: Vec<_> = testlist
+        .tests
+        .iter()
+        .filter_map(|test| Some((test, result_for_test(results, &test.id)?)))
+        .collect();
+
+    let mut out = String::from("TAP version 13\n");
+    let _ = writeln!(out, "1..{}", points.len());
+    let _ = writeln!(out, "# {}", testlist.meta.title);
+    for (n, (test, result)) in points.iter().enumerate() {
+        let ok = match result.status {
+            Status::Passed | Status::Skipped => true,
+            Status::Custom => !result.is_open(testlist),
+            _ => false,
+        };
+        let _ = write!(
+            out,
+            "{} {} - {}",
+            if ok { "ok" } else { "not ok" },
+            n + 1,
+            description(&test.id)
+        );
+        match result.status {
+            Status::Skipped => {
+                let reason = first_line(result).unwrap_or("skipped");
+                let _ = write!(out, " # SKIP {}", description(reason));
+            }
+            Status::Pending => out.push_str(" # TODO not run"),
+            Status::Retest => out.push_str(" # TODO awaiting retest"),
+            Status::InProgress => out.push_str(" # TODO in progress"),
+            Status::Blocked => {
+                let _ = write!(out, " # TODO blocked");
+                if let Some(reason) = &result.blocked_reason {
+                    let _ = write!(out, ": {}", description(reason));
+                }
+            }
+            Status::Custom if result.is_open(testlist) => {
+                let _ = write!(out, " # TODO {}", description(&result.status_name()));
+            }
+            _ => {}
+        }
+        out.push('\n');
+        if result.status != Status::Passed || result.notes.is_some() {
+            out.push_str(&diagnostics(test, result));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    use crate::data::results::Severity;
+
+    fn make_run() -> (Testlist, TestlistResults) {
+        let mut testlist = fixtures::testlist(
+            ["build", "login", "docs", "perf"]
+                .iter()
+                .map(|id| Test {
+                    id: id.to_string(),
+                    title: format!("Check {}", id),
+                    ..Default::default()
+                })
+                .collect(),
+        );
+        testlist.meta.title = "Release".to_string();
+        let mut results = TestlistResults::new_for_testlist(&testlist, "release.ron", "alice");
+        results.results[0].status = Status::Passed;
+        let login = &mut results.results[1];
+        login.status = Status::Failed;
+        login.severity = Some(Severity::Major);
+        login.notes = Some("Spinner hangs: see #12".to_string());
+        login.defects.push("BUG-12".to_string());
+        results.results[2].status = Status::Skipped;
+        results.results[2].notes = Some("No docs build # yet".to_string());
+        (testlist, results)
+    }
+
+    #[test]
+    fn test_render_test_points() {
+        let (testlist, results) = make_run();
+        let tap = render(&testlist, &results);
+        let points: Vec<&str> = tap
+            .lines()
+            .filter(|l| l.starts_with("ok") || l.starts_with("not ok"))
+            .collect();
+        assert_eq!(
+            points,
+            vec![
+                "ok 1 - build",
+                "not ok 2 - login",
+                "ok 3 - docs # SKIP No docs build \\# yet",
+                "not ok 4 - perf # TODO not run",
+            ]
+        );
+        assert!(tap.starts_with("TAP version 13\n1..4\n# Release\n"));
+    }
+
+    #[test]
+    fn test_render_diagnostics() {
+        let (testlist, results) = make_run();
+        let tap = render(&testlist, &results);
+        assert!(tap.contains(
+            "not ok 2 - login\n  ---\n  title: Check login\n  status: Failed\n  \
+             message: 'Spinner hangs: see #12'\n  severity: major\n  defects:\n  - BUG-12\n  ...\n"
+        ));
+        assert!(tap.contains("ok 1 - build\nnot ok 2"));
+    }
+}
@@ -0,0 +1,282 @@
+//! TestRail exports: testlist definitions as a case suite XML file, and
+//! results as the JSON body of TestRail's `add_results_for_cases` call.
+//!
+//! Each testlist section becomes a `<section>` (tests outside a section go
+//! in one named after the testlist) and each test a `<case>` using the
+//! "Test Case (Steps)" template:
+//!
+//! - description and setup items: `preconds`, setup items as `- ` bullets
+//! - action and verify items: one step, verify items as `- ` bullets of
+//!   its expected result
+//! - priority, expected duration, and references: `priority`, `estimate`,
+//!   and `references`
+//!
+//! Tests whose ID is a TestRail case ID (`C123`, as given to cases imported
+//! from TestRail) keep it as the case `<id>`; only those tests can be
+//! exported as results. Statuses map to TestRail's default status IDs as
+//! Passed 1, Blocked 2, Retest and Inconclusive 4, and Failed 5; pending,
+//! in-progress, skipped, and custom-status tests are left out.
+
+use std::fmt::Write;
+
+use serde_json::json;
+
+use crate::data::definition::{Priority, Test, Testlist};
+use crate::data::results::{Status, TestlistResults};
+use crate::error::Result;
+use crate::queries::tests::result_for_test;
+use crate::report::escape_xml;
+
+/// The numeric TestRail case ID in a test ID such as `C123`.
+pub fn case_id(test_id: &str) -> Option<u64> {
+    test_id
+        .strip_prefix(['C', 'c'])
+        .filter(|digits| digits.chars().all(|c| c.is_ascii_digit()))
+        .and_then(|digits| digits.parse().ok())
+}
+
+/// TestRail status ID for a result, if it is reported at all.
+fn status_id(status: Status) -> Option<u64> {
+    match status {
+        Status::Passed => Some(1),
+        Status::Blocked => Some(2),
+        Status::Retest | Status::Inconclusive => Some(4),
+        Status::Failed => Some(5),
+        Status::Pending | Status::InProgress | Status::Skipped | Status::Custom => None,
+    }
+}
+
+/// TestRail's name for a priority in its default configuration.
+pub fn priority_name(priority: Priority) -> &'static str {
+    match priority {
+        Priority::Critical => "Critical",
+        Priority::High => "High",
+        Priority::Medium => "Medium",
+        Priority::Low => "Low",
+    }
+}
+
+/// A duration in TestRail's timespan format, e.g. `1h 5m 30s`.
+pub fn timespan(secs: u64) -> String {
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    let parts: Vec<String> = [(h, "h"), (m, "m"), (s, "s")]
+        .iter()
+        .filter(|(value, _)| *value > 0)
+        .map(|(value, unit)| format!("{}{}", value, unit))
+        .collect();
+    if parts.is_empty() {
+        "0s".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+fn bullets<'a>(items: impl Iterator<Item = &'a str>) -> String {
+    items
+        .map(|text| format!("- {}", text))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn element(out: &mut String, indent: usize, name: &str, text: &str) {
+    if !text.is_empty() {
+        let _ = writeln!(
+            out,
+            "{:indent$}<{name}>{}</{name}>",
+            "",
+            escape_xml(text),
+            indent = indent,
+            name = name
+        );
+    }
+}
+
+fn write_case(out: &mut String, test: &Test) {
+    out.push_str("        <case>\n");
+    if case_id(&test.id).is_some() {
+        element(out, 10, "id", &test.id.to_ascii_uppercase());
+    }
+    element(out, 10, "title", &test.title);
+    element(out, 10, "template", "Test Case (Steps)");
+    element(out, 10, "type", "Other");
+    if let Some(priority) = test.priority {
+        element(out, 10, "priority", priority_name(priority));
+    }
+    if let Some(secs) = test.expected_secs() {
+        element(out, 10, "estimate", &timespan(secs));
+    }
+    element(out, 10, "references", &test.references.join(", "));
+
+    let setup = bullets(test.setup.iter().map(|i| i.text.as_str()));
+    let preconds = [test.description.trim(), setup.as_str()]
+        .iter()
+        .filter(|part| !part.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    out.push_str("          <custom>\n");
+    element(out, 12, "preconds", &preconds);
+    out.push_str("            <steps_separated>\n");
+    out.push_str("              <step>\n");
+    element(out, 16, "index", "1");
+    element(out, 16, "content", &test.action);
+    element(
+        out,
+        16,
+        "expected",
+        &bullets(test.verify.iter().map(|i| i.text.as_str())),
+    );
+    out.push_str("              </step>\n");
+    out.push_str("            </steps_separated>\n");
+    out.push_str("          </custom>\n");
+    out.push_str("        </case>\n");
+}
+
+/// Render a testlist definition as a TestRail suite XML file.
+pub fn render_cases(testlist: &Testlist) -> String {
+    // Sections in order of first appearance
+    let mut sections: Vec<(&str, Vec<&Test>)> = Vec::new();
+    for test in &testlist.tests {
+        let name = test.section.as_deref().unwrap_or(&testlist.meta.title);
+        match sections.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, tests)) => tests.push(test),
+            None => sections.push((name, vec![test])),
+        }
+    }
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<suite>\n");
+    element(&mut out, 2, "name", &testlist.meta.title);
+    element(&mut out, 2, "description", &testlist.meta.description);
+    out.push_str("  <sections>\n");
+    for (name, tests) in sections {
+        out.push_str("    <section>\n");
+        element(&mut out, 6, "name", name);
+        out.push_str("      <cases>\n");
+        for test in tests {
+            write_case(&mut out, test);
+        }
+        out.push_str("      </cases>\n");
+        out.push_str("    </section>\n");
+    }
+    out.push_str("  </sections>\n</suite>\n");
+    out
+}
+
+/// Render results as a TestRail `add_results_for_cases` request body.
+pub fn render_results(testlist: &Testlist, results: &TestlistResults) -> Result<String> {
+    let mut entries = Vec::new();
+    for test in &testlist.tests {
+        let Some(case) = case_id(&test.id) else {
+            continue;
+        };
+        let Some(result) = result_for_test(results, &test.id) else {
+            continue;
+        };
+        let Some(status) = status_id(result.status) else {
+            continue;
+        };
+        let mut comment = result.notes.clone().unwrap_or_default();
+        if let Some(severity) = result.severity {
+            if !comment.is_empty() {
+                comment.push_str("\n\n");
+            }
+            comment.push_str(&format!("Severity: {}", severity.label()));
+        }
+        let mut entry = json!({ "case_id": case, "status_id": status });
+        if !comment.is_empty() {
+            entry["comment"] = json!(comment);
+        }
+        if result.time_spent_secs > 0 {
+            entry["elapsed"] = json!(timespan(result.time_spent_secs));
+        }
+        if !result.defects.is_empty() {
+            entry["defects"] = json!(result.defects.join(", "));
+        }
+        entries.push(entry);
+    }
+    let mut out = serde_json::to_string_pretty(&json!({ "results": entries }))?;
+    out.push('\n');
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::ChecklistItem;
+    use crate::data::results::Severity;
+    use crate::fixtures;
+
+    fn make_testlist() -> Testlist {
+        let mut testlist = fixtures::testlist(vec![
+            Test {
+                id: "C12".to_string(),
+                title: "Login & logout".to_string(),
+                description: "Use a fresh account".to_string(),
+                setup: vec![ChecklistItem {
+                    id: "s0".to_string(),
+                    text: "Server running".to_string(),
+                }],
+                action: "Sign in".to_string(),
+                verify: vec![ChecklistItem {
+                    id: "v0".to_string(),
+                    text: "Dashboard <shown>".to_string(),
+                }],
+                priority: Some(Priority::High),
+                expected_duration: Some("90s".to_string()),
+                section: Some("Auth".to_string()),
+                ..Default::default()
+            },
+            Test {
+                id: "build".to_string(),
+                title: "Build".to_string(),
+                action: "cargo build".to_string(),
+                ..Default::default()
+            },
+        ]);
+        testlist.meta.title = "Release".to_string();
+        testlist
+    }
+
+    #[test]
+    fn test_case_id() {
+        assert_eq!(case_id("C123"), Some(123));
+        assert_eq!(case_id("c7"), Some(7));
+        assert_eq!(case_id("C"), None);
+        assert_eq!(case_id("build"), None);
+    }
+
+    #[test]
+    fn test_render_cases() {
+        let xml = render_cases(&make_testlist());
+        assert!(xml.contains("<name>Auth</name>"));
+        assert!(xml.contains("<name>Release</name>"));
+        assert!(xml.contains("<id>C12</id>"));
+        assert!(xml.contains("<title>Login &amp; logout</title>"));
+        assert!(xml.contains("<priority>High</priority>"));
+        assert!(xml.contains("<estimate>1m 30s</estimate>"));
+        assert!(xml.contains("<preconds>Use a fresh account\n\n- Server running</preconds>"));
+        assert!(xml.contains("<expected>- Dashboard &lt;shown&gt;</expected>"));
+        assert_eq!(xml.matches("<id>").count(), 1);
+        assert!(roxmltree::Document::parse(&xml).is_ok());
+    }
+
+    #[test]
+    fn test_render_results() {
+        let testlist = make_testlist();
+        let mut results = TestlistResults::new_for_testlist(&testlist, "t.ron", "alice");
+        results.results[0].status = Status::Failed;
+        results.results[0].notes = Some("Crashed".to_string());
+        results.results[0].severity = Some(Severity::Major);
+        results.results[0].time_spent_secs = 65;
+        results.results[1].status = Status::Passed;
+
+        let json: serde_json::Value =
+            serde_json::from_str(&render_results(&testlist, &results).unwrap()).unwrap();
+        let entries = json["results"].as_array().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["case_id"], 12);
+        assert_eq!(entries[0]["status_id"], 5);
+        assert_eq!(entries[0]["comment"], "Crashed\n\nSeverity: major");
+        assert_eq!(entries[0]["elapsed"], "1m 5s");
+    }
+}
@@ -0,0 +1,390 @@
+//! Headless testing sessions for driving testlist from other programs.
+//!
+//! A [`Session`] holds a testlist, its results, and the file they are saved
+//! to, with none of the TUI state of `AppState`:
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use testlist::data::results::{ChecklistSection, Status};
+//! use testlist::session::Session;
+//!
+//! let mut session = Session::open(Path::new("release.testlist.ron"), None, "ci")?;
+//! session.set_checklist_item("build", ChecklistSection::Verify, "no-warnings", true)?;
+//! session.set_status("build", Status::Passed)?;
+//! session.add_note("build", "Built on the nightly runner")?;
+//! session.save()?;
+//! # Ok::<(), testlist::error::Error>(())
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use crate::actions::{files, script, signing};
+use crate::data::config::{Signing, DEFAULT_BACKUPS};
+use crate::data::definition::{ChecklistItem, Testlist};
+use crate::data::results::{
+    checklist_key, ChecklistSection, Severity, Status, TestResult, TestlistResults,
+};
+use crate::data::script::apply_actions;
+use crate::error::{Error, Result};
+
+/// Results file used for a testlist when none is given:
+/// `<testlist stem>.results.ron` next to the testlist.
+pub fn default_results_path(testlist_path: &Path) -> PathBuf {
+    let stem = testlist_path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy();
+    testlist_path.with_file_name(format!("{}.results.ron", stem))
+}
+
+/// A testlist and its results being updated outside the TUI.
+pub struct Session {
+    pub testlist: Testlist,
+    pub results: TestlistResults,
+    pub results_path: PathBuf,
+    /// Backups kept when overwriting the results file.
+    pub backups: usize,
+    /// Signs the results file after each save.
+    pub signing: Option<Signing>,
+    /// Refuses to pass tests with unchecked verify items, as the testlist's
+    /// own `require_checks` does.
+    pub require_checks: bool,
+    /// Whether there are changes since the last save.
+    pub dirty: bool,
+}
+
+impl Session {
+    /// Wrap already loaded results; no sitting is started.
+    pub fn new(testlist: Testlist, results: TestlistResults, results_path: PathBuf) -> Self {
+        Self {
+            testlist,
+            results,
+            results_path,
+            backups: DEFAULT_BACKUPS,
+            signing: None,
+            require_checks: false,
+            dirty: false,
+        }
+    }
+
+    /// Load a testlist and continue its results file, or start new results
+    /// if the file doesn't exist yet, then begin a sitting by `tester`.
+    ///
+    /// `results_path` defaults to [`default_results_path`].
+    pub fn open(testlist_path: &Path, results_path: Option<&Path>, tester: &str) -> Result<Self> {
+        let testlist = files::load_testlist(testlist_path)?;
+        let results_path = results_path
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| default_results_path(testlist_path));
+        let mut results = if results_path.exists() {
+            files::load_results(&results_path, &testlist)?
+        } else {
+            TestlistResults::new_for_testlist(&testlist, &testlist_path.to_string_lossy(), tester)
+        };
+        results.reconcile(&testlist);
+        results.start_session(tester, None);
+        Ok(Self::new(testlist, results, results_path))
+    }
+
+    /// The result recorded for a test.
+    pub fn result(&self, test_id: &str) -> Option<&TestResult> {
+        self.results.results.iter().find(|r| r.test_id == test_id)
+    }
+
+    fn result_mut(&mut self, test_id: &str) -> Result<&mut TestResult> {
+        self.results
+            .get_result_mut(test_id)
+            .ok_or_else(|| Error::InvalidTestId(test_id.to_string()))
+    }
+
+    /// Whether tests may only pass once all their verify items are checked.
+    fn checks_required(&self) -> bool {
+        self.require_checks || self.testlist.meta.require_checks
+    }
+
+    /// Set a test's status, as `p`, `f`, and the other status keys do.
+    ///
+    /// When checks are required, a test with unchecked verify items can't
+    /// be marked Passed.
+    pub fn set_status(&mut self, test_id: &str, status: Status) -> Result<()> {
+        if status == Status::Passed {
+            if let Some(test) = self.testlist.tests.iter().find(|t| t.id == test_id) {
+                if !self.results.may_pass(test, self.checks_required()) {
+                    let left = self.results.unchecked_verify_count(test);
+                    return Err(Error::UncheckedVerifyItems(test_id.to_string(), left));
+                }
+            }
+        }
+        if !self.results.set_status(test_id, status) {
+            return Err(Error::InvalidTestId(test_id.to_string()));
+        }
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Mark a test Blocked, giving why; `None` or an empty string gives no reason.
+    pub fn set_blocked(&mut self, test_id: &str, reason: Option<&str>) -> Result<()> {
+        self.set_status(test_id, Status::Blocked)?;
+        let result = self.result_mut(test_id)?;
+        result.blocked_reason = reason.filter(|r| !r.is_empty()).map(str::to_string);
+        Ok(())
+    }
+
+    /// Set one of the testlist's custom statuses by name, as `m` does.
+    pub fn set_custom_status(&mut self, test_id: &str, name: &str) -> Result<()> {
+        let def = self
+            .testlist
+            .custom_status(name)
+            .ok_or_else(|| Error::UnknownStatus(name.to_string()))?;
+        if !self.results.set_custom_status(test_id, def) {
+            return Err(Error::InvalidTestId(test_id.to_string()));
+        }
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Set the severity of a failed test; ignored for other statuses.
+    pub fn set_severity(&mut self, test_id: &str, severity: Severity) -> Result<()> {
+        let result = self.result_mut(test_id)?;
+        if result.status == Status::Failed {
+            result.severity = Some(severity);
+            self.dirty = true;
+        }
+        Ok(())
+    }
+
+    /// Replace a test's notes; `None` or an empty string clears them.
+    pub fn set_notes(&mut self, test_id: &str, notes: Option<&str>) -> Result<()> {
+        let result = self.result_mut(test_id)?;
+        result.notes = notes.filter(|n| !n.is_empty()).map(str::to_string);
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Append a line to a test's notes.
+    pub fn add_note(&mut self, test_id: &str, text: &str) -> Result<()> {
+        self.result_mut(test_id)?.append_note(text);
+        self.dirty = true;
+        Ok(())
+    }
+
+    fn checklist_item(
+        &self,
+        test_id: &str,
+        section: ChecklistSection,
+        item_id: &str,
+    ) -> Result<&ChecklistItem> {
+        let test = self
+            .testlist
+            .tests
+            .iter()
+            .find(|t| t.id == test_id)
+            .ok_or_else(|| Error::InvalidTestId(test_id.to_string()))?;
+        let items = match section {
+            ChecklistSection::Setup => &test.setup,
+            ChecklistSection::Verify => &test.verify,
+        };
+        items
+            .iter()
+            .find(|item| item.id == item_id)
+            .ok_or_else(|| Error::UnknownChecklistItem(checklist_key(test_id, section, item_id)))
+    }
+
+    /// Whether a setup or verify item is checked.
+    pub fn is_checked(&self, test_id: &str, section: ChecklistSection, item_id: &str) -> bool {
+        self.results
+            .checklist_results
+            .get(&checklist_key(test_id, section, item_id))
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Check or uncheck a setup or verify item.
+    pub fn set_checklist_item(
+        &mut self,
+        test_id: &str,
+        section: ChecklistSection,
+        item_id: &str,
+        checked: bool,
+    ) -> Result<()> {
+        self.checklist_item(test_id, section, item_id)?;
+        self.results
+            .checklist_results
+            .insert(checklist_key(test_id, section, item_id), checked);
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Flip a setup or verify item, returning whether it is now checked.
+    pub fn toggle_checklist_item(
+        &mut self,
+        test_id: &str,
+        section: ChecklistSection,
+        item_id: &str,
+    ) -> Result<bool> {
+        let checked = !self.is_checked(test_id, section, item_id);
+        self.set_checklist_item(test_id, section, item_id, checked)?;
+        Ok(checked)
+    }
+
+    /// Run a Rhai script (see [`crate::actions::script`]) and apply the
+    /// changes it asks for, returning how many were applied.
+    pub fn run_script(&mut self, script: &str) -> Result<usize> {
+        let actions = script::run(script, &self.testlist, &self.results, None)?;
+        let require_checks = self.checks_required();
+        let applied = apply_actions(&self.testlist, &mut self.results, &actions, require_checks);
+        self.dirty |= applied > 0;
+        Ok(applied)
+    }
+
+    /// End the current sitting and write the results file, rotating
+    /// backups, then sign it if `signing` is set.
+    pub fn save(&mut self) -> Result<()> {
+        self.results.end_session(&self.testlist);
+        files::save_results(&self.results, &self.results_path, self.backups)?;
+        self.dirty = false;
+        if let Some(ref signing) = self.signing {
+            signing::sign(&self.results_path, signing)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::Test;
+    use crate::fixtures;
+
+    fn make_testlist() -> Testlist {
+        fixtures::testlist(vec![Test {
+            id: "t1".to_string(),
+            title: "Test 1".to_string(),
+            action: "Do it".to_string(),
+            verify: vec![ChecklistItem {
+                id: "v0".to_string(),
+                text: "Check".to_string(),
+            }],
+            ..Default::default()
+        }])
+    }
+
+    #[test]
+    fn test_default_results_path() {
+        assert_eq!(
+            default_results_path(Path::new("dir/release.testlist.ron")),
+            PathBuf::from("dir/release.testlist.results.ron")
+        );
+    }
+
+    #[test]
+    fn test_session_updates_results() {
+        let testlist = make_testlist();
+        let results = TestlistResults::new_for_testlist(&testlist, "t.ron", "alice");
+        let mut session = Session::new(testlist, results, PathBuf::from("t.results.ron"));
+
+        assert!(matches!(
+            session.set_status("missing", Status::Passed),
+            Err(Error::InvalidTestId(_))
+        ));
+        assert!(!session.dirty);
+
+        session.set_status("t1", Status::Failed).unwrap();
+        session.set_severity("t1", Severity::Minor).unwrap();
+        session.add_note("t1", "crashed").unwrap();
+        session.add_note("t1", "twice").unwrap();
+        let result = session.result("t1").unwrap();
+        assert_eq!(result.status, Status::Failed);
+        assert_eq!(result.severity, Some(Severity::Minor));
+        assert_eq!(result.notes.as_deref(), Some("crashed\ntwice"));
+        assert!(result.completed_at.is_some() && session.dirty);
+
+        session.set_status("t1", Status::Passed).unwrap();
+        session.set_severity("t1", Severity::Major).unwrap();
+        assert_eq!(session.result("t1").unwrap().severity, None);
+        session.set_notes("t1", Some("")).unwrap();
+        assert_eq!(session.result("t1").unwrap().notes, None);
+
+        session.set_blocked("t1", Some("no device")).unwrap();
+        let result = session.result("t1").unwrap();
+        assert_eq!(result.status, Status::Blocked);
+        assert_eq!(result.blocked_reason.as_deref(), Some("no device"));
+        assert!(result.completed_at.is_none());
+    }
+
+    #[test]
+    fn test_session_checklist() {
+        let testlist = make_testlist();
+        let results = TestlistResults::new_for_testlist(&testlist, "t.ron", "alice");
+        let mut session = Session::new(testlist, results, PathBuf::from("t.results.ron"));
+
+        let verify = ChecklistSection::Verify;
+        assert!(session.toggle_checklist_item("t1", verify, "v0").unwrap());
+        assert!(session.is_checked("t1", verify, "v0"));
+        assert!(!session.toggle_checklist_item("t1", verify, "v0").unwrap());
+        assert!(matches!(
+            session.set_checklist_item("t1", ChecklistSection::Setup, "v0", true),
+            Err(Error::UnknownChecklistItem(_))
+        ));
+    }
+
+    #[test]
+    fn test_session_run_script() {
+        let testlist = make_testlist();
+        let results = TestlistResults::new_for_testlist(&testlist, "t.ron", "alice");
+        let mut session = Session::new(testlist, results, PathBuf::from("t.results.ron"));
+
+        let script = r#"check("t1", "verify", "v0"); set_status("t1", "passed")"#;
+        assert_eq!(session.run_script(script).unwrap(), 2);
+        assert!(session.is_checked("t1", ChecklistSection::Verify, "v0"));
+        assert_eq!(session.result("t1").unwrap().status, Status::Passed);
+        assert!(session.dirty);
+    }
+
+    #[test]
+    fn test_session_requires_checks_to_pass() {
+        let testlist = make_testlist();
+        let results = TestlistResults::new_for_testlist(&testlist, "t.ron", "alice");
+        let mut session = Session::new(testlist, results, PathBuf::from("t.results.ron"));
+        session.require_checks = true;
+
+        assert!(matches!(
+            session.set_status("t1", Status::Passed),
+            Err(Error::UncheckedVerifyItems(_, 1))
+        ));
+        assert_eq!(
+            session.run_script(r#"set_status("t1", "passed")"#).unwrap(),
+            0
+        );
+        assert_eq!(session.result("t1").unwrap().status, Status::Pending);
+
+        session
+            .set_checklist_item("t1", ChecklistSection::Verify, "v0", true)
+            .unwrap();
+        session.set_status("t1", Status::Passed).unwrap();
+        assert_eq!(session.result("t1").unwrap().status, Status::Passed);
+    }
+
+    #[test]
+    fn test_session_open_and_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let testlist_path = dir.path().join("t.testlist.ron");
+        make_testlist()
+            .save(&testlist_path, crate::data::definition::Format::Ron)
+            .unwrap();
+
+        let mut session = Session::open(&testlist_path, None, "alice").unwrap();
+        assert_eq!(
+            session.results_path,
+            dir.path().join("t.testlist.results.ron")
+        );
+        session.set_status("t1", Status::Passed).unwrap();
+        session.save().unwrap();
+        assert!(!session.dirty);
+
+        let session = Session::open(&testlist_path, None, "bob").unwrap();
+        assert_eq!(session.result("t1").unwrap().status, Status::Passed);
+        assert_eq!(session.results.meta.sessions.len(), 2);
+        assert_eq!(session.results.current_tester(), "bob");
+    }
+}
@@ -0,0 +1,315 @@
+//! Transforms for filtering and ordering the tests pane.
+
+use crate::data::results::Status;
+use crate::data::state::{AppState, LaunchFilter, StatusFilter, StatusView};
+use crate::queries::tests::{custom_order, display_order};
+use crate::transforms::history;
+use crate::transforms::navigation::ensure_selection_visible;
+
+/// Start entering a tag filter, pre-filled with the active one.
+pub fn start_tag_filter(state: &mut AppState) {
+    state.filtering_tags = true;
+    state.tag_filter_input = state.tag_filter.clone().unwrap_or_default();
+}
+
+/// Cancel tag filter input, leaving the active filter unchanged.
+pub fn cancel_tag_filter(state: &mut AppState) {
+    state.filtering_tags = false;
+    state.tag_filter_input.clear();
+}
+
+/// Apply the entered tag filter. An empty input clears the filter, along
+/// with any status filter from `--filter`.
+pub fn confirm_tag_filter(state: &mut AppState) {
+    let tag = state.tag_filter_input.trim();
+    state.tag_filter = if tag.is_empty() {
+        state.status_filter = None;
+        None
+    } else {
+        Some(tag.to_string())
+    };
+    state.filtering_tags = false;
+    state.tag_filter_input.clear();
+    state.tests_scroll_offset = 0;
+    ensure_selection_visible(state);
+}
+
+/// Restrict the session to the given tests, hiding all others.
+pub fn focus_tests(state: &mut AppState, ids: Vec<String>) {
+    state.only_tests = Some(ids);
+    state.tests_scroll_offset = 0;
+    ensure_selection_visible(state);
+}
+
+/// Apply the filters given with `--filter`.
+///
+/// Status filters are combined: a test is shown if it has any of the
+/// statuses. The tests shown are fixed now, so re-marking a test doesn't
+/// hide it. Of several tag filters the last one applies.
+pub fn apply_launch_filters(state: &mut AppState, filters: Vec<LaunchFilter>) {
+    let mut statuses: Vec<Status> = Vec::new();
+    for filter in filters {
+        match filter {
+            LaunchFilter::Status(s) => statuses.extend(s),
+            LaunchFilter::Tag(tag) => state.tag_filter = Some(tag),
+        }
+    }
+    if !statuses.is_empty() {
+        let test_ids = state
+            .results
+            .results
+            .iter()
+            .filter(|r| statuses.contains(&r.status))
+            .map(|r| r.test_id.clone())
+            .collect();
+        state.status_filter = Some(StatusFilter { statuses, test_ids });
+    }
+    state.tests_scroll_offset = 0;
+    ensure_selection_visible(state);
+}
+
+/// Toggle hiding tests that are no longer open, leaving the remaining work.
+pub fn toggle_hide_completed(state: &mut AppState) {
+    let view = if state.status_view == StatusView::Pending {
+        StatusView::All
+    } else {
+        StatusView::Pending
+    };
+    set_status_view(state, view);
+}
+
+/// Cycle the tests pane through all, pending, failed, and blocked tests.
+pub fn cycle_status_view(state: &mut AppState) {
+    set_status_view(state, state.status_view.next());
+}
+
+fn set_status_view(state: &mut AppState, view: StatusView) {
+    state.status_view = view;
+    state.tests_scroll_offset = 0;
+    ensure_selection_visible(state);
+}
+
+/// Toggle between definition order and priority order.
+pub fn toggle_priority_sort(state: &mut AppState) {
+    state.sort_by_priority = !state.sort_by_priority;
+}
+
+/// Move the selected test one place up (`-1`) or down (`1`) among the
+/// tests shown in its section, saving the new order in the results.
+///
+/// The testlist itself is not changed. Tests can't be moved while sorted by
+/// priority, since the sort would put them straight back.
+pub fn move_test(state: &mut AppState, offset: isize) {
+    if state.selected_section.is_some() {
+        return;
+    }
+    if state.sort_by_priority {
+        state.status_message = Some("Turn off priority sorting (P) to reorder tests".to_string());
+        return;
+    }
+    let shown = display_order(state);
+    let Some(pos) = shown.iter().position(|&i| i == state.selected_test) else {
+        return;
+    };
+    let Some(&other) = pos
+        .checked_add_signed(offset)
+        .and_then(|target| shown.get(target))
+    else {
+        return;
+    };
+    let tests = &state.testlist.tests;
+    if tests[other].section != tests[state.selected_test].section {
+        return;
+    }
+
+    let mut order = custom_order(state);
+    let a = order.iter().position(|&i| i == state.selected_test);
+    let b = order.iter().position(|&i| i == other);
+    if let (Some(a), Some(b)) = (a, b) {
+        order.swap(a, b);
+    }
+    let test_order = order.into_iter().map(|i| tests[i].id.clone()).collect();
+    history::record(state);
+    state.results.test_order = test_order;
+    state.dirty = true;
+    ensure_selection_visible(state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::Test;
+    use crate::fixtures;
+
+    fn make_state() -> AppState {
+        fixtures::state(fixtures::testlist(vec![
+            Test {
+                id: "t1".to_string(),
+                title: "Test 1".to_string(),
+                action: "Do it".to_string(),
+                tags: vec!["regression".to_string()],
+                ..Default::default()
+            },
+            Test {
+                id: "t2".to_string(),
+                title: "Test 2".to_string(),
+                action: "Do it".to_string(),
+                tags: vec!["smoke".to_string()],
+                ..Default::default()
+            },
+        ]))
+    }
+
+    #[test]
+    fn test_confirm_tag_filter_moves_selection() {
+        let mut state = make_state();
+        start_tag_filter(&mut state);
+        assert!(state.filtering_tags);
+        state.tag_filter_input = "smoke".to_string();
+        confirm_tag_filter(&mut state);
+        assert!(!state.filtering_tags);
+        assert_eq!(state.tag_filter, Some("smoke".to_string()));
+        assert_eq!(state.selected_test, 1);
+    }
+
+    #[test]
+    fn test_empty_tag_filter_clears() {
+        let mut state = make_state();
+        state.tag_filter = Some("smoke".to_string());
+        start_tag_filter(&mut state);
+        assert_eq!(state.tag_filter_input, "smoke");
+        state.tag_filter_input.clear();
+        confirm_tag_filter(&mut state);
+        assert!(state.tag_filter.is_none());
+    }
+
+    #[test]
+    fn test_move_test_saves_custom_order() {
+        let mut state = make_state();
+        move_test(&mut state, -1);
+        assert!(state.results.test_order.is_empty());
+
+        move_test(&mut state, 1);
+        assert_eq!(state.results.test_order, vec!["t2", "t1"]);
+        assert_eq!(state.selected_test, 0);
+        assert_eq!(crate::queries::tests::visible_tests(&state), vec![1, 0]);
+        assert!(state.dirty);
+
+        crate::transforms::history::undo(&mut state);
+        assert!(state.results.test_order.is_empty());
+
+        state.sort_by_priority = true;
+        move_test(&mut state, 1);
+        assert!(state.results.test_order.is_empty());
+    }
+
+    #[test]
+    fn test_hide_completed_keeps_selected_until_moving_on() {
+        use crate::queries::tests::{hidden_by_view_count, visible_tests};
+        use crate::transforms::{navigation, tests::set_status};
+
+        let mut state = make_state();
+        toggle_hide_completed(&mut state);
+        set_status(&mut state, Status::Passed);
+        assert_eq!(visible_tests(&state), vec![0, 1]);
+        assert_eq!(hidden_by_view_count(&state), 0);
+
+        navigation::select_next(&mut state);
+        assert_eq!(state.selected_test, 1);
+        assert_eq!(visible_tests(&state), vec![1]);
+        assert_eq!(hidden_by_view_count(&state), 1);
+
+        toggle_hide_completed(&mut state);
+        assert_eq!(visible_tests(&state), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_cycle_status_view() {
+        use crate::queries::tests::{hidden_by_view_count, visible_tests};
+        use crate::transforms::{navigation, tests::set_status};
+
+        let mut state = make_state();
+        navigation::select_next(&mut state);
+        set_status(&mut state, Status::Failed);
+        navigation::select_prev(&mut state);
+
+        cycle_status_view(&mut state);
+        assert_eq!(state.status_view, StatusView::Pending);
+        assert_eq!(visible_tests(&state), vec![0]);
+
+        cycle_status_view(&mut state);
+        assert_eq!(state.status_view, StatusView::Failed);
+        assert_eq!(visible_tests(&state), vec![0, 1]);
+        assert_eq!(hidden_by_view_count(&state), 0);
+
+        navigation::select_next(&mut state);
+        assert_eq!(visible_tests(&state), vec![1]);
+        assert_eq!(hidden_by_view_count(&state), 1);
+
+        cycle_status_view(&mut state);
+        assert_eq!(state.status_view, StatusView::Blocked);
+        cycle_status_view(&mut state);
+        assert_eq!(state.status_view, StatusView::All);
+        assert_eq!(visible_tests(&state), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_focus_tests_hides_others() {
+        let mut state = make_state();
+        focus_tests(&mut state, vec!["t2".to_string()]);
+        assert_eq!(state.selected_test, 1);
+        assert_eq!(crate::queries::tests::visible_tests(&state), vec![1]);
+
+        state.tag_filter = Some("regression".to_string());
+        assert!(crate::queries::tests::visible_tests(&state).is_empty());
+    }
+
+    #[test]
+    fn test_launch_status_filter_keeps_remarked_tests() {
+        let mut state = make_state();
+        state.results.results[1].status = Status::Failed;
+        apply_launch_filters(
+            &mut state,
+            vec![
+                LaunchFilter::Status(vec![Status::Failed]),
+                LaunchFilter::Tag("smoke".to_string()),
+            ],
+        );
+        assert_eq!(state.selected_test, 1);
+        assert_eq!(crate::queries::tests::visible_tests(&state), vec![1]);
+        assert_eq!(
+            crate::queries::tests::filter_label(&state).as_deref(),
+            Some("status=failed tag=smoke")
+        );
+
+        state.results.results[1].status = Status::Passed;
+        assert_eq!(crate::queries::tests::visible_tests(&state), vec![1]);
+
+        start_tag_filter(&mut state);
+        state.tag_filter_input.clear();
+        confirm_tag_filter(&mut state);
+        assert!(state.status_filter.is_none());
+        assert_eq!(crate::queries::tests::visible_tests(&state), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_toggle_priority_sort_keeps_selection() {
+        let mut state = make_state();
+        state.selected_test = 1;
+        toggle_priority_sort(&mut state);
+        assert!(state.sort_by_priority);
+        assert_eq!(state.selected_test, 1);
+        toggle_priority_sort(&mut state);
+        assert!(!state.sort_by_priority);
+    }
+
+    #[test]
+    fn test_cancel_tag_filter_keeps_active_filter() {
+        let mut state = make_state();
+        state.tag_filter = Some("smoke".to_string());
+        start_tag_filter(&mut state);
+        state.tag_filter_input = "regression".to_string();
+        cancel_tag_filter(&mut state);
+        assert_eq!(state.tag_filter, Some("smoke".to_string()));
+    }
+}
@@ -0,0 +1,174 @@
+//! Transforms for undoing and redoing changes to results.
+//!
+//! Each undoable change snapshots `state.results` before it mutates them.
+//! Time spent and the command logs are not part of the history: undo and
+//! redo keep their current values so the running timer is never rolled back
+//! and executed commands stay on record.
+
+use crate::data::results::TestlistResults;
+use crate::data::state::AppState;
+
+/// Maximum number of snapshots kept for undo.
+pub const HISTORY_LIMIT: usize = 100;
+
+/// Snapshot the results before an undoable change, clearing the redo stack.
+pub fn record(state: &mut AppState) {
+    state.undo_stack.push(state.results.clone());
+    if state.undo_stack.len() > HISTORY_LIMIT {
+        state.undo_stack.remove(0);
+    }
+    state.redo_stack.clear();
+}
+
+/// Revert the most recent change.
+pub fn undo(state: &mut AppState) {
+    match state.undo_stack.pop() {
+        Some(previous) => {
+            let current = restore(state, previous);
+            state.redo_stack.push(current);
+            state.status_message = Some("Undone".to_string());
+        }
+        None => state.status_message = Some("Nothing to undo".to_string()),
+    }
+}
+
+/// Re-apply the most recently undone change.
+pub fn redo(state: &mut AppState) {
+    match state.redo_stack.pop() {
+        Some(next) => {
+            let current = restore(state, next);
+            state.undo_stack.push(current);
+            state.status_message = Some("Redone".to_string());
+        }
+        None => state.status_message = Some("Nothing to redo".to_string()),
+    }
+}
+
+/// Swap in a snapshot, carrying sessions, time spent, command logs, and the
+/// status audit trail over, and return the replaced results.
+fn restore(state: &mut AppState, mut snapshot: TestlistResults) -> TestlistResults {
+    snapshot.meta.sessions = state.results.meta.sessions.clone();
+    let now = chrono::Utc::now().to_rfc3339();
+    let tester = state.results.current_tester().to_string();
+    for result in &mut snapshot.results {
+        if let Some(current) = state
+            .results
+            .results
+            .iter()
+            .find(|r| r.test_id == result.test_id)
+        {
+            // Time moved into an attempt by a retest moves back out on undo
+            let attempts_secs = |r: &crate::data::results::TestResult| {
+                r.attempts.iter().map(|a| a.time_spent_secs).sum::<u64>()
+            };
+            result.time_spent_secs = (current.time_spent_secs + attempts_secs(current))
+                .saturating_sub(attempts_secs(result));
+            result.command_runs = current.command_runs.clone();
+            result.command_history = current.command_history.clone();
+            // The audit trail keeps undone changes and records the undo itself
+            let status = std::mem::replace(&mut result.status, current.status);
+            let (severity, review) = (result.severity, result.review.take());
+            result.status_history = current.status_history.clone();
+            result.change_status(status, &now, &tester);
+            result.severity = severity;
+            result.review = review;
+        }
+    }
+    state.dirty = true;
+    std::mem::replace(&mut state.results, snapshot)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::Test;
+    use crate::data::results::Status;
+    use crate::fixtures;
+    use crate::transforms::tests::set_status;
+
+    fn make_state() -> AppState {
+        fixtures::state(fixtures::testlist(vec![Test {
+            id: "t1".to_string(),
+            title: "Test 1".to_string(),
+            ..Default::default()
+        }]))
+    }
+
+    #[test]
+    fn test_undo_restores_status_and_timestamp() {
+        let mut state = make_state();
+        set_status(&mut state, Status::Passed);
+        let passed_at = state.results.results[0].completed_at.clone();
+        set_status(&mut state, Status::Failed);
+        undo(&mut state);
+        assert_eq!(state.results.results[0].status, Status::Passed);
+        assert_eq!(state.results.results[0].completed_at, passed_at);
+        undo(&mut state);
+        assert_eq!(state.results.results[0].status, Status::Pending);
+        undo(&mut state);
+        assert_eq!(state.status_message.as_deref(), Some("Nothing to undo"));
+    }
+
+    #[test]
+    fn test_redo_reapplies_and_new_change_clears_redo() {
+        let mut state = make_state();
+        set_status(&mut state, Status::Failed);
+        undo(&mut state);
+        redo(&mut state);
+        assert_eq!(state.results.results[0].status, Status::Failed);
+        undo(&mut state);
+        set_status(&mut state, Status::Skipped);
+        redo(&mut state);
+        assert_eq!(state.results.results[0].status, Status::Skipped);
+        assert_eq!(state.status_message.as_deref(), Some("Nothing to redo"));
+    }
+
+    #[test]
+    fn test_undo_keeps_time_spent() {
+        let mut state = make_state();
+        set_status(&mut state, Status::Failed);
+        state.results.results[0].time_spent_secs = 42;
+        undo(&mut state);
+        assert_eq!(state.results.results[0].status, Status::Pending);
+        assert_eq!(state.results.results[0].time_spent_secs, 42);
+    }
+
+    #[test]
+    fn test_undo_keeps_command_history() {
+        let mut state = make_state();
+        set_status(&mut state, Status::Failed);
+        state.results.results[0]
+            .command_history
+            .push("make test".to_string());
+        undo(&mut state);
+        assert_eq!(state.results.results[0].command_history, vec!["make test"]);
+    }
+
+    #[test]
+    fn test_undo_is_recorded_in_status_history() {
+        let mut state = make_state();
+        set_status(&mut state, Status::Failed);
+        undo(&mut state);
+        let trail: Vec<(Status, Status)> = state.results.results[0]
+            .status_history
+            .iter()
+            .map(|c| (c.from, c.to))
+            .collect();
+        assert_eq!(
+            trail,
+            vec![
+                (Status::Pending, Status::Failed),
+                (Status::Failed, Status::Pending)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut state = make_state();
+        for _ in 0..HISTORY_LIMIT + 10 {
+            record(&mut state);
+        }
+        assert_eq!(state.undo_stack.len(), HISTORY_LIMIT);
+    }
+}
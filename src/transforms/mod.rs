@@ -1,5 +1,10 @@
 //! Transform layer: pure functions that mutate targeted fields of AppState.
 
+pub mod filter;
+pub mod history;
 pub mod navigation;
+pub mod search;
+pub mod terminal;
 pub mod tests;
+pub mod timer;
 pub mod ui;
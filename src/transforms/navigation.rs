@@ -1,19 +1,129 @@
 //! Transforms for navigation within the tests pane.
 
 use crate::data::state::AppState;
-use crate::queries::tests::selected_line_number;
+use crate::data::state::PaneEntry;
+use crate::queries::tests::{
+    find_test, first_open_test, is_in_focus, is_test_visible, pane_entries, pane_line_count,
+    selected_entry, selected_line_number,
+};
 
-/// Navigate down in the tests pane — always moves between test headers.
+/// Navigate down in the tests pane — always moves between visible headers.
 pub fn select_next(state: &mut AppState) {
-    if state.selected_test < state.testlist.tests.len().saturating_sub(1) {
-        state.selected_test += 1;
+    let entries = pane_entries(state);
+    match entries.iter().position(|e| *e == selected_entry(state)) {
+        Some(pos) => {
+            if let Some(next) = entries.get(pos + 1) {
+                select_entry(state, next.clone());
+            }
+        }
+        None => ensure_selection_visible(state),
     }
 }
 
-/// Navigate up in the tests pane — always moves between test headers.
+/// Navigate up in the tests pane — always moves between visible headers.
 pub fn select_prev(state: &mut AppState) {
-    if state.selected_test > 0 {
-        state.selected_test -= 1;
+    let entries = pane_entries(state);
+    match entries.iter().position(|e| *e == selected_entry(state)) {
+        Some(pos) if pos > 0 => select_entry(state, entries[pos - 1].clone()),
+        Some(_) => {}
+        None => ensure_selection_visible(state),
+    }
+}
+
+/// Put the cursor on a pane entry.
+pub fn select_entry(state: &mut AppState, entry: PaneEntry) {
+    match entry {
+        PaneEntry::Section(title) => state.selected_section = Some(title),
+        PaneEntry::Test(i) => {
+            state.selected_section = None;
+            state.selected_test = i;
+        }
+    }
+}
+
+/// Select a test, expanding its section if it is collapsed.
+pub fn reveal_test(state: &mut AppState, index: usize) {
+    if let Some(section) = state
+        .testlist
+        .tests
+        .get(index)
+        .and_then(|t| t.section.as_ref())
+    {
+        state.collapsed_sections.remove(section);
+    }
+    select_entry(state, PaneEntry::Test(index));
+}
+
+/// Select the first test still to be done when continuing a session,
+/// optionally expanding it. Nothing changes if every test is finished.
+pub fn resume(state: &mut AppState, expand: bool) {
+    let Some(index) = first_open_test(state) else {
+        return;
+    };
+    reveal_test(state, index);
+    if expand {
+        let id = state.testlist.tests[index].id.clone();
+        state.expanded_tests.insert(id);
+    }
+}
+
+/// Start the jump-to-test prompt.
+pub fn start_goto(state: &mut AppState) {
+    state.goto_active = true;
+    state.goto_input.clear();
+}
+
+/// Cancel the jump-to-test prompt.
+pub fn cancel_goto(state: &mut AppState) {
+    state.goto_active = false;
+    state.goto_input.clear();
+}
+
+/// Jump to the test named in the prompt and expand it.
+///
+/// A tag filter that hides the target is cleared so the jump always lands.
+pub fn confirm_goto(state: &mut AppState) {
+    match find_test(state, &state.goto_input) {
+        Some(index) if !is_in_focus(state, &state.testlist.tests[index]) => {
+            state.status_message = Some(format!(
+                "'{}' is not one of the --only tests",
+                state.testlist.tests[index].id
+            ));
+        }
+        Some(index) => {
+            reveal_test(state, index);
+            if !is_test_visible(state, &state.testlist.tests[index]) {
+                state.tag_filter = None;
+            }
+            let id = state.testlist.tests[index].id.clone();
+            state.expanded_tests.insert(id);
+        }
+        None => {
+            state.status_message = Some(format!("No test '{}'", state.goto_input.trim()));
+        }
+    }
+    state.goto_active = false;
+    state.goto_input.clear();
+}
+
+/// Move the cursor to a visible entry if the current one is hidden.
+///
+/// A test hidden inside a collapsed section moves the cursor to that
+/// section's header; otherwise the first visible entry is selected.
+pub fn ensure_selection_visible(state: &mut AppState) {
+    let entries = pane_entries(state);
+    if entries.contains(&selected_entry(state)) {
+        return;
+    }
+    let section_header = state
+        .testlist
+        .tests
+        .get(state.selected_test)
+        .and_then(|t| t.section.clone())
+        .map(PaneEntry::Section)
+        .filter(|e| state.selected_section.is_none() && entries.contains(e));
+    if let Some(entry) = section_header.or_else(|| entries.first().cloned()) {
+        select_entry(state, entry);
     }
 }
 
@@ -29,54 +139,84 @@ pub fn adjust_scroll(state: &mut AppState) {
     }
 }
 
+/// Scroll the tests pane by `delta` rows without moving the selection.
+pub fn scroll_tests(state: &mut AppState, delta: isize) {
+    let max = pane_line_count(state).saturating_sub(state.tests_visible_height);
+    state.tests_scroll_offset = state
+        .tests_scroll_offset
+        .saturating_add_signed(delta)
+        .min(max);
+}
+
+/// Scroll the notes view by `delta` rows, where `max` is the largest
+/// offset that still fills the pane.
+pub fn scroll_notes(state: &mut AppState, delta: isize, max: usize) {
+    state.notes_scroll = state.notes_scroll.saturating_add_signed(delta).min(max);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data::definition::{ChecklistItem, Meta, Test, Testlist};
-    use crate::data::results::TestlistResults;
+    use crate::data::definition::{ChecklistItem, Test};
+    use crate::data::results::{Status, TestlistResults};
+    use crate::fixtures;
 
     fn make_state() -> AppState {
-        let testlist = Testlist {
-            meta: Meta {
-                title: "Test".to_string(),
+        fixtures::state(fixtures::testlist(vec![
+            Test {
+                id: "t1".to_string(),
+                title: "Test 1".to_string(),
+                description: "".to_string(),
+                setup: vec![ChecklistItem {
+                    id: "s0".to_string(),
+                    text: "Step".to_string(),
+                }],
+                action: "Do it".to_string(),
+                verify: vec![ChecklistItem {
+                    id: "v0".to_string(),
+                    text: "Check".to_string(),
+                }],
+                suggested_command: None,
+                tags: vec!["smoke".to_string()],
+                ..Default::default()
+            },
+            Test {
+                id: "t2".to_string(),
+                title: "Test 2".to_string(),
                 description: "".to_string(),
-                created: "".to_string(),
-                version: "1".to_string(),
+                setup: vec![],
+                action: "Do it".to_string(),
+                verify: vec![],
+                suggested_command: None,
+                ..Default::default()
             },
-            tests: vec![
-                Test {
-                    id: "t1".to_string(),
-                    title: "Test 1".to_string(),
-                    description: "".to_string(),
-                    setup: vec![ChecklistItem {
-                        id: "s0".to_string(),
-                        text: "Step".to_string(),
-                    }],
-                    action: "Do it".to_string(),
-                    verify: vec![ChecklistItem {
-                        id: "v0".to_string(),
-                        text: "Check".to_string(),
-                    }],
-                    suggested_command: None,
-                },
-                Test {
-                    id: "t2".to_string(),
-                    title: "Test 2".to_string(),
-                    description: "".to_string(),
-                    setup: vec![],
-                    action: "Do it".to_string(),
-                    verify: vec![],
-                    suggested_command: None,
-                },
-            ],
-        };
-        let results = TestlistResults::new_for_testlist(&testlist, "test.ron", "tester");
-        AppState::new(
-            testlist,
-            results,
-            std::path::PathBuf::from("test.testlist.ron"),
-            std::path::PathBuf::from("test.testlist.results.ron"),
-        )
+        ]))
+    }
+
+    #[test]
+    fn test_scroll_tests_keeps_selection() {
+        let mut state = make_state();
+        state.expanded_tests.insert("t1".to_string());
+        state.tests_visible_height = 2;
+        scroll_tests(&mut state, 3);
+        let max = pane_line_count(&state) - 2;
+        assert_eq!(state.tests_scroll_offset, 3.min(max));
+        scroll_tests(&mut state, 100);
+        assert_eq!(state.tests_scroll_offset, max);
+        scroll_tests(&mut state, -100);
+        assert_eq!(state.tests_scroll_offset, 0);
+        assert_eq!(state.selected_test, 0);
+    }
+
+    #[test]
+    fn test_scroll_notes_is_clamped() {
+        let mut state = make_state();
+        scroll_notes(&mut state, 3, 5);
+        assert_eq!(state.notes_scroll, 3);
+        scroll_notes(&mut state, 3, 5);
+        assert_eq!(state.notes_scroll, 5);
+        scroll_notes(&mut state, -9, 5);
+        assert_eq!(state.notes_scroll, 0);
     }
 
     #[test]
@@ -112,4 +252,98 @@ mod tests {
         select_prev(&mut state);
         assert_eq!(state.selected_test, 0);
     }
+
+    #[test]
+    fn test_select_next_skips_filtered_tests() {
+        let mut state = make_state();
+        state.testlist.tests.push(Test {
+            id: "t3".to_string(),
+            title: "Test 3".to_string(),
+            action: "Do it".to_string(),
+            tags: vec!["smoke".to_string()],
+            ..Default::default()
+        });
+        state.results = TestlistResults::new_for_testlist(&state.testlist, "test.ron", "tester");
+        state.tag_filter = Some("smoke".to_string());
+        select_next(&mut state);
+        assert_eq!(state.selected_test, 2);
+        select_prev(&mut state);
+        assert_eq!(state.selected_test, 0);
+    }
+
+    #[test]
+    fn test_ensure_selection_visible() {
+        let mut state = make_state();
+        state.selected_test = 1;
+        state.tag_filter = Some("smoke".to_string());
+        ensure_selection_visible(&mut state);
+        assert_eq!(state.selected_test, 0);
+    }
+
+    #[test]
+    fn test_navigation_stops_on_section_headers() {
+        let mut state = make_state();
+        state.testlist.tests[1].section = Some("Auth".to_string());
+        select_next(&mut state);
+        assert_eq!(state.selected_section, Some("Auth".to_string()));
+        select_next(&mut state);
+        assert_eq!(state.selected_section, None);
+        assert_eq!(state.selected_test, 1);
+        select_prev(&mut state);
+        assert_eq!(state.selected_section, Some("Auth".to_string()));
+    }
+
+    #[test]
+    fn test_collapsed_section_moves_selection_to_header() {
+        let mut state = make_state();
+        state.testlist.tests[1].section = Some("Auth".to_string());
+        state.selected_test = 1;
+        state.collapsed_sections.insert("Auth".to_string());
+        ensure_selection_visible(&mut state);
+        assert_eq!(state.selected_section, Some("Auth".to_string()));
+    }
+
+    #[test]
+    fn test_goto_expands_and_reveals_test() {
+        let mut state = make_state();
+        state.testlist.tests[1].section = Some("Auth".to_string());
+        state.collapsed_sections.insert("Auth".to_string());
+        state.tag_filter = Some("smoke".to_string());
+        start_goto(&mut state);
+        state.goto_input = "t2".to_string();
+        confirm_goto(&mut state);
+        assert_eq!(state.selected_test, 1);
+        assert_eq!(state.selected_section, None);
+        assert!(state.expanded_tests.contains("t2"));
+        assert!(!state.collapsed_sections.contains("Auth"));
+        assert_eq!(state.tag_filter, None);
+        assert!(!state.goto_active);
+    }
+
+    #[test]
+    fn test_resume_selects_first_open_test() {
+        let mut state = make_state();
+        state.testlist.tests[1].section = Some("Auth".to_string());
+        state.collapsed_sections.insert("Auth".to_string());
+        state.results.results[0].status = Status::Passed;
+        resume(&mut state, true);
+        assert_eq!(state.selected_test, 1);
+        assert!(state.expanded_tests.contains("t2"));
+        assert!(!state.collapsed_sections.contains("Auth"));
+
+        state.results.results[1].status = Status::Skipped;
+        state.selected_test = 0;
+        resume(&mut state, false);
+        assert_eq!(state.selected_test, 0);
+    }
+
+    #[test]
+    fn test_goto_unknown_test_reports_status() {
+        let mut state = make_state();
+        start_goto(&mut state);
+        state.goto_input = "nope".to_string();
+        confirm_goto(&mut state);
+        assert_eq!(state.selected_test, 0);
+        assert!(state.status_message.is_some());
+    }
 }
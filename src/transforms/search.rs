@@ -0,0 +1,199 @@
+//! Transforms for incremental search in the tests pane.
+
+use crate::data::state::{AppState, PaneEntry};
+use crate::queries::tests::{display_order, search_matches, selected_entry};
+use crate::transforms::navigation::{reveal_test, select_entry};
+
+/// Start typing a new search, remembering the selection to restore on cancel.
+pub fn start_search(state: &mut AppState) {
+    state.searching = true;
+    state.search_query.clear();
+    state.search_origin = Some(selected_entry(state));
+}
+
+/// Append a character to the query and jump to the first match.
+pub fn search_push(state: &mut AppState, c: char) {
+    state.search_query.push(c);
+    jump_to_first_match(state);
+}
+
+/// Remove the last character of the query and jump to the first match.
+pub fn search_pop(state: &mut AppState) {
+    state.search_query.pop();
+    jump_to_first_match(state);
+}
+
+/// Finish typing, keeping the query active for `n`/`N`.
+pub fn confirm_search(state: &mut AppState) {
+    state.searching = false;
+    state.search_origin = None;
+    if search_matches(state).is_empty() && !state.search_query.is_empty() {
+        state.status_message = Some(format!("No tests match '{}'", state.search_query));
+        state.search_query.clear();
+    }
+}
+
+/// Abandon the search and restore the original selection.
+pub fn cancel_search(state: &mut AppState) {
+    state.searching = false;
+    state.search_query.clear();
+    if let Some(origin) = state.search_origin.take() {
+        select_entry(state, origin);
+    }
+}
+
+/// Clear the active query.
+pub fn clear_search(state: &mut AppState) {
+    state.search_query.clear();
+}
+
+/// Jump to the next match after the selection, wrapping around.
+pub fn next_match(state: &mut AppState) {
+    step_match(state, true);
+}
+
+/// Jump to the previous match before the selection, wrapping around.
+pub fn prev_match(state: &mut AppState) {
+    step_match(state, false);
+}
+
+fn jump_to_first_match(state: &mut AppState) {
+    // Like vim, each keystroke searches forward from where the search started
+    let matches = search_matches(state);
+    let order = display_order(state);
+    let position_of = |test: usize| order.iter().position(|&i| i == test);
+    let start = match state.search_origin {
+        Some(PaneEntry::Test(i)) => position_of(i),
+        _ => None,
+    };
+    let target = matches
+        .iter()
+        .find(|&&m| position_of(m) >= start)
+        .or_else(|| matches.first());
+    match target {
+        Some(&index) => reveal_test(state, index),
+        None => {
+            if let Some(origin) = state.search_origin.clone() {
+                select_entry(state, origin);
+            }
+        }
+    }
+}
+
+fn step_match(state: &mut AppState, forward: bool) {
+    let matches = search_matches(state);
+    if matches.is_empty() {
+        return;
+    }
+    let order = display_order(state);
+    let position_of = |test: usize| order.iter().position(|&i| i == test);
+    let current = if state.selected_section.is_none() {
+        position_of(state.selected_test)
+    } else {
+        None
+    };
+    let target = if forward {
+        matches
+            .iter()
+            .find(|&&m| position_of(m) > current)
+            .or_else(|| matches.first())
+    } else {
+        matches
+            .iter()
+            .rev()
+            .find(|&&m| current.is_none() || position_of(m) < current)
+            .or_else(|| matches.last())
+    };
+    if let Some(&index) = target {
+        reveal_test(state, index);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::Test;
+    use crate::fixtures;
+
+    fn make_state() -> AppState {
+        let test = |id: &str, title: &str| Test {
+            id: id.to_string(),
+            title: title.to_string(),
+            ..Default::default()
+        };
+        fixtures::state(fixtures::testlist(vec![
+            test("build", "Build project"),
+            test("login", "Login flow"),
+            test("logout", "Logout flow"),
+            test("docs", "Build docs"),
+        ]))
+    }
+
+    #[test]
+    fn test_incremental_search_jumps_while_typing() {
+        let mut state = make_state();
+        start_search(&mut state);
+        search_push(&mut state, 'l');
+        search_push(&mut state, 'o');
+        assert_eq!(state.selected_test, 1);
+        search_push(&mut state, 'g');
+        search_push(&mut state, 'o');
+        assert_eq!(state.selected_test, 2);
+        search_pop(&mut state);
+        assert_eq!(state.selected_test, 1);
+    }
+
+    #[test]
+    fn test_cancel_search_restores_selection() {
+        let mut state = make_state();
+        state.selected_test = 3;
+        start_search(&mut state);
+        search_push(&mut state, 'g');
+        assert_eq!(state.selected_test, 1);
+        cancel_search(&mut state);
+        assert_eq!(state.selected_test, 3);
+        assert!(state.search_query.is_empty());
+        assert!(!state.searching);
+    }
+
+    #[test]
+    fn test_next_and_prev_match_wrap() {
+        let mut state = make_state();
+        start_search(&mut state);
+        for c in "build".chars() {
+            search_push(&mut state, c);
+        }
+        confirm_search(&mut state);
+        assert_eq!(state.selected_test, 0);
+        next_match(&mut state);
+        assert_eq!(state.selected_test, 3);
+        next_match(&mut state);
+        assert_eq!(state.selected_test, 0);
+        prev_match(&mut state);
+        assert_eq!(state.selected_test, 3);
+    }
+
+    #[test]
+    fn test_confirm_without_matches_clears_query() {
+        let mut state = make_state();
+        start_search(&mut state);
+        search_push(&mut state, 'z');
+        confirm_search(&mut state);
+        assert!(state.search_query.is_empty());
+        assert!(state.status_message.is_some());
+    }
+
+    #[test]
+    fn test_search_expands_collapsed_section() {
+        let mut state = make_state();
+        state.testlist.tests[2].section = Some("Auth".to_string());
+        state.collapsed_sections.insert("Auth".to_string());
+        start_search(&mut state);
+        for c in "logout".chars() {
+            search_push(&mut state, c);
+        }
+        assert_eq!(state.selected_test, 2);
+        assert!(state.selected_section.is_none());
+        assert!(!state.collapsed_sections.contains("Auth"));
+    }
+}
@@ -0,0 +1,108 @@
+//! Transforms tracking command lines entered in the embedded terminal.
+//!
+//! Keystrokes are mirrored into `state.terminal_line` so that on Enter the
+//! line can be logged against the selected test. Keys that edit the line in
+//! ways we can't follow (history recall, cursor movement) mark it as edited,
+//! in which case the caller supplies the cursor row of the screen instead.
+
+use crate::data::state::AppState;
+use crate::queries::tests::current_test;
+
+/// Mirror text typed or pasted into the terminal.
+pub fn terminal_type(state: &mut AppState, text: &str) {
+    state.terminal_line.push_str(text);
+}
+
+/// Mirror a backspace in the terminal.
+pub fn terminal_backspace(state: &mut AppState) {
+    state.terminal_line.pop();
+}
+
+/// Forget the current line, e.g. after Ctrl-c.
+pub fn terminal_discard_line(state: &mut AppState) {
+    state.terminal_line.clear();
+    state.terminal_line_edited = false;
+}
+
+/// Note that the line was changed by a key we can't mirror.
+pub fn terminal_mark_edited(state: &mut AppState) {
+    state.terminal_line_edited = true;
+}
+
+/// Log the line on Enter. `screen_line` is the cursor row of the terminal,
+/// used when the mirrored line can't be trusted.
+pub fn terminal_submit(state: &mut AppState, screen_line: &str) {
+    let line = if state.terminal_line_edited {
+        screen_line.to_string()
+    } else {
+        std::mem::take(&mut state.terminal_line)
+    };
+    record_command(state, &line);
+    terminal_discard_line(state);
+}
+
+/// Append a command line to the selected test's command history.
+pub fn record_command(state: &mut AppState, command: &str) {
+    let command = command.trim();
+    if command.is_empty() {
+        return;
+    }
+    let Some(test_id) = current_test(state).map(|t| t.id.clone()) else {
+        return;
+    };
+    if let Some(result) = state.results.get_result_mut(&test_id) {
+        result.command_history.push(command.to_string());
+        state.dirty = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::Test;
+    use crate::fixtures;
+
+    fn make_state() -> AppState {
+        let test = |id: &str| Test {
+            id: id.to_string(),
+            title: id.to_string(),
+            ..Default::default()
+        };
+        fixtures::state(fixtures::testlist(vec![test("build"), test("login")]))
+    }
+
+    #[test]
+    fn test_typed_line_is_logged_for_selected_test() {
+        let mut state = make_state();
+        terminal_type(&mut state, "make tesx");
+        terminal_backspace(&mut state);
+        terminal_type(&mut state, "t");
+        terminal_submit(&mut state, "$ ignored");
+        assert_eq!(state.results.results[0].command_history, vec!["make test"]);
+        assert!(state.results.results[1].command_history.is_empty());
+        assert!(state.terminal_line.is_empty());
+        assert!(state.dirty);
+    }
+
+    #[test]
+    fn test_edited_line_uses_screen_row() {
+        let mut state = make_state();
+        state.selected_test = 1;
+        terminal_type(&mut state, "ls");
+        terminal_mark_edited(&mut state);
+        terminal_submit(&mut state, "$ ls -la   ");
+        assert_eq!(state.results.results[1].command_history, vec!["$ ls -la"]);
+        assert!(!state.terminal_line_edited);
+    }
+
+    #[test]
+    fn test_empty_and_discarded_lines_are_not_logged() {
+        let mut state = make_state();
+        terminal_submit(&mut state, "$");
+        terminal_type(&mut state, "rm -rf build");
+        terminal_discard_line(&mut state);
+        terminal_submit(&mut state, "$");
+        assert!(state.results.results[0].command_history.is_empty());
+        assert!(!state.dirty);
+    }
+}
@@ -1,59 +1,558 @@
 //! Transforms for test status.
 
-use crate::data::results::Status;
-use crate::data::state::AppState;
-use crate::queries::tests::current_test;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::data::definition::Testlist;
+use crate::data::effect::Effect;
+use crate::data::results::{
+    checklist_key, Attempt, ChecklistSection, CommandRun, Severity, Status,
+};
+use crate::data::script::{apply_actions, ScriptAction};
+use crate::data::state::{AppState, FailureCapture, RunningCommand};
+use crate::queries::checklist::checks_required;
+use crate::queries::tests::{
+    current_result, current_test, missing_capture_detail, note_required, result_for_test,
+    selected_entry, severity_required, unmet_dependencies, visual_selection,
+};
+use crate::transforms::{history, navigation};
 
 /// Set the status of the currently selected test.
+///
+/// Marking a test whose dependencies have not passed is allowed, but leaves
+/// a warning in the status bar. When checks are required, a test with
+/// unchecked verify items can't be marked Passed.
 pub fn set_status(state: &mut AppState, status: Status) {
-    let test_id = match current_test(state) {
-        Some(t) => t.id.clone(),
+    let (test_id, unmet) = match current_test(state) {
+        Some(t) => (
+            t.id.clone(),
+            unmet_dependencies(&state.testlist, &state.results, t).join(", "),
+        ),
         None => return,
     };
+    if status == Status::Passed && !may_pass(state, state.selected_test) {
+        let left = state
+            .results
+            .unchecked_verify_count(&state.testlist.tests[state.selected_test]);
+        state.status_message = Some(format!(
+            "Check all verify items before passing ({} left)",
+            left
+        ));
+        return;
+    }
+    history::record(state);
+    if apply_status(state, &test_id, status) && !unmet.is_empty() && status != Status::Pending {
+        state.status_message = Some(format!("Warning: waiting on unmet dependencies: {}", unmet));
+    }
+}
+
+/// Open the failure dialog for the selected test, which asks for the
+/// reason, severity, defect link, and screenshot before marking it Failed.
+pub fn mark_failed(state: &mut AppState) {
+    start_failure_capture(state, Status::Failed);
+}
+
+/// Mark the selected test Inconclusive, or open the failure dialog if a
+/// note is required.
+pub fn mark_inconclusive(state: &mut AppState) {
+    if note_required(state, Status::Inconclusive) {
+        start_failure_capture(state, Status::Inconclusive);
+    } else {
+        set_status(state, Status::Inconclusive);
+    }
+}
+
+/// Open the failure dialog for marking the selected test with `status`
+/// (Failed or Inconclusive); the status is only set once it's confirmed.
+pub fn start_failure_capture(state: &mut AppState, status: Status) {
+    if current_test(state).is_some() {
+        state.failure_capture = Some(FailureCapture::new(status));
+    }
+}
+
+/// Close the failure dialog, leaving the status unchanged.
+pub fn cancel_failure_capture(state: &mut AppState) {
+    state.failure_capture = None;
+}
+
+/// Move the failure dialog's focus to the next field, or the previous one
+/// with `back`.
+pub fn cycle_capture_field(state: &mut AppState, back: bool) {
+    let Some(capture) = state.failure_capture.as_mut() else {
+        return;
+    };
+    let fields = capture.fields();
+    let i = fields.iter().position(|&f| f == capture.field).unwrap_or(0);
+    let next = if back { i + fields.len() - 1 } else { i + 1 };
+    capture.field = fields[next % fields.len()];
+}
+
+/// Choose the severity in the failure dialog.
+pub fn set_capture_severity(state: &mut AppState, severity: Severity) {
+    if let Some(capture) = state.failure_capture.as_mut() {
+        capture.severity = Some(severity);
+    }
+}
+
+/// Mark the selected test with the failure dialog's status, adding its
+/// note to the notes and setting its severity, defect link, and screenshot.
+/// A required note or severity that is missing keeps the dialog open.
+///
+/// The screenshot path is stored as entered; the caller checks that the
+/// file exists.
+pub fn confirm_failure_capture(state: &mut AppState) {
+    let Some(capture) = state.failure_capture.clone() else {
+        return;
+    };
+    if let Some(missing) = missing_capture_detail(state, &capture) {
+        state.status_message = Some(format!(
+            "{:?} needs {} for this testlist",
+            capture.status, missing
+        ));
+        return;
+    }
+    state.failure_capture = None;
+    let Some(test_id) = current_test(state).map(|t| t.id.clone()) else {
+        return;
+    };
+    set_status(state, capture.status);
+    if let Some(result) = state.results.get_result_mut(&test_id) {
+        let note = capture.note.trim();
+        if !note.is_empty() {
+            result.append_note(note);
+        }
+        if capture.status == Status::Failed {
+            result.severity = capture.severity;
+            let defect = capture.defect.trim();
+            if !defect.is_empty() && !result.defects.iter().any(|d| d == defect) {
+                result.defects.push(defect.to_string());
+            }
+        }
+        if !capture.screenshot.is_empty() {
+            result.screenshots.push(PathBuf::from(&capture.screenshot));
+        }
+        state.dirty = true;
+    }
+}
+
+/// Start entering why the selected test is blocked.
+pub fn start_blocked_reason(state: &mut AppState) {
+    if current_test(state).is_some() {
+        state.adding_blocked_reason = true;
+        state.blocked_reason_input.clear();
+    }
+}
+
+/// Cancel blocked reason input, leaving the status unchanged.
+pub fn cancel_blocked_reason(state: &mut AppState) {
+    state.adding_blocked_reason = false;
+    state.blocked_reason_input.clear();
+}
+
+/// Mark the selected test Blocked with the entered reason; an empty input
+/// gives no reason. A test already blocked gets the new reason.
+pub fn confirm_blocked_reason(state: &mut AppState) {
+    let reason = state.blocked_reason_input.trim().to_string();
+    cancel_blocked_reason(state);
+    let Some(test_id) = current_test(state).map(|t| t.id.clone()) else {
+        return;
+    };
+    set_status(state, Status::Blocked);
+    if let Some(result) = state.results.get_result_mut(&test_id) {
+        result.blocked_reason = (!reason.is_empty()).then_some(reason);
+        state.dirty = true;
+    }
+}
+
+/// Wait for the user to pick one of the testlist's custom statuses for the
+/// selected test.
+pub fn start_custom_status(state: &mut AppState) {
+    if current_test(state).is_none() {
+        return;
+    }
+    if state.testlist.statuses.is_empty() {
+        state.status_message = Some("This testlist declares no custom statuses".to_string());
+        return;
+    }
+    state.choosing_custom_status = true;
+}
+
+/// Give the selected test the `n`th custom status, counting from 1, ending
+/// the choice. A number without a status just ends it.
+pub fn choose_custom_status(state: &mut AppState, n: usize) {
+    state.choosing_custom_status = false;
+    let Some(def) = n
+        .checked_sub(1)
+        .and_then(|i| state.testlist.statuses.get(i))
+        .cloned()
+    else {
+        return;
+    };
+    let Some(test_id) = current_test(state).map(|t| t.id.clone()) else {
+        return;
+    };
+    history::record(state);
+    state.dirty |= state.results.set_custom_status(&test_id, &def);
+}
+
+/// Mark every open test (Pending, Retest, or In progress) as Skipped, for
+/// wrapping up a session.
+///
+/// Blocked tests and tests in an open custom status keep their status. With
+/// `--only`, tests outside the focus are left alone.
+pub fn skip_remaining(state: &mut AppState) {
+    history::record(state);
+    let now = chrono::Utc::now().to_rfc3339();
+    let tester = state.results.current_tester().to_string();
+    let only = state.only_tests.clone();
+    let mut skipped = 0;
+    for result in &mut state.results.results {
+        let in_focus = only
+            .as_ref()
+            .is_none_or(|ids| ids.contains(&result.test_id));
+        let remaining = matches!(
+            result.status,
+            Status::Pending | Status::Retest | Status::InProgress
+        );
+        if in_focus && remaining {
+            result.change_status(Status::Skipped, &now, &tester);
+            result.completed_at = Some(now.clone());
+            skipped += 1;
+        }
+    }
+    if skipped > 0 {
+        state.dirty = true;
+    }
+    state.status_message = Some(format!(
+        "Skipped {} remaining test{}",
+        skipped,
+        if skipped == 1 { "" } else { "s" }
+    ));
+}
+
+/// Reset the selected test to Pending for a retest, clearing its checklist.
+///
+/// Notes, screenshots, and time spent are kept.
+pub fn reset_test(state: &mut AppState) {
+    let Some(test_id) = current_test(state).map(|t| t.id.clone()) else {
+        return;
+    };
+    history::record(state);
+    let tester = state.results.current_tester().to_string();
+    if let Some(result) = state.results.get_result_mut(&test_id) {
+        result.change_status(Status::Pending, &chrono::Utc::now().to_rfc3339(), &tester);
+        result.completed_at = None;
+        state.results.clear_checklist(&test_id);
+        state.dirty = true;
+    }
+}
+
+/// Put a failed or inconclusive test back up for testing.
+///
+/// The finished attempt (status, notes, severity, and time spent) is moved
+/// to the test's `attempts`, the checklist is cleared, and the status
+/// becomes Retest. Linked defects and screenshots are kept.
+pub fn mark_for_retest(state: &mut AppState) {
+    let Some(test_id) = current_test(state).map(|t| t.id.clone()) else {
+        return;
+    };
+    let status = current_result(state).map(|r| r.status);
+    if !matches!(status, Some(Status::Failed | Status::Inconclusive)) {
+        state.status_message =
+            Some("Only failed or inconclusive tests can be retested".to_string());
+        return;
+    }
+    history::record(state);
+    let tester = state.results.current_tester().to_string();
     if let Some(result) = state.results.get_result_mut(&test_id) {
-        result.status = status;
-        result.completed_at = Some(chrono::Utc::now().to_rfc3339());
+        result.attempts.push(Attempt {
+            status: result.status,
+            notes: result.notes.take(),
+            severity: result.severity,
+            time_spent_secs: std::mem::take(&mut result.time_spent_secs),
+            completed_at: result.completed_at.take(),
+        });
+        let attempt = result.attempts.len() + 1;
+        result.change_status(Status::Retest, &chrono::Utc::now().to_rfc3339(), &tester);
+        state.results.clear_checklist(&test_id);
+        state.dirty = true;
+        state.status_message = Some(format!("Marked for retest (attempt {})", attempt));
+    }
+}
+
+/// Advance the selected test's status: Pending, Passed, Failed,
+/// Inconclusive, Skipped, and back to Pending. Tests awaiting retest, in
+/// progress, blocked, or in a custom status go to Passed. Failed and
+/// Inconclusive open the failure dialog when a note or severity is required.
+pub fn cycle_status(state: &mut AppState) {
+    let Some(status) = current_result(state).map(|r| r.status) else {
+        return;
+    };
+    let next = match status {
+        Status::Pending => Status::Passed,
+        Status::Passed => Status::Failed,
+        Status::Failed => Status::Inconclusive,
+        Status::Inconclusive => Status::Skipped,
+        Status::Skipped => Status::Pending,
+        Status::Retest | Status::InProgress | Status::Blocked | Status::Custom => Status::Passed,
+    };
+    if note_required(state, next) || severity_required(state, next) {
+        start_failure_capture(state, next);
+        return;
+    }
+    set_status(state, next);
+    if next == Status::Pending {
+        if let Some(test_id) = current_test(state).map(|t| t.id.clone()) {
+            if let Some(result) = state.results.get_result_mut(&test_id) {
+                result.completed_at = None;
+            }
+        }
+    }
+}
+
+/// Check or uncheck a setup or verify item of a test.
+///
+/// Checking the last unchecked verify item of an open test offers to mark
+/// it Passed.
+pub fn toggle_checklist_item(
+    state: &mut AppState,
+    test_index: usize,
+    section: ChecklistSection,
+    item_index: usize,
+) {
+    let Some(test) = state.testlist.tests.get(test_index) else {
+        return;
+    };
+    let items = match section {
+        ChecklistSection::Setup => &test.setup,
+        ChecklistSection::Verify => &test.verify,
+    };
+    let Some(item) = items.get(item_index) else {
+        return;
+    };
+    let key = checklist_key(&test.id, section, &item.id);
+    history::record(state);
+    let checked = state.results.checklist_results.entry(key).or_insert(false);
+    *checked = !*checked;
+    let now_checked = *checked;
+    state.dirty = true;
+
+    let test = &state.testlist.tests[test_index];
+    state.suggesting_pass = section == ChecklistSection::Verify
+        && now_checked
+        && state.results.unchecked_verify_count(test) == 0
+        && result_for_test(&state.results, &test.id).is_some_and(|r| r.is_open(&state.testlist));
+}
+
+/// Mark the selected test Passed as suggested once its checks were done.
+pub fn accept_suggested_pass(state: &mut AppState) {
+    state.suggesting_pass = false;
+    set_status(state, Status::Passed);
+}
+
+/// Leave the test's status as it is.
+pub fn dismiss_suggested_pass(state: &mut AppState) {
+    state.suggesting_pass = false;
+}
+
+/// Start running the current test's suggested command in the terminal.
+pub fn request_run_command(state: &mut AppState) -> Option<Effect> {
+    if state.running_command.is_some() {
+        state.status_message = Some("A command is already running".to_string());
+        return None;
+    }
+    let test = current_test(state)?;
+    let command = test.suggested_command.clone()?;
+    state.running_command = Some(RunningCommand {
+        test_id: test.id.clone(),
+        command: command.clone(),
+        started: chrono::Utc::now().to_rfc3339(),
+        started_at: Instant::now(),
+    });
+    Some(Effect::RunTerminalCommand(command))
+}
+
+/// Record the outcome of the running command in its test's result.
+pub fn finish_command_run(state: &mut AppState, exit_code: i32, output_tail: String) {
+    let Some(running) = state.running_command.take() else {
+        return;
+    };
+    if let Some(result) = state.results.get_result_mut(&running.test_id) {
+        result.command_runs.push(CommandRun {
+            command: running.command.clone(),
+            exit_code,
+            started: running.started,
+            finished: chrono::Utc::now().to_rfc3339(),
+            output_tail,
+        });
+        state.dirty = true;
+    }
+    state.status_message = Some(format!(
+        "`{}` exited with code {}",
+        running.command, exit_code
+    ));
+}
+
+/// Apply the changes a test script asked for, reporting them in the status bar.
+pub fn apply_script_actions(state: &mut AppState, actions: &[ScriptAction]) {
+    if actions.is_empty() {
+        return;
+    }
+    history::record(state);
+    let require_checks = checks_required(state);
+    let applied = apply_actions(&state.testlist, &mut state.results, actions, require_checks);
+    if applied > 0 {
+        state.dirty = true;
+    }
+    state.status_message = Some(format!(
+        "Script applied {} change{}",
+        applied,
+        if applied == 1 { "" } else { "s" }
+    ));
+}
+
+/// Start visual mode at the cursor, or leave it if already active.
+pub fn toggle_visual(state: &mut AppState) {
+    state.visual_anchor = match state.visual_anchor {
+        Some(_) => None,
+        None => Some(selected_entry(state)),
+    };
+}
+
+/// Leave visual mode without changing anything.
+pub fn cancel_visual(state: &mut AppState) {
+    state.visual_anchor = None;
+}
+
+/// Set the status of every test in the visual-mode range, then leave visual mode.
+///
+/// When checks are required, tests with unchecked verify items are left
+/// out of a Pass. Failed and Inconclusive are refused when they need a note
+/// or severity, since each test needs its own.
+pub fn set_status_for_selection(state: &mut AppState, status: Status) {
+    if note_required(state, status) || severity_required(state, status) {
+        state.status_message = Some(format!(
+            "{:?} needs details for each test; mark them one at a time",
+            status
+        ));
+        return;
+    }
+    let (ids, unchecked): (Vec<usize>, Vec<usize>) = visual_selection(state)
+        .into_iter()
+        .partition(|&i| status != Status::Passed || may_pass(state, i));
+    let ids: Vec<String> = ids
+        .into_iter()
+        .map(|i| state.testlist.tests[i].id.clone())
+        .collect();
+    history::record(state);
+    let marked = ids
+        .iter()
+        .filter(|id| apply_status(state, id, status))
+        .count();
+    state.visual_anchor = None;
+    let mut message = format!(
+        "Marked {} test{} {:?}",
+        marked,
+        if marked == 1 { "" } else { "s" },
+        status
+    );
+    if !unchecked.is_empty() {
+        message.push_str(&format!(
+            "; {} with unchecked verify items left alone",
+            unchecked.len()
+        ));
+    }
+    state.status_message = Some(message);
+}
+
+/// Check whether the test at `index` may be marked Passed: always, unless
+/// checks are required and some of its verify items are unchecked.
+fn may_pass(state: &AppState, index: usize) -> bool {
+    state
+        .results
+        .may_pass(&state.testlist.tests[index], checks_required(state))
+}
+
+/// Swap in a testlist that changed on disk.
+///
+/// Results are kept and new tests get pending results; the cursor stays on
+/// the selected test if it still exists. Undo history is dropped because its
+/// snapshots predate the new tests.
+pub fn replace_testlist(state: &mut AppState, testlist: Testlist) {
+    let selected_id = state
+        .testlist
+        .tests
+        .get(state.selected_test)
+        .map(|t| t.id.clone());
+    let is_new = |old: &Testlist, id: &str| !old.tests.iter().any(|t| t.id == id);
+    let added = testlist
+        .tests
+        .iter()
+        .filter(|t| is_new(&state.testlist, &t.id))
+        .count();
+    let removed = state
+        .testlist
+        .tests
+        .iter()
+        .filter(|t| is_new(&testlist, &t.id))
+        .count();
+
+    state.results.reconcile(&testlist);
+    let sha256 = testlist.sha256().map(str::to_string);
+    if state.results.meta.testlist_sha256 != sha256 {
+        state.results.meta.testlist_sha256 = sha256;
+        state.dirty = true;
+    }
+    state.testlist = testlist;
+    state.selected_test = selected_id
+        .and_then(|id| state.testlist.tests.iter().position(|t| t.id == id))
+        .unwrap_or(0);
+    state.visual_anchor = None;
+    state.search_origin = None;
+    state.undo_stack.clear();
+    state.redo_stack.clear();
+    navigation::ensure_selection_visible(state);
+    if added > 0 {
         state.dirty = true;
     }
+    state.status_message = Some(format!(
+        "Reloaded testlist: {} added, {} removed",
+        added, removed
+    ));
+}
+
+fn apply_status(state: &mut AppState, test_id: &str, status: Status) -> bool {
+    let applied = state.results.set_status(test_id, status);
+    state.dirty |= applied;
+    applied
 }
 
 #[cfg(test)]
 mod tests_mod {
     use super::*;
-    use crate::data::definition::{ChecklistItem, Meta, Test, Testlist};
+    use crate::data::definition::{ChecklistItem, StatusDef, Test};
     use crate::data::results::TestlistResults;
+    use crate::data::state::CaptureField;
+    use crate::fixtures;
+    use crate::queries::tests::result_for_test;
 
     fn make_state() -> AppState {
-        let testlist = Testlist {
-            meta: Meta {
-                title: "Test".to_string(),
-                description: "".to_string(),
-                created: "".to_string(),
-                version: "1".to_string(),
-            },
-            tests: vec![Test {
-                id: "t1".to_string(),
-                title: "Test 1".to_string(),
-                description: "".to_string(),
-                setup: vec![ChecklistItem {
-                    id: "s0".to_string(),
-                    text: "Step".to_string(),
-                }],
-                action: "Do it".to_string(),
-                verify: vec![ChecklistItem {
-                    id: "v0".to_string(),
-                    text: "Check".to_string(),
-                }],
-                suggested_command: None,
+        fixtures::state(fixtures::testlist(vec![Test {
+            id: "t1".to_string(),
+            title: "Test 1".to_string(),
+            description: "".to_string(),
+            setup: vec![ChecklistItem {
+                id: "s0".to_string(),
+                text: "Step".to_string(),
+            }],
+            action: "Do it".to_string(),
+            verify: vec![ChecklistItem {
+                id: "v0".to_string(),
+                text: "Check".to_string(),
             }],
-        };
-        let results = TestlistResults::new_for_testlist(&testlist, "test.ron", "tester");
-        AppState::new(
-            testlist,
-            results,
-            std::path::PathBuf::from("test.testlist.ron"),
-            std::path::PathBuf::from("test.testlist.results.ron"),
-        )
+            suggested_command: None,
+            ..Default::default()
+        }]))
     }
 
     #[test]
@@ -64,4 +563,408 @@ mod tests_mod {
         assert!(state.results.results[0].completed_at.is_some());
         assert!(state.dirty);
     }
+
+    #[test]
+    fn test_checking_last_verify_item_suggests_pass() {
+        let mut state = make_state();
+        toggle_checklist_item(&mut state, 0, ChecklistSection::Setup, 0);
+        assert!(!state.suggesting_pass);
+        toggle_checklist_item(&mut state, 0, ChecklistSection::Verify, 0);
+        assert!(state.suggesting_pass);
+        accept_suggested_pass(&mut state);
+        assert!(!state.suggesting_pass);
+        assert_eq!(state.results.results[0].status, Status::Passed);
+
+        // Unchecking and checking again doesn't ask once the test passed
+        toggle_checklist_item(&mut state, 0, ChecklistSection::Verify, 0);
+        toggle_checklist_item(&mut state, 0, ChecklistSection::Verify, 0);
+        assert!(!state.suggesting_pass);
+    }
+
+    #[test]
+    fn test_blocked_reason() {
+        let mut state = make_state();
+        start_blocked_reason(&mut state);
+        assert!(state.adding_blocked_reason);
+        state.blocked_reason_input = " staging is down ".to_string();
+        confirm_blocked_reason(&mut state);
+        assert!(!state.adding_blocked_reason);
+        let result = &state.results.results[0];
+        assert_eq!(result.status, Status::Blocked);
+        assert_eq!(result.blocked_reason.as_deref(), Some("staging is down"));
+        assert!(result.completed_at.is_none());
+
+        set_status(&mut state, Status::InProgress);
+        assert_eq!(state.results.results[0].blocked_reason, None);
+        assert!(state.results.results[0].status.is_open());
+    }
+
+    #[test]
+    fn test_choose_custom_status() {
+        let mut state = make_state();
+        start_custom_status(&mut state);
+        assert!(!state.choosing_custom_status);
+
+        state.testlist.statuses = vec![StatusDef {
+            name: "Passed with deviation".to_string(),
+            icon: "≈".to_string(),
+            color: None,
+            counts_as_complete: true,
+        }];
+        start_custom_status(&mut state);
+        assert!(state.choosing_custom_status);
+        choose_custom_status(&mut state, 1);
+        assert!(!state.choosing_custom_status);
+        let result = &state.results.results[0];
+        assert_eq!(result.status, Status::Custom);
+        assert_eq!(result.status_name(), "Passed with deviation");
+        assert!(result.completed_at.is_some());
+        assert!(state.dirty);
+
+        set_status(&mut state, Status::Failed);
+        assert_eq!(state.results.results[0].custom_status, None);
+    }
+
+    #[test]
+    fn test_set_status_requires_checks() {
+        let mut state = make_state();
+        state.testlist.meta.require_checks = true;
+        set_status(&mut state, Status::Passed);
+        assert_eq!(state.results.results[0].status, Status::Pending);
+        assert!(state.status_message.as_ref().unwrap().contains("1 left"));
+
+        set_status(&mut state, Status::Failed);
+        assert_eq!(state.results.results[0].status, Status::Failed);
+
+        state
+            .results
+            .checklist_results
+            .insert(checklist_key("t1", ChecklistSection::Verify, "v0"), true);
+        set_status(&mut state, Status::Passed);
+        assert_eq!(state.results.results[0].status, Status::Passed);
+    }
+
+    #[test]
+    fn test_failure_capture_requires_note_and_severity() {
+        let mut state = make_state();
+        state.testlist.meta.require_failure_notes = true;
+        state.require_severity = true;
+        mark_failed(&mut state);
+        assert_eq!(state.results.results[0].status, Status::Pending);
+        assert!(state.failure_capture.is_some());
+
+        confirm_failure_capture(&mut state);
+        assert!(state.status_message.as_ref().unwrap().contains("a note"));
+        state.failure_capture.as_mut().unwrap().note = "Crashes on save".to_string();
+        confirm_failure_capture(&mut state);
+        assert!(state
+            .status_message
+            .as_ref()
+            .unwrap()
+            .contains("a severity"));
+        assert_eq!(state.results.results[0].status, Status::Pending);
+
+        cycle_capture_field(&mut state, false);
+        assert_eq!(
+            state.failure_capture.as_ref().unwrap().field,
+            CaptureField::Severity
+        );
+        set_capture_severity(&mut state, Severity::Major);
+        confirm_failure_capture(&mut state);
+        assert!(state.failure_capture.is_none());
+        let result = &state.results.results[0];
+        assert_eq!(result.status, Status::Failed);
+        assert_eq!(result.severity, Some(Severity::Major));
+        assert!(result.notes.as_deref().unwrap().contains("Crashes on save"));
+
+        mark_inconclusive(&mut state);
+        assert_eq!(
+            state.failure_capture.as_ref().unwrap().fields(),
+            &[CaptureField::Note, CaptureField::Screenshot]
+        );
+        cancel_failure_capture(&mut state);
+        assert_eq!(state.results.results[0].status, Status::Failed);
+    }
+
+    #[test]
+    fn test_set_status_on_waiting_test_warns() {
+        let mut state = make_state();
+        state.testlist.tests[0].depends_on = vec!["other".to_string()];
+        set_status(&mut state, Status::Passed);
+        assert_eq!(state.results.results[0].status, Status::Passed);
+        assert!(state.status_message.as_deref().unwrap().contains("other"));
+    }
+
+    #[test]
+    fn test_visual_mode_marks_range() {
+        let mut state = make_state();
+        for n in 2..=4 {
+            state.testlist.tests.push(Test {
+                id: format!("t{}", n),
+                title: format!("Test {}", n),
+                ..Default::default()
+            });
+        }
+        state.results = TestlistResults::new_for_testlist(&state.testlist, "test.ron", "tester");
+        state.selected_test = 1;
+        toggle_visual(&mut state);
+        state.selected_test = 2;
+        set_status_for_selection(&mut state, Status::Skipped);
+
+        let statuses: Vec<Status> = state.results.results.iter().map(|r| r.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                Status::Pending,
+                Status::Skipped,
+                Status::Skipped,
+                Status::Pending
+            ]
+        );
+        assert!(state.visual_anchor.is_none());
+        assert!(state.dirty);
+    }
+
+    #[test]
+    fn test_toggle_visual_leaves_mode() {
+        let mut state = make_state();
+        toggle_visual(&mut state);
+        assert!(state.visual_anchor.is_some());
+        toggle_visual(&mut state);
+        assert!(state.visual_anchor.is_none());
+        toggle_visual(&mut state);
+        cancel_visual(&mut state);
+        assert!(state.visual_anchor.is_none());
+    }
+
+    #[test]
+    fn test_skip_remaining_only_touches_pending() {
+        let mut state = make_state();
+        state.testlist.tests.push(Test {
+            id: "t2".to_string(),
+            ..Default::default()
+        });
+        state.results = TestlistResults::new_for_testlist(&state.testlist, "test.ron", "tester");
+        set_status(&mut state, Status::Failed);
+        skip_remaining(&mut state);
+        assert_eq!(state.results.results[0].status, Status::Failed);
+        assert_eq!(state.results.results[1].status, Status::Skipped);
+        assert!(state.results.results[1].completed_at.is_some());
+    }
+
+    #[test]
+    fn test_skip_remaining_respects_focus() {
+        let mut state = make_state();
+        state.testlist.tests.push(Test {
+            id: "t2".to_string(),
+            ..Default::default()
+        });
+        state.results = TestlistResults::new_for_testlist(&state.testlist, "test.ron", "tester");
+        state.only_tests = Some(vec!["t2".to_string()]);
+        skip_remaining(&mut state);
+        assert_eq!(state.results.results[0].status, Status::Pending);
+        assert_eq!(state.results.results[1].status, Status::Skipped);
+    }
+
+    #[test]
+    fn test_reset_test_clears_status_and_checklist() {
+        let mut state = make_state();
+        set_status(&mut state, Status::Failed);
+        state.results.results[0].notes = Some("flaky".to_string());
+        state
+            .results
+            .checklist_results
+            .insert("t1:setup:s0".to_string(), true);
+        reset_test(&mut state);
+        let result = &state.results.results[0];
+        assert_eq!(result.status, Status::Pending);
+        assert!(result.completed_at.is_none());
+        assert_eq!(result.notes.as_deref(), Some("flaky"));
+        assert!(state.results.checklist_results.is_empty());
+    }
+
+    #[test]
+    fn test_run_command_records_exit_code() {
+        let mut state = make_state();
+        state.testlist.tests[0].suggested_command = Some("make check".to_string());
+        assert_eq!(
+            request_run_command(&mut state),
+            Some(Effect::RunTerminalCommand("make check".to_string()))
+        );
+        assert_eq!(request_run_command(&mut state), None);
+
+        finish_command_run(&mut state, 2, "error: boom".to_string());
+        let run = &state.results.results[0].command_runs[0];
+        assert_eq!(run.command, "make check");
+        assert_eq!(run.exit_code, 2);
+        assert_eq!(run.output_tail, "error: boom");
+        assert!(state.running_command.is_none());
+        assert!(state.dirty);
+    }
+
+    #[test]
+    fn test_run_command_without_suggestion() {
+        let mut state = make_state();
+        assert_eq!(request_run_command(&mut state), None);
+        assert!(state.running_command.is_none());
+    }
+
+    #[test]
+    fn test_cycle_status_wraps_to_pending() {
+        let mut state = make_state();
+        for expected in [
+            Status::Passed,
+            Status::Failed,
+            Status::Inconclusive,
+            Status::Skipped,
+        ] {
+            cycle_status(&mut state);
+            assert_eq!(state.results.results[0].status, expected);
+        }
+        cycle_status(&mut state);
+        assert_eq!(state.results.results[0].status, Status::Pending);
+        assert_eq!(state.results.results[0].completed_at, None);
+    }
+
+    #[test]
+    fn test_toggle_checklist_item() {
+        let mut state = make_state();
+        let key = checklist_key("t1", ChecklistSection::Verify, "v0");
+        toggle_checklist_item(&mut state, 0, ChecklistSection::Verify, 0);
+        assert_eq!(state.results.checklist_results.get(&key), Some(&true));
+        assert!(state.dirty);
+        toggle_checklist_item(&mut state, 0, ChecklistSection::Verify, 0);
+        assert_eq!(state.results.checklist_results.get(&key), Some(&false));
+        toggle_checklist_item(&mut state, 0, ChecklistSection::Verify, 5);
+        assert_eq!(state.undo_stack.len(), 2);
+    }
+
+    #[test]
+    fn test_failure_capture() {
+        let mut state = make_state();
+        mark_failed(&mut state);
+        let capture = state.failure_capture.as_mut().unwrap();
+        assert_eq!(capture.fields().len(), 4);
+        capture.note = "Crashes on save".to_string();
+        capture.severity = Some(Severity::Major);
+        capture.defect = "BUG-7".to_string();
+        capture.screenshot = "crash.png".to_string();
+        confirm_failure_capture(&mut state);
+        assert!(state.failure_capture.is_none());
+        assert_eq!(state.undo_stack.len(), 1);
+        let result = &state.results.results[0];
+        assert_eq!(result.status, Status::Failed);
+        assert_eq!(result.notes.as_deref(), Some("Crashes on save"));
+        assert_eq!(result.severity, Some(Severity::Major));
+        assert_eq!(result.defects, vec!["BUG-7"]);
+        assert_eq!(result.screenshots, vec![PathBuf::from("crash.png")]);
+
+        set_status(&mut state, Status::Passed);
+        assert_eq!(state.results.results[0].severity, None);
+    }
+
+    #[test]
+    fn test_mark_for_retest_keeps_attempt() {
+        let mut state = make_state();
+        mark_for_retest(&mut state);
+        assert_eq!(state.results.results[0].status, Status::Pending);
+        assert!(state.undo_stack.is_empty());
+
+        mark_failed(&mut state);
+        set_capture_severity(&mut state, Severity::Major);
+        confirm_failure_capture(&mut state);
+        toggle_checklist_item(&mut state, 0, ChecklistSection::Verify, 0);
+        let result = &mut state.results.results[0];
+        result.notes = Some("crashed".to_string());
+        result.time_spent_secs = 120;
+        result.defects.push("BUG-1".to_string());
+
+        mark_for_retest(&mut state);
+        let result = &state.results.results[0];
+        assert_eq!(result.status, Status::Retest);
+        assert_eq!((result.notes.as_deref(), result.time_spent_secs), (None, 0));
+        assert_eq!(result.completed_at, None);
+        assert_eq!(result.defects, vec!["BUG-1"]);
+        assert_eq!(result.attempts.len(), 1);
+        let attempt = &result.attempts[0];
+        assert_eq!(attempt.status, Status::Failed);
+        assert_eq!(attempt.notes.as_deref(), Some("crashed"));
+        assert_eq!(attempt.severity, Some(Severity::Major));
+        assert_eq!(attempt.time_spent_secs, 120);
+        assert!(state.results.checklist_results.values().all(|&v| !v));
+
+        cycle_status(&mut state);
+        assert_eq!(state.results.results[0].status, Status::Passed);
+    }
+
+    #[test]
+    fn test_undo_retest_restores_time_spent() {
+        let mut state = make_state();
+        set_status(&mut state, Status::Failed);
+        state.results.results[0].time_spent_secs = 120;
+        mark_for_retest(&mut state);
+        state.results.results[0].time_spent_secs = 5;
+        history::undo(&mut state);
+        let result = &state.results.results[0];
+        assert_eq!(result.status, Status::Failed);
+        assert!(result.attempts.is_empty());
+        assert_eq!(result.time_spent_secs, 125);
+    }
+
+    #[test]
+    fn test_status_changes_are_audited() {
+        let mut state = make_state();
+        state.results.start_session("carol", None);
+        set_status(&mut state, Status::Failed);
+        set_status(&mut state, Status::Passed);
+        reset_test(&mut state);
+        let trail: Vec<(Status, Status, &str)> = state.results.results[0]
+            .status_history
+            .iter()
+            .map(|c| (c.from, c.to, c.tester.as_str()))
+            .collect();
+        assert_eq!(
+            trail,
+            vec![
+                (Status::Pending, Status::Failed, "carol"),
+                (Status::Failed, Status::Passed, "carol"),
+                (Status::Passed, Status::Pending, "carol"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_replace_testlist_keeps_results_and_selection() {
+        let mut state = make_state();
+        set_status(&mut state, Status::Passed);
+        let mut testlist = state.testlist.clone();
+        testlist.tests[0].title = "Renamed".to_string();
+        testlist.tests.insert(
+            0,
+            Test {
+                id: "t0".to_string(),
+                title: "New first".to_string(),
+                ..Default::default()
+            },
+        );
+        testlist.source_sha256 = Some("edited".to_string());
+
+        replace_testlist(&mut state, testlist);
+        assert_eq!(state.selected_test, 1);
+        assert_eq!(current_test(&state).unwrap().title, "Renamed");
+        assert_eq!(current_result(&state).unwrap().status, Status::Passed);
+        assert_eq!(
+            result_for_test(&state.results, "t0").unwrap().status,
+            Status::Pending
+        );
+        assert!(state.undo_stack.is_empty());
+        assert_eq!(
+            state.results.meta.testlist_sha256.as_deref(),
+            Some("edited")
+        );
+        assert_eq!(
+            state.status_message.as_deref(),
+            Some("Reloaded testlist: 1 added, 0 removed")
+        );
+    }
 }
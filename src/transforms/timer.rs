@@ -0,0 +1,93 @@
+//! Transforms for tracking time spent on each test.
+
+use std::time::{Duration, Instant};
+
+use crate::data::state::AppState;
+use crate::queries::tests::current_test;
+
+/// Credit elapsed time to the test being timed and follow selection changes.
+///
+/// Called every loop iteration with the current time. Whole seconds are
+/// added to the timed test's `time_spent_secs`; the fractional remainder
+/// carries over until the selection changes.
+pub fn tick_timer(state: &mut AppState, now: Instant) {
+    credit_elapsed(state, now);
+
+    let current = current_test(state).map(|t| t.id.clone());
+    if current != state.timer_test_id {
+        state.timer_test_id = current;
+        state.timer_started = Some(now);
+    }
+}
+
+/// Stop timing, crediting any whole seconds still owed.
+pub fn stop_timer(state: &mut AppState, now: Instant) {
+    credit_elapsed(state, now);
+    state.timer_test_id = None;
+    state.timer_started = None;
+}
+
+fn credit_elapsed(state: &mut AppState, now: Instant) {
+    let (Some(test_id), Some(started)) = (state.timer_test_id.clone(), state.timer_started) else {
+        return;
+    };
+    let secs = now.saturating_duration_since(started).as_secs();
+    if secs == 0 {
+        return;
+    }
+    if let Some(result) = state.results.get_result_mut(&test_id) {
+        result.time_spent_secs += secs;
+    }
+    state.timer_started = Some(started + Duration::from_secs(secs));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::Test;
+    use crate::fixtures;
+
+    fn make_state() -> AppState {
+        fixtures::state(fixtures::testlist(vec![
+            Test {
+                id: "t1".to_string(),
+                ..Default::default()
+            },
+            Test {
+                id: "t2".to_string(),
+                ..Default::default()
+            },
+        ]))
+    }
+
+    #[test]
+    fn test_timer_credits_selected_test() {
+        let mut state = make_state();
+        let start = Instant::now();
+        tick_timer(&mut state, start);
+        assert_eq!(state.timer_test_id, Some("t1".to_string()));
+
+        tick_timer(&mut state, start + Duration::from_millis(2500));
+        assert_eq!(state.results.results[0].time_spent_secs, 2);
+
+        // Fractional remainder carries over
+        tick_timer(&mut state, start + Duration::from_millis(3000));
+        assert_eq!(state.results.results[0].time_spent_secs, 3);
+        assert!(!state.dirty);
+    }
+
+    #[test]
+    fn test_timer_follows_selection() {
+        let mut state = make_state();
+        let start = Instant::now();
+        tick_timer(&mut state, start);
+        state.selected_test = 1;
+        tick_timer(&mut state, start + Duration::from_secs(4));
+        assert_eq!(state.results.results[0].time_spent_secs, 4);
+        assert_eq!(state.timer_test_id, Some("t2".to_string()));
+
+        stop_timer(&mut state, start + Duration::from_secs(7));
+        assert_eq!(state.results.results[1].time_spent_secs, 3);
+        assert!(state.timer_test_id.is_none());
+    }
+}
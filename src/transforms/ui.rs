@@ -1,17 +1,117 @@
 //! Transforms for UI state changes.
 
-use crate::data::state::{AppState, FocusedPane};
-use crate::queries::tests::current_result;
+use std::time::SystemTime;
+
+use crate::actions::paths::common_prefix;
+use crate::data::config::PaneLayout;
+use crate::data::editor::TextEditor;
+use crate::data::effect::Effect;
+use crate::data::results::{parse_key_value, Review, Status, TestlistResults, Verdict};
+use crate::data::state::{AppState, CaptureField, FocusedPane, PaneBorder};
+use crate::data::theme::Theme;
+use crate::queries::tests::{
+    current_result, current_test, note_templates, resolve_reference, result_for_test,
+    screenshot_path,
+};
+use crate::transforms::history;
 
 /// Cycle focus to the next pane.
+/// Hidden panes are skipped.
 pub fn cycle_focus(state: &mut AppState) {
     state.focused_pane = state.focused_pane.next();
+    while !pane_shown(state, state.focused_pane) {
+        state.focused_pane = state.focused_pane.next();
+    }
+    state.terminal_zoomed = false;
+}
+
+/// Check whether a pane is part of the layout. The tests pane always is.
+fn pane_shown(state: &AppState, pane: FocusedPane) -> bool {
+    match pane {
+        FocusedPane::Tests => true,
+        FocusedPane::Notes => state.layout.notes,
+        FocusedPane::Terminal => state.layout.terminal,
+    }
+}
+
+/// Switch to the next layout preset: full, tests+terminal, tests only.
+pub fn cycle_layout_preset(state: &mut AppState) -> Option<Effect> {
+    set_layout(state, state.layout.next_preset())
+}
+
+/// Show or hide the notes pane.
+pub fn toggle_notes_pane(state: &mut AppState) -> Option<Effect> {
+    let layout = PaneLayout {
+        notes: !state.layout.notes,
+        ..state.layout
+    };
+    set_layout(state, layout)
+}
+
+/// Show or hide the terminal pane.
+pub fn toggle_terminal_pane(state: &mut AppState) -> Option<Effect> {
+    let layout = PaneLayout {
+        terminal: !state.layout.terminal,
+        ..state.layout
+    };
+    set_layout(state, layout)
+}
+
+/// Apply a layout, moving focus off a pane that was hidden, and request
+/// that it be saved to the config file.
+fn set_layout(state: &mut AppState, layout: PaneLayout) -> Option<Effect> {
+    state.layout = layout;
+    if !pane_shown(state, state.focused_pane) {
+        state.focused_pane = FocusedPane::Tests;
+    }
+    state.status_message = Some(format!("Layout: {}", layout.label()));
+    Some(Effect::SaveLayout(layout))
+}
+
+/// Narrowest tests or notes pane, in percent of the width.
+pub const MIN_PANE_PERCENT: u16 = 20;
+
+/// Smallest terminal pane height, borders included.
+pub const MIN_TERMINAL_HEIGHT: u16 = 3;
+
+/// Start dragging a pane border.
+pub fn start_drag(state: &mut AppState, border: PaneBorder) {
+    state.dragging = Some(border);
+}
+
+/// Stop dragging.
+pub fn end_drag(state: &mut AppState) {
+    state.dragging = None;
+}
+
+/// Set the tests pane's share of the width, keeping both panes usable.
+pub fn set_tests_width(state: &mut AppState, percent: u16) {
+    state.tests_width_percent = percent.clamp(MIN_PANE_PERCENT, 100 - MIN_PANE_PERCENT);
+}
+
+/// Set the terminal height, leaving `max` rows at most.
+pub fn set_terminal_height(state: &mut AppState, rows: u16, max: u16) {
+    state.terminal_height = rows.min(max).max(MIN_TERMINAL_HEIGHT);
+}
+
+/// Toggle the terminal pane filling the screen, focusing it when zoomed.
+pub fn toggle_terminal_zoom(state: &mut AppState) {
+    state.terminal_zoomed = !state.terminal_zoomed;
+    if state.terminal_zoomed {
+        state.focused_pane = FocusedPane::Terminal;
+    }
+}
+
+/// Leave the terminal, restoring the normal layout.
+pub fn leave_terminal(state: &mut AppState) {
+    state.focused_pane = FocusedPane::Tests;
+    state.terminal_zoomed = false;
 }
 
 /// Enter notes editing mode.
 pub fn enter_notes_edit(state: &mut AppState) {
     if let Some(result) = current_result(state) {
-        state.notes_input = result.notes.clone().unwrap_or_default();
+        state.notes_input = TextEditor::from_text(result.notes.as_deref().unwrap_or_default());
         state.editing_notes = true;
         state.focused_pane = FocusedPane::Notes;
     }
@@ -19,26 +119,145 @@ pub fn enter_notes_edit(state: &mut AppState) {
 
 /// Save notes and exit editing mode.
 pub fn save_notes(state: &mut AppState) {
-    let notes = if state.notes_input.is_empty() {
-        None
-    } else {
-        Some(state.notes_input.clone())
-    };
+    let notes = state.notes_input.text();
     let test_id = state
         .testlist
         .tests
         .get(state.selected_test)
         .map(|t| t.id.clone());
     if let Some(test_id) = test_id {
-        if let Some(result) = state.results.get_result_mut(&test_id) {
-            result.notes = notes;
-            state.dirty = true;
-        }
+        set_notes(state, &test_id, &notes);
     }
     state.editing_notes = false;
     state.focused_pane = FocusedPane::Tests;
 }
 
+/// Insert a note template while writing notes or a failure's reason; with
+/// several templates, wait for the user to pick one.
+pub fn start_note_template(state: &mut AppState) {
+    match note_templates(state).as_slice() {
+        [] => {
+            state.status_message = Some(
+                "No note templates; add some under note_templates in the testlist or config"
+                    .to_string(),
+            )
+        }
+        [(_, text)] => {
+            let text = text.to_string();
+            insert_note_text(state, &text);
+        }
+        _ => state.choosing_note_template = true,
+    }
+}
+
+/// Insert the `n`th note template, counting from 1, ending the choice. A
+/// number without a template just ends it.
+pub fn choose_note_template(state: &mut AppState, n: usize) {
+    state.choosing_note_template = false;
+    let text = n.checked_sub(1).and_then(|i| {
+        note_templates(state)
+            .get(i)
+            .map(|(_, text)| text.to_string())
+    });
+    if let Some(text) = text {
+        insert_note_text(state, &text);
+    }
+}
+
+/// Insert text at the notes editor's cursor, or at the end of the failure
+/// dialog's reason.
+fn insert_note_text(state: &mut AppState, text: &str) {
+    if let Some(capture) = state.failure_capture.as_mut() {
+        capture.field = CaptureField::Note;
+        capture.note.push_str(text);
+    } else if state.editing_notes {
+        state.notes_input.insert_str(text);
+    }
+}
+
+/// Request editing the current test's notes in the user's `$EDITOR`.
+pub fn request_external_notes_edit(state: &mut AppState) -> Option<Effect> {
+    let result = current_result(state)?;
+    Some(Effect::EditNotesExternally {
+        test_id: result.test_id.clone(),
+        notes: result.notes.clone().unwrap_or_default(),
+    })
+}
+
+/// Store notes written in the external editor.
+pub fn apply_external_notes(state: &mut AppState, test_id: &str, notes: &str) {
+    // Editors conventionally end files with a newline; don't keep it
+    set_notes(state, test_id, notes.trim_end_matches('\n'));
+}
+
+/// Append the last `lines` non-blank lines of terminal screen text to the
+/// current test's notes as a fenced code block.
+pub fn capture_terminal_output(state: &mut AppState, screen: &str, lines: usize) {
+    let rows: Vec<&str> = screen.lines().map(str::trim_end).collect();
+    let end = rows
+        .iter()
+        .rposition(|l| !l.is_empty())
+        .map_or(0, |i| i + 1);
+    let start = rows[..end]
+        .iter()
+        .position(|l| !l.is_empty())
+        .unwrap_or(end)
+        .max(end.saturating_sub(lines));
+    if start == end {
+        state.status_message = Some("Terminal is empty".to_string());
+        return;
+    }
+    if append_code_block(state, &rows[start..end].join("\n")) {
+        state.status_message = Some(format!("Captured {} lines into notes", end - start));
+    }
+}
+
+/// Append text copied in the terminal's copy mode to the current test's notes.
+pub fn yank_to_notes(state: &mut AppState, text: &str) {
+    if append_code_block(state, text) {
+        state.status_message = Some("Copied selection into notes".to_string());
+    }
+}
+
+/// Append `text` to the current test's notes as a fenced code block.
+/// Returns false when no test is selected.
+fn append_code_block(state: &mut AppState, text: &str) -> bool {
+    let Some(test_id) = current_test(state).map(|t| t.id.clone()) else {
+        return false;
+    };
+    let block = format!("```\n{}\n```", text);
+    let existing = state
+        .results
+        .results
+        .iter()
+        .find(|r| r.test_id == test_id)
+        .and_then(|r| r.notes.clone());
+    let notes = match existing {
+        Some(notes) => format!("{notes}\n\n{block}"),
+        None => block,
+    };
+    set_notes(state, &test_id, &notes);
+    true
+}
+
+/// Replace a test's notes, recording undo history when they change.
+/// Empty text clears the notes.
+fn set_notes(state: &mut AppState, test_id: &str, text: &str) {
+    let notes = (!text.is_empty()).then(|| text.to_string());
+    let changed = state
+        .results
+        .results
+        .iter()
+        .any(|r| r.test_id == test_id && r.notes != notes);
+    if changed {
+        history::record(state);
+    }
+    if let Some(result) = state.results.get_result_mut(test_id) {
+        result.notes = notes;
+        state.dirty = true;
+    }
+}
+
 /// Start adding a screenshot.
 pub fn start_screenshot(state: &mut AppState) {
     if state.testlist.tests.get(state.selected_test).is_some() {
@@ -52,6 +271,8 @@ pub fn start_screenshot(state: &mut AppState) {
 pub fn cancel_screenshot(state: &mut AppState) {
     state.adding_screenshot = false;
     state.screenshot_input.clear();
+    state.screenshot_completions.clear();
+    state.screenshot_error = None;
     state.focused_pane = FocusedPane::Tests;
 }
 
@@ -65,6 +286,7 @@ pub fn confirm_screenshot(state: &mut AppState) {
             .get(state.selected_test)
             .map(|t| t.id.clone());
         if let Some(test_id) = test_id {
+            history::record(state);
             if let Some(result) = state.results.get_result_mut(&test_id) {
                 result.screenshots.push(path);
                 state.dirty = true;
@@ -73,16 +295,269 @@ pub fn confirm_screenshot(state: &mut AppState) {
     }
     state.adding_screenshot = false;
     state.screenshot_input.clear();
+    state.screenshot_completions.clear();
+    state.screenshot_error = None;
     state.focused_pane = FocusedPane::Tests;
 }
 
-/// Toggle theme between dark and light.
+/// Apply tab completion to the screenshot path.
+///
+/// The input is extended to the candidates' common prefix; when several
+/// candidates remain they are listed for the user.
+pub fn complete_screenshot_input(state: &mut AppState, candidates: &[String]) {
+    state.screenshot_error = None;
+    if candidates.is_empty() {
+        state.screenshot_completions.clear();
+        state.screenshot_error = Some("No matching files".to_string());
+        return;
+    }
+    let prefix = common_prefix(candidates);
+    if prefix.len() > state.screenshot_input.len() {
+        state.screenshot_input = prefix;
+    }
+    state.screenshot_completions = if candidates.len() > 1 {
+        candidates.to_vec()
+    } else {
+        Vec::new()
+    };
+}
+
+/// Keep the screenshot prompt open with an error for a path that doesn't exist.
+pub fn reject_screenshot(state: &mut AppState) {
+    state.screenshot_error = Some(format!("File not found: {}", state.screenshot_input));
+}
+
+/// Request a screenshot capture for the current test.
+pub fn request_screenshot_capture(state: &mut AppState) -> Option<Effect> {
+    let test_id = current_test(state)?.id.clone();
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S").to_string();
+    let path = screenshot_path(state, &test_id, &timestamp);
+    Some(Effect::CaptureScreenshot { test_id, path })
+}
+
+/// Attach a captured screenshot to a test's result.
+pub fn attach_screenshot(state: &mut AppState, test_id: &str, path: std::path::PathBuf) {
+    history::record(state);
+    if let Some(result) = state.results.get_result_mut(test_id) {
+        state.status_message = Some(format!("Saved screenshot {}", path.display()));
+        result.screenshots.push(path);
+        state.dirty = true;
+    }
+}
+
+/// Start entering an environment `key=value` pair.
+pub fn start_env_input(state: &mut AppState) {
+    state.adding_env = true;
+    state.env_input.clear();
+}
+
+/// Cancel environment input.
+pub fn cancel_env_input(state: &mut AppState) {
+    state.adding_env = false;
+    state.env_input.clear();
+}
+
+/// Record the entered environment pair in the results metadata.
+pub fn confirm_env_input(state: &mut AppState) {
+    match parse_key_value(&state.env_input) {
+        Some((key, value)) => {
+            history::record(state);
+            state
+                .results
+                .meta
+                .environment
+                .get_or_insert_with(Default::default)
+                .set(&key, &value);
+            state.dirty = true;
+            state.status_message = Some(format!("Recorded environment: {}={}", key, value));
+        }
+        None => {
+            state.status_message = Some("Environment entries must be key=value".to_string());
+        }
+    }
+    state.adding_env = false;
+    state.env_input.clear();
+}
+
+/// Start entering a defect link for the selected test, if it failed.
+pub fn start_defect_input(state: &mut AppState) {
+    if current_result(state).is_some_and(|r| r.status == Status::Failed) {
+        state.adding_defect = true;
+        state.defect_input.clear();
+    } else {
+        state.status_message = Some("Defects can only be linked to failed tests".to_string());
+    }
+}
+
+/// Cancel defect input.
+pub fn cancel_defect_input(state: &mut AppState) {
+    state.adding_defect = false;
+    state.defect_input.clear();
+}
+
+/// Link the entered bug tracker ID or URL to the selected test.
+pub fn confirm_defect_input(state: &mut AppState) {
+    let defect = state.defect_input.trim().to_string();
+    state.adding_defect = false;
+    state.defect_input.clear();
+    let Some(test_id) = current_test(state).map(|t| t.id.clone()) else {
+        return;
+    };
+    link_defect(state, &test_id, &defect);
+}
+
+/// Link a bug tracker ID or URL to a test's result, unless it is empty or
+/// already linked.
+pub fn link_defect(state: &mut AppState, test_id: &str, defect: &str) {
+    let linked = result_for_test(&state.results, test_id)
+        .is_some_and(|r| r.defects.iter().any(|d| d == defect));
+    if defect.is_empty() || linked {
+        return;
+    }
+    history::record(state);
+    if let Some(result) = state.results.get_result_mut(test_id) {
+        result.defects.push(defect.to_string());
+        state.dirty = true;
+        state.status_message = Some(format!("Linked defect {}", defect));
+    }
+}
+
+/// Request filing a GitHub issue for the selected test, if it failed.
+pub fn request_issue(state: &mut AppState) -> Option<Effect> {
+    if !current_result(state).is_some_and(|r| r.status == Status::Failed) {
+        state.status_message = Some("Issues can only be filed for failed tests".to_string());
+        return None;
+    }
+    let test_id = current_test(state)?.id.clone();
+    Some(Effect::CreateIssue { test_id })
+}
+
+/// Start signing off the selected result in review mode; the comment is
+/// entered next.
+pub fn start_review(state: &mut AppState, verdict: Verdict) {
+    if state.reviewer.is_none() {
+        return;
+    }
+    if current_result(state).is_some_and(|r| !r.is_open(&state.testlist)) {
+        state.review_verdict = Some(verdict);
+        state.review_input.clear();
+    } else {
+        state.status_message = Some("Only finished results can be reviewed".to_string());
+    }
+}
+
+/// Cancel the sign-off.
+pub fn cancel_review_input(state: &mut AppState) {
+    state.review_verdict = None;
+    state.review_input.clear();
+}
+
+/// Record the verdict with the entered comment (optional) on the selected result.
+pub fn confirm_review_input(state: &mut AppState) {
+    let comment = state.review_input.trim().to_string();
+    state.review_input.clear();
+    let (Some(verdict), Some(reviewer)) = (state.review_verdict.take(), state.reviewer.clone())
+    else {
+        return;
+    };
+    let Some(test_id) = current_test(state).map(|t| t.id.clone()) else {
+        return;
+    };
+    history::record(state);
+    if let Some(result) = state.results.get_result_mut(&test_id) {
+        result.review = Some(Review {
+            verdict,
+            reviewer,
+            at: chrono::Utc::now().to_rfc3339(),
+            comment: (!comment.is_empty()).then_some(comment),
+        });
+        state.dirty = true;
+        state.status_message = Some(format!("{:?} {}", verdict, test_id));
+    }
+}
+
+/// Open a reference of the current test: directly if there is only one,
+/// otherwise wait for the user to pick one by number.
+pub fn request_open_reference(state: &mut AppState) -> Option<Effect> {
+    let references = current_test(state)?.references.clone();
+    match references.len() {
+        0 => {
+            state.status_message = Some("This test has no references".to_string());
+            None
+        }
+        1 => Some(Effect::OpenExternal(resolve_reference(
+            state,
+            &references[0],
+        ))),
+        _ => {
+            state.choosing_reference = true;
+            None
+        }
+    }
+}
+
+/// Pick the reference with the given 1-based number while choosing.
+pub fn choose_reference(state: &mut AppState, number: usize) -> Option<Effect> {
+    state.choosing_reference = false;
+    let reference = current_test(state)?
+        .references
+        .get(number.checked_sub(1)?)?
+        .clone();
+    Some(Effect::OpenExternal(resolve_reference(state, &reference)))
+}
+
+/// Stop waiting for a reference choice.
+pub fn cancel_reference_choice(state: &mut AppState) {
+    state.choosing_reference = false;
+}
+
+/// Wait for the user to pick what of the selected test to copy.
+pub fn start_copy(state: &mut AppState) {
+    state.choosing_copy = current_test(state).is_some();
+}
+
+/// Copy the selected test's ID (`i`), title (`t`), or suggested command
+/// (`c`), ending the choice. Any other key just ends it.
+pub fn choose_copy(state: &mut AppState, key: char) -> Option<Effect> {
+    state.choosing_copy = false;
+    let test = current_test(state)?;
+    let text = match key {
+        'i' => test.id.clone(),
+        't' => test.title.clone(),
+        'c' => match &test.suggested_command {
+            Some(command) => command.clone(),
+            None => {
+                state.status_message = Some("This test has no suggested command".to_string());
+                return None;
+            }
+        },
+        _ => return None,
+    };
+    Some(Effect::CopyToClipboard(text))
+}
+
+/// Cycle to the next available theme.
 pub fn toggle_theme(state: &mut AppState) {
-    state.theme = state.theme.toggle();
+    if let Some(theme) = state.theme.next_in(&state.themes) {
+        state.theme = theme;
+    }
 }
 
-/// Toggle expand/collapse on the currently selected test header.
+/// Make a user-defined theme the active theme, keeping the built-ins for cycling.
+pub fn set_user_theme(state: &mut AppState, theme: Theme) {
+    state.themes.retain(|t| *t != theme);
+    state.themes.insert(0, theme);
+    state.theme = theme;
+}
+
+/// Toggle expand/collapse on the currently selected test or section header.
 pub fn toggle_expand(state: &mut AppState) {
+    if let Some(title) = state.selected_section.clone() {
+        if !state.collapsed_sections.remove(&title) {
+            state.collapsed_sections.insert(title);
+        }
+        return;
+    }
     if let Some(test) = state.testlist.tests.get(state.selected_test) {
         let id = test.id.clone();
         if state.expanded_tests.contains(&id) {
@@ -93,9 +568,38 @@ pub fn toggle_expand(state: &mut AppState) {
     }
 }
 
+/// Expand (`true`) or collapse every test at once.
+///
+/// With a section header selected only that section's tests change, and
+/// expanding also opens the section. Otherwise expanding opens every section
+/// too, while collapsing leaves sections as they are.
+pub fn set_all_expanded(state: &mut AppState, expanded: bool) {
+    let section = state.selected_section.clone();
+    let ids: Vec<String> = state
+        .testlist
+        .tests
+        .iter()
+        .filter(|t| section.is_none() || t.section == section)
+        .map(|t| t.id.clone())
+        .collect();
+    if expanded {
+        match &section {
+            Some(title) => {
+                state.collapsed_sections.remove(title);
+            }
+            None => state.collapsed_sections.clear(),
+        }
+        state.expanded_tests.extend(ids);
+    } else {
+        for id in &ids {
+            state.expanded_tests.remove(id);
+        }
+    }
+}
+
 /// Request quit — shows confirmation if dirty.
 pub fn request_quit(state: &mut AppState) {
-    if state.dirty {
+    if state.dirty && !state.read_only {
         state.confirm_quit = true;
         state.quit_selection = 0;
     } else {
@@ -119,44 +623,68 @@ pub fn cancel_quit(state: &mut AppState) {
     state.confirm_quit = false;
 }
 
+/// Note the testlist file's modification time, offering a reload when it
+/// changed since the file was last seen.
+pub fn testlist_changed(state: &mut AppState, modified: Option<SystemTime>) {
+    if modified.is_some() && modified != state.testlist_modified {
+        state.confirm_reload = true;
+    }
+    state.testlist_modified = modified;
+}
+
+/// Swap in results reloaded from disk while watching a session.
+///
+/// The cursor and view are kept; undo history is dropped since it belongs
+/// to the replaced results.
+pub fn replace_results(state: &mut AppState, mut results: TestlistResults) {
+    results.reconcile(&state.testlist);
+    state.results = results;
+    state.undo_stack.clear();
+    state.redo_stack.clear();
+    state.status_message = Some(format!(
+        "Reloaded results at {}",
+        chrono::Local::now().format("%H:%M:%S")
+    ));
+}
+
+/// Reload the changed testlist (from dialog).
+pub fn confirm_reload(state: &mut AppState) -> Option<Effect> {
+    state.confirm_reload = false;
+    Some(Effect::ReloadTestlist)
+}
+
+/// Keep the loaded testlist (from dialog); the next change asks again.
+pub fn cancel_reload(state: &mut AppState) {
+    state.confirm_reload = false;
+}
+
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
     use super::*;
-    use crate::data::definition::{ChecklistItem, Meta, Test, Testlist};
-    use crate::data::results::{Status, TestlistResults};
+    use crate::data::definition::{ChecklistItem, Test};
+    use crate::fixtures;
+
     use crate::transforms::tests::set_status;
 
     fn make_state() -> AppState {
-        let testlist = Testlist {
-            meta: Meta {
-                title: "Test".to_string(),
-                description: "".to_string(),
-                created: "".to_string(),
-                version: "1".to_string(),
-            },
-            tests: vec![Test {
-                id: "t1".to_string(),
-                title: "Test 1".to_string(),
-                description: "".to_string(),
-                setup: vec![ChecklistItem {
-                    id: "s0".to_string(),
-                    text: "Step".to_string(),
-                }],
-                action: "Do it".to_string(),
-                verify: vec![ChecklistItem {
-                    id: "v0".to_string(),
-                    text: "Check".to_string(),
-                }],
-                suggested_command: None,
+        fixtures::state(fixtures::testlist(vec![Test {
+            id: "t1".to_string(),
+            title: "Test 1".to_string(),
+            description: "".to_string(),
+            setup: vec![ChecklistItem {
+                id: "s0".to_string(),
+                text: "Step".to_string(),
             }],
-        };
-        let results = TestlistResults::new_for_testlist(&testlist, "test.ron", "tester");
-        AppState::new(
-            testlist,
-            results,
-            std::path::PathBuf::from("test.testlist.ron"),
-            std::path::PathBuf::from("test.testlist.results.ron"),
-        )
+            action: "Do it".to_string(),
+            verify: vec![ChecklistItem {
+                id: "v0".to_string(),
+                text: "Check".to_string(),
+            }],
+            suggested_command: None,
+            ..Default::default()
+        }]))
     }
 
     // === Bug 1 verification tests ===
@@ -175,7 +703,7 @@ mod tests {
         assert!(state.editing_notes);
 
         // User types some notes
-        state.notes_input.push_str("looks good");
+        state.notes_input.insert_str("looks good");
 
         // User presses Esc to save
         save_notes(&mut state);
@@ -243,4 +771,456 @@ mod tests {
         // Screenshot was actually saved
         assert_eq!(state.results.results[0].screenshots.len(), 1);
     }
+
+    #[test]
+    fn test_toggle_expand_on_section_header() {
+        let mut state = make_state();
+        state.selected_section = Some("Auth".to_string());
+        toggle_expand(&mut state);
+        assert!(state.collapsed_sections.contains("Auth"));
+        assert!(state.expanded_tests.is_empty());
+        toggle_expand(&mut state);
+        assert!(!state.collapsed_sections.contains("Auth"));
+    }
+
+    #[test]
+    fn test_choose_copy() {
+        let mut state = make_state();
+        start_copy(&mut state);
+        assert!(state.choosing_copy);
+        assert_eq!(
+            choose_copy(&mut state, 't'),
+            Some(Effect::CopyToClipboard("Test 1".to_string()))
+        );
+        assert!(!state.choosing_copy);
+        assert_eq!(
+            choose_copy(&mut state, 'i'),
+            Some(Effect::CopyToClipboard("t1".to_string()))
+        );
+        assert_eq!(choose_copy(&mut state, 'c'), None);
+        assert!(state.status_message.is_some());
+        assert_eq!(choose_copy(&mut state, 'q'), None);
+    }
+
+    #[test]
+    fn test_set_all_expanded() {
+        let mut state = make_state();
+        let mut other = state.testlist.tests[0].clone();
+        other.id = "t2".to_string();
+        other.section = Some("Auth".to_string());
+        state.testlist.tests.push(other);
+        state.collapsed_sections.insert("Auth".to_string());
+
+        state.selected_section = Some("Auth".to_string());
+        set_all_expanded(&mut state, true);
+        assert!(state.collapsed_sections.is_empty());
+        assert_eq!(state.expanded_tests.len(), 1);
+        assert!(state.expanded_tests.contains("t2"));
+
+        state.selected_section = None;
+        set_all_expanded(&mut state, true);
+        assert_eq!(state.expanded_tests.len(), 2);
+
+        state.selected_section = Some("Auth".to_string());
+        set_all_expanded(&mut state, false);
+        assert!(state.expanded_tests.contains("t1"));
+        assert!(!state.expanded_tests.contains("t2"));
+
+        state.selected_section = None;
+        set_all_expanded(&mut state, false);
+        assert!(state.expanded_tests.is_empty());
+    }
+
+    #[test]
+    fn test_confirm_env_input() {
+        let mut state = make_state();
+        start_env_input(&mut state);
+        assert!(state.adding_env);
+        state.env_input = "browser=firefox".to_string();
+        confirm_env_input(&mut state);
+        assert!(!state.adding_env);
+        assert!(state.dirty);
+        let env = state.results.meta.environment.as_ref().unwrap();
+        assert_eq!(env.custom.get("browser"), Some(&"firefox".to_string()));
+    }
+
+    #[test]
+    fn test_defect_input_links_failed_tests_only() {
+        let mut state = make_state();
+        start_defect_input(&mut state);
+        assert!(!state.adding_defect);
+
+        set_status(&mut state, Status::Failed);
+        for input in [" BUG-42 ", "BUG-42", "  "] {
+            start_defect_input(&mut state);
+            assert!(state.adding_defect);
+            state.defect_input = input.to_string();
+            confirm_defect_input(&mut state);
+        }
+        assert!(!state.adding_defect);
+        assert_eq!(state.results.results[0].defects, vec!["BUG-42"]);
+    }
+
+    #[test]
+    fn test_request_issue_for_failed_tests_only() {
+        let mut state = make_state();
+        assert_eq!(request_issue(&mut state), None);
+        set_status(&mut state, Status::Failed);
+        let test_id = state.results.results[0].test_id.clone();
+        assert_eq!(
+            request_issue(&mut state),
+            Some(Effect::CreateIssue {
+                test_id: test_id.clone()
+            })
+        );
+    }
+
+    #[test]
+    fn test_review_signs_off_finished_results() {
+        let mut state = make_state();
+        start_review(&mut state, Verdict::Approved);
+        assert_eq!(state.review_verdict, None);
+
+        state.reviewer = Some("rita".to_string());
+        start_review(&mut state, Verdict::Approved);
+        assert_eq!(state.review_verdict, None);
+        assert_eq!(
+            state.status_message.as_deref(),
+            Some("Only finished results can be reviewed")
+        );
+
+        set_status(&mut state, Status::Failed);
+        start_review(&mut state, Verdict::Rejected);
+        state.review_input = " steps not followed ".to_string();
+        confirm_review_input(&mut state);
+        let review = state.results.results[0].review.clone().unwrap();
+        assert_eq!(review.verdict, Verdict::Rejected);
+        assert_eq!(review.reviewer, "rita");
+        assert_eq!(review.comment.as_deref(), Some("steps not followed"));
+
+        // Changing the status afterwards invalidates the sign-off
+        set_status(&mut state, Status::Passed);
+        assert_eq!(state.results.results[0].review, None);
+        history::undo(&mut state);
+        assert_eq!(state.results.results[0].review, Some(review));
+    }
+
+    #[test]
+    fn test_confirm_env_input_rejects_malformed() {
+        let mut state = make_state();
+        start_env_input(&mut state);
+        state.env_input = "nonsense".to_string();
+        confirm_env_input(&mut state);
+        assert!(state.results.meta.environment.is_none());
+        assert!(!state.dirty);
+        assert!(state.status_message.is_some());
+    }
+
+    #[test]
+    fn test_request_open_single_reference() {
+        let mut state = make_state();
+        state.testlist.tests[0].references = vec!["https://example.com".to_string()];
+        assert_eq!(
+            request_open_reference(&mut state),
+            Some(Effect::OpenExternal("https://example.com".to_string()))
+        );
+        assert!(!state.choosing_reference);
+    }
+
+    #[test]
+    fn test_choose_among_multiple_references() {
+        let mut state = make_state();
+        state.testlist.tests[0].references = vec![
+            "https://a.example".to_string(),
+            "https://b.example".to_string(),
+        ];
+        assert_eq!(request_open_reference(&mut state), None);
+        assert!(state.choosing_reference);
+        assert_eq!(
+            choose_reference(&mut state, 2),
+            Some(Effect::OpenExternal("https://b.example".to_string()))
+        );
+        assert!(!state.choosing_reference);
+
+        request_open_reference(&mut state);
+        assert_eq!(choose_reference(&mut state, 9), None);
+        assert!(!state.choosing_reference);
+    }
+
+    #[test]
+    fn test_request_open_without_references() {
+        let mut state = make_state();
+        assert_eq!(request_open_reference(&mut state), None);
+        assert!(state.status_message.is_some());
+    }
+
+    #[test]
+    fn test_toggle_theme_cycles_builtins() {
+        let mut state = make_state();
+        assert_eq!(state.theme, Theme::DARK);
+        toggle_theme(&mut state);
+        assert_eq!(state.theme, Theme::LIGHT);
+        toggle_theme(&mut state);
+        assert_eq!(state.theme, Theme::HIGH_CONTRAST);
+        toggle_theme(&mut state);
+        assert_eq!(state.theme, Theme::DARK);
+    }
+
+    #[test]
+    fn test_user_theme_joins_cycle() {
+        let mut state = make_state();
+        let custom = crate::data::theme::ThemeFile {
+            accent: Some("#ff8800".to_string()),
+            ..Default::default()
+        }
+        .into_theme()
+        .unwrap();
+        set_user_theme(&mut state, custom);
+        assert_eq!(state.theme, custom);
+        toggle_theme(&mut state);
+        assert_eq!(state.theme, Theme::DARK);
+        toggle_theme(&mut state);
+        assert_eq!(state.theme, Theme::LIGHT);
+        toggle_theme(&mut state);
+        assert_eq!(state.theme, Theme::HIGH_CONTRAST);
+        toggle_theme(&mut state);
+        assert_eq!(state.theme, custom);
+    }
+
+    #[test]
+    fn test_external_notes_edit_roundtrip() {
+        let mut state = make_state();
+        let effect = request_external_notes_edit(&mut state);
+        assert_eq!(
+            effect,
+            Some(Effect::EditNotesExternally {
+                test_id: "t1".to_string(),
+                notes: String::new(),
+            })
+        );
+        apply_external_notes(&mut state, "t1", "Line one\nLine two\n");
+        assert_eq!(
+            state.results.results[0].notes.as_deref(),
+            Some("Line one\nLine two")
+        );
+        apply_external_notes(&mut state, "t1", "\n");
+        assert_eq!(state.results.results[0].notes, None);
+    }
+
+    #[test]
+    fn test_screenshot_capture_attaches_to_result() {
+        let mut state = make_state();
+        let Some(Effect::CaptureScreenshot { test_id, path }) =
+            request_screenshot_capture(&mut state)
+        else {
+            panic!("expected a capture effect");
+        };
+        assert_eq!(test_id, "t1");
+        assert!(path.starts_with("test.testlist.results.assets"));
+        attach_screenshot(&mut state, &test_id, path.clone());
+        assert_eq!(state.results.results[0].screenshots, vec![path]);
+        assert!(state.dirty);
+    }
+
+    #[test]
+    fn test_complete_screenshot_input() {
+        let mut state = make_state();
+        start_screenshot(&mut state);
+        state.screenshot_input = "shots/lo".to_string();
+        let candidates = vec![
+            "shots/login.png".to_string(),
+            "shots/logout.png".to_string(),
+        ];
+        complete_screenshot_input(&mut state, &candidates);
+        assert_eq!(state.screenshot_input, "shots/log");
+        assert_eq!(state.screenshot_completions.len(), 2);
+
+        complete_screenshot_input(&mut state, &candidates[..1]);
+        assert_eq!(state.screenshot_input, "shots/login.png");
+        assert!(state.screenshot_completions.is_empty());
+
+        complete_screenshot_input(&mut state, &[]);
+        assert!(state.screenshot_error.is_some());
+    }
+
+    #[test]
+    fn test_note_templates() {
+        use crate::data::state::FailureCapture;
+        use crate::queries::tests::missing_capture_detail;
+
+        let mut state = make_state();
+        state.failure_capture = Some(FailureCapture::new(Status::Failed));
+        start_note_template(&mut state);
+        assert!(state.status_message.as_deref().unwrap().contains("No note"));
+
+        state.note_templates = BTreeMap::from([
+            ("Bug".to_string(), "From config".to_string()),
+            ("Crash".to_string(), "Stack trace:".to_string()),
+        ]);
+        state.testlist.meta.note_templates =
+            BTreeMap::from([("Bug".to_string(), "Expected:\nActual:".to_string())]);
+        start_note_template(&mut state);
+        assert!(state.choosing_note_template);
+        choose_note_template(&mut state, 1);
+        assert!(!state.choosing_note_template);
+        let capture = state.failure_capture.clone().unwrap();
+        assert_eq!(capture.note, "Expected:\nActual:");
+
+        state.testlist.meta.require_failure_notes = true;
+        assert_eq!(missing_capture_detail(&state, &capture), Some("a note"));
+        state
+            .failure_capture
+            .as_mut()
+            .unwrap()
+            .note
+            .push_str(" crash");
+        let capture = state.failure_capture.as_ref().unwrap();
+        assert_eq!(missing_capture_detail(&state, capture), None);
+    }
+
+    #[test]
+    fn test_reject_screenshot_keeps_prompt_open() {
+        let mut state = make_state();
+        start_screenshot(&mut state);
+        state.screenshot_input = "typo.png".to_string();
+        reject_screenshot(&mut state);
+        assert!(state.adding_screenshot);
+        assert!(state
+            .screenshot_error
+            .as_deref()
+            .unwrap()
+            .contains("typo.png"));
+        assert!(state.results.results[0].screenshots.is_empty());
+    }
+
+    #[test]
+    fn test_capture_terminal_output() {
+        let mut state = make_state();
+        let screen = "\n$ make\nerror: boom   \n$\n\n\n";
+        capture_terminal_output(&mut state, screen, 2);
+        assert_eq!(
+            state.results.results[0].notes.as_deref(),
+            Some("```\nerror: boom\n$\n```")
+        );
+
+        capture_terminal_output(&mut state, "ok", 20);
+        assert_eq!(
+            state.results.results[0].notes.as_deref(),
+            Some("```\nerror: boom\n$\n```\n\n```\nok\n```")
+        );
+        assert!(state.dirty);
+    }
+
+    #[test]
+    fn test_layout_presets_and_toggles() {
+        let mut state = make_state();
+        state.focused_pane = FocusedPane::Notes;
+        assert_eq!(
+            cycle_layout_preset(&mut state),
+            Some(Effect::SaveLayout(PaneLayout::TESTS_TERMINAL))
+        );
+        assert_eq!(state.focused_pane, FocusedPane::Tests);
+        cycle_layout_preset(&mut state);
+        assert_eq!(state.layout, PaneLayout::TESTS_ONLY);
+
+        toggle_notes_pane(&mut state);
+        assert!(state.layout.notes);
+        assert!(!state.layout.terminal);
+        toggle_terminal_pane(&mut state);
+        assert_eq!(state.layout, PaneLayout::FULL);
+        assert_eq!(
+            state.status_message.as_deref(),
+            Some("Layout: tests, notes, and terminal")
+        );
+    }
+
+    #[test]
+    fn test_cycle_focus_skips_hidden_panes() {
+        let mut state = make_state();
+        state.layout = PaneLayout::TESTS_TERMINAL;
+        cycle_focus(&mut state);
+        assert_eq!(state.focused_pane, FocusedPane::Terminal);
+        cycle_focus(&mut state);
+        assert_eq!(state.focused_pane, FocusedPane::Tests);
+        state.layout = PaneLayout::TESTS_ONLY;
+        cycle_focus(&mut state);
+        assert_eq!(state.focused_pane, FocusedPane::Tests);
+    }
+
+    #[test]
+    fn test_pane_sizes_are_clamped() {
+        let mut state = make_state();
+        start_drag(&mut state, PaneBorder::TestsNotes);
+        assert_eq!(state.dragging, Some(PaneBorder::TestsNotes));
+        set_tests_width(&mut state, 65);
+        assert_eq!(state.tests_width_percent, 65);
+        set_tests_width(&mut state, 5);
+        assert_eq!(state.tests_width_percent, MIN_PANE_PERCENT);
+        set_tests_width(&mut state, 100);
+        assert_eq!(state.tests_width_percent, 100 - MIN_PANE_PERCENT);
+        end_drag(&mut state);
+        assert_eq!(state.dragging, None);
+
+        set_terminal_height(&mut state, 12, 20);
+        assert_eq!(state.terminal_height, 12);
+        set_terminal_height(&mut state, 40, 20);
+        assert_eq!(state.terminal_height, 20);
+        set_terminal_height(&mut state, 1, 20);
+        assert_eq!(state.terminal_height, MIN_TERMINAL_HEIGHT);
+    }
+
+    #[test]
+    fn test_terminal_zoom() {
+        let mut state = make_state();
+        toggle_terminal_zoom(&mut state);
+        assert!(state.terminal_zoomed);
+        assert_eq!(state.focused_pane, FocusedPane::Terminal);
+        toggle_terminal_zoom(&mut state);
+        assert!(!state.terminal_zoomed);
+        assert_eq!(state.focused_pane, FocusedPane::Terminal);
+
+        toggle_terminal_zoom(&mut state);
+        leave_terminal(&mut state);
+        assert!(!state.terminal_zoomed);
+        assert_eq!(state.focused_pane, FocusedPane::Tests);
+
+        toggle_terminal_zoom(&mut state);
+        cycle_focus(&mut state);
+        assert!(!state.terminal_zoomed);
+    }
+
+    #[test]
+    fn test_yank_to_notes() {
+        let mut state = make_state();
+        yank_to_notes(&mut state, "error: boom");
+        assert_eq!(
+            state.results.results[0].notes.as_deref(),
+            Some("```\nerror: boom\n```")
+        );
+    }
+
+    #[test]
+    fn test_capture_empty_terminal() {
+        let mut state = make_state();
+        capture_terminal_output(&mut state, "\n  \n", 20);
+        assert_eq!(state.results.results[0].notes, None);
+    }
+
+    #[test]
+    fn test_testlist_change_offers_reload() {
+        use std::time::Duration;
+
+        let mut state = make_state();
+        let loaded = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+        state.testlist_modified = Some(loaded);
+        testlist_changed(&mut state, Some(loaded));
+        assert!(!state.confirm_reload);
+        testlist_changed(&mut state, None);
+        assert!(!state.confirm_reload);
+
+        testlist_changed(&mut state, Some(loaded + Duration::from_secs(1)));
+        assert!(state.confirm_reload);
+        assert_eq!(confirm_reload(&mut state), Some(Effect::ReloadTestlist));
+        assert!(!state.confirm_reload);
+    }
 }
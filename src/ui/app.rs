@@ -1,32 +1,84 @@
 //! Application setup, teardown, and main entry point.
 
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
+    event::{DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
 use ratatui::Terminal;
 use std::io::stdout;
+use std::sync::mpsc;
 
+use crate::actions::serve::ProgressServer;
 use crate::data::state::AppState;
 use crate::error::Result;
+use crate::ui::input::{self, InputReader};
 use crate::ui::panes::terminal::EmbeddedTerminal;
 
+/// Release the terminal, run `f` (e.g. an external editor), then take it back.
+///
+/// The caller should request a full redraw afterwards.
+pub fn suspend<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    // Keep input for the program being run
+    let _paused = input::pause();
+    stdout().execute(DisableBracketedPaste)?;
+    stdout().execute(DisableMouseCapture)?;
+    disable_raw_mode()?;
+    stdout().execute(LeaveAlternateScreen)?;
+
+    let result = f();
+
+    enable_raw_mode()?;
+    stdout().execute(EnterAlternateScreen)?;
+    stdout().execute(EnableMouseCapture)?;
+    stdout().execute(EnableBracketedPaste)?;
+
+    result
+}
+
 /// Run the TUI application.
 pub fn run(state: &mut AppState) -> Result<()> {
-    // Create embedded terminal (may fail on some systems)
-    let mut terminal_pty = EmbeddedTerminal::new(24, 80).ok();
+    // Start the progress server first so a taken port fails before the TUI opens
+    let server = state
+        .serve_port
+        .map(|port| ProgressServer::start((state.serve_addr, port).into()))
+        .transpose()?;
+    let (wake_tx, wake_rx) = mpsc::channel();
+
+    // Create embedded terminal, falling back to running commands without a
+    // PTY where none can be opened; observers don't get one
+    let mut terminal_pty = if state.read_only {
+        None
+    } else {
+        match EmbeddedTerminal::new(24, 80, state.shell.as_deref(), Some(wake_tx.clone())) {
+            Ok(term) => Some(term),
+            Err(e) => {
+                log::error!("Could not start embedded terminal: {}", e);
+                state.status_message = Some(format!(
+                    "No PTY ({}): C runs commands without a terminal",
+                    e
+                ));
+                Some(EmbeddedTerminal::fallback(24, 80, Some(wake_tx.clone())))
+            }
+        }
+    };
 
     // Setup terminal
     enable_raw_mode()?;
     stdout().execute(EnterAlternateScreen)?;
     stdout().execute(EnableMouseCapture)?;
+    stdout().execute(EnableBracketedPaste)?;
     let mut terminal = Terminal::new(ratatui::backend::CrosstermBackend::new(stdout()))?;
 
-    // Main loop
-    let result = super::main_loop(&mut terminal, state, &mut terminal_pty);
+    // Main loop, woken by input and terminal output
+    let reader = InputReader::spawn(wake_tx);
+    let result = super::main_loop(&mut terminal, state, &mut terminal_pty, server, wake_rx);
+    // Wait for the reader to let go of the terminal before restoring it
+    drop(reader);
+    crate::transforms::timer::stop_timer(state, std::time::Instant::now());
 
     // Restore terminal
+    stdout().execute(DisableBracketedPaste)?;
     stdout().execute(DisableMouseCapture)?;
     disable_raw_mode()?;
     stdout().execute(LeaveAlternateScreen)?;
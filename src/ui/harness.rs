@@ -0,0 +1,333 @@
+//! Drive the full event loop against ratatui's `TestBackend` for
+//! end-to-end TUI tests.
+//!
+//! ```
+//! use testlist::data::definition::Testlist;
+//! use testlist::data::results::{Status, TestlistResults};
+//! use testlist::data::state::AppState;
+//! use testlist::ui::harness::{Harness, KeyCode};
+//!
+//! let testlist = Testlist::parse(
+//!     r#"Testlist(
+//!         meta: Meta(title: "Demo", description: "", created: "", version: "1"),
+//!         tests: [Test(id: "build", title: "Build it", description: "", action: "cargo build")],
+//!     )"#,
+//!     testlist::data::definition::Format::Ron,
+//! )?;
+//! let results = TestlistResults::new_for_testlist(&testlist, "demo.ron", "alice");
+//! let dir = tempfile::tempdir()?;
+//! let state = AppState::new(
+//!     testlist,
+//!     results,
+//!     dir.path().join("demo.ron"),
+//!     dir.path().join("demo.results.ron"),
+//! );
+//!
+//! let mut harness = Harness::new(state, 80, 24)?;
+//! assert!(harness.screen().contains("Build it"));
+//! harness.key(KeyCode::Char('p'))?;
+//! assert_eq!(harness.state.results.results[0].status, Status::Passed);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! There is no embedded terminal or image preview. Everything else behaves
+//! as in a real session, including writing the crash journal and saving
+//! results to `state.results_path`, so point it at a temporary directory.
+
+use crossterm::event::KeyEvent;
+use ratatui::{backend::TestBackend, Terminal};
+
+pub use crossterm::event::{Event, KeyCode, KeyModifiers};
+pub use ratatui::buffer::Buffer;
+
+use super::EventLoop;
+use crate::data::state::AppState;
+use crate::error::Result;
+
+/// A TUI session rendered to an in-memory buffer.
+pub struct Harness {
+    pub state: AppState,
+    terminal: Terminal<TestBackend>,
+    event_loop: EventLoop,
+}
+
+impl Harness {
+    /// Start a session of `width` x `height` cells and render the first frame.
+    pub fn new(state: AppState, width: u16, height: u16) -> Result<Self> {
+        let terminal = Terminal::new(TestBackend::new(width, height))?;
+        let event_loop = EventLoop::new(&state);
+        let mut harness = Self {
+            state,
+            terminal,
+            event_loop,
+        };
+        harness.render()?;
+        Ok(harness)
+    }
+
+    /// Run one loop iteration: poll timers and files, then draw a frame.
+    pub fn render(&mut self) -> Result<&Buffer> {
+        let mut pty = None;
+        self.event_loop.tick(&mut self.state, &mut pty);
+        self.state.needs_full_redraw = false;
+        self.event_loop
+            .draw(&mut self.terminal, &mut self.state, &mut pty, false)?;
+        Ok(self.terminal.backend().buffer())
+    }
+
+    /// Feed an input event through the loop and render the resulting frame.
+    pub fn send(&mut self, event: Event) -> Result<&Buffer> {
+        let mut pty = None;
+        self.event_loop
+            .handle_event(&mut self.state, &mut pty, event);
+        self.event_loop.record_changes(&mut self.state);
+        self.render()
+    }
+
+    /// Press a key without modifiers.
+    pub fn key(&mut self, code: KeyCode) -> Result<&Buffer> {
+        self.key_with(code, KeyModifiers::NONE)
+    }
+
+    /// Press a key with modifiers, e.g. `Ctrl-n`.
+    pub fn key_with(&mut self, code: KeyCode, modifiers: KeyModifiers) -> Result<&Buffer> {
+        self.send(Event::Key(KeyEvent::new(code, modifiers)))
+    }
+
+    /// Type each character of `text` as a key press.
+    pub fn type_text(&mut self, text: &str) -> Result<&Buffer> {
+        for c in text.chars() {
+            self.send(Event::Key(KeyEvent::new(
+                KeyCode::Char(c),
+                KeyModifiers::NONE,
+            )))?;
+        }
+        Ok(self.buffer())
+    }
+
+    /// Change the screen size and render.
+    pub fn resize(&mut self, width: u16, height: u16) -> Result<&Buffer> {
+        self.terminal.backend_mut().resize(width, height);
+        self.send(Event::Resize(width, height))
+    }
+
+    /// The last rendered frame.
+    pub fn buffer(&self) -> &Buffer {
+        self.terminal.backend().buffer()
+    }
+
+    /// The last rendered frame as text, one line per row with trailing
+    /// spaces removed.
+    pub fn screen(&self) -> String {
+        let buffer = self.buffer();
+        let area = buffer.area;
+        (area.top()..area.bottom())
+            .map(|y| {
+                let row: String = (area.left()..area.right())
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect();
+                row.trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::definition::{ChecklistItem, Test};
+    use crate::data::results::{Severity, Status, TestlistResults};
+    use crate::fixtures;
+
+    fn make_harness(dir: &std::path::Path) -> Harness {
+        let mut testlist = fixtures::testlist(
+            (1..=3)
+                .map(|n| Test {
+                    id: format!("t{}", n),
+                    title: format!("Test number {}", n),
+                    action: "Do it".to_string(),
+                    verify: vec![ChecklistItem {
+                        id: "v0".to_string(),
+                        text: "It worked".to_string(),
+                    }],
+                    ..Default::default()
+                })
+                .collect(),
+        );
+        testlist.meta.title = "Demo".to_string();
+        let results = TestlistResults::new_for_testlist(&testlist, "demo.ron", "alice");
+        let state = AppState::new(
+            testlist,
+            results,
+            dir.join("demo.testlist.ron"),
+            dir.join("demo.testlist.results.ron"),
+        );
+        Harness::new(state, 100, 30).unwrap()
+    }
+
+    #[test]
+    fn test_harness_renders_and_marks() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut harness = make_harness(dir.path());
+        let screen = harness.screen();
+        assert!(screen.contains("Test number 1"));
+        assert!(screen.contains("Test number 3"));
+
+        harness.key(KeyCode::Char('j')).unwrap();
+        harness.key(KeyCode::Char('f')).unwrap();
+        assert_eq!(harness.state.selected_test, 1);
+        assert_eq!(harness.state.results.results[1].status, Status::Pending);
+        assert!(harness.screen().contains("Severity"));
+
+        harness.key(KeyCode::Tab).unwrap();
+        harness.key(KeyCode::Char('2')).unwrap();
+        harness.key(KeyCode::Enter).unwrap();
+        assert!(!harness.screen().contains("Severity"));
+        assert_eq!(harness.state.results.results[1].status, Status::Failed);
+        assert_eq!(
+            harness.state.results.results[1].severity,
+            Some(Severity::Major)
+        );
+        assert!(crate::actions::journal::journal_path(&harness.state.results_path).exists());
+    }
+
+    #[test]
+    fn test_harness_watch_reloads_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut harness = make_harness(dir.path());
+        let results_path = harness.state.results_path.clone();
+        crate::actions::files::save_results(&harness.state.results, &results_path, 0).unwrap();
+        harness.state.read_only = true;
+
+        harness.key(KeyCode::Char('p')).unwrap();
+        assert_eq!(harness.state.results.results[0].status, Status::Pending);
+        assert!(harness.screen().contains("read-only"));
+
+        let mut results = harness.state.results.clone();
+        results.set_status("t2", Status::Failed);
+        crate::actions::files::save_results(&results, &results_path, 0).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        harness.render().unwrap();
+        assert_eq!(harness.state.results.results[1].status, Status::Failed);
+        assert!(!crate::actions::journal::journal_path(&results_path).exists());
+    }
+
+    #[test]
+    fn test_harness_append_only_saves_to_journal() {
+        use crate::actions::journal;
+        use crate::data::journal::JournalEntry;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut harness = make_harness(dir.path());
+        harness.state.append_only = true;
+        let journal_path = journal::journal_path(&harness.state.results_path);
+
+        harness.key(KeyCode::Char('p')).unwrap();
+        harness.key(KeyCode::Char('w')).unwrap();
+        assert!(!harness.state.dirty);
+        assert!(!harness.state.results_path.exists());
+        let entries = journal::load(&journal_path).unwrap();
+        assert!(matches!(entries[0], JournalEntry::Status { .. }));
+        assert_eq!(entries.last(), Some(&JournalEntry::Saved));
+
+        // Quitting without saving keeps what was saved to the journal
+        harness.key(KeyCode::Char('j')).unwrap();
+        harness.key(KeyCode::Char('p')).unwrap();
+        journal::discard_unsaved(&journal_path).unwrap();
+        assert_eq!(journal::load(&journal_path).unwrap(), entries);
+    }
+
+    #[test]
+    fn test_harness_append_only_save_keeps_every_field() {
+        use crate::actions::journal;
+        use crate::data::journal::replay;
+        use crate::data::results::CommandRun;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut harness = make_harness(dir.path());
+        harness.state.append_only = true;
+        harness.state.results.start_session("alice", None);
+
+        // Time spent and commands run don't make the results dirty
+        let result = &mut harness.state.results.results[0];
+        result.screenshots.push("assets/t1.png".into());
+        result.time_spent_secs = 42;
+        result.command_runs.push(CommandRun {
+            command: "make check".to_string(),
+            exit_code: 0,
+            started: "2025-01-24T14:30:00Z".to_string(),
+            finished: "2025-01-24T14:31:00Z".to_string(),
+            output_tail: "ok".to_string(),
+        });
+        harness.render().unwrap();
+        harness.key(KeyCode::Char('p')).unwrap();
+        harness.key(KeyCode::Char('w')).unwrap();
+
+        let mut replayed =
+            TestlistResults::new_for_testlist(&harness.state.testlist, "demo.ron", "alice");
+        let journal_path = journal::journal_path(&harness.state.results_path);
+        replay(&mut replayed, &journal::load(&journal_path).unwrap());
+        let result = &replayed.results[0];
+        assert_eq!(result.status, Status::Passed);
+        assert_eq!(
+            result.screenshots,
+            vec![std::path::PathBuf::from("assets/t1.png")]
+        );
+        assert_eq!(result.time_spent_secs, 42);
+        assert_eq!(result.command_runs[0].command, "make check");
+        assert_eq!(replayed.meta, harness.state.results.meta);
+        assert!(replayed.meta.sessions[0].ended.is_some());
+    }
+
+    #[test]
+    fn test_harness_autosave_backs_up_once() {
+        use crate::actions::files::backups;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mut harness = make_harness(dir.path());
+        harness.state.autosave_interval = Some(std::time::Duration::ZERO);
+        let results_path = harness.state.results_path.clone();
+        harness.state.results.save(&results_path).unwrap();
+
+        // Only the first autosave backs up the file from before the session
+        for _ in 0..3 {
+            harness.key(KeyCode::Char('p')).unwrap();
+            harness.key(KeyCode::Char('j')).unwrap();
+            assert!(!harness.state.dirty);
+        }
+        assert_eq!(backups(&results_path).len(), 1);
+
+        harness.key(KeyCode::Char('w')).unwrap();
+        assert_eq!(backups(&results_path).len(), 2);
+    }
+
+    #[test]
+    fn test_harness_accessible_announces() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut harness = make_harness(dir.path());
+        let announce_path = dir.path().join("announce.txt");
+        harness.state.accessible = true;
+        harness.state.announce_path = Some(announce_path.clone());
+
+        harness.key(KeyCode::Char('p')).unwrap();
+        let screen = harness.screen();
+        assert!(screen.contains("passed: Test number 1"));
+        assert!(screen.contains("1 passed, 0 failed, 2 pending"));
+        let announced = std::fs::read_to_string(&announce_path).unwrap();
+        assert!(announced.starts_with("Test number 1: passed\n"));
+    }
+
+    #[test]
+    fn test_harness_expands_test() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut harness = make_harness(dir.path());
+        assert!(!harness.screen().contains("It worked"));
+        harness.key(KeyCode::Enter).unwrap();
+        assert!(harness.screen().contains("It worked"));
+
+        harness.resize(60, 20).unwrap();
+        assert_eq!(harness.buffer().area.width, 60);
+        assert!(harness.screen().contains("It worked"));
+    }
+}
@@ -0,0 +1,96 @@
+//! Terminal input read on a background thread, so that the event loop can
+//! sleep until input, embedded terminal output, or a deadline wakes it.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crossterm::event::{self, Event};
+
+/// What wakes the event loop.
+#[derive(Debug)]
+pub enum Wake {
+    /// A key press, mouse event, paste, or resize, or the error that ended
+    /// reading them.
+    Input(std::io::Result<Event>),
+    /// The embedded terminal has new output, or its command's output ended.
+    Output,
+}
+
+/// How long the reader waits for input before checking whether to pause or stop.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Held by the reader while it waits for input, so [`pause`] can wait for
+/// it to let go of the terminal.
+static READING: Mutex<()> = Mutex::new(());
+static PAUSED: AtomicBool = AtomicBool::new(false);
+
+/// Reads terminal input on a background thread until dropped.
+///
+/// Dropping it waits for the thread to finish, at most one poll interval,
+/// so that no read is in progress once the terminal is restored.
+pub struct InputReader {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl InputReader {
+    /// Start sending input events to `wake`.
+    pub fn spawn(wake: Sender<Wake>) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        let thread = thread::spawn(move || {
+            while !stopped.load(Ordering::Relaxed) {
+                if PAUSED.load(Ordering::Acquire) {
+                    thread::sleep(Duration::from_millis(10));
+                    continue;
+                }
+                let _reading = READING.lock().unwrap_or_else(|e| e.into_inner());
+                let input = match event::poll(POLL_INTERVAL) {
+                    Ok(true) => event::read(),
+                    Ok(false) => continue,
+                    Err(e) => Err(e),
+                };
+                let failed = input.is_err();
+                if wake.send(Wake::Input(input)).is_err() || failed {
+                    break;
+                }
+            }
+        });
+        Self {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for InputReader {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Keeps the reader off the terminal while alive; see [`pause`].
+pub struct PauseGuard {
+    _reading: MutexGuard<'static, ()>,
+}
+
+impl Drop for PauseGuard {
+    fn drop(&mut self) {
+        PAUSED.store(false, Ordering::Release);
+    }
+}
+
+/// Stop reading input until the guard is dropped, e.g. while an external
+/// editor has the terminal. Waits for a read in progress to finish.
+pub fn pause() -> PauseGuard {
+    PAUSED.store(true, Ordering::Release);
+    PauseGuard {
+        _reading: READING.lock().unwrap_or_else(|e| e.into_inner()),
+    }
+}
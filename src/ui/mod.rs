@@ -1,111 +1,601 @@
 //! UI layer: event loop, key/mouse dispatch, and rendering coordination.
 
+// Keys that only act in one pane keep the focus check inside their arm
+#![allow(clippy::collapsible_match)]
+
 pub mod app;
+pub mod harness;
+pub mod input;
 pub mod panes;
 
-use crossterm::event::{
-    self, Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind,
-};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
+use std::time::{Duration, SystemTime};
+
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::Style,
+    style::{Modifier, Style},
     text::Line,
     widgets::{Block, Borders, Clear, Paragraph},
     Frame, Terminal,
 };
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::actions::pty::{output_tail, Signal, OUTPUT_TAIL_LINES};
+use crate::actions::serve::ProgressServer;
+use crate::actions::{announce, config, github, hooks, journal, paths, script, webhook};
+use crate::data::copy_mode::CopyMode;
+use crate::data::definition::format_duration;
+use crate::data::effect::Effect;
+use crate::data::journal::{diff as journal_diff, JournalEntry};
+use crate::data::results::{Severity, Status, Verdict};
+use crate::data::state::{
+    AppState, CaptureField, FailureCapture, FocusedPane, PaneBorder, PaneEntry, PaneRow,
+};
+use crate::error::{Error, Result};
+use crate::queries::announce::{announcements, initial as announce_initial, snapshot, Announced};
+use crate::queries::hooks::{triggered_hooks, webhook_requests};
+use crate::queries::layout::{notes_visible, terminal_visible};
+use crate::queries::progress::{progress, Progress};
+use crate::queries::stats::{
+    custom_status_counts, retest_summary, review_counts, session_summary, severity_counts,
+    status_counts, timing_stats,
+};
+use crate::queries::tests::{
+    all_tags, command_timed_out, current_result, current_test, filter_label, is_over_budget,
+    map_y_to_entry, map_y_to_row, missing_capture_detail, note_required, note_templates,
+    result_for_test, search_matches, selected_entry, severity_required, visual_selection,
+};
+use crate::report::issue;
+use crate::transforms::{
+    filter as filter_transforms, history as history_transforms, navigation,
+    search as search_transforms, terminal as terminal_transforms, tests as test_transforms,
+    timer as timer_transforms, ui as ui_transforms,
+};
+use input::Wake;
+use panes::preview::{self, preview_path, ImagePreview, ImageProtocol};
+use panes::terminal::{key_sequence, visible_rows, EmbeddedTerminal};
 
-use crate::data::state::{AppState, FocusedPane};
-use crate::error::Result;
-use crate::queries::tests::{current_test, map_y_to_test_index};
-use crate::transforms::{navigation, tests as test_transforms, ui as ui_transforms};
-use panes::terminal::EmbeddedTerminal;
+/// How often the testlist file is checked for changes on disk.
+const TESTLIST_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// How often a run command is checked for finishing or timing out.
+const COMMAND_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
 
 /// Stores layout information for mouse click handling.
 struct LayoutAreas {
     tests_pane: Rect,
     notes_pane: Rect,
     terminal_pane: Rect,
+    // Screenshot to show inline and the cells it occupies
+    preview: Option<(std::path::PathBuf, Rect)>,
 }
 
+/// Run the TUI until quit. Between frames the loop sleeps until `wake`
+/// brings input or terminal output, or until the next deadline of `tick`.
 fn main_loop(
     terminal: &mut Terminal<ratatui::backend::CrosstermBackend<std::io::Stdout>>,
     state: &mut AppState,
     pty: &mut Option<EmbeddedTerminal>,
+    server: Option<ProgressServer>,
+    wake: Receiver<Wake>,
 ) -> Result<()> {
-    let mut layout_areas: Option<LayoutAreas> = None;
+    let mut event_loop = EventLoop::new(state);
+    event_loop.server = server;
+    let mut preview = ImagePreview::new(ImageProtocol::detect());
 
     while !state.should_quit {
-        // Poll PTY output
+        event_loop.tick(state, pty);
+
+        if std::mem::take(&mut state.needs_full_redraw) {
+            terminal.clear()?;
+            preview.invalidate();
+        }
+
+        let show_preview = preview.protocol != ImageProtocol::None;
+        event_loop.draw(terminal, state, pty, show_preview)?;
+        preview.sync(
+            terminal.backend_mut(),
+            event_loop
+                .layout_areas
+                .as_ref()
+                .and_then(|a| a.preview.clone()),
+        );
+
+        // Handle everything that arrived before drawing again, so bursts of
+        // output or pasted keys don't cost a frame each
+        let timeout = event_loop.idle_timeout(state);
+        let mut next = match wake.recv_timeout(timeout) {
+            Ok(woken) => Some(woken),
+            Err(RecvTimeoutError::Timeout) => None,
+            // Nothing can wake the loop any more; keep to the deadlines
+            Err(RecvTimeoutError::Disconnected) => {
+                std::thread::sleep(timeout);
+                None
+            }
+        };
+        while let Some(woken) = next {
+            if let Wake::Input(event) = woken {
+                event_loop.handle_event(state, pty, event?);
+            }
+            next = if state.should_quit {
+                None
+            } else {
+                wake.try_recv().ok()
+            };
+        }
+        event_loop.record_changes(state);
+    }
+    Ok(())
+}
+
+/// Bookkeeping carried between iterations of the event loop, whose steps
+/// are shared by the TUI and the test [`harness`].
+struct EventLoop {
+    layout_areas: Option<LayoutAreas>,
+    // Results as of the last journal write
+    journaled: crate::data::results::TestlistResults,
+    // Results as of the previous iteration, for hooks and webhooks
+    previous: crate::data::results::TestlistResults,
+    testlist_checked: std::time::Instant,
+    // When the results were last saved or unchanged, for autosave
+    clean_since: std::time::Instant,
+    // Progress server for `--serve` and what it was last given
+    server: Option<ProgressServer>,
+    published: Option<Progress>,
+    // Modification times of the watched results file and its journal
+    watched: (Option<SystemTime>, Option<SystemTime>),
+    // State as of the last announcement, for `--announce`
+    announced: Announced,
+}
+
+impl EventLoop {
+    fn new(state: &AppState) -> Self {
+        Self {
+            layout_areas: None,
+            journaled: state.results.clone(),
+            previous: state.results.clone(),
+            testlist_checked: std::time::Instant::now(),
+            clean_since: std::time::Instant::now(),
+            server: None,
+            published: None,
+            watched: watched_times(&state.results_path),
+            announced: announce_initial(state),
+        }
+    }
+
+    /// How long the loop may sleep without missing work for [`Self::tick`]:
+    /// the next testlist check or autosave, or a short while when a run
+    /// command could finish or time out without output to wake the loop.
+    fn idle_timeout(&self, state: &AppState) -> Duration {
+        let mut timeout = TESTLIST_CHECK_INTERVAL.saturating_sub(self.testlist_checked.elapsed());
+        if let Some(interval) = state.autosave_interval.filter(|_| state.dirty) {
+            timeout = timeout.min(interval.saturating_sub(self.clean_since.elapsed()));
+        }
+        if state.running_command.is_some() {
+            timeout = timeout.min(COMMAND_CHECK_INTERVAL);
+        }
+        timeout
+    }
+
+    /// Poll the terminal pane's command, the timer, autosave, and the testlist file.
+    fn tick(&mut self, state: &mut AppState, pty: &mut Option<EmbeddedTerminal>) {
         if let Some(ref mut term) = pty {
             term.poll_output();
+            if state.running_command.is_some() {
+                if let Some((code, output)) = term.take_finished_capture() {
+                    log::info!("Command finished with exit code {}", code);
+                    let test_id = state.running_command.as_ref().map(|r| r.test_id.clone());
+                    test_transforms::finish_command_run(
+                        state,
+                        code,
+                        output_tail(&output, OUTPUT_TAIL_LINES),
+                    );
+                    if let Some(test_id) = test_id {
+                        run_test_script(state, &test_id, code, &output);
+                    }
+                } else if command_timed_out(state, std::time::Instant::now()) {
+                    time_out_command(state, term);
+                }
+            }
+        }
+
+        timer_transforms::tick_timer(state, std::time::Instant::now());
+
+        // Autosave once changes have been unsaved for the configured interval
+        if !state.dirty {
+            self.clean_since = std::time::Instant::now();
+        } else if state
+            .autosave_interval
+            .is_some_and(|interval| self.clean_since.elapsed() >= interval)
+        {
+            run_effect(state, Some(Effect::Autosave), pty);
+            self.clean_since = std::time::Instant::now();
+            if state.journal_save_pending {
+                self.record_changes(state);
+            }
         }
 
+        if self.testlist_checked.elapsed() >= TESTLIST_CHECK_INTERVAL
+            && !crate::actions::files::is_stdin(&state.testlist_path)
+        {
+            self.testlist_checked = std::time::Instant::now();
+            let modified = crate::actions::files::modified_time(&state.testlist_path);
+            ui_transforms::testlist_changed(state, modified);
+
+            if state.read_only {
+                let watched = watched_times(&state.results_path);
+                if watched != self.watched {
+                    self.watched = watched;
+                    reload_results(state);
+                }
+            }
+        }
+    }
+
+    /// Render a frame and adapt the state and PTY to the resulting pane sizes.
+    fn draw<B: ratatui::backend::Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        state: &mut AppState,
+        pty: &mut Option<EmbeddedTerminal>,
+        show_preview: bool,
+    ) -> Result<()> {
         terminal.draw(|frame| {
-            layout_areas = Some(draw(frame, state, pty));
+            self.layout_areas = Some(draw(frame, state, pty, show_preview));
         })?;
 
-        if let Some(ref areas) = layout_areas {
-            state.tests_visible_height = areas.tests_pane.height.saturating_sub(2) as usize;
+        if let Some(ref areas) = self.layout_areas {
+            if !state.terminal_zoomed {
+                state.tests_visible_height = areas.tests_pane.height.saturating_sub(2) as usize;
+            }
 
             let new_rows = areas.terminal_pane.height.saturating_sub(2);
             let new_cols = areas.terminal_pane.width.saturating_sub(2);
-            if (new_rows, new_cols) != state.terminal_size {
+            // Keep the PTY size while the terminal pane is hidden
+            if new_rows > 0 && (new_rows, new_cols) != state.terminal_size {
                 state.terminal_size = (new_rows, new_cols);
                 if let Some(ref mut term) = pty {
                     term.resize(new_rows, new_cols);
                 }
             }
         }
+        Ok(())
+    }
 
-        if event::poll(std::time::Duration::from_millis(50))? {
-            match event::read()? {
-                Event::Key(key) => {
-                    if key.kind == KeyEventKind::Press {
-                        handle_key(state, key.code, key.modifiers, pty);
+    /// Dispatch one input event.
+    fn handle_event(
+        &mut self,
+        state: &mut AppState,
+        pty: &mut Option<EmbeddedTerminal>,
+        event: Event,
+    ) {
+        let selected_before = state.selected_test;
+        match event {
+            Event::Key(key) => {
+                if key.kind == KeyEventKind::Press {
+                    handle_key(state, key.code, key.modifiers, pty);
+                    navigation::adjust_scroll(state);
+                }
+            }
+            Event::Mouse(mouse) => {
+                if let Some(ref areas) = self.layout_areas {
+                    handle_mouse(state, mouse, areas);
+                    // The wheel scrolls away from the selection on purpose
+                    if !matches!(
+                        mouse.kind,
+                        MouseEventKind::ScrollUp | MouseEventKind::ScrollDown
+                    ) {
                         navigation::adjust_scroll(state);
                     }
                 }
-                Event::Mouse(mouse) => {
-                    if let Some(ref areas) = layout_areas {
-                        handle_mouse(state, mouse, areas);
-                        navigation::adjust_scroll(state);
+            }
+            Event::Paste(text) => handle_paste(state, &text, pty),
+            _ => {}
+        }
+        if state.selected_test != selected_before {
+            state.notes_scroll = 0;
+        }
+    }
+
+    /// Journal result changes since the last call, run the hooks and
+    /// webhook they trigger, and update the progress server and announcements.
+    fn record_changes(&mut self, state: &mut AppState) {
+        if let Some(path) = state.announce_path.clone() {
+            let lines = announcements(state, &self.announced);
+            if !lines.is_empty() {
+                if let Err(e) = announce::append(&path, &lines) {
+                    log::error!("Could not write announcements {}: {}", path.display(), e);
+                    state.status_message = Some(format!("Could not write announcements: {}", e));
+                }
+            }
+            self.announced = snapshot(state);
+        }
+        if let Some(ref server) = self.server {
+            let progress = progress(state);
+            if self.published.as_ref() != Some(&progress) {
+                server.publish(&progress);
+                self.published = Some(progress);
+            }
+        }
+        // An observer's view of the results is not a change to record
+        if state.read_only {
+            return;
+        }
+
+        // Changes saved in this iteration are already on disk, unless they
+        // were saved to the journal. Time spent, which changes every second
+        // the timer runs, goes in with the next other change.
+        let saved_to_journal = std::mem::take(&mut state.journal_save_pending);
+        if state.dirty || saved_to_journal {
+            let mut entries = journal_diff(&self.journaled, &state.results);
+            if saved_to_journal {
+                entries.push(JournalEntry::Saved);
+            }
+            let time_only = entries
+                .iter()
+                .all(|e| matches!(e, JournalEntry::TimeSpent { .. }));
+            if !time_only {
+                for entry in &entries {
+                    match entry {
+                        JournalEntry::Status {
+                            test_id, status, ..
+                        } => log::info!("Test {}: {:?}", test_id, status),
+                        other => log::debug!("{:?}", other),
+                    }
+                }
+                let path = journal::journal_path(&state.results_path);
+                match journal::append(&path, &entries) {
+                    Ok(()) => self.journaled = state.results.clone(),
+                    Err(e) => {
+                        log::error!("Could not write journal {}: {}", path.display(), e);
+                        state.status_message = Some(format!("Could not write journal: {}", e));
+                        state.dirty = true;
                     }
                 }
-                Event::Resize(_, _) => {}
-                _ => {}
             }
         }
+        for (command, event) in triggered_hooks(state, &self.previous) {
+            log::info!("Running {} hook: {}", event.event, command);
+            if let Err(e) = hooks::run(&command, &event) {
+                log::error!("Could not run {} hook: {}", event.event, e);
+                state.status_message = Some(format!("Could not run {} hook: {}", event.event, e));
+            }
+        }
+        for (url, body) in webhook_requests(state, &self.previous) {
+            if let Err(e) = webhook::post(&url, &body) {
+                log::error!("Could not notify webhook {}: {}", url, e);
+                state.status_message = Some(format!("Could not notify webhook: {}", e));
+            }
+        }
+        self.previous = state.results.clone();
     }
-    Ok(())
 }
 
+/// Modification times of a results file and its journal.
+fn watched_times(results_path: &std::path::Path) -> (Option<SystemTime>, Option<SystemTime>) {
+    (
+        crate::actions::files::modified_time(results_path),
+        crate::actions::files::modified_time(&journal::journal_path(results_path)),
+    )
+}
+
+/// Load the watched results file again, with the changes journaled by the
+/// tester since its last save.
+fn reload_results(state: &mut AppState) {
+    let loaded = crate::actions::files::load_results(&state.results_path, &state.testlist);
+    let mut results = match loaded {
+        Ok(results) => results,
+        Err(e) => {
+            log::warn!("Could not reload results: {}", e);
+            state.status_message = Some(format!("Could not reload results: {}", e));
+            return;
+        }
+    };
+    let journal_path = journal::journal_path(&state.results_path);
+    if journal::has_unsaved_changes(&journal_path, &state.results_path) {
+        match journal::load(&journal_path) {
+            Ok(entries) => crate::data::journal::replay(&mut results, &entries),
+            Err(e) => log::warn!("Could not read journal {}: {}", journal_path.display(), e),
+        }
+    }
+    log::info!("Reloaded watched results {}", state.results_path.display());
+    ui_transforms::replace_results(state, results);
+}
+
+/// Whether a normal-mode key changes the results, and so is refused in
+/// observer mode.
+fn edits_results(state: &AppState, key: KeyCode, modifiers: KeyModifiers) -> bool {
+    let tests_pane = state.focused_pane == FocusedPane::Tests;
+    match key {
+        KeyCode::Char('n') => tests_pane && state.search_query.is_empty(),
+        KeyCode::Char(
+            'e' | 'A' | 'a' | 'p' | 'f' | 'i' | 's' | 'I' | '!' | 'm' | 'X' | 'R' | 'b' | 'B' | 'J'
+            | 'K',
+        ) => tests_pane,
+        KeyCode::Char('y' | 'x') => tests_pane && state.reviewer.is_some(),
+        KeyCode::Char('r') => tests_pane || modifiers.contains(KeyModifiers::CONTROL),
+        KeyCode::Char('c') => !modifiers.contains(KeyModifiers::CONTROL),
+        KeyCode::Char('u' | 'C' | 'E' | 'w') => true,
+        _ => false,
+    }
+}
+
+/// File a GitHub issue for a test in the configured repository, or the one
+/// of the `origin` remote, and return its URL.
+fn file_issue(state: &AppState, test_id: &str) -> Result<String> {
+    let repo = state
+        .github_repo
+        .clone()
+        .or_else(github::detect_repo)
+        .ok_or_else(|| {
+            Error::GitHub("no github_repo in the config file or GitHub origin remote".to_string())
+        })?;
+    let test = state.testlist.tests.iter().find(|t| t.id == test_id);
+    let result = result_for_test(&state.results, test_id);
+    let (Some(test), Some(result)) = (test, result) else {
+        return Err(Error::InvalidTestId(test_id.to_string()));
+    };
+    let body = issue::render(&state.testlist, &state.results, test, result);
+    github::create_issue(&repo, &issue::title(test), &body)
+}
+
+fn handle_paste(state: &mut AppState, text: &str, pty: &mut Option<EmbeddedTerminal>) {
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    let first_line: String = text
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect();
+    if state.editing_notes {
+        state.notes_input.insert_str(&text);
+    } else if state.adding_screenshot {
+        state.screenshot_input.push_str(&paths::pasted_path(&text));
+        state.screenshot_error = None;
+    } else if state.searching {
+        first_line
+            .chars()
+            .for_each(|c| search_transforms::search_push(state, c));
+    } else if let Some(input) = single_line_input(state) {
+        input.push_str(&first_line);
+    } else if state.focused_pane == FocusedPane::Terminal && state.copy_mode.is_none() {
+        if let Some(ref mut term) = pty {
+            term.scroll_to_bottom();
+            term.paste(&text);
+            if text.contains('\n') {
+                terminal_transforms::terminal_mark_edited(state);
+            } else {
+                terminal_transforms::terminal_type(state, &text);
+            }
+        }
+    }
+}
+
+/// The active one-line prompt other than search and the screenshot path.
+fn single_line_input(state: &mut AppState) -> Option<&mut String> {
+    if state.filtering_tags {
+        Some(&mut state.tag_filter_input)
+    } else if state.goto_active {
+        Some(&mut state.goto_input)
+    } else if state.adding_env {
+        Some(&mut state.env_input)
+    } else if state.adding_defect {
+        Some(&mut state.defect_input)
+    } else if state.adding_blocked_reason {
+        Some(&mut state.blocked_reason_input)
+    } else if let Some(input) = state.failure_capture.as_mut().and_then(|c| c.input_mut()) {
+        Some(input)
+    } else if state.review_verdict.is_some() {
+        Some(&mut state.review_input)
+    } else {
+        None
+    }
+}
+
+/// Rows scrolled per mouse wheel step.
+const WHEEL_STEP: usize = 3;
+
 fn handle_mouse(state: &mut AppState, mouse: crossterm::event::MouseEvent, areas: &LayoutAreas) {
     // Don't change focus via mouse during editing modes or modal dialogs
-    if state.editing_notes || state.adding_screenshot || state.confirm_quit || state.show_help {
+    if state.editing_notes
+        || state.adding_screenshot
+        || state.filtering_tags
+        || state.searching
+        || state.goto_active
+        || state.adding_env
+        || state.adding_defect
+        || state.adding_blocked_reason
+        || state.failure_capture.is_some()
+        || state.review_verdict.is_some()
+        || state.confirm_quit
+        || state.confirm_reload
+        || state.show_help
+        || state.show_stats
+    {
         return;
     }
 
-    // Only change focus on left click, not on scroll/motion/drag/release
+    let x = mouse.column;
+    let y = mouse.row;
+
+    // The wheel scrolls the pane under the pointer without moving focus
+    let delta = match mouse.kind {
+        MouseEventKind::ScrollUp => -(WHEEL_STEP as isize),
+        MouseEventKind::ScrollDown => WHEEL_STEP as isize,
+        _ => 0,
+    };
+    if delta != 0 {
+        if areas.tests_pane.contains((x, y).into()) {
+            navigation::scroll_tests(state, delta);
+        } else if areas.notes_pane.contains((x, y).into()) {
+            let visible = areas.notes_pane.height.saturating_sub(2) as usize;
+            let max = panes::notes::view_lines(state)
+                .len()
+                .saturating_sub(visible);
+            navigation::scroll_notes(state, delta, max);
+        }
+        return;
+    }
+
+    match (mouse.kind, state.dragging) {
+        (MouseEventKind::Drag(MouseButton::Left), Some(border)) => {
+            drag_border(state, border, x, y, areas);
+            return;
+        }
+        (MouseEventKind::Up(MouseButton::Left), Some(_)) => {
+            ui_transforms::end_drag(state);
+            return;
+        }
+        (MouseEventKind::Down(MouseButton::Left), _) => {
+            if let Some(border) = border_at(x, y, areas) {
+                ui_transforms::start_drag(state, border);
+                return;
+            }
+        }
+        _ => {}
+    }
+
+    // Only change focus on left click, not on motion/drag/release
     let MouseEventKind::Down(MouseButton::Left) = mouse.kind else {
         return;
     };
 
-    let x = mouse.column;
-    let y = mouse.row;
-
     if areas.tests_pane.contains((x, y).into()) {
         state.focused_pane = FocusedPane::Tests;
 
+        let relative_x = x.saturating_sub(areas.tests_pane.x + 1);
         let relative_y = y.saturating_sub(areas.tests_pane.y + 1) as usize;
         let absolute_y = relative_y + state.tests_scroll_offset;
 
-        if let Some(test_idx) = map_y_to_test_index(state, absolute_y) {
-            if test_idx == state.selected_test {
-                // Click on already-selected test: toggle expand/collapse
+        match map_y_to_row(state, absolute_y) {
+            Some(PaneRow::Header(PaneEntry::Test(i)))
+                if !state.read_only
+                    && panes::tests::status_icon_columns(state, &state.testlist.tests[i])
+                        .contains(&relative_x) =>
+            {
+                // Click on the status icon: cycle the test's status
+                navigation::select_entry(state, PaneEntry::Test(i));
+                test_transforms::cycle_status(state);
+                return;
+            }
+            Some(PaneRow::Checklist(i, section, item)) if !state.read_only => {
+                navigation::select_entry(state, PaneEntry::Test(i));
+                test_transforms::toggle_checklist_item(state, i, section, item);
+                return;
+            }
+            _ => {}
+        }
+
+        if let Some(entry) = map_y_to_entry(state, absolute_y) {
+            if entry == selected_entry(state) {
+                // Click on already-selected header: toggle expand/collapse
                 ui_transforms::toggle_expand(state);
             } else {
-                // Click on different test: select it
-                state.selected_test = test_idx;
+                // Click on different header: select it
+                navigation::select_entry(state, entry);
             }
         }
     } else if areas.notes_pane.contains((x, y).into()) {
@@ -115,12 +605,58 @@ fn handle_mouse(state: &mut AppState, mouse: crossterm::event::MouseEvent, areas
     }
 }
 
+/// The draggable border under the pointer, if any.
+fn border_at(x: u16, y: u16, areas: &LayoutAreas) -> Option<PaneBorder> {
+    let tests = areas.tests_pane;
+    let notes = areas.notes_pane;
+    let terminal = areas.terminal_pane;
+    // Adjacent panes each draw a border; either column or row grabs it
+    if notes.width > 0
+        && (x + 1 == tests.x + tests.width || x == notes.x)
+        && y >= tests.y
+        && y < tests.y + tests.height
+    {
+        return Some(PaneBorder::TestsNotes);
+    }
+    if terminal.height > 0
+        && tests.height > 0
+        && (y == terminal.y || y + 1 == terminal.y)
+        && x >= terminal.x
+        && x < terminal.x + terminal.width
+    {
+        return Some(PaneBorder::Terminal);
+    }
+    None
+}
+
+/// Resize panes so the dragged border follows the pointer.
+fn drag_border(state: &mut AppState, border: PaneBorder, x: u16, y: u16, areas: &LayoutAreas) {
+    match border {
+        PaneBorder::TestsNotes => {
+            let left = areas.tests_pane.x;
+            let width = areas.tests_pane.width + areas.notes_pane.width;
+            if width > 0 {
+                let percent = (x.saturating_sub(left) as u32 * 100 / width as u32) as u16;
+                ui_transforms::set_tests_width(state, percent);
+            }
+        }
+        PaneBorder::Terminal => {
+            let bottom = areas.terminal_pane.y + areas.terminal_pane.height;
+            // Leave room for a minimal tests pane above
+            let max = bottom.saturating_sub(areas.tests_pane.y + 3);
+            ui_transforms::set_terminal_height(state, bottom.saturating_sub(y), max);
+        }
+    }
+}
+
 fn handle_key(
     state: &mut AppState,
     key: KeyCode,
     modifiers: KeyModifiers,
     pty: &mut Option<EmbeddedTerminal>,
 ) {
+    state.status_message = None;
+
     // Handle quit confirmation dialog
     if state.confirm_quit {
         match key {
@@ -143,6 +679,21 @@ fn handle_key(
         return;
     }
 
+    // Handle testlist reload dialog
+    if state.confirm_reload {
+        match key {
+            KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                let effect = ui_transforms::confirm_reload(state);
+                run_effect(state, effect, pty);
+            }
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                ui_transforms::cancel_reload(state)
+            }
+            _ => {}
+        }
+        return;
+    }
+
     // Handle help popup
     if state.show_help {
         match key {
@@ -152,6 +703,51 @@ fn handle_key(
         return;
     }
 
+    // Handle statistics popup
+    if state.show_stats {
+        match key {
+            KeyCode::Char('S') | KeyCode::Esc => state.show_stats = false,
+            _ => {}
+        }
+        return;
+    }
+
+    // Handle note template choice
+    if state.choosing_note_template {
+        match key {
+            KeyCode::Char(c @ '1'..='9') => {
+                ui_transforms::choose_note_template(state, c as usize - '0' as usize);
+            }
+            _ => state.choosing_note_template = false,
+        }
+        return;
+    }
+
+    // Ctrl-t inserts a note template into the notes or a failure's reason
+    if key == KeyCode::Char('t')
+        && modifiers.contains(KeyModifiers::CONTROL)
+        && (state.editing_notes || state.failure_capture.is_some())
+    {
+        ui_transforms::start_note_template(state);
+        return;
+    }
+
+    // Ctrl-v pastes the local clipboard into text inputs, for terminals
+    // that send it through instead of pasting themselves
+    if key == KeyCode::Char('v')
+        && modifiers.contains(KeyModifiers::CONTROL)
+        && (state.editing_notes
+            || state.adding_screenshot
+            || state.searching
+            || single_line_input(state).is_some())
+    {
+        match crate::actions::clipboard::paste() {
+            Ok(text) => handle_paste(state, &text, pty),
+            Err(e) => state.status_message = Some(e.to_string()),
+        }
+        return;
+    }
+
     // Handle notes editing mode
     if state.editing_notes {
         handle_notes_editing(state, key);
@@ -164,23 +760,176 @@ fn handle_key(
         return;
     }
 
+    // Handle tag filter input mode
+    if state.filtering_tags {
+        handle_tag_filter_input(state, key);
+        return;
+    }
+
+    // Handle search input mode
+    if state.searching {
+        handle_search_input(state, key);
+        return;
+    }
+
+    // Handle jump-to-test prompt
+    if state.goto_active {
+        handle_goto_input(state, key);
+        return;
+    }
+
+    // Handle environment entry input mode
+    if state.adding_env {
+        handle_env_input(state, key);
+        return;
+    }
+
+    // Handle defect link input mode
+    if state.adding_defect {
+        handle_defect_input(state, key);
+        return;
+    }
+
+    // Handle blocked reason input mode
+    if state.adding_blocked_reason {
+        handle_blocked_reason_input(state, key);
+        return;
+    }
+
+    // Handle the failure dialog
+    if state.failure_capture.is_some() {
+        handle_failure_capture(state, key);
+        return;
+    }
+
+    // Handle review comment input mode
+    if state.review_verdict.is_some() {
+        handle_review_input(state, key);
+        return;
+    }
+
+    // Handle reference choice
+    if state.choosing_reference {
+        match key {
+            KeyCode::Char(c @ '1'..='9') => {
+                let effect = ui_transforms::choose_reference(state, c as usize - '0' as usize);
+                run_effect(state, effect, pty);
+            }
+            _ => ui_transforms::cancel_reference_choice(state),
+        }
+        return;
+    }
+
+    // Handle the choice of what to copy
+    if state.choosing_copy {
+        let effect = match key {
+            KeyCode::Char(c) => ui_transforms::choose_copy(state, c),
+            _ => {
+                state.choosing_copy = false;
+                None
+            }
+        };
+        run_effect(state, effect, pty);
+        return;
+    }
+
+    // Handle custom status choice
+    if state.choosing_custom_status {
+        match key {
+            KeyCode::Char(c @ '1'..='9') => {
+                test_transforms::choose_custom_status(state, c as usize - '0' as usize);
+            }
+            _ => state.choosing_custom_status = false,
+        }
+        return;
+    }
+
+    // Handle the offer to pass a test whose checks are all done; any other
+    // key dismisses it and is handled as usual
+    if state.suggesting_pass {
+        match key {
+            KeyCode::Char('y') | KeyCode::Enter => {
+                test_transforms::accept_suggested_pass(state);
+                return;
+            }
+            KeyCode::Char('n') | KeyCode::Esc => {
+                test_transforms::dismiss_suggested_pass(state);
+                return;
+            }
+            _ => test_transforms::dismiss_suggested_pass(state),
+        }
+    }
+
     // Handle terminal input when focused
     if state.focused_pane == FocusedPane::Terminal && pty.is_some() {
+        if state.copy_mode.is_some() {
+            handle_copy_mode(state, pty, key);
+            return;
+        }
+        if key == KeyCode::Char('v') && modifiers.contains(KeyModifiers::ALT) {
+            if let Some(ref term) = pty {
+                let screen = term.screen();
+                state.copy_mode = Some(CopyMode::new(screen.cursor_position(), screen.size()));
+            }
+            return;
+        }
         if key == KeyCode::Esc {
-            state.focused_pane = FocusedPane::Tests;
+            ui_transforms::leave_terminal(state);
+            return;
+        }
+        if key == KeyCode::Char('z') && modifiers.contains(KeyModifiers::ALT) {
+            ui_transforms::toggle_terminal_zoom(state);
             return;
         }
         if key == KeyCode::Tab {
             ui_transforms::cycle_focus(state);
             return;
         }
-        handle_terminal_input(pty, key, modifiers);
+        if key == KeyCode::Char('x') && modifiers.contains(KeyModifiers::CONTROL) {
+            if let Some(ref mut term) = pty {
+                stop_terminal_command(state, term);
+            }
+            return;
+        }
+        if key == KeyCode::Char('c') && modifiers.contains(KeyModifiers::ALT) {
+            if let Some(ref term) = pty {
+                let screen = term.screen().contents();
+                ui_transforms::capture_terminal_output(state, &screen, OUTPUT_TAIL_LINES);
+            }
+            return;
+        }
+        handle_terminal_input(state, pty, key, modifiers);
+        return;
+    }
+
+    // Keys remapped in the config file act as the key they are mapped to
+    let key = match key {
+        KeyCode::Char(c) if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+            KeyCode::Char(state.keymap.get(&c).copied().unwrap_or(c))
+        }
+        _ => key,
+    };
+
+    if state.read_only && edits_results(state, key, modifiers) {
+        state.status_message = Some("Watching: results are read-only".to_string());
         return;
     }
 
     // Normal mode — thin dispatcher calling transforms
     match key {
         KeyCode::Char('q') => ui_transforms::request_quit(state),
+        KeyCode::Char('n') if modifiers.contains(KeyModifiers::CONTROL) => {
+            let effect = ui_transforms::toggle_notes_pane(state);
+            run_effect(state, effect, pty);
+        }
+        KeyCode::Char('t') if modifiers.contains(KeyModifiers::CONTROL) => {
+            let effect = ui_transforms::toggle_terminal_pane(state);
+            run_effect(state, effect, pty);
+        }
+        KeyCode::Char('L') => {
+            let effect = ui_transforms::cycle_layout_preset(state);
+            run_effect(state, effect, pty);
+        }
         KeyCode::Tab => ui_transforms::cycle_focus(state),
         KeyCode::Up | KeyCode::Char('k') => {
             if state.focused_pane == FocusedPane::Tests {
@@ -195,6 +944,64 @@ fn handle_key(
         KeyCode::Enter | KeyCode::Char('l') | KeyCode::Char(' ') => {
             if state.focused_pane == FocusedPane::Tests {
                 ui_transforms::toggle_expand(state);
+                navigation::ensure_selection_visible(state);
+            }
+        }
+        KeyCode::Char(c @ ('+' | '-')) => {
+            if state.focused_pane == FocusedPane::Tests {
+                ui_transforms::set_all_expanded(state, c == '+');
+                navigation::ensure_selection_visible(state);
+            }
+        }
+        KeyCode::Char('K') => {
+            if state.focused_pane == FocusedPane::Tests {
+                filter_transforms::move_test(state, -1);
+            }
+        }
+        KeyCode::Char('J') => {
+            if state.focused_pane == FocusedPane::Tests {
+                filter_transforms::move_test(state, 1);
+            }
+        }
+        KeyCode::Char('F') => {
+            if state.focused_pane == FocusedPane::Tests {
+                filter_transforms::start_tag_filter(state);
+            }
+        }
+        KeyCode::Char('h') => {
+            if state.focused_pane == FocusedPane::Tests {
+                filter_transforms::toggle_hide_completed(state);
+            }
+        }
+        KeyCode::Char('v') => {
+            if state.focused_pane == FocusedPane::Tests {
+                filter_transforms::cycle_status_view(state);
+            }
+        }
+        KeyCode::Char('P') => {
+            if state.focused_pane == FocusedPane::Tests {
+                filter_transforms::toggle_priority_sort(state);
+            }
+        }
+        KeyCode::Char('/') => {
+            if state.focused_pane == FocusedPane::Tests {
+                search_transforms::start_search(state);
+            }
+        }
+        KeyCode::Char(':') if state.focused_pane == FocusedPane::Tests => {
+            navigation::start_goto(state);
+        }
+        KeyCode::Char('n') if !state.search_query.is_empty() => {
+            search_transforms::next_match(state);
+        }
+        KeyCode::Char('N') if !state.search_query.is_empty() => {
+            search_transforms::prev_match(state);
+        }
+        KeyCode::Esc if !state.search_query.is_empty() => search_transforms::clear_search(state),
+        KeyCode::Char('o') => {
+            if state.focused_pane == FocusedPane::Tests {
+                let effect = ui_transforms::request_open_reference(state);
+                run_effect(state, effect, pty);
             }
         }
         KeyCode::Char('n') => {
@@ -202,139 +1009,769 @@ fn handle_key(
                 ui_transforms::enter_notes_edit(state);
             }
         }
+        KeyCode::Char('e') => {
+            if state.focused_pane == FocusedPane::Tests {
+                let effect = ui_transforms::request_external_notes_edit(state);
+                run_effect(state, effect, pty);
+            }
+        }
+        KeyCode::Char('A') => {
+            if state.focused_pane == FocusedPane::Tests {
+                let effect = ui_transforms::request_screenshot_capture(state);
+                run_effect(state, effect, pty);
+            }
+        }
         KeyCode::Char('a') => {
             if state.focused_pane == FocusedPane::Tests {
                 ui_transforms::start_screenshot(state);
             }
         }
-        KeyCode::Char('p') => {
+        KeyCode::Char(c @ ('p' | 'f' | 'i' | 's' | 'I')) => {
+            if state.focused_pane == FocusedPane::Tests {
+                let status = match c {
+                    'p' => Status::Passed,
+                    'f' => Status::Failed,
+                    'i' => Status::Inconclusive,
+                    'I' => Status::InProgress,
+                    _ => Status::Skipped,
+                };
+                if state.visual_anchor.is_some() {
+                    test_transforms::set_status_for_selection(state, status);
+                } else if status == Status::Failed {
+                    test_transforms::mark_failed(state);
+                } else if status == Status::Inconclusive {
+                    test_transforms::mark_inconclusive(state);
+                } else {
+                    test_transforms::set_status(state, status);
+                }
+            }
+        }
+        KeyCode::Char('!') => {
             if state.focused_pane == FocusedPane::Tests {
-                test_transforms::set_status(state, crate::data::results::Status::Passed);
+                test_transforms::start_blocked_reason(state);
             }
         }
-        KeyCode::Char('f') => {
+        KeyCode::Char('m') => {
             if state.focused_pane == FocusedPane::Tests {
-                test_transforms::set_status(state, crate::data::results::Status::Failed);
+                test_transforms::start_custom_status(state);
             }
         }
-        KeyCode::Char('i') => {
+        KeyCode::Char('X') => {
             if state.focused_pane == FocusedPane::Tests {
-                test_transforms::set_status(state, crate::data::results::Status::Inconclusive);
+                test_transforms::skip_remaining(state);
+            }
+        }
+        KeyCode::Char('R') => {
+            if state.focused_pane == FocusedPane::Tests {
+                test_transforms::reset_test(state);
+            }
+        }
+        KeyCode::Char('r')
+            if state.focused_pane == FocusedPane::Tests
+                && !modifiers.contains(KeyModifiers::CONTROL) =>
+        {
+            test_transforms::mark_for_retest(state);
+        }
+        KeyCode::Char('V') => {
+            if state.focused_pane == FocusedPane::Tests {
+                test_transforms::toggle_visual(state);
+            }
+        }
+        KeyCode::Esc if state.visual_anchor.is_some() => test_transforms::cancel_visual(state),
+        KeyCode::Char('c') => {
+            let effect = current_test(state)
+                .and_then(|t| t.suggested_command.clone())
+                .map(Effect::InsertTerminalCommand);
+            run_effect(state, effect, pty);
+        }
+        KeyCode::Char('r') if modifiers.contains(KeyModifiers::CONTROL) => {
+            history_transforms::redo(state);
+        }
+        KeyCode::Char('u') => history_transforms::undo(state),
+        KeyCode::Char('x') if modifiers.contains(KeyModifiers::CONTROL) => {
+            if let Some(ref mut term) = pty {
+                stop_terminal_command(state, term);
+            }
+        }
+        KeyCode::Char('C') => {
+            let effect = test_transforms::request_run_command(state);
+            run_effect(state, effect, pty);
+        }
+        KeyCode::Char('E') => ui_transforms::start_env_input(state),
+        KeyCode::Char('b') if state.focused_pane == FocusedPane::Tests => {
+            ui_transforms::start_defect_input(state)
+        }
+        KeyCode::Char('B') if state.focused_pane == FocusedPane::Tests => {
+            let effect = ui_transforms::request_issue(state);
+            run_effect(state, effect, pty);
+        }
+        KeyCode::Char(c @ ('y' | 'x'))
+            if state.reviewer.is_some() && state.focused_pane == FocusedPane::Tests =>
+        {
+            let verdict = if c == 'y' {
+                Verdict::Approved
+            } else {
+                Verdict::Rejected
+            };
+            ui_transforms::start_review(state, verdict);
+        }
+        KeyCode::Char('y') if state.focused_pane == FocusedPane::Tests => {
+            ui_transforms::start_copy(state);
+        }
+        KeyCode::Char('z') => ui_transforms::toggle_terminal_zoom(state),
+        KeyCode::Char('t') => ui_transforms::toggle_theme(state),
+        KeyCode::Char('?') => state.show_help = true,
+        KeyCode::Char('S') => state.show_stats = true,
+        KeyCode::Char('w') => run_effect(state, Some(Effect::SaveResults), pty),
+        _ => {}
+    }
+}
+
+/// Interrupt (or, if already interrupted, kill) the terminal's foreground
+/// command. A run started with `C` is recorded with the signal's exit code.
+fn stop_terminal_command(state: &mut AppState, term: &mut EmbeddedTerminal) {
+    match term.stop_foreground() {
+        Ok(Some(signal)) => {
+            if state.running_command.is_some() {
+                let output = term.cancel_capture().unwrap_or_default();
+                test_transforms::finish_command_run(
+                    state,
+                    signal.exit_code(),
+                    output_tail(&output, OUTPUT_TAIL_LINES),
+                );
+            }
+            state.status_message = Some(format!("Sent SIG{} to the command", signal.name()));
+        }
+        Ok(None) => state.status_message = Some("No command running".to_string()),
+        Err(e) => {
+            log::error!("Could not stop terminal command: {}", e);
+            state.status_message = Some(e.to_string())
+        }
+    }
+}
+
+/// Run the test's script, if it has one, on the outcome of its command.
+fn run_test_script(state: &mut AppState, test_id: &str, exit_code: i32, output: &str) {
+    let Some(source) = state
+        .testlist
+        .tests
+        .iter()
+        .find(|t| t.id == test_id)
+        .and_then(|t| t.script.clone())
+    else {
+        return;
+    };
+    let outcome = script::CommandOutcome {
+        test_id,
+        exit_code,
+        output,
+    };
+    match script::run(&source, &state.testlist, &state.results, Some(&outcome)) {
+        Ok(actions) => test_transforms::apply_script_actions(state, &actions),
+        Err(e) => {
+            log::error!("Script of test {} failed: {}", test_id, e);
+            state.status_message = Some(e.to_string());
+        }
+    }
+}
+
+/// Interrupt a run that exceeded the command timeout and record it as such.
+fn time_out_command(state: &mut AppState, term: &mut EmbeddedTerminal) {
+    let Some(running) = state.running_command.clone() else {
+        return;
+    };
+    log::warn!(
+        "Command timed out after {}s: {}",
+        running.started_at.elapsed().as_secs(),
+        running.command
+    );
+    if let Err(e) = term.stop_foreground() {
+        log::error!("Could not interrupt timed-out command: {}", e);
+    }
+    let output = term.cancel_capture().unwrap_or_default();
+    test_transforms::finish_command_run(
+        state,
+        Signal::Interrupt.exit_code(),
+        output_tail(&output, OUTPUT_TAIL_LINES),
+    );
+    state.status_message = Some(format!(
+        "`{}` timed out after {}s",
+        running.command,
+        running.started_at.elapsed().as_secs()
+    ));
+}
+
+/// Execute a side effect requested by a transform.
+fn run_effect(state: &mut AppState, effect: Option<Effect>, pty: &mut Option<EmbeddedTerminal>) {
+    match effect {
+        Some(Effect::ReloadTestlist) => {
+            let loaded = if state.strict {
+                crate::actions::files::load_testlist_strict(
+                    &state.testlist_path,
+                    state.testlist_format,
+                )
+            } else {
+                crate::actions::files::load_testlist_as(&state.testlist_path, state.testlist_format)
+            };
+            match loaded {
+                Ok(testlist) => {
+                    log::info!(
+                        "Reloaded testlist {} ({} tests)",
+                        state.testlist_path.display(),
+                        testlist.tests.len()
+                    );
+                    test_transforms::replace_testlist(state, testlist)
+                }
+                Err(e) => {
+                    log::error!("Could not reload testlist: {}", e);
+                    state.status_message = Some(format!("Could not reload testlist: {}", e))
+                }
+            }
+        }
+        Some(Effect::CopyToClipboard(text)) => {
+            state.status_message = Some(match crate::actions::clipboard::copy(&text) {
+                Ok(()) => format!("Copied '{}'", text),
+                Err(e) => format!("Could not copy: {}", e),
+            });
+        }
+        Some(Effect::OpenExternal(target)) => {
+            if let Err(e) = crate::actions::open::open_external(&target) {
+                state.status_message = Some(format!("Could not open {}: {}", target, e));
+            }
+        }
+        Some(Effect::EditNotesExternally { test_id, notes }) => {
+            match app::suspend(|| crate::actions::editor::edit_text(&notes)) {
+                Ok(edited) => ui_transforms::apply_external_notes(state, &test_id, &edited),
+                Err(e) => state.status_message = Some(e.to_string()),
+            }
+            state.needs_full_redraw = true;
+        }
+        Some(Effect::CaptureScreenshot { test_id, path }) => {
+            let command = state.screenshot_command.clone();
+            match crate::actions::screenshot::capture(command.as_deref(), &path) {
+                Ok(()) => {
+                    let path = if state.copy_attachments {
+                        crate::actions::files::relative_to_results(&path, &state.results_path)
+                    } else {
+                        path
+                    };
+                    ui_transforms::attach_screenshot(state, &test_id, path)
+                }
+                Err(e) => state.status_message = Some(e.to_string()),
+            }
+        }
+        Some(Effect::CreateIssue { test_id }) => match file_issue(state, &test_id) {
+            Ok(url) => {
+                log::info!("Filed issue {} for {}", url, test_id);
+                ui_transforms::link_defect(state, &test_id, &url);
+                state.status_message = Some(format!("Filed issue {}", url));
+            }
+            Err(e) => {
+                log::error!("Could not file issue for {}: {}", test_id, e);
+                state.status_message = Some(e.to_string());
+            }
+        },
+        Some(Effect::RunTerminalCommand(cmd)) => match pty {
+            Some(ref mut term) => {
+                log::info!("Running command: {}", cmd);
+                if let Err(e) = term.run_command(&cmd) {
+                    log::error!("Could not run command {}: {}", cmd, e);
+                    state.running_command = None;
+                    state.status_message = Some(format!("Could not run `{}`: {}", cmd, e));
+                    return;
+                }
+                terminal_transforms::record_command(state, &cmd);
+                terminal_transforms::terminal_discard_line(state);
+                state.focused_pane = FocusedPane::Terminal;
+            }
+            None => {
+                log::warn!("No embedded terminal to run command: {}", cmd);
+                state.running_command = None;
+                state.status_message = Some("No embedded terminal available".to_string());
+            }
+        },
+        Some(Effect::InsertTerminalCommand(cmd)) => {
+            if let Some(ref mut term) = pty {
+                if term.is_fallback() {
+                    state.status_message =
+                        Some("No PTY to type into: run the command with C".to_string());
+                    return;
+                }
+                term.send_str(&cmd);
+                terminal_transforms::terminal_type(state, &cmd);
+                state.focused_pane = FocusedPane::Terminal;
+            }
+        }
+        Some(Effect::SaveLayout(layout)) => {
+            if let Some(path) = config::config_path() {
+                let saved = config::load_config(&path).and_then(|mut config| {
+                    config.layout = layout;
+                    config::save_config(&path, &config)
+                });
+                if let Err(e) = saved {
+                    state.status_message = Some(format!("Could not save layout: {}", e));
+                }
+            }
+        }
+        Some(Effect::SaveResults | Effect::Autosave) if state.append_only => {
+            // The event loop journals this iteration's changes, then marks them saved
+            state.results.end_session(&state.testlist);
+            log::info!(
+                "Saved results to the journal of {}",
+                state.results_path.display()
+            );
+            state.journal_save_pending = true;
+            state.dirty = false;
+        }
+        Some(effect @ (Effect::SaveResults | Effect::Autosave)) => {
+            state.results.end_session(&state.testlist);
+            let backups = if effect == Effect::Autosave && state.results_backed_up {
+                0
+            } else {
+                state.backup_count
+            };
+            match crate::actions::files::save_results(&state.results, &state.results_path, backups)
+            {
+                Ok(()) => {
+                    log::info!("Saved results to {}", state.results_path.display());
+                    state.results_backed_up = true;
+                    state.dirty = false;
+                    let _ = journal::clear(&journal::journal_path(&state.results_path));
+                    // Autosaves stay unsigned; `w` and quitting sign what they leave on disk
+                    if let (Effect::SaveResults, Some(signing)) = (effect, &state.signing) {
+                        if let Err(e) = crate::actions::signing::sign(&state.results_path, signing)
+                        {
+                            log::error!("Could not sign results: {}", e);
+                            state.status_message = Some(format!("Saved but not signed: {}", e));
+                        }
+                    }
+                }
+                Err(e) => {
+                    log::error!(
+                        "Could not save results to {}: {}",
+                        state.results_path.display(),
+                        e
+                    );
+                    state.status_message = Some(format!("Could not save results: {}", e));
+                }
+            }
+        }
+        Some(Effect::Quit) => state.should_quit = true,
+        None => {}
+    }
+}
+
+fn handle_terminal_input(
+    state: &mut AppState,
+    pty: &mut Option<EmbeddedTerminal>,
+    key: KeyCode,
+    modifiers: KeyModifiers,
+) {
+    let Some(ref mut term) = pty else { return };
+
+    let page = term.screen().size().0 as usize;
+    match key {
+        KeyCode::PageUp => return term.scroll_up(page),
+        KeyCode::PageDown => return term.scroll_down(page),
+        _ => term.scroll_to_bottom(),
+    }
+    // The fallback runner's output can be scrolled, but there's no shell to type into
+    if term.is_fallback() {
+        return;
+    }
+
+    match key {
+        KeyCode::Char(c) => {
+            if modifiers.contains(KeyModifiers::CONTROL) && c.is_ascii() {
+                term.send_key(&[c as u8 & 0x1f]);
+                match c {
+                    'c' | 'u' => terminal_transforms::terminal_discard_line(state),
+                    _ => terminal_transforms::terminal_mark_edited(state),
+                }
+            } else if modifiers.contains(KeyModifiers::ALT) {
+                // Meta sends ESC before the character
+                term.send_str(&format!("\x1b{}", c));
+                terminal_transforms::terminal_mark_edited(state);
+            } else {
+                term.send_char(c);
+                terminal_transforms::terminal_type(state, c.encode_utf8(&mut [0u8; 4]));
+            }
+        }
+        KeyCode::Enter => {
+            let screen = term.screen();
+            let (row, _) = screen.cursor_position();
+            let (_, cols) = screen.size();
+            let line = screen.rows(0, cols).nth(row as usize).unwrap_or_default();
+            terminal_transforms::terminal_submit(state, &line);
+            term.send_key(b"\r");
+        }
+        KeyCode::Backspace => {
+            term.send_key(b"\x7f");
+            terminal_transforms::terminal_backspace(state);
+        }
+        _ => {
+            if let Some(sequence) = key_sequence(key, modifiers) {
+                term.send_key(&sequence);
+                terminal_transforms::terminal_mark_edited(state);
+            }
+        }
+    }
+}
+
+fn handle_copy_mode(state: &mut AppState, pty: &mut Option<EmbeddedTerminal>, key: KeyCode) {
+    let (Some(ref mut term), Some(ref mut mode)) = (pty, state.copy_mode.as_mut()) else {
+        return;
+    };
+    let (rows, cols) = term.screen().size();
+    match key {
+        KeyCode::Left | KeyCode::Char('h') => mode.move_by(0, -1),
+        KeyCode::Right | KeyCode::Char('l') => mode.move_by(0, 1),
+        KeyCode::Up | KeyCode::Char('k') => mode.move_by(-1, 0),
+        KeyCode::Down | KeyCode::Char('j') => mode.move_by(1, 0),
+        KeyCode::Home | KeyCode::Char('0') => mode.move_to(mode.cursor.0, 0),
+        KeyCode::End | KeyCode::Char('$') => mode.move_to(mode.cursor.0, cols),
+        KeyCode::Char('g') => mode.move_to(0, mode.cursor.1),
+        KeyCode::Char('G') => mode.move_to(rows, mode.cursor.1),
+        KeyCode::PageUp => term.scroll_up(rows as usize),
+        KeyCode::PageDown => term.scroll_down(rows as usize),
+        KeyCode::Char('v') => mode.toggle_selection(false),
+        KeyCode::Char('V') => mode.toggle_selection(true),
+        KeyCode::Char('y') => {
+            let text = mode.selected_text(&visible_rows(term.screen()));
+            state.copy_mode = None;
+            state.status_message = Some(match crate::actions::clipboard::copy(&text) {
+                Ok(()) => "Copied selection to clipboard".to_string(),
+                Err(e) => e.to_string(),
+            });
+        }
+        KeyCode::Char('n') => {
+            let text = mode.selected_text(&visible_rows(term.screen()));
+            state.copy_mode = None;
+            ui_transforms::yank_to_notes(state, &text);
+        }
+        KeyCode::Esc | KeyCode::Char('q') => state.copy_mode = None,
+        _ => {}
+    }
+}
+
+fn handle_notes_editing(state: &mut AppState, key: KeyCode) {
+    match key {
+        KeyCode::Esc => ui_transforms::save_notes(state),
+        KeyCode::Enter => state.notes_input.newline(),
+        KeyCode::Backspace => state.notes_input.backspace(),
+        KeyCode::Delete => state.notes_input.delete(),
+        KeyCode::Left => state.notes_input.move_left(),
+        KeyCode::Right => state.notes_input.move_right(),
+        KeyCode::Up => state.notes_input.move_up(),
+        KeyCode::Down => state.notes_input.move_down(),
+        KeyCode::Home => state.notes_input.move_home(),
+        KeyCode::End => state.notes_input.move_end(),
+        KeyCode::Char(c) => state.notes_input.insert_char(c),
+        _ => {}
+    }
+}
+
+fn handle_screenshot_input(state: &mut AppState, key: KeyCode) {
+    match key {
+        KeyCode::Esc => ui_transforms::cancel_screenshot(state),
+        KeyCode::Tab => {
+            let candidates = paths::complete(&state.screenshot_input);
+            ui_transforms::complete_screenshot_input(state, &candidates);
+        }
+        KeyCode::Enter => {
+            let path = paths::expand_tilde(&state.screenshot_input);
+            if state.screenshot_input.is_empty() {
+                ui_transforms::confirm_screenshot(state);
+            } else if path.is_file() {
+                let stored = if state.copy_attachments {
+                    crate::actions::files::copy_attachment(&path, &state.results_path)
+                } else {
+                    Ok(path)
+                };
+                match stored {
+                    Ok(stored) => {
+                        state.screenshot_input = stored.to_string_lossy().into_owned();
+                        ui_transforms::confirm_screenshot(state);
+                    }
+                    Err(e) => state.screenshot_error = Some(e.to_string()),
+                }
+            } else {
+                ui_transforms::reject_screenshot(state);
             }
         }
-        KeyCode::Char('s') => {
-            if state.focused_pane == FocusedPane::Tests {
-                test_transforms::set_status(state, crate::data::results::Status::Skipped);
-            }
+        KeyCode::Backspace => {
+            state.screenshot_input.pop();
+            state.screenshot_error = None;
         }
-        KeyCode::Char('c') => {
-            let cmd = current_test(state).and_then(|t| t.suggested_command.clone());
-            if let Some(cmd) = cmd {
-                if let Some(ref mut term) = pty {
-                    term.send_str(&cmd);
-                    state.focused_pane = FocusedPane::Terminal;
-                }
-            }
+        KeyCode::Char(c) => {
+            state.screenshot_input.push(c);
+            state.screenshot_error = None;
         }
-        KeyCode::Char('t') => ui_transforms::toggle_theme(state),
-        KeyCode::Char('?') => state.show_help = true,
-        KeyCode::Char('w') => {
-            if let Ok(()) = crate::actions::files::save_results(&state.results, &state.results_path)
-            {
-                state.dirty = false;
-            }
+        _ => {}
+    }
+}
+
+fn handle_tag_filter_input(state: &mut AppState, key: KeyCode) {
+    match key {
+        KeyCode::Esc => filter_transforms::cancel_tag_filter(state),
+        KeyCode::Enter => filter_transforms::confirm_tag_filter(state),
+        KeyCode::Backspace => {
+            state.tag_filter_input.pop();
         }
+        KeyCode::Char(c) => state.tag_filter_input.push(c),
         _ => {}
     }
 }
 
-fn handle_terminal_input(
-    pty: &mut Option<EmbeddedTerminal>,
-    key: KeyCode,
-    modifiers: KeyModifiers,
-) {
-    let Some(ref mut term) = pty else { return };
+fn handle_search_input(state: &mut AppState, key: KeyCode) {
+    match key {
+        KeyCode::Esc => search_transforms::cancel_search(state),
+        KeyCode::Enter => search_transforms::confirm_search(state),
+        KeyCode::Backspace => search_transforms::search_pop(state),
+        KeyCode::Char(c) => search_transforms::search_push(state, c),
+        _ => {}
+    }
+}
 
+fn handle_goto_input(state: &mut AppState, key: KeyCode) {
     match key {
-        KeyCode::Char(c) => {
-            if modifiers.contains(KeyModifiers::CONTROL) {
-                let ctrl_char = (c as u8).wrapping_sub(b'a').wrapping_add(1);
-                term.send_key(&[ctrl_char]);
-            } else {
-                term.send_char(c);
+        KeyCode::Esc => navigation::cancel_goto(state),
+        KeyCode::Enter => navigation::confirm_goto(state),
+        KeyCode::Backspace => {
+            state.goto_input.pop();
+        }
+        KeyCode::Char(c) => state.goto_input.push(c),
+        _ => {}
+    }
+}
+
+fn handle_review_input(state: &mut AppState, key: KeyCode) {
+    match key {
+        KeyCode::Esc => ui_transforms::cancel_review_input(state),
+        KeyCode::Enter => ui_transforms::confirm_review_input(state),
+        KeyCode::Backspace => {
+            state.review_input.pop();
+        }
+        KeyCode::Char(c) => state.review_input.push(c),
+        _ => {}
+    }
+}
+
+fn handle_blocked_reason_input(state: &mut AppState, key: KeyCode) {
+    match key {
+        KeyCode::Esc => test_transforms::cancel_blocked_reason(state),
+        KeyCode::Enter => test_transforms::confirm_blocked_reason(state),
+        KeyCode::Backspace => {
+            state.blocked_reason_input.pop();
+        }
+        KeyCode::Char(c) => state.blocked_reason_input.push(c),
+        _ => {}
+    }
+}
+
+fn handle_failure_capture(state: &mut AppState, key: KeyCode) {
+    let Some(capture) = state.failure_capture.as_mut() else {
+        return;
+    };
+    let field = capture.field;
+    match (field, key) {
+        (_, KeyCode::Esc) => test_transforms::cancel_failure_capture(state),
+        (_, KeyCode::Enter) => {
+            if let Err(message) = store_capture_screenshot(state) {
+                state.status_message = Some(message);
+                return;
+            }
+            test_transforms::confirm_failure_capture(state);
+        }
+        (_, KeyCode::Tab | KeyCode::Down) => test_transforms::cycle_capture_field(state, false),
+        (_, KeyCode::BackTab | KeyCode::Up) => test_transforms::cycle_capture_field(state, true),
+        (_, KeyCode::Backspace) => {
+            capture.input_mut().map(String::pop);
+        }
+        (CaptureField::Severity, KeyCode::Char(c @ '1'..='4')) => {
+            let severity = Severity::ALL[c as usize - '1' as usize];
+            test_transforms::set_capture_severity(state, severity);
+        }
+        (CaptureField::Severity, KeyCode::Left | KeyCode::Right) => {
+            let current = state
+                .failure_capture
+                .as_ref()
+                .and_then(|c| c.severity)
+                .and_then(|s| Severity::ALL.iter().position(|&x| x == s));
+            let n = Severity::ALL.len();
+            let next = match (current, key) {
+                (None, _) => 0,
+                (Some(i), KeyCode::Left) => (i + n - 1) % n,
+                (Some(i), _) => (i + 1) % n,
+            };
+            test_transforms::set_capture_severity(state, Severity::ALL[next]);
+        }
+        (_, KeyCode::Char(c)) => {
+            if let Some(input) = capture.input_mut() {
+                input.push(c);
             }
         }
-        KeyCode::Enter => term.send_key(b"\r"),
-        KeyCode::Backspace => term.send_key(b"\x7f"),
-        KeyCode::Delete => term.send_key(b"\x1b[3~"),
-        KeyCode::Up => term.send_key(b"\x1b[A"),
-        KeyCode::Down => term.send_key(b"\x1b[B"),
-        KeyCode::Right => term.send_key(b"\x1b[C"),
-        KeyCode::Left => term.send_key(b"\x1b[D"),
-        KeyCode::Home => term.send_key(b"\x1b[H"),
-        KeyCode::End => term.send_key(b"\x1b[F"),
         _ => {}
     }
 }
 
-fn handle_notes_editing(state: &mut AppState, key: KeyCode) {
+/// Check the failure dialog's screenshot path before the failure is
+/// marked, copying the file next to the results when attachments are
+/// copied. Nothing is checked while other details are still missing.
+fn store_capture_screenshot(state: &mut AppState) -> std::result::Result<(), String> {
+    let Some(capture) = state.failure_capture.as_ref() else {
+        return Ok(());
+    };
+    if capture.screenshot.is_empty() || missing_capture_detail(state, capture).is_some() {
+        return Ok(());
+    }
+    let path = paths::expand_tilde(&capture.screenshot);
+    if !path.is_file() {
+        return Err(format!("File not found: {}", capture.screenshot));
+    }
+    let stored = if state.copy_attachments {
+        crate::actions::files::copy_attachment(&path, &state.results_path)
+            .map_err(|e| e.to_string())?
+    } else {
+        path
+    };
+    if let Some(capture) = state.failure_capture.as_mut() {
+        capture.screenshot = stored.to_string_lossy().into_owned();
+    }
+    Ok(())
+}
+
+fn handle_defect_input(state: &mut AppState, key: KeyCode) {
     match key {
-        KeyCode::Esc => ui_transforms::save_notes(state),
-        KeyCode::Enter => state.notes_input.push('\n'),
+        KeyCode::Esc => ui_transforms::cancel_defect_input(state),
+        KeyCode::Enter => ui_transforms::confirm_defect_input(state),
         KeyCode::Backspace => {
-            state.notes_input.pop();
+            state.defect_input.pop();
         }
-        KeyCode::Char(c) => state.notes_input.push(c),
+        KeyCode::Char(c) => state.defect_input.push(c),
         _ => {}
     }
 }
 
-fn handle_screenshot_input(state: &mut AppState, key: KeyCode) {
+fn handle_env_input(state: &mut AppState, key: KeyCode) {
     match key {
-        KeyCode::Esc => ui_transforms::cancel_screenshot(state),
-        KeyCode::Enter => ui_transforms::confirm_screenshot(state),
+        KeyCode::Esc => ui_transforms::cancel_env_input(state),
+        KeyCode::Enter => ui_transforms::confirm_env_input(state),
         KeyCode::Backspace => {
-            state.screenshot_input.pop();
+            state.env_input.pop();
         }
-        KeyCode::Char(c) => state.screenshot_input.push(c),
+        KeyCode::Char(c) => state.env_input.push(c),
         _ => {}
     }
 }
 
-fn draw(frame: &mut Frame, state: &AppState, pty: &Option<EmbeddedTerminal>) -> LayoutAreas {
+fn draw(
+    frame: &mut Frame,
+    state: &AppState,
+    pty: &Option<EmbeddedTerminal>,
+    show_preview: bool,
+) -> LayoutAreas {
     let size = frame.area();
 
+    if state.terminal_zoomed {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(1)])
+            .split(size);
+        panes::terminal::draw(frame, state, pty, chunks[0]);
+        draw_status_bar(frame, state, chunks[1]);
+        draw_dialogs(frame, state, size);
+        return LayoutAreas {
+            tests_pane: Rect::default(),
+            notes_pane: Rect::default(),
+            terminal_pane: chunks[0],
+            preview: None,
+        };
+    }
+
+    let terminal_height = if terminal_visible(state) {
+        state.terminal_height
+    } else {
+        0
+    };
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Min(3),
-            Constraint::Length(8),
+            Constraint::Length(terminal_height),
+            Constraint::Length(1),
             Constraint::Length(1),
         ])
         .split(size);
 
+    let notes_width = if notes_visible(state) {
+        100 - state.tests_width_percent
+    } else {
+        0
+    };
     let top_chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .constraints([
+            Constraint::Percentage(100 - notes_width),
+            Constraint::Percentage(notes_width),
+        ])
         .split(main_chunks[0]);
 
     panes::tests::draw(frame, state, top_chunks[0]);
-    panes::notes::draw(frame, state, top_chunks[1]);
-    panes::terminal::draw(frame, state, pty, main_chunks[1]);
-    draw_status_bar(frame, state, main_chunks[2]);
-
-    if state.confirm_quit {
-        draw_quit_dialog(frame, state, size);
+    if notes_visible(state) {
+        panes::notes::draw(frame, state, top_chunks[1]);
     }
 
-    if state.show_help {
-        draw_help_dialog(frame, state, size);
+    // Modal dialogs would be covered by an image drawn outside ratatui
+    let modal_open =
+        state.confirm_quit || state.confirm_reload || state.show_help || state.show_stats;
+    let preview = preview_path(state)
+        .filter(|_| show_preview && !modal_open && !state.editing_notes)
+        .filter(|_| notes_visible(state) && top_chunks[1].height > preview::PREVIEW_HEIGHT + 4)
+        .map(|path| {
+            let notes = top_chunks[1];
+            let area = Rect::new(
+                notes.x + 1,
+                notes.y + notes.height - 1 - preview::PREVIEW_HEIGHT,
+                notes.width.saturating_sub(2),
+                preview::PREVIEW_HEIGHT,
+            );
+            preview::draw(frame, state, path, area)
+        });
+    if terminal_visible(state) {
+        panes::terminal::draw(frame, state, pty, main_chunks[1]);
     }
+    panes::progress::draw(frame, state, main_chunks[2]);
+    draw_status_bar(frame, state, main_chunks[3]);
+    draw_dialogs(frame, state, size);
 
     LayoutAreas {
         tests_pane: top_chunks[0],
         notes_pane: top_chunks[1],
         terminal_pane: main_chunks[1],
+        preview,
+    }
+}
+
+fn draw_dialogs(frame: &mut Frame, state: &AppState, area: Rect) {
+    if state.confirm_quit {
+        draw_quit_dialog(frame, state, area);
+    }
+
+    if state.confirm_reload {
+        draw_reload_dialog(frame, state, area);
+    }
+
+    if let Some(capture) = &state.failure_capture {
+        draw_failure_capture_dialog(frame, state, capture, area);
+    }
+
+    if state.show_help {
+        draw_help_dialog(frame, state, area);
+    }
+
+    if state.show_stats {
+        draw_stats_dialog(frame, state, area);
     }
 }
 
@@ -394,37 +1831,220 @@ fn draw_quit_dialog(frame: &mut Frame, state: &AppState, area: Rect) {
     frame.render_widget(dialog, dialog_area);
 }
 
-fn draw_help_dialog(frame: &mut Frame, state: &AppState, area: Rect) {
+fn draw_reload_dialog(frame: &mut Frame, state: &AppState, area: Rect) {
+    use ratatui::text::Span;
+
     let theme = state.theme;
-    let dialog_width = 54u16;
-    let dialog_height = 19u16;
+    let dialog_width = 44;
+    let dialog_height = 5;
+    let x = area.width.saturating_sub(dialog_width) / 2;
+    let y = area.height.saturating_sub(dialog_height) / 2;
+    let dialog_area = Rect::new(x, y, dialog_width, dialog_height);
+
+    frame.render_widget(Clear, dialog_area);
+
+    let text = vec![
+        Line::from(""),
+        Line::from(" The testlist file changed on disk."),
+        Line::from(Span::styled(
+            " Reload it? (y/n)",
+            Style::default().fg(theme.accent()),
+        )),
+    ];
+
+    let dialog = Paragraph::new(text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(ratatui::style::Color::Yellow))
+                .title(" Testlist Changed "),
+        )
+        .style(Style::default().bg(theme.bg()).fg(theme.fg()));
+
+    frame.render_widget(dialog, dialog_area);
+}
+
+/// The end of `text` that fits in `width` display columns.
+fn fit_tail(text: &str, width: usize) -> String {
+    let mut used = 0;
+    let start = text
+        .grapheme_indices(true)
+        .rev()
+        .take_while(|(_, g)| {
+            used += g.width();
+            used <= width
+        })
+        .last()
+        .map_or(text.len(), |(i, _)| i);
+    text[start..].to_string()
+}
+
+/// Lines of the failure dialog's reason shown at most.
+const CAPTURE_REASON_LINES: usize = 6;
+
+fn draw_failure_capture_dialog(
+    frame: &mut Frame,
+    state: &AppState,
+    capture: &FailureCapture,
+    area: Rect,
+) {
+    use ratatui::text::Span;
+
+    let theme = state.theme;
+    let dialog_width = 60.min(area.width);
+    // Show the end of text too long for the dialog
+    let shown = dialog_width.saturating_sub(7) as usize;
+    // A reason from a note template spans lines; show its last few
+    let input_lines = |field: CaptureField, input: &str| {
+        let lines: Vec<&str> = input.split('\n').collect();
+        let first = lines.len().saturating_sub(CAPTURE_REASON_LINES);
+        let last = lines.len() - 1;
+        lines[first..]
+            .iter()
+            .enumerate()
+            .map(|(i, line)| {
+                let line = fit_tail(line, shown);
+                let prompt = if i == 0 { ">" } else { " " };
+                let cursor = if capture.field == field && first + i == last {
+                    "█"
+                } else {
+                    ""
+                };
+                Line::from(format!("  {} {}{}", prompt, line, cursor))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut text = vec![];
+    for &field in capture.fields() {
+        let (name, required) = match field {
+            CaptureField::Note => ("Reason", note_required(state, capture.status)),
+            CaptureField::Severity => ("Severity", severity_required(state, capture.status)),
+            CaptureField::Defect => ("Defect ID or URL", false),
+            CaptureField::Screenshot => ("Screenshot path", false),
+        };
+        let name_style = if capture.field == field {
+            Style::default().fg(theme.accent())
+        } else {
+            Style::default()
+        };
+        text.push(Line::from(""));
+        text.push(Line::from(vec![
+            Span::styled(format!("  {}", name), name_style),
+            Span::styled(
+                if required { " (required)" } else { "" },
+                Style::default().fg(theme.dim()),
+            ),
+        ]));
+        text.extend(match field {
+            CaptureField::Note => input_lines(field, &capture.note),
+            CaptureField::Defect => input_lines(field, &capture.defect),
+            CaptureField::Screenshot => input_lines(field, &capture.screenshot),
+            CaptureField::Severity => vec![Line::from(
+                std::iter::once(Span::raw(" "))
+                    .chain(Severity::ALL.iter().enumerate().map(|(i, &severity)| {
+                        let item = format!(" [{}] {}", i + 1, severity.label());
+                        if capture.severity == Some(severity) {
+                            Span::styled(
+                                item,
+                                Style::default().fg(theme.status_color(Status::Failed)),
+                            )
+                        } else {
+                            Span::raw(item)
+                        }
+                    }))
+                    .collect::<Vec<_>>(),
+            )],
+        });
+    }
+    let hint = if note_templates(state).is_empty() {
+        "  Tab next field   Enter save   Esc cancel"
+    } else {
+        "  Tab field   Ctrl-t template   Enter save   Esc cancel"
+    };
+    text.push(Line::from(""));
+    text.push(Line::from(Span::styled(
+        hint,
+        Style::default().fg(theme.dim()),
+    )));
+
+    let dialog_height = text.len() as u16 + 2;
     let x = area.width.saturating_sub(dialog_width) / 2;
     let y = area.height.saturating_sub(dialog_height) / 2;
     let dialog_area = Rect::new(x, y, dialog_width, dialog_height);
 
     frame.render_widget(Clear, dialog_area);
 
+    let title = if capture.status == Status::Failed {
+        " Mark Failed "
+    } else {
+        " Mark Inconclusive "
+    };
+    let dialog = Paragraph::new(text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.status_color(capture.status)))
+                .title(title),
+        )
+        .style(Style::default().bg(theme.bg()).fg(theme.fg()));
+
+    frame.render_widget(dialog, dialog_area);
+}
+
+fn draw_help_dialog(frame: &mut Frame, state: &AppState, area: Rect) {
+    let theme = state.theme;
     let text = vec![
         Line::from(""),
         Line::from(" Navigation"),
         Line::from("   j/k or ↑/↓   Navigate tests"),
-        Line::from("   Enter/Space   Expand/collapse test"),
+        Line::from("   Enter/Space   Expand/collapse test or section"),
+        Line::from("   +/-           Expand/collapse all (or the section's)"),
         Line::from("   Tab           Cycle pane focus"),
+        Line::from("   F             Filter by tag"),
+        Line::from("   h  Hide completed   v  All/pending/failed/blocked"),
+        Line::from("   P             Sort by priority"),
+        Line::from("   J/K           Move test down/up"),
+        Line::from("   /  n/N        Search, next/previous match"),
+        Line::from("   :             Go to test by ID or number"),
         Line::from(""),
         Line::from(" Test Status"),
-        Line::from("   p  Pass    f  Fail"),
+        Line::from("   p  Pass    f  Fail, with reason, severity, defect"),
         Line::from("   i  Inconclusive    s  Skip"),
+        Line::from("   I  In progress     !  Blocked, with a reason"),
+        Line::from("   m  Custom status from the testlist, then 1-9"),
+        Line::from("   R  Reset to pending  X  Skip all remaining"),
+        Line::from("   r  Mark a failure for retest"),
+        Line::from("   V  Visual mode: select a range, then p/f/i/s"),
         Line::from(""),
         Line::from(" Actions"),
         Line::from("   n  Edit notes       a  Add screenshot"),
-        Line::from("   c  Run suggested command"),
+        Line::from("   e  Edit notes in $EDITOR  A  Capture screenshot"),
+        Line::from("   Ctrl-t  Insert note template (while writing)"),
+        Line::from("   c  Paste suggested command   C  Run and record it"),
+        Line::from("   o  Open reference   E  Record environment"),
+        Line::from("   b  Link defect to a failure   B  File GitHub issue"),
+        Line::from("   y  Copy ID, title, or command (then i/t/c)"),
+        Line::from("   y/x  Approve/reject (review mode)"),
+        Line::from("   Alt-c  Copy terminal output into notes"),
         Line::from(""),
         Line::from(" Other"),
+        Line::from("   u  Undo     Ctrl-r  Redo"),
         Line::from("   w  Save     t  Theme     ?  Help     q  Quit"),
+        Line::from("   S  Session statistics   z  Zoom terminal (Alt-z)"),
+        Line::from("   L  Layout preset   Ctrl-n/Ctrl-t  Hide notes/terminal"),
         Line::from(""),
         Line::from(" Press ? or Esc to close"),
     ];
 
+    let dialog_width = 54u16;
+    let dialog_height = text.len() as u16 + 2;
+    let x = area.width.saturating_sub(dialog_width) / 2;
+    let y = area.height.saturating_sub(dialog_height) / 2;
+    let dialog_area = Rect::new(x, y, dialog_width, dialog_height);
+
+    frame.render_widget(Clear, dialog_area);
+
     let dialog = Paragraph::new(text)
         .block(
             Block::default()
@@ -437,16 +2057,232 @@ fn draw_help_dialog(frame: &mut Frame, state: &AppState, area: Rect) {
     frame.render_widget(dialog, dialog_area);
 }
 
+fn draw_stats_dialog(frame: &mut Frame, state: &AppState, area: Rect) {
+    let theme = state.theme;
+    let counts = status_counts(&state.testlist, &state.results);
+    let retests = retest_summary(&state.results);
+    let (approved, rejected) = review_counts(&state.results);
+    let passed_breakdown = if retests.retested > 0 {
+        format!(
+            " ({} first pass, {} on retest)",
+            retests.passed_first_time, retests.passed_after_retest
+        )
+    } else {
+        String::new()
+    };
+    let timing = timing_stats(state);
+    let sessions = session_summary(&state.results);
+    let plural = |n: usize| if n == 1 { "" } else { "s" };
+    let estimate = |secs: Option<u64>| secs.map(format_duration).unwrap_or("—".to_string());
+
+    let mut text = vec![
+        Line::from(""),
+        Line::from(" Status"),
+        Line::from(format!(
+            "   Passed        {}{}",
+            counts.passed, passed_breakdown
+        )),
+        Line::from(format!(
+            "   Failed        {}{}",
+            counts.failed,
+            failure_breakdown(&state.results)
+        )),
+        Line::from(format!("   Inconclusive  {}", counts.inconclusive)),
+        Line::from(format!("   Skipped       {}", counts.skipped)),
+        Line::from(format!("   To retest     {}", counts.retest)),
+        Line::from(format!("   In progress   {}", counts.in_progress)),
+        Line::from(format!("   Blocked       {}", counts.blocked)),
+    ];
+    text.extend(
+        custom_status_counts(&state.testlist, &state.results)
+            .into_iter()
+            .map(|(def, n)| Line::from(format!("   {:<13} {}", def.name, n))),
+    );
+    text.extend([
+        Line::from(format!(
+            "   Remaining     {} of {}",
+            counts.open(),
+            counts.total()
+        )),
+        Line::from(""),
+        Line::from(" Time"),
+        Line::from(format!(
+            "   This session  {}",
+            format_duration(state.session_started.elapsed().as_secs())
+        )),
+        Line::from(format!(
+            "   On tests      {}",
+            format_duration(timing.time_spent_secs)
+        )),
+        Line::from(format!(
+            "   Avg per test  {}",
+            estimate(timing.average_secs)
+        )),
+        Line::from(format!(
+            "   Est. to go    {}",
+            estimate(timing.remaining_secs)
+        )),
+        Line::from(""),
+        Line::from(" Run"),
+        Line::from(format!(
+            "   Sessions      {} over {} day{}",
+            sessions.sessions,
+            sessions.days,
+            plural(sessions.days)
+        )),
+        Line::from(format!("   Testers       {}", sessions.testers.join(", "))),
+        Line::from(format!(
+            "   Reviewed      {} approved, {} rejected",
+            approved, rejected
+        )),
+        Line::from(""),
+        Line::from(" Press S or Esc to close"),
+    ]);
+
+    let dialog_width = 44u16;
+    let dialog_height = text.len() as u16 + 2;
+    let x = area.width.saturating_sub(dialog_width) / 2;
+    let y = area.height.saturating_sub(dialog_height) / 2;
+    let dialog_area = Rect::new(x, y, dialog_width, dialog_height);
+
+    frame.render_widget(Clear, dialog_area);
+
+    let dialog = Paragraph::new(text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.accent()))
+                .title(" Session Statistics "),
+        )
+        .style(Style::default().bg(theme.bg()).fg(theme.fg()));
+
+    frame.render_widget(dialog, dialog_area);
+}
+
+/// Failures by severity for the statistics popup, e.g. " (1 blocker, 2 major)".
+fn failure_breakdown(results: &crate::data::results::TestlistResults) -> String {
+    let counts: Vec<String> = severity_counts(results)
+        .into_iter()
+        .map(|(severity, n)| format!("{} {}", n, severity.label()))
+        .collect();
+    if counts.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", counts.join(", "))
+    }
+}
+
 fn draw_status_bar(frame: &mut Frame, state: &AppState, area: Rect) {
     let theme = state.theme;
     let test_name = current_test(state)
         .map(|t| t.title.as_str())
         .unwrap_or("No test selected");
 
-    let status = if state.editing_notes {
-        " EDITING NOTES │ [Esc] Save and exit │ Type to edit ".to_string()
+    let status = if state.choosing_note_template {
+        let templates = note_templates(state)
+            .iter()
+            .take(9)
+            .enumerate()
+            .map(|(i, (name, _))| format!("[{}] {}", i + 1, name))
+            .collect::<Vec<_>>()
+            .join("  ");
+        format!(" INSERT TEMPLATE │ {} │ any other key cancels ", templates)
+    } else if state.editing_notes {
+        " EDITING NOTES │ [Esc] Save and exit │ Arrows/Home/End move │ [Ctrl-t] Template "
+            .to_string()
     } else if state.adding_screenshot {
-        " ADDING SCREENSHOT │ [Enter] Confirm │ [Esc] Cancel │ Type path ".to_string()
+        " ADDING SCREENSHOT │ [Tab] Complete │ [Enter] Confirm │ [Esc] Cancel │ Type path (~ ok) "
+            .to_string()
+    } else if state.filtering_tags {
+        let tags = all_tags(state);
+        let available = if tags.is_empty() {
+            "(no tags defined)".to_string()
+        } else {
+            tags.join(", ")
+        };
+        format!(
+            " FILTER BY TAG │ > {}_ │ [Enter] Apply (empty clears) │ [Esc] Cancel │ {} ",
+            state.tag_filter_input, available
+        )
+    } else if state.searching {
+        let count = search_matches(state).len();
+        format!(
+            " SEARCH │ /{}_ │ {} match{} │ [Enter] Keep │ [Esc] Cancel ",
+            state.search_query,
+            count,
+            if count == 1 { "" } else { "es" }
+        )
+    } else if state.goto_active {
+        format!(
+            " GO TO TEST │ :{}_ │ [Enter] Jump (ID or number) │ [Esc] Cancel ",
+            state.goto_input
+        )
+    } else if state.suggesting_pass {
+        " ALL CHECKS DONE │ Mark Passed? [y] Yes  [n] No ".to_string()
+    } else if state.choosing_copy {
+        " COPY │ [i] ID  [t] title  [c] suggested command │ any other key cancels ".to_string()
+    } else if state.choosing_custom_status {
+        let statuses = state
+            .testlist
+            .statuses
+            .iter()
+            .take(9)
+            .enumerate()
+            .map(|(i, def)| format!("[{}] {} {}", i + 1, def.icon, def.name))
+            .collect::<Vec<_>>()
+            .join("  ");
+        format!(" MARK AS │ {} │ any other key cancels ", statuses)
+    } else if state.choosing_reference {
+        let references = current_test(state)
+            .map(|t| {
+                t.references
+                    .iter()
+                    .enumerate()
+                    .map(|(i, r)| format!("[{}] {}", i + 1, r))
+                    .collect::<Vec<_>>()
+                    .join("  ")
+            })
+            .unwrap_or_default();
+        format!(" OPEN REFERENCE │ {} │ any other key cancels ", references)
+    } else if state.adding_env {
+        format!(
+            " RECORD ENVIRONMENT │ > {}_ │ [Enter] Save key=value │ [Esc] Cancel ",
+            state.env_input
+        )
+    } else if state.adding_defect {
+        format!(
+            " LINK DEFECT │ > {}_ │ [Enter] Link bug ID or URL │ [Esc] Cancel ",
+            state.defect_input
+        )
+    } else if state.adding_blocked_reason {
+        format!(
+            " BLOCKED │ reason > {}_ │ [Enter] Mark blocked │ [Esc] Cancel ",
+            state.blocked_reason_input
+        )
+    } else if let Some(verdict) = state.review_verdict {
+        format!(
+            " {} │ comment > {}_ │ [Enter] Sign off │ [Esc] Cancel ",
+            verdict.label().to_uppercase(),
+            state.review_input
+        )
+    } else if state.visual_anchor.is_some() {
+        let count = visual_selection(state).len();
+        format!(
+            " VISUAL │ {} selected │ j/k Extend │ [p]ass [f]ail [i]nc [s]kip all │ [V/Esc] Cancel ",
+            count
+        )
+    } else if let Some(ref message) = state.status_message {
+        format!(" {} ", message)
+    } else if state.read_only {
+        format!(
+            " WATCH │ results reload as they change │ [?] Help │ [Q]uit │ {} ",
+            test_name
+        )
+    } else if let Some(ref reviewer) = state.reviewer {
+        format!(
+            " REVIEW ({}) │ [y] Approve [x] Reject │ [Tab] Pane │ [?] Help │ [Q]uit │ {} ",
+            reviewer, test_name
+        )
     } else {
         format!(
             " [P]ass [F]ail [I]nc [S]kip │ [Tab] Pane │ [?] Help │ [w] Save │ [Q]uit │ {} ",
@@ -454,8 +2290,45 @@ fn draw_status_bar(frame: &mut Frame, state: &AppState, area: Rect) {
         )
     };
 
-    let paragraph = Paragraph::new(Line::from(status))
-        .style(Style::default().bg(theme.selection_bg()).fg(theme.fg()));
+    let style = if state.status_message.is_some() {
+        Style::default()
+            .bg(theme.selection_bg())
+            .fg(ratatui::style::Color::Yellow)
+    } else {
+        Style::default().bg(theme.selection_bg()).fg(theme.fg())
+    };
+
+    let mut spans = Vec::new();
+    let in_input_mode = state.editing_notes
+        || state.adding_screenshot
+        || state.filtering_tags
+        || state.searching
+        || state.goto_active
+        || state.adding_env;
+    if let (false, Some(test), Some(result)) =
+        (in_input_mode, current_test(state), current_result(state))
+    {
+        let spent = format_duration(result.time_spent_secs);
+        let timer = match test.expected_secs() {
+            Some(expected) => format!(" ⏱ {}/{} │", spent, format_duration(expected)),
+            None => format!(" ⏱ {} │", spent),
+        };
+        let timer_style = if is_over_budget(test, result) {
+            style.fg(ratatui::style::Color::Red)
+        } else {
+            style
+        };
+        spans.push(ratatui::text::Span::styled(timer, timer_style));
+    }
+    if let (false, Some(filter)) = (in_input_mode, filter_label(state)) {
+        spans.push(ratatui::text::Span::styled(
+            format!(" FILTER {} │", filter),
+            style.add_modifier(Modifier::BOLD),
+        ));
+    }
+    spans.push(ratatui::text::Span::styled(status, style));
+
+    let paragraph = Paragraph::new(Line::from(spans)).style(style);
 
     frame.render_widget(paragraph, area);
 }
@@ -463,6 +2336,7 @@ fn draw_status_bar(frame: &mut Frame, state: &AppState, area: Rect) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures;
 
     // === Bug 2 verification test ===
     // On a small terminal (e.g. 15 rows), the status bar must still get its 1 row.
@@ -528,39 +2402,24 @@ mod tests {
     // Reproduce user-reported bug: p/f/i/s stops working after notes editing
 
     fn make_test_state() -> AppState {
-        use crate::data::definition::{ChecklistItem, Meta, Test, Testlist};
-        use crate::data::results::TestlistResults;
-
-        let testlist = Testlist {
-            meta: Meta {
-                title: "Test".to_string(),
-                description: "".to_string(),
-                created: "".to_string(),
-                version: "1".to_string(),
-            },
-            tests: vec![Test {
-                id: "t1".to_string(),
-                title: "Test 1".to_string(),
-                description: "".to_string(),
-                setup: vec![ChecklistItem {
-                    id: "s0".to_string(),
-                    text: "Step".to_string(),
-                }],
-                action: "Do it".to_string(),
-                verify: vec![ChecklistItem {
-                    id: "v0".to_string(),
-                    text: "Check".to_string(),
-                }],
-                suggested_command: None,
+        use crate::data::definition::{ChecklistItem, Test};
+
+        fixtures::state(fixtures::testlist(vec![Test {
+            id: "t1".to_string(),
+            title: "Test 1".to_string(),
+            description: "".to_string(),
+            setup: vec![ChecklistItem {
+                id: "s0".to_string(),
+                text: "Step".to_string(),
             }],
-        };
-        let results = TestlistResults::new_for_testlist(&testlist, "test.ron", "tester");
-        AppState::new(
-            testlist,
-            results,
-            std::path::PathBuf::from("test.testlist.ron"),
-            std::path::PathBuf::from("test.testlist.results.ron"),
-        )
+            action: "Do it".to_string(),
+            verify: vec![ChecklistItem {
+                id: "v0".to_string(),
+                text: "Check".to_string(),
+            }],
+            suggested_command: None,
+            ..Default::default()
+        }]))
     }
 
     #[test]
@@ -591,7 +2450,7 @@ mod tests {
         // Step 3: Type some text
         handle_key(&mut state, KeyCode::Char('h'), no_mods, &mut pty);
         handle_key(&mut state, KeyCode::Char('i'), no_mods, &mut pty);
-        assert_eq!(state.notes_input, "hi");
+        assert_eq!(state.notes_input.text(), "hi");
 
         // Step 4: Press Esc to save notes
         handle_key(&mut state, KeyCode::Esc, no_mods, &mut pty);
@@ -612,8 +2471,10 @@ mod tests {
             "Notes should be saved"
         );
 
-        // Step 5: Press 'f' — should change status to Failed
+        // Step 5: Press 'f' and confirm the failure dialog — should change
+        // status to Failed
         handle_key(&mut state, KeyCode::Char('f'), no_mods, &mut pty);
+        handle_key(&mut state, KeyCode::Enter, no_mods, &mut pty);
         assert_eq!(
             state.results.results[0].status,
             Status::Failed,
@@ -693,4 +2554,92 @@ mod tests {
             "New layout should not be greedier than old for top area"
         );
     }
+
+    #[test]
+    fn test_wide_characters_line_up() {
+        assert_eq!(fit_tail("ab日本", 5), "b日本");
+        assert_eq!(fit_tail("ab日本", 3), "本");
+        assert_eq!(fit_tail("e\u{301}x", 5), "e\u{301}x");
+
+        let mut parser = vt100::Parser::new(2, 6, 0);
+        parser.process("日本e\u{301}x".as_bytes());
+        let rows = visible_rows(parser.screen());
+        assert_eq!(rows[0].concat(), "日本e\u{301}x");
+        assert_eq!(rows[1].concat(), "      ");
+        assert_eq!(rows[0][1], "");
+        assert_eq!(rows[0][4], "e\u{301}");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_fallback_runner_records_exit_code() {
+        let mut state = make_test_state();
+        state.testlist.tests[0].suggested_command =
+            Some("echo out; echo err >&2; exit 3".to_string());
+        let mut pty = Some(EmbeddedTerminal::fallback(6, 40, None));
+        let mut event_loop = EventLoop::new(&state);
+
+        handle_key(&mut state, KeyCode::Char('C'), KeyModifiers::NONE, &mut pty);
+        assert!(state.running_command.is_some());
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+        while state.running_command.is_some() && std::time::Instant::now() < deadline {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            event_loop.tick(&mut state, &mut pty);
+        }
+
+        let run = &state.results.results[0].command_runs[0];
+        assert_eq!(run.exit_code, 3);
+        assert!(run.output_tail.contains("out"));
+        assert!(run.output_tail.contains("err"));
+        let screen = pty.as_ref().unwrap().screen().contents();
+        assert!(screen.contains("[exit code 3]"));
+
+        // Typing goes nowhere, since there is no shell
+        handle_key(&mut state, KeyCode::Char('x'), KeyModifiers::NONE, &mut pty);
+        assert_eq!(pty.as_ref().unwrap().screen().contents(), screen);
+    }
+
+    #[test]
+    fn test_terminal_key_sequences() {
+        assert_eq!(
+            key_sequence(KeyCode::Left, KeyModifiers::NONE).unwrap(),
+            b"\x1b[D"
+        );
+        assert_eq!(
+            key_sequence(KeyCode::Left, KeyModifiers::CONTROL).unwrap(),
+            b"\x1b[1;5D"
+        );
+        assert_eq!(
+            key_sequence(KeyCode::End, KeyModifiers::SHIFT).unwrap(),
+            b"\x1b[1;2F"
+        );
+        assert_eq!(
+            key_sequence(KeyCode::Delete, KeyModifiers::ALT).unwrap(),
+            b"\x1b[3;3~"
+        );
+        assert_eq!(key_sequence(KeyCode::Tab, KeyModifiers::NONE), None);
+    }
+
+    #[test]
+    fn test_idle_timeout_follows_deadlines() {
+        let mut state = make_test_state();
+        let event_loop = EventLoop::new(&state);
+        let idle = event_loop.idle_timeout(&state);
+        assert!(idle <= TESTLIST_CHECK_INTERVAL && idle > COMMAND_CHECK_INTERVAL);
+
+        // Autosave is due right away once changes are older than the interval
+        state.autosave_interval = Some(Duration::ZERO);
+        assert!(event_loop.idle_timeout(&state) > COMMAND_CHECK_INTERVAL);
+        state.dirty = true;
+        assert_eq!(event_loop.idle_timeout(&state), Duration::ZERO);
+
+        state.dirty = false;
+        state.running_command = Some(crate::data::state::RunningCommand {
+            test_id: "t1".to_string(),
+            command: "make".to_string(),
+            started: String::new(),
+            started_at: std::time::Instant::now(),
+        });
+        assert!(event_loop.idle_timeout(&state) <= COMMAND_CHECK_INTERVAL);
+    }
 }
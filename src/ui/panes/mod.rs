@@ -1,5 +1,7 @@
 //! UI pane rendering modules.
 
 pub mod notes;
+pub mod preview;
+pub mod progress;
 pub mod terminal;
 pub mod tests;
@@ -8,8 +8,99 @@ use ratatui::{
     Frame,
 };
 
+use crate::data::results::Status;
 use crate::data::state::{AppState, FocusedPane};
-use crate::queries::tests::current_result;
+use crate::queries::tests::{current_result, current_test, result_for_test};
+
+/// Lines of the notes view for the current test (outside editing modes).
+pub fn view_lines(state: &AppState) -> Vec<Line<'static>> {
+    let theme = state.theme;
+    let Some(result) = current_result(state) else {
+        return vec![Line::from("Select a test to view notes")];
+    };
+    let mut lines = Vec::new();
+
+    if let Some(notes) = &result.notes {
+        for line in notes.lines() {
+            lines.push(Line::from(line.to_string()));
+        }
+    } else {
+        lines.push(Line::from(Span::styled(
+            "(No notes - press 'n' to add)",
+            Style::default().fg(theme.dim()),
+        )));
+    }
+
+    if !result.screenshots.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from("Screenshots:"));
+        for (i, path) in result.screenshots.iter().enumerate() {
+            lines.push(Line::from(format!("  [{}] {}", i + 1, path.display())));
+        }
+    }
+
+    if !result.defects.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from("Defects:"));
+        for defect in &result.defects {
+            lines.push(Line::from(format!("  {}", defect)));
+        }
+    }
+
+    if let Some(review) = &result.review {
+        lines.push(Line::from(""));
+        let at = chrono::DateTime::parse_from_rfc3339(&review.at)
+            .map(|t| {
+                t.with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string()
+            })
+            .unwrap_or_else(|_| review.at.clone());
+        lines.push(Line::from(format!(
+            "Review: {} by {} at {}",
+            review.verdict.label(),
+            review.reviewer,
+            at
+        )));
+        for line in review.comment.iter().flat_map(|c| c.lines()) {
+            lines.push(Line::from(format!("  {}", line)));
+        }
+    }
+
+    if let (Some(comparison), Some(test)) = (&state.comparison, current_test(state)) {
+        lines.push(Line::from(""));
+        let baseline = result_for_test(&comparison.results, &test.id);
+        let status = baseline.map_or("not recorded".to_string(), |r| r.status_name());
+        lines.push(Line::from(Span::styled(
+            format!("In {}: {}", comparison.label, status),
+            Style::default().fg(theme.accent()),
+        )));
+        match baseline.and_then(|r| r.notes.as_ref()) {
+            Some(notes) => {
+                for line in notes.lines() {
+                    lines.push(Line::from(format!("  {}", line)));
+                }
+            }
+            None => lines.push(Line::from(Span::styled(
+                "  (No notes)",
+                Style::default().fg(theme.dim()),
+            ))),
+        }
+    }
+
+    let hint = if result.status == Status::Failed {
+        "[n] Edit notes  [a] Add screenshot  [b] Link defect"
+    } else {
+        "[n] Edit notes  [a] Add screenshot"
+    };
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        hint,
+        Style::default().fg(theme.dim()),
+    )));
+
+    lines
+}
 
 /// Draw the notes pane.
 pub fn draw(frame: &mut Frame, state: &AppState, area: Rect) {
@@ -30,56 +121,39 @@ pub fn draw(frame: &mut Frame, state: &AppState, area: Rect) {
     };
 
     let content = if state.adding_screenshot {
-        vec![
+        let mut lines = vec![
             Line::from("Enter screenshot path:"),
             Line::from(""),
             Line::from(format!("> {}_", state.screenshot_input)),
-        ]
-    } else if state.editing_notes {
-        let mut lines = Vec::new();
-        for line in state.notes_input.lines() {
-            lines.push(Line::from(line.to_string()));
-        }
-        if state.notes_input.ends_with('\n') || state.notes_input.is_empty() {
-            lines.push(Line::from("_"));
-        } else if let Some(last) = lines.last_mut() {
-            *last = Line::from(format!(
-                "{}_",
-                last.spans.first().map(|s| s.content.as_ref()).unwrap_or("")
-            ));
+        ];
+        if let Some(ref error) = state.screenshot_error {
+            lines.push(Line::from(Span::styled(
+                error.clone(),
+                Style::default().fg(theme.failed()),
+            )));
         }
-        lines
-    } else if let Some(result) = current_result(state) {
-        let mut lines = Vec::new();
-
-        if let Some(notes) = &result.notes {
-            for line in notes.lines() {
-                lines.push(Line::from(line.to_string()));
-            }
-        } else {
+        for candidate in &state.screenshot_completions {
             lines.push(Line::from(Span::styled(
-                "(No notes - press 'n' to add)",
+                format!("  {}", candidate),
                 Style::default().fg(theme.dim()),
             )));
         }
-
-        if !result.screenshots.is_empty() {
-            lines.push(Line::from(""));
-            lines.push(Line::from("Screenshots:"));
-            for (i, path) in result.screenshots.iter().enumerate() {
-                lines.push(Line::from(format!("  [{}] {}", i + 1, path.display())));
-            }
-        }
-
-        lines.push(Line::from(""));
-        lines.push(Line::from(Span::styled(
-            "[n] Edit notes  [a] Add screenshot",
-            Style::default().fg(theme.dim()),
-        )));
-
         lines
+    } else if state.editing_notes {
+        let inner_width = area.width.saturating_sub(2) as usize;
+        let inner_height = area.height.saturating_sub(2) as usize;
+        let (lines, (cursor_row, cursor_col)) = state.notes_input.wrapped(inner_width);
+        let scroll = (cursor_row + 1).saturating_sub(inner_height);
+        frame.set_cursor_position((
+            area.x + 1 + cursor_col as u16,
+            area.y + 1 + (cursor_row - scroll) as u16,
+        ));
+        lines.into_iter().skip(scroll).map(Line::from).collect()
     } else {
-        vec![Line::from("Select a test to view notes")]
+        view_lines(state)
+            .into_iter()
+            .skip(state.notes_scroll)
+            .collect()
     };
 
     let paragraph = Paragraph::new(content).block(
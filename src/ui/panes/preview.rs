@@ -0,0 +1,172 @@
+//! Inline screenshot previews using terminal graphics protocols.
+//!
+//! Only the kitty graphics protocol is supported; it accepts PNG data
+//! directly, so no image decoding is needed. Terminals without it (including
+//! sixel-only terminals) keep the plain path listing in the notes pane.
+//!
+//! Images are written straight to the terminal after each frame, outside of
+//! ratatui's buffer, and only re-sent when the previewed file or its area
+//! changes.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    widgets::{Block, Borders, Clear},
+    Frame,
+};
+
+use crate::data::state::AppState;
+use crate::queries::tests::{current_result, resolve_attachment};
+
+/// Rows reserved at the bottom of the notes pane for a preview.
+pub const PREVIEW_HEIGHT: u16 = 10;
+
+/// Graphics protocol supported by the terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageProtocol {
+    Kitty,
+    None,
+}
+
+impl ImageProtocol {
+    /// Detect support from the environment variables terminals set.
+    pub fn detect() -> Self {
+        let var = |name: &str| std::env::var(name).unwrap_or_default();
+        let kitty_like = std::env::var_os("KITTY_WINDOW_ID").is_some()
+            || var("TERM") == "xterm-kitty"
+            || matches!(var("TERM_PROGRAM").as_str(), "WezTerm" | "ghostty");
+        if kitty_like {
+            ImageProtocol::Kitty
+        } else {
+            ImageProtocol::None
+        }
+    }
+}
+
+/// Check whether a screenshot can be previewed (the kitty protocol takes PNG only).
+pub fn is_previewable(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
+        && path.is_file()
+}
+
+/// The screenshot to preview: the most recent previewable one of the current test.
+pub fn preview_path(state: &AppState) -> Option<PathBuf> {
+    current_result(state)?
+        .screenshots
+        .iter()
+        .rev()
+        .map(|path| resolve_attachment(state, path))
+        .find(|path| is_previewable(path))
+}
+
+/// Draw the preview frame and return the image with the cells it should fill.
+pub fn draw(frame: &mut Frame, state: &AppState, path: PathBuf, area: Rect) -> (PathBuf, Rect) {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(state.theme.dim()))
+        .title(format!(" Preview: {} ", name));
+    let inner = block.inner(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+    (path, inner)
+}
+
+/// Tracks which image is on screen so it is only sent when it changes.
+pub struct ImagePreview {
+    pub protocol: ImageProtocol,
+    shown: Option<(PathBuf, Rect)>,
+}
+
+impl ImagePreview {
+    pub fn new(protocol: ImageProtocol) -> Self {
+        Self {
+            protocol,
+            shown: None,
+        }
+    }
+
+    /// Show `target` (an image file and the cells to draw it in), or clear the preview.
+    pub fn sync(&mut self, out: &mut impl Write, target: Option<(PathBuf, Rect)>) {
+        if self.protocol == ImageProtocol::None || self.shown == target {
+            return;
+        }
+        let _ = write!(out, "\x1b_Ga=d,q=2\x1b\\");
+        if let Some((ref path, area)) = target {
+            if let Ok(png) = std::fs::read(path) {
+                let _ = write!(out, "\x1b7\x1b[{};{}H", area.y + 1, area.x + 1);
+                let _ = out.write_all(&kitty_sequence(&png, area.width, area.height));
+                let _ = write!(out, "\x1b8");
+            }
+        }
+        let _ = out.flush();
+        self.shown = target;
+    }
+
+    /// Forget what is on screen, e.g. after the terminal was cleared.
+    pub fn invalidate(&mut self) {
+        self.shown = None;
+    }
+}
+
+/// Encode PNG data as kitty graphics commands scaled to `cols` x `rows` cells.
+///
+/// The payload is split into 4096-byte chunks as the protocol requires.
+fn kitty_sequence(png: &[u8], cols: u16, rows: u16) -> Vec<u8> {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(png);
+    let chunks: Vec<&[u8]> = encoded.as_bytes().chunks(4096).collect();
+    let mut out = Vec::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        if i == 0 {
+            out.extend_from_slice(
+                format!("\x1b_Ga=T,f=100,q=2,C=1,c={},r={},m={};", cols, rows, more).as_bytes(),
+            );
+        } else {
+            out.extend_from_slice(format!("\x1b_Gm={};", more).as_bytes());
+        }
+        out.extend_from_slice(chunk);
+        out.extend_from_slice(b"\x1b\\");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kitty_sequence_chunks_payload() {
+        let png = vec![0u8; 6000];
+        let sequence = String::from_utf8(kitty_sequence(&png, 20, 10)).unwrap();
+        assert!(sequence.starts_with("\x1b_Ga=T,f=100,q=2,C=1,c=20,r=10,m=1;"));
+        assert!(sequence.contains("\x1b_Gm=0;"));
+        assert_eq!(sequence.matches("\x1b\\").count(), 2);
+    }
+
+    #[test]
+    fn test_sync_skips_unchanged_and_unsupported() {
+        let mut out = Vec::new();
+        let mut preview = ImagePreview::new(ImageProtocol::None);
+        preview.sync(&mut out, None);
+        assert!(out.is_empty());
+
+        let mut preview = ImagePreview::new(ImageProtocol::Kitty);
+        preview.sync(&mut out, None);
+        assert!(out.is_empty());
+        let target = Some((PathBuf::from("missing.png"), Rect::new(1, 1, 10, 5)));
+        preview.sync(&mut out, target.clone());
+        assert!(!out.is_empty());
+        out.clear();
+        preview.sync(&mut out, target);
+        assert!(out.is_empty());
+    }
+}
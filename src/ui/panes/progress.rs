@@ -0,0 +1,98 @@
+//! Progress bar rendering: a stacked bar of test statuses.
+
+use ratatui::{
+    layout::Rect,
+    style::Style,
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+use crate::data::results::Status;
+use crate::data::state::AppState;
+use crate::queries::stats::{segment_widths, status_counts, StatusCounts};
+
+/// The legend in words for accessibility mode, leaving out statuses no
+/// test has.
+fn word_legend(counts: &StatusCounts, percent: usize) -> String {
+    let mut parts = vec![
+        format!("{} passed", counts.passed),
+        format!("{} failed", counts.failed),
+    ];
+    for (count, label) in [
+        (counts.inconclusive, "inconclusive"),
+        (counts.skipped, "skipped"),
+        (counts.custom + counts.custom_open, "custom"),
+        (counts.blocked, "blocked"),
+        (counts.in_progress, "in progress"),
+        (counts.retest, "retest"),
+    ] {
+        if count > 0 {
+            parts.push(format!("{} {}", count, label));
+        }
+    }
+    parts.push(format!("{} pending", counts.pending));
+    format!(" {}, {}% done ", parts.join(", "), percent)
+}
+
+/// Draw the progress bar with a per-status legend.
+pub fn draw(frame: &mut Frame, state: &AppState, area: Rect) {
+    let theme = state.theme;
+    let counts = status_counts(&state.testlist, &state.results);
+    let total = counts.total();
+    let done = total - counts.open();
+    let percent = (done * 100).checked_div(total).unwrap_or(0);
+
+    let optional = |icon: &str, count: usize| {
+        if count > 0 {
+            format!(" {}{}", icon, count)
+        } else {
+            String::new()
+        }
+    };
+    let legend = if state.accessible {
+        word_legend(&counts, percent)
+    } else {
+        format!(
+            " ✓{} ✗{} ?{} -{}{}{}{}{} ·{} {:>3}% ",
+            counts.passed,
+            counts.failed,
+            counts.inconclusive,
+            counts.skipped,
+            optional("*", counts.custom + counts.custom_open),
+            optional("!", counts.blocked),
+            optional("◐", counts.in_progress),
+            optional("↻", counts.retest),
+            counts.pending,
+            percent
+        )
+    };
+    let bar_width = (area.width as usize).saturating_sub(legend.width() + 1);
+
+    let by_status = counts.by_status();
+    let widths = segment_widths(&by_status.map(|(_, n)| n), bar_width);
+
+    let mut spans = vec![Span::raw(" ")];
+    for ((status, _), width) in by_status.iter().zip(widths) {
+        let fill = if *status == Status::Pending {
+            "░"
+        } else {
+            "█"
+        };
+        spans.push(Span::styled(
+            fill.repeat(width),
+            Style::default().fg(if *status == Status::Pending {
+                theme.dim()
+            } else {
+                theme.status_color(*status)
+            }),
+        ));
+    }
+    spans.push(Span::styled(legend, Style::default().fg(theme.fg())));
+
+    frame.render_widget(
+        Paragraph::new(Line::from(spans)).style(Style::default().bg(theme.bg())),
+        area,
+    );
+}
@@ -2,31 +2,119 @@
 
 use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
 use std::io::{Read, Write};
-use std::sync::mpsc::{self, Receiver, Sender};
+use std::process::Child;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
 use std::thread;
 
+use crossterm::event::{KeyCode, KeyModifiers};
 use ratatui::{
     layout::Rect,
-    style::Style,
-    text::Line,
+    style::{Modifier, Style},
+    text::{Line, Span},
     widgets::{Block, Borders, Paragraph},
     Frame,
 };
+use unicode_width::UnicodeWidthStr;
 
+use crate::actions::pty::{
+    cursor_position_report, cursor_position_requests, default_windows_shell, exit_code,
+    find_exit_marker, find_on_path, paste_sequence, signal_process_group, spawn_piped,
+    wrap_command, ShellKind, Signal,
+};
+use crate::data::copy_mode::CopyMode;
 use crate::data::state::{AppState, FocusedPane};
+use crate::data::theme::Theme;
 use crate::queries::tests::current_test;
+use crate::ui::input::Wake;
+
+/// Where the terminal's output comes from.
+enum Backend {
+    /// An interactive shell in a PTY.
+    Pty {
+        master: Box<dyn MasterPty + Send>,
+        writer: Box<dyn Write + Send>,
+        // Only Unix tells the shell from the command it runs
+        #[cfg_attr(not(unix), allow(dead_code))]
+        shell_pid: Option<u32>,
+        shell_kind: ShellKind,
+    },
+    /// No PTY could be opened: run commands are spawned one at a time with
+    /// their output piped to the screen, and nothing can be typed.
+    Fallback {
+        child: Option<Child>,
+        // Exit code of the last command, until its run is taken
+        exited: Option<i32>,
+    },
+}
 
-/// Manages an embedded terminal with PTY.
+/// Manages an embedded terminal with PTY, or the fallback runner where
+/// there is no PTY.
 pub struct EmbeddedTerminal {
-    master: Box<dyn MasterPty + Send>,
-    writer: Box<dyn Write + Send>,
+    backend: Backend,
     parser: vt100::Parser,
     output_rx: Receiver<Vec<u8>>,
+    // Raw output recorded since a command run started
+    capture: Option<Vec<u8>>,
+    // Foreground process group already sent SIGINT by `stop_foreground`
+    interrupted: Option<i32>,
+    // Wakes the event loop when output arrives (None = it polls)
+    waker: Option<Sender<Wake>>,
+}
+
+/// Forward everything read from `reader` to `tx` on a background thread,
+/// waking the event loop for each chunk and when the output ends.
+fn forward_output(
+    mut reader: impl Read + Send + 'static,
+    tx: Sender<Vec<u8>>,
+    waker: Option<Sender<Wake>>,
+) {
+    thread::spawn(move || {
+        let mut buf = [0u8; 4096];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    if tx.send(buf[..n].to_vec()).is_err() {
+                        break;
+                    }
+                    if let Some(ref waker) = waker {
+                        let _ = waker.send(Wake::Output);
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+        drop(tx);
+        if let Some(waker) = waker {
+            let _ = waker.send(Wake::Output);
+        }
+    });
+}
+
+/// Piped output with bare line feeds turned into CRLF, which the screen
+/// needs to start the next line at its first column.
+fn crlf(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    for &byte in data {
+        if byte == b'\n' && out.last() != Some(&b'\r') {
+            out.push(b'\r');
+        }
+        out.push(byte);
+    }
+    out
 }
 
 impl EmbeddedTerminal {
-    /// Create a new embedded terminal with the given size.
-    pub fn new(rows: u16, cols: u16) -> Result<Self, Box<dyn std::error::Error>> {
+    /// Create a new embedded terminal with the given size, running `shell`
+    /// or the default shell: the login shell on Unix, and PowerShell (or
+    /// cmd.exe where there is none) on Windows. Output is announced to
+    /// `waker`, if given.
+    pub fn new(
+        rows: u16,
+        cols: u16,
+        shell: Option<&str>,
+        waker: Option<Sender<Wake>>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
         let pty_system = native_pty_system();
 
         let pty_pair = pty_system.openpty(PtySize {
@@ -36,75 +124,288 @@ impl EmbeddedTerminal {
             pixel_height: 0,
         })?;
 
-        let cmd = CommandBuilder::new_default_prog();
-        let _child = pty_pair.slave.spawn_command(cmd)?;
+        let program = match shell {
+            Some(shell) => Some(shell.to_string()),
+            None if cfg!(windows) => Some(default_windows_shell(
+                find_on_path,
+                std::env::var("COMSPEC").ok(),
+            )),
+            None => None,
+        };
+        let shell_kind = program
+            .as_deref()
+            .map_or(ShellKind::Posix, ShellKind::detect);
+        // On Unix the default program is the login shell named by $SHELL
+        let cmd = match program {
+            Some(program) if cfg!(windows) => CommandBuilder::new(program),
+            _ => {
+                let mut cmd = CommandBuilder::new_default_prog();
+                if let Some(shell) = shell {
+                    cmd.env("SHELL", shell);
+                }
+                cmd
+            }
+        };
+        let child = pty_pair.slave.spawn_command(cmd)?;
+        let shell_pid = child.process_id();
 
         let writer = pty_pair.master.take_writer()?;
 
-        let mut reader = pty_pair.master.try_clone_reader()?;
+        let reader = pty_pair.master.try_clone_reader()?;
         let (tx, rx): (Sender<Vec<u8>>, Receiver<Vec<u8>>) = mpsc::channel();
-
-        thread::spawn(move || {
-            let mut buf = [0u8; 4096];
-            loop {
-                match reader.read(&mut buf) {
-                    Ok(0) => break,
-                    Ok(n) => {
-                        if tx.send(buf[..n].to_vec()).is_err() {
-                            break;
-                        }
-                    }
-                    Err(_) => break,
-                }
-            }
-        });
+        forward_output(reader, tx, waker.clone());
 
         let parser = vt100::Parser::new(rows, cols, 1000);
 
         Ok(Self {
-            master: pty_pair.master,
-            writer,
+            backend: Backend::Pty {
+                master: pty_pair.master,
+                writer,
+                shell_pid,
+                shell_kind,
+            },
             parser,
             output_rx: rx,
+            capture: None,
+            interrupted: None,
+            waker,
         })
     }
 
+    /// Create the fallback runner for when no PTY can be opened: suggested
+    /// commands still run and show their output, but there is no shell to
+    /// type into.
+    pub fn fallback(rows: u16, cols: u16, waker: Option<Sender<Wake>>) -> Self {
+        let mut parser = vt100::Parser::new(rows, cols, 1000);
+        parser.process(b"No PTY available: commands run with C show their output here.\r\n");
+        // Nothing is sent until a command runs
+        let (_, rx) = mpsc::channel();
+        Self {
+            backend: Backend::Fallback {
+                child: None,
+                exited: None,
+            },
+            parser,
+            output_rx: rx,
+            capture: None,
+            interrupted: None,
+            waker,
+        }
+    }
+
+    /// Check whether this is the fallback runner, which takes no input.
+    pub fn is_fallback(&self) -> bool {
+        matches!(self.backend, Backend::Fallback { .. })
+    }
+
     /// Resize the terminal.
     pub fn resize(&mut self, rows: u16, cols: u16) {
-        let _ = self.master.resize(PtySize {
-            rows,
-            cols,
-            pixel_width: 0,
-            pixel_height: 0,
-        });
+        if let Backend::Pty { master, .. } = &self.backend {
+            let _ = master.resize(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            });
+        }
         self.parser.set_size(rows, cols);
     }
 
-    /// Process any pending output from the PTY.
+    /// Process any pending output from the PTY, or from the fallback
+    /// runner's command, noting when that command has finished.
     pub fn poll_output(&mut self) {
-        while let Ok(data) = self.output_rx.try_recv() {
-            self.parser.process(&data);
+        let fallback = self.is_fallback();
+        let drained = loop {
+            let data = match self.output_rx.try_recv() {
+                Ok(data) => data,
+                Err(TryRecvError::Empty) => break false,
+                Err(TryRecvError::Disconnected) => break true,
+            };
+            if fallback {
+                self.parser.process(&crlf(&data));
+            } else {
+                self.parser.process(&data);
+                // ConPTY stalls until its cursor position requests are answered
+                for _ in 0..cursor_position_requests(&data) {
+                    let (row, col) = self.parser.screen().cursor_position();
+                    self.send_str(&cursor_position_report(row, col));
+                }
+            }
+            if let Some(ref mut capture) = self.capture {
+                capture.extend_from_slice(&data);
+            }
+        };
+        // The command is done once its output is closed and it has exited
+        if let Backend::Fallback { child, exited } = &mut self.backend {
+            if !drained {
+                return;
+            }
+            let status = child.as_mut().and_then(|c| c.try_wait().ok().flatten());
+            if let Some(status) = status {
+                let code = exit_code(status);
+                self.parser
+                    .process(format!("[exit code {}]\r\n", code).as_bytes());
+                *child = None;
+                *exited = Some(code);
+            }
         }
     }
 
+    /// Run a command and capture its output until it finishes; see
+    /// [`Self::take_finished_capture`].
+    pub fn run_command(&mut self, command: &str) -> std::io::Result<()> {
+        match &mut self.backend {
+            Backend::Pty { shell_kind, .. } => {
+                let line = wrap_command(command, *shell_kind);
+                self.start_capture();
+                self.send_str(&line);
+            }
+            Backend::Fallback { child, exited } => {
+                let mut spawned = spawn_piped(command)?;
+                let (tx, rx) = mpsc::channel();
+                if let Some(stdout) = spawned.stdout.take() {
+                    forward_output(stdout, tx.clone(), self.waker.clone());
+                }
+                if let Some(stderr) = spawned.stderr.take() {
+                    forward_output(stderr, tx, self.waker.clone());
+                }
+                *child = Some(spawned);
+                *exited = None;
+                self.output_rx = rx;
+                self.interrupted = None;
+                // Captured like the shell's echo of a command line, which
+                // `output_tail` skips
+                let echo = format!("$ {}\r\n", command);
+                self.parser.process(echo.as_bytes());
+                self.capture = Some(echo.into_bytes());
+            }
+        }
+        Ok(())
+    }
+
+    /// Start recording output for a command run.
+    pub fn start_capture(&mut self) {
+        self.capture = Some(Vec::new());
+    }
+
+    /// If the captured command has finished, stop capturing and return its
+    /// exit code and the output preceding the exit marker.
+    pub fn take_finished_capture(&mut self) -> Option<(i32, String)> {
+        let text = String::from_utf8_lossy(self.capture.as_ref()?).into_owned();
+        let (end, code) = match &mut self.backend {
+            Backend::Pty { .. } => find_exit_marker(&text)?,
+            Backend::Fallback { exited, .. } => (text.len(), exited.take()?),
+        };
+        self.capture = None;
+        Some((code, text[..end].to_string()))
+    }
+
+    /// Stop recording output, returning what was captured.
+    pub fn cancel_capture(&mut self) -> Option<String> {
+        self.capture
+            .take()
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// Signal the foreground process group of the shell: SIGINT first, then
+    /// SIGKILL if the same group is still in the foreground. On Windows the
+    /// PTY is sent Ctrl-C instead, every time.
+    ///
+    /// Returns `Ok(None)` when only the shell itself is running.
+    pub fn stop_foreground(&mut self) -> crate::error::Result<Option<Signal>> {
+        #[cfg(not(unix))]
+        match &mut self.backend {
+            Backend::Fallback {
+                child: Some(child), ..
+            } => {
+                child.kill()?;
+                return Ok(Some(Signal::Kill));
+            }
+            // ConPTY turns Ctrl-C into a CTRL_C_EVENT for the console's
+            // processes; there are no process groups to signal
+            Backend::Pty { .. } => {
+                self.send_key(b"\x03");
+                return Ok(Some(Signal::Interrupt));
+            }
+            Backend::Fallback { .. } => {}
+        }
+        let Some(pgid) = self.foreground_group() else {
+            return Ok(None);
+        };
+        let signal = if self.interrupted == Some(pgid) {
+            Signal::Kill
+        } else {
+            Signal::Interrupt
+        };
+        signal_process_group(pgid, signal)?;
+        self.interrupted = Some(pgid);
+        Ok(Some(signal))
+    }
+
+    /// Process group in the foreground of the PTY, unless it is the shell,
+    /// or the fallback runner's command.
+    #[cfg(unix)]
+    fn foreground_group(&self) -> Option<i32> {
+        match &self.backend {
+            Backend::Pty {
+                master, shell_pid, ..
+            } => master
+                .process_group_leader()
+                .filter(|&pgid| Some(pgid as u32) != *shell_pid),
+            Backend::Fallback { child, .. } => child.as_ref().map(|c| c.id() as i32),
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn foreground_group(&self) -> Option<i32> {
+        None
+    }
+
     /// Send a character to the PTY.
     pub fn send_char(&mut self, c: char) {
         let mut buf = [0u8; 4];
-        let s = c.encode_utf8(&mut buf);
-        let _ = self.writer.write_all(s.as_bytes());
-        let _ = self.writer.flush();
+        self.send_key(c.encode_utf8(&mut buf).as_bytes());
     }
 
     /// Send a string to the PTY.
     pub fn send_str(&mut self, s: &str) {
-        let _ = self.writer.write_all(s.as_bytes());
-        let _ = self.writer.flush();
+        self.send_key(s.as_bytes());
     }
 
-    /// Send a special key sequence to the PTY.
+    /// Send pasted text, using bracketed paste if the program asked for it.
+    pub fn paste(&mut self, text: &str) {
+        let bracketed = self.parser.screen().bracketed_paste();
+        self.send_str(&paste_sequence(text, bracketed));
+    }
+
+    /// Send a special key sequence to the PTY; the fallback runner ignores it.
     pub fn send_key(&mut self, key: &[u8]) {
-        let _ = self.writer.write_all(key);
-        let _ = self.writer.flush();
+        if let Backend::Pty { writer, .. } = &mut self.backend {
+            let _ = writer.write_all(key);
+            let _ = writer.flush();
+        }
+    }
+
+    /// Scroll the view back by `rows` lines, stopping at the oldest line kept.
+    pub fn scroll_up(&mut self, rows: usize) {
+        let offset = self.parser.screen().scrollback();
+        self.parser.set_scrollback(offset.saturating_add(rows));
+    }
+
+    /// Scroll the view towards the live screen by `rows` lines.
+    pub fn scroll_down(&mut self, rows: usize) {
+        let offset = self.parser.screen().scrollback();
+        self.parser.set_scrollback(offset.saturating_sub(rows));
+    }
+
+    /// Return the view to the live screen.
+    pub fn scroll_to_bottom(&mut self) {
+        self.parser.set_scrollback(0);
+    }
+
+    /// Number of lines the view is scrolled back; 0 for the live screen.
+    pub fn scroll_offset(&self) -> usize {
+        self.parser.screen().scrollback()
     }
 
     /// Get the current screen contents.
@@ -113,6 +414,96 @@ impl EmbeddedTerminal {
     }
 }
 
+/// Bytes an editing or cursor key sends to the terminal, in xterm encoding
+/// (which ConPTY also reads): arrows, Home, and End carry a modifier
+/// parameter when Shift, Alt, or Ctrl is held, e.g. `ESC [1;5D` for
+/// Ctrl-Left, which shells and PSReadLine use to move by word.
+pub fn key_sequence(key: KeyCode, modifiers: KeyModifiers) -> Option<Vec<u8>> {
+    let mut param = 1;
+    if modifiers.contains(KeyModifiers::SHIFT) {
+        param += 1;
+    }
+    if modifiers.contains(KeyModifiers::ALT) {
+        param += 2;
+    }
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        param += 4;
+    }
+    let sequence = match key {
+        KeyCode::Up
+        | KeyCode::Down
+        | KeyCode::Right
+        | KeyCode::Left
+        | KeyCode::Home
+        | KeyCode::End => {
+            let letter = match key {
+                KeyCode::Up => 'A',
+                KeyCode::Down => 'B',
+                KeyCode::Right => 'C',
+                KeyCode::Left => 'D',
+                KeyCode::Home => 'H',
+                _ => 'F',
+            };
+            if param == 1 {
+                format!("\x1b[{}", letter)
+            } else {
+                format!("\x1b[1;{}{}", param, letter)
+            }
+        }
+        KeyCode::Insert | KeyCode::Delete => {
+            let number = if key == KeyCode::Insert { 2 } else { 3 };
+            if param == 1 {
+                format!("\x1b[{}~", number)
+            } else {
+                format!("\x1b[{};{}~", number, param)
+            }
+        }
+        _ => return None,
+    };
+    Some(sequence.into_bytes())
+}
+
+/// The visible rows of the screen, one string per cell: the cell's
+/// grapheme, a space for an empty cell, or nothing for the second half of a
+/// wide character, so joining a row lines up with the screen.
+pub fn visible_rows(screen: &vt100::Screen) -> Vec<Vec<String>> {
+    let (rows, cols) = screen.size();
+    (0..rows)
+        .map(|row| {
+            (0..cols)
+                .map(|col| match screen.cell(row, col) {
+                    Some(cell) if cell.is_wide_continuation() => String::new(),
+                    Some(cell) if cell.has_contents() => cell.contents(),
+                    _ => " ".to_string(),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Render a row with the copy-mode selection and cursor highlighted.
+fn copy_mode_line(mode: &CopyMode, row: u16, cells: &[String], theme: &Theme) -> Line<'static> {
+    let spans: Vec<Span> = cells
+        .iter()
+        .enumerate()
+        .filter(|(_, text)| !text.is_empty())
+        .map(|(col, text)| {
+            let col = col as u16;
+            // A wide character also covers the cell to its right
+            let span = col..col + text.width().max(1) as u16;
+            let style = if mode.cursor.0 == row && span.contains(&mode.cursor.1) {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else if mode.is_selected(row, col) {
+                Style::default().bg(theme.selection_bg())
+            } else {
+                Style::default()
+            };
+            Span::styled(text.clone(), style)
+        })
+        .collect();
+    Line::from(spans)
+}
+
 /// Draw the terminal pane.
 pub fn draw(frame: &mut Frame, state: &AppState, terminal: &Option<EmbeddedTerminal>, area: Rect) {
     let theme = state.theme;
@@ -123,31 +514,30 @@ pub fn draw(frame: &mut Frame, state: &AppState, terminal: &Option<EmbeddedTermi
         Style::default().fg(theme.dim())
     };
 
-    let title = if is_focused {
-        " Terminal (Esc to exit, Tab to switch pane) "
+    let scroll_offset = terminal.as_ref().map_or(0, |t| t.scroll_offset());
+    let title = if state.copy_mode.is_some() {
+        " Terminal [COPY] (v/V select, y clipboard, n notes, Esc exit) ".to_string()
+    } else if scroll_offset > 0 {
+        format!(" Terminal [↑{} lines, PgDn to return] ", scroll_offset)
+    } else if terminal.as_ref().is_some_and(|t| t.is_fallback()) {
+        " Terminal (no PTY: output of C only) ".to_string()
+    } else if is_focused {
+        " Terminal (Esc exit, Tab switch pane, Alt-c copy to notes, Alt-v copy mode) ".to_string()
     } else {
-        " Terminal "
+        " Terminal ".to_string()
     };
 
     let content: Vec<Line> = if let Some(ref term) = terminal {
-        let screen = term.screen();
-        let mut lines = Vec::new();
-        let inner_height = area.height.saturating_sub(2);
-        let screen_rows = screen.size().0;
-
-        for row in 0..inner_height.min(screen_rows) {
-            let mut row_str = String::new();
-            for col in 0..screen.size().1 {
-                let cell = screen.cell(row, col);
-                if let Some(cell) = cell {
-                    row_str.push(cell.contents().chars().next().unwrap_or(' '));
-                } else {
-                    row_str.push(' ');
-                }
-            }
-            let text = row_str.trim_end().to_string();
-            lines.push(Line::from(text));
-        }
+        let inner_height = area.height.saturating_sub(2) as usize;
+        let mut lines: Vec<Line> = visible_rows(term.screen())
+            .into_iter()
+            .take(inner_height)
+            .enumerate()
+            .map(|(row, cells)| match state.copy_mode {
+                Some(ref mode) => copy_mode_line(mode, row as u16, &cells, &theme),
+                None => Line::from(cells.concat().trim_end().to_string()),
+            })
+            .collect();
 
         if lines.is_empty() {
             lines.push(Line::from(""));
@@ -175,7 +565,8 @@ pub fn draw(frame: &mut Frame, state: &AppState, terminal: &Option<EmbeddedTermi
 
     frame.render_widget(paragraph, area);
 
-    if is_focused {
+    // The cursor belongs to the live screen; hide it while scrolled back
+    if is_focused && scroll_offset == 0 && state.copy_mode.is_none() {
         if let Some(ref term) = terminal {
             let screen = term.screen();
             let cursor_pos = screen.cursor_position();
@@ -8,8 +8,72 @@ use ratatui::{
     Frame,
 };
 
-use crate::data::state::{AppState, FocusedPane};
-use crate::queries::tests::{completed_count, result_for_test};
+use std::ops::Range;
+
+use crate::data::definition::{ChecklistItem, Test};
+use crate::data::results::{ChecklistSection, Status, Verdict};
+use crate::data::state::{AppState, FocusedPane, PaneEntry, StatusView};
+use crate::queries::checklist::is_checked;
+use crate::queries::diff::compared_change;
+use crate::queries::sections::{current_section, section_progress};
+use crate::queries::tests::{
+    completed_count, custom_status_def, hidden_by_view_count, is_waiting, matches_search,
+    pane_entries, result_for_test, status_label, visual_selection,
+};
+
+/// Columns of a test header before the expand marker.
+fn header_indent(test: &Test) -> u16 {
+    if test.section.is_some() {
+        2
+    } else {
+        0
+    }
+}
+
+/// Columns (relative to the pane interior) of a test header's status icon,
+/// which follows the indent and the "▶ " expand marker, and in compare mode
+/// the baseline's icon.
+pub fn status_icon_columns(state: &AppState, test: &Test) -> Range<u16> {
+    let baseline = if state.comparison.is_some() { 3 } else { 0 };
+    let start = header_indent(test) + 2 + baseline;
+    start..start + 3
+}
+
+/// Icon for a status; pending tests waiting on dependencies get their own.
+fn status_icon(status: Status, waiting: bool) -> &'static str {
+    match status {
+        Status::Pending if waiting => "[⊘]",
+        Status::Pending => "[ ]",
+        Status::Passed => "[✓]",
+        Status::Failed => "[✗]",
+        Status::Inconclusive => "[?]",
+        Status::Skipped => "[-]",
+        Status::Retest => "[↻]",
+        Status::InProgress => "[◐]",
+        Status::Blocked => "[!]",
+        Status::Custom => "[*]",
+    }
+}
+
+/// A setup or verify row with its checkbox.
+fn checklist_item(
+    state: &AppState,
+    test: &Test,
+    section: ChecklistSection,
+    item: &ChecklistItem,
+) -> ListItem<'static> {
+    let checked = is_checked(&state.results, &test.id, section, &item.id);
+    let (mark, style) = if checked {
+        ("[x]", Style::default().fg(state.theme.passed()))
+    } else {
+        ("[ ]", Style::default())
+    };
+    ListItem::new(Line::from(vec![
+        Span::raw("   "),
+        Span::styled(mark, style),
+        Span::raw(format!(" {}", item.text)),
+    ]))
+}
 
 /// Draw the tests pane.
 pub fn draw(frame: &mut Frame, state: &AppState, area: Rect) {
@@ -25,43 +89,146 @@ pub fn draw(frame: &mut Frame, state: &AppState, area: Rect) {
         .bg(theme.selection_bg())
         .add_modifier(Modifier::BOLD);
 
+    let visual_style = Style::default().bg(theme.selection_bg());
+    let in_visual = visual_selection(state);
+
     let mut items: Vec<ListItem> = Vec::new();
 
-    for (i, test) in state.testlist.tests.iter().enumerate() {
+    for entry in pane_entries(state) {
+        let i = match entry {
+            PaneEntry::Section(title) => {
+                let is_selected = state.selected_section.as_deref() == Some(title.as_str());
+                let prefix = if state.collapsed_sections.contains(&title) {
+                    "▶"
+                } else {
+                    "▼"
+                };
+                let (done, total) = section_progress(state, &title);
+                let style = if is_selected {
+                    selected_style
+                } else {
+                    Style::default()
+                        .fg(theme.accent())
+                        .add_modifier(Modifier::BOLD)
+                };
+                let line = format!("{} {} ({}/{})", prefix, title, done, total);
+                items.push(ListItem::new(Line::from(Span::styled(line, style))));
+                continue;
+            }
+            PaneEntry::Test(i) => i,
+        };
+        let test = &state.testlist.tests[i];
         let result = result_for_test(&state.results, &test.id);
         let status = result.map(|r| r.status).unwrap_or_default();
-        let status_icon = match status {
-            crate::data::results::Status::Pending => "[ ]",
-            crate::data::results::Status::Passed => "[✓]",
-            crate::data::results::Status::Failed => "[✗]",
-            crate::data::results::Status::Inconclusive => "[?]",
-            crate::data::results::Status::Skipped => "[-]",
-        };
+        let waiting = is_waiting(&state.testlist, &state.results, test);
 
-        let is_selected_test = i == state.selected_test;
+        let is_selected_test = state.selected_section.is_none() && i == state.selected_test;
         let is_expanded = state.expanded_tests.contains(&test.id);
 
         let prefix = if is_expanded { "▼" } else { "▶" };
-        let line = format!("{} {} {}", prefix, status_icon, test.title);
+        let indent = " ".repeat(header_indent(test) as usize);
 
         let header_style = if is_selected_test {
             selected_style
+        } else if in_visual.contains(&i) {
+            visual_style
+        } else if waiting && status == Status::Pending {
+            Style::default().fg(theme.dim())
+        } else if matches_search(test, &state.search_query) {
+            Style::default().fg(theme.accent())
         } else {
             Style::default()
         };
 
-        items.push(ListItem::new(Line::from(Span::styled(line, header_style))));
+        let custom = result.and_then(|r| custom_status_def(&state.testlist, r));
+        let icon_style = if waiting && status == Status::Pending {
+            header_style
+        } else if let Some(def) = custom {
+            header_style.fg(theme.custom_status_color(def))
+        } else {
+            header_style.fg(theme.status_color(status))
+        };
+        let change = compared_change(state, test);
+        let mut spans = vec![Span::styled(format!("{}{} ", indent, prefix), header_style)];
+        if let Some(ref change) = change {
+            spans.push(match change.old_status {
+                Some(old) => Span::styled(
+                    status_icon(old, false),
+                    header_style.fg(theme.status_color(old)),
+                ),
+                None => Span::styled("   ", header_style),
+            });
+        }
+        spans.push(match custom {
+            Some(def) => Span::styled(format!("[{}]", def.icon), icon_style),
+            None => Span::styled(status_icon(status, waiting), icon_style),
+        });
+        // Spell the status out, so it isn't told by color and symbol alone
+        if state.accessible {
+            spans.push(Span::styled(
+                format!(" {}:", status_label(&state.testlist, &state.results, test)),
+                icon_style,
+            ));
+        }
+        spans.push(Span::styled(format!(" {}", test.title), header_style));
+        match change {
+            Some(ref c) if c.is_regression() => spans.push(Span::styled(
+                " ↓ regression",
+                header_style.fg(theme.failed()),
+            )),
+            Some(ref c) if c.is_newly_passing() && c.old_status.is_some() => spans.push(
+                Span::styled(" ↑ now passing", header_style.fg(theme.passed())),
+            ),
+            _ => {}
+        }
+        if let Some(severity) = result.and_then(|r| r.severity) {
+            spans.push(Span::styled(
+                format!(" ({})", severity.label()),
+                header_style.fg(theme.status_color(status)),
+            ));
+        }
+        if let Some(def) = custom.filter(|_| !state.accessible) {
+            spans.push(Span::styled(format!(" ({})", def.name), icon_style));
+        }
+        if let Some(reason) = result.and_then(|r| r.blocked_reason.as_ref()) {
+            spans.push(Span::styled(
+                format!(" (blocked: {})", reason),
+                header_style.fg(theme.status_color(status)),
+            ));
+        }
+        if let Some(review) = result.and_then(|r| r.review.as_ref()) {
+            let color = match review.verdict {
+                Verdict::Approved => theme.passed(),
+                Verdict::Rejected => theme.failed(),
+            };
+            spans.push(Span::styled(
+                format!(" [{}]", review.verdict.label()),
+                header_style.fg(color),
+            ));
+        }
+        if let Some(priority) = test.priority {
+            spans.push(Span::styled(
+                format!(" !{}", priority.label()),
+                header_style.fg(theme.priority_color(priority)),
+            ));
+        }
+
+        items.push(ListItem::new(Line::from(spans)));
 
         if is_expanded {
             // Setup steps
             if !test.setup.is_empty() {
                 items.push(ListItem::new(Line::from("   Setup:")));
                 for item in &test.setup {
-                    let item_line = format!("   • {}", item.text);
-                    items.push(ListItem::new(Line::from(item_line)));
+                    items.push(checklist_item(state, test, ChecklistSection::Setup, item));
                 }
             }
 
+            if !test.depends_on.is_empty() {
+                let deps_line = format!("   Depends on: {}", test.depends_on.join(", "));
+                items.push(ListItem::new(Line::from(deps_line)));
+            }
+
             // Action
             let action_line = format!("   Action: {}", test.action);
             items.push(ListItem::new(Line::from(action_line)));
@@ -70,13 +237,31 @@ pub fn draw(frame: &mut Frame, state: &AppState, area: Rect) {
             if !test.verify.is_empty() {
                 items.push(ListItem::new(Line::from("   Verify:")));
                 for item in &test.verify {
-                    let item_line = format!("   • {}", item.text);
-                    items.push(ListItem::new(Line::from(item_line)));
+                    items.push(checklist_item(state, test, ChecklistSection::Verify, item));
+                }
+            }
+
+            // References
+            if !test.references.is_empty() {
+                items.push(ListItem::new(Line::from("   References (o to open):")));
+                for (n, reference) in test.references.iter().enumerate() {
+                    let reference_line = format!("   [{}] {}", n + 1, reference);
+                    items.push(ListItem::new(Line::from(Span::styled(
+                        reference_line,
+                        Style::default().fg(theme.accent()),
+                    ))));
                 }
             }
         }
     }
 
+    if items.is_empty() && state.tag_filter.is_some() {
+        items.push(ListItem::new(Line::from(Span::styled(
+            "(No tests match filter - press 'F' to change)",
+            Style::default().fg(theme.dim()),
+        ))));
+    }
+
     let visible_height = area.height.saturating_sub(2) as usize;
     let scroll_offset = state.tests_scroll_offset.min(items.len().saturating_sub(1));
     let visible_items: Vec<ListItem> = items
@@ -101,11 +286,67 @@ pub fn draw(frame: &mut Frame, state: &AppState, area: Rect) {
         String::new()
     };
 
+    let filter_indicator = state
+        .tag_filter
+        .as_ref()
+        .map(|tag| format!("[tag: {}] ", tag))
+        .unwrap_or_default();
+    let hidden_indicator = match state.status_view {
+        StatusView::All => String::new(),
+        StatusView::Pending => format!("[pending, {} hidden] ", hidden_by_view_count(state)),
+        StatusView::Failed => format!("[failed, {} hidden] ", hidden_by_view_count(state)),
+        StatusView::Blocked => format!("[blocked, {} hidden] ", hidden_by_view_count(state)),
+    };
+    let sort_indicator = if state.sort_by_priority {
+        "[by priority] "
+    } else {
+        ""
+    };
+
+    let visual_indicator = if state.visual_anchor.is_some() {
+        format!("[VISUAL {}] ", in_visual.len())
+    } else {
+        String::new()
+    };
+
+    let search_indicator = if state.search_query.is_empty() {
+        String::new()
+    } else {
+        format!("[/{}] ", state.search_query)
+    };
+
+    let section_indicator = current_section(state)
+        .map(|title| {
+            let (done, total) = section_progress(state, title);
+            format!("[{}: {}/{}] ", title, done, total)
+        })
+        .unwrap_or_default();
+
+    let only_indicator = state
+        .only_tests
+        .as_ref()
+        .map(|ids| format!("[only {}] ", ids.len()))
+        .unwrap_or_default();
+
+    let comparison_indicator = state
+        .comparison
+        .as_ref()
+        .map(|c| format!(" [vs {}]", c.label))
+        .unwrap_or_default();
+
     let title = format!(
-        " Tests ({}/{}){}",
+        " Tests ({}/{}){}{}{}{}{}{}{}{}{}",
         completed_count(state),
         state.testlist.tests.len(),
+        comparison_indicator,
         scroll_indicator,
+        section_indicator,
+        only_indicator,
+        filter_indicator,
+        hidden_indicator,
+        sort_indicator,
+        search_indicator,
+        visual_indicator,
     );
     let list = List::new(visible_items).block(
         Block::default()